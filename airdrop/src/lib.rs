@@ -0,0 +1,133 @@
+#![no_std]
+
+//! Airdrop distributor that only stores the root of a Merkle tree of `(index, recipient, amount)`
+//! leaves, whatever the number of recipients. Each recipient claims its amount by submitting its leaf
+//! and the proof for it, which the contract checks with `env.crypto().sha256`.
+//!
+//! A leaf hashes to `sha256(xdr((index, recipient, amount)))` and a node to `sha256(left || right)`.
+//! The index gives the position of the leaf in the tree: bit `i` of it tells whether the node at
+//! level `i` is the right (1) or the left (0) child, so a proof is just the list of sibling hashes from
+//! the leaf up. Trees whose leaf count is not a power of two are padded with any hash.
+
+use soroban_examples_events as events;
+use soroban_sdk::{contract, contracterror, contractimpl, contracttype, symbol_short, token, xdr::ToXdr, Address, Bytes, BytesN, Env, Symbol, Vec};
+
+mod storage;
+
+// Topics follow the workspace event schema: (CONTRACT_TAG, action)
+pub const CONTRACT_TAG: Symbol = symbol_short!("AIRDROP");
+pub const TOPIC_CLAIMED: Symbol = symbol_short!("CLAIMED");
+pub const TOPIC_RECLAIMED: Symbol = symbol_short!("RECLAIMED");
+
+macro_rules! require {
+    ($cond:expr, $err:expr) => {
+        if !$cond {
+            return Err($err);
+        }
+    };
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyClaimed = 1901,
+    InvalidProof = 1902,
+    AirdropEnded = 1903,
+    AirdropNotEnded = 1904,
+    InvalidTerms = 1905,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AirdropConfig {
+    pub funder: Address,
+    pub token: Address,
+    pub merkle_root: BytesN<32>,
+    /// From this timestamp on nothing can be claimed, and the funder can take back what is left.
+    pub end: u64,
+}
+
+/// Hash of the leaf paying `amount` to `recipient` at position `index`.
+pub fn leaf_hash(e: &Env, index: u32, recipient: &Address, amount: i128) -> BytesN<32> {
+    e.crypto().sha256(&(index, recipient.clone(), amount).to_xdr(e)).into()
+}
+
+/// Hash of the node whose children are `left` and `right`.
+pub fn node_hash(e: &Env, left: &BytesN<32>, right: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = Bytes::from_array(e, &left.to_array());
+    preimage.append(&Bytes::from_array(e, &right.to_array()));
+    e.crypto().sha256(&preimage).into()
+}
+
+fn verify_proof(e: &Env, root: &BytesN<32>, index: u32, leaf: BytesN<32>, proof: &Vec<BytesN<32>>) -> bool {
+    // An index with bits above the tree depth would be accepted at the position of its lower bits
+    if proof.len() > u32::BITS || (proof.len() < u32::BITS && index >> proof.len() != 0) {
+        return false;
+    }
+
+    let mut node = leaf;
+    for (level, sibling) in proof.iter().enumerate() {
+        node = if (index >> level) & 1 == 0 { node_hash(e, &node, &sibling) } else { node_hash(e, &sibling, &node) };
+    }
+
+    node == *root
+}
+
+#[contract]
+pub struct MerkleAirdrop;
+
+#[contractimpl]
+impl MerkleAirdrop {
+    /// `funder` deposits `total_amount` of `token`, to be claimed by the leaves of the tree with root
+    /// `merkle_root` until `end`.
+    pub fn __constructor(e: Env, funder: Address, token: Address, merkle_root: BytesN<32>, total_amount: i128, end: u64) -> Result<(), Error> {
+        funder.require_auth();
+        require!(total_amount > 0 && end > e.ledger().timestamp(), Error::InvalidTerms);
+
+        storage::set_config(&e, &AirdropConfig { funder: funder.clone(), token: token.clone(), merkle_root, end });
+        token::Client::new(&e, &token).transfer(&funder, &e.current_contract_address(), &total_amount);
+        Ok(())
+    }
+
+    /// Pays `amount` to `recipient` if `proof` shows that the leaf `(index, recipient, amount)` is in the
+    /// tree. Anyone can submit the claim, since the tokens can only go to `recipient`.
+    pub fn claim(e: Env, index: u32, recipient: Address, amount: i128, proof: Vec<BytesN<32>>) -> Result<(), Error> {
+        let config = storage::get_config(&e);
+        require!(e.ledger().timestamp() < config.end, Error::AirdropEnded);
+        require!(!storage::is_claimed(&e, index), Error::AlreadyClaimed);
+        let leaf = leaf_hash(&e, index, &recipient, amount);
+        require!(verify_proof(&e, &config.merkle_root, index, leaf, &proof), Error::InvalidProof);
+
+        storage::set_claimed(&e, index);
+        token::Client::new(&e, &config.token).transfer(&e.current_contract_address(), &recipient, &amount);
+        events::publish(&e, CONTRACT_TAG, TOPIC_CLAIMED, (index, recipient, amount));
+        Ok(())
+    }
+
+    /// The funder takes back whatever was not claimed by the end of the airdrop. Returns the amount.
+    pub fn reclaim(e: Env) -> Result<i128, Error> {
+        let config = storage::get_config(&e);
+        config.funder.require_auth();
+        require!(e.ledger().timestamp() >= config.end, Error::AirdropNotEnded);
+
+        let token = token::Client::new(&e, &config.token);
+        let amount = token.balance(&e.current_contract_address());
+        if amount > 0 {
+            token.transfer(&e.current_contract_address(), &config.funder, &amount);
+        }
+        events::publish(&e, CONTRACT_TAG, TOPIC_RECLAIMED, amount);
+        Ok(amount)
+    }
+
+    pub fn config(e: Env) -> AirdropConfig {
+        storage::get_config(&e)
+    }
+
+    pub fn is_claimed(e: Env, index: u32) -> bool {
+        storage::is_claimed(&e, index)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,39 @@
+use crate::AirdropConfig;
+use soroban_examples_storage::{StorageCell, StorageMap};
+use soroban_sdk::{contracttype, Env};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Config,
+    Claimed(u32),
+}
+
+// Claims are tracked as bits, 128 indexes per persistent entry, so a large airdrop costs one entry per
+// 128 recipients instead of one each
+const CLAIMED_PER_WORD: u32 = 128;
+
+const CONFIG: StorageCell<DataKey, AirdropConfig> = StorageCell::instance(DataKey::Config);
+const CLAIMED: StorageMap<u32, u128, DataKey> = StorageMap::persistent(|word| DataKey::Claimed(*word));
+
+pub fn get_config(e: &Env) -> AirdropConfig {
+    CONFIG.get_or_else(e, || panic!("Airdrop config has expired"))
+}
+
+pub fn set_config(e: &Env, config: &AirdropConfig) {
+    CONFIG.set(e, config);
+}
+
+fn word_and_mask(index: u32) -> (u32, u128) {
+    (index / CLAIMED_PER_WORD, 1 << (index % CLAIMED_PER_WORD))
+}
+
+pub fn is_claimed(e: &Env, index: u32) -> bool {
+    let (word, mask) = word_and_mask(index);
+    CLAIMED.get_or_else(e, &word, || 0) & mask != 0
+}
+
+pub fn set_claimed(e: &Env, index: u32) {
+    let (word, mask) = word_and_mask(index);
+    CLAIMED.update(e, &word, |bits| bits.unwrap_or(0) | mask);
+}
@@ -0,0 +1,152 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::{leaf_hash, node_hash, MerkleAirdrop, MerkleAirdropClient, CONTRACT_TAG, TOPIC_CLAIMED};
+use soroban_examples_events::EVENT_VERSION;
+use soroban_examples_testutils::{create_env, create_token_contract, mint_to, TokenClient, SECONDS_IN_DAY};
+use soroban_sdk::{testutils::{Address as _, Events, Ledger}, vec, Address, BytesN, Env, IntoVal, Vec};
+
+const START: u64 = 1_000;
+const END: u64 = START + 90 * SECONDS_IN_DAY;
+const AMOUNTS: [i128; 4] = [100, 200, 300, 400];
+
+struct TestData<'a> {
+    funder: Address,
+    recipients: std::vec::Vec<Address>,
+    token: TokenClient<'a>,
+    // Levels of the tree, from the leaves up to the root
+    tree: std::vec::Vec<std::vec::Vec<BytesN<32>>>,
+    client: MerkleAirdropClient<'a>,
+}
+
+impl TestData<'_> {
+    fn proof(&self, index: u32) -> Vec<BytesN<32>> {
+        let mut proof = Vec::new(&self.client.env);
+        for (level, nodes) in self.tree[..self.tree.len() - 1].iter().enumerate() {
+            proof.push_back(nodes[((index >> level) ^ 1) as usize].clone());
+        }
+        proof
+    }
+}
+
+// Four recipients, with the amounts of AMOUNTS at the index of their position
+fn create_airdrop(e: &Env) -> TestData<'_> {
+    let funder = Address::generate(e);
+    let recipients: std::vec::Vec<Address> = AMOUNTS.iter().map(|_| Address::generate(e)).collect();
+    let (token, token_admin) = create_token_contract(e, &Address::generate(e));
+    mint_to(&token_admin, &[&funder], 1_000);
+    e.ledger().set_timestamp(START);
+
+    let leaves = recipients.iter().zip(AMOUNTS).enumerate().map(|(index, (recipient, amount))| leaf_hash(e, index as u32, recipient, amount)).collect();
+    let mut tree: std::vec::Vec<std::vec::Vec<BytesN<32>>> = std::vec![leaves];
+    while tree.last().unwrap().len() > 1 {
+        let level = tree.last().unwrap().chunks(2).map(|pair| node_hash(e, &pair[0], &pair[1])).collect();
+        tree.push(level);
+    }
+
+    let root = tree.last().unwrap()[0].clone();
+    let client = MerkleAirdropClient::new(e, &e.register(MerkleAirdrop, (funder.clone(), token.address.clone(), root, 1_000_i128, END)));
+
+    TestData { funder, recipients, token, tree, client }
+}
+
+#[test]
+fn test_every_recipient_claims_its_amount() {
+    let e = create_env();
+    let test_data = create_airdrop(&e);
+    assert_eq!(test_data.token.balance(&test_data.client.address), 1_000);
+
+    for (index, recipient) in test_data.recipients.iter().enumerate().rev() {
+        let index = index as u32;
+        test_data.client.claim(&index, recipient, &AMOUNTS[index as usize], &test_data.proof(index));
+        assert_eq!(
+            e.events().all().last().unwrap(),
+            (test_data.client.address.clone(), (CONTRACT_TAG, TOPIC_CLAIMED).into_val(&e), (EVENT_VERSION, (index, recipient.clone(), AMOUNTS[index as usize])).into_val(&e))
+        );
+        assert!(test_data.client.is_claimed(&index));
+        assert_eq!(test_data.token.balance(recipient), AMOUNTS[index as usize]);
+    }
+    assert_eq!(test_data.token.balance(&test_data.client.address), 0);
+}
+
+#[test]
+fn test_funder_reclaims_what_is_left_after_the_end() {
+    let e = create_env();
+    let test_data = create_airdrop(&e);
+
+    test_data.client.claim(&1, &test_data.recipients[1], &200, &test_data.proof(1));
+    assert!(!test_data.client.is_claimed(&0));
+    assert!(test_data.client.try_reclaim().is_err());
+
+    e.ledger().set_timestamp(END);
+    assert_eq!(test_data.client.reclaim(), 800);
+    assert_eq!(test_data.token.balance(&test_data.funder), 800);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1901)")]
+fn test_claim_twice() {
+    let e = create_env();
+    let test_data = create_airdrop(&e);
+
+    test_data.client.claim(&2, &test_data.recipients[2], &300, &test_data.proof(2));
+    test_data.client.claim(&2, &test_data.recipients[2], &300, &test_data.proof(2));
+}
+
+#[test]
+fn test_claims_that_are_not_in_the_tree() {
+    let e = create_env();
+    let test_data = create_airdrop(&e);
+    let stranger = Address::generate(&e);
+
+    let invalid_claims = [
+        (0, &test_data.recipients[0], 101, test_data.proof(0)),
+        (0, &stranger, 100, test_data.proof(0)),
+        (1, &test_data.recipients[0], 100, test_data.proof(1)),
+        (0, &test_data.recipients[0], 100, test_data.proof(1)),
+        // Same position in the tree as index 0
+        (4, &test_data.recipients[0], 100, test_data.proof(0)),
+        (0, &test_data.recipients[0], 100, vec![&e]),
+    ];
+    for (index, recipient, amount, proof) in invalid_claims {
+        assert_eq!(test_data.client.try_claim(&index, recipient, &amount, &proof), Err(Ok(crate::Error::InvalidProof)));
+    }
+    assert!(!test_data.client.is_claimed(&0));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1903)")]
+fn test_claim_after_the_end() {
+    let e = create_env();
+    let test_data = create_airdrop(&e);
+
+    e.ledger().set_timestamp(END);
+    test_data.client.claim(&0, &test_data.recipients[0], &100, &test_data.proof(0));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1904)")]
+fn test_reclaim_before_the_end() {
+    let e = create_env();
+    let test_data = create_airdrop(&e);
+
+    test_data.client.reclaim();
+}
+
+#[test]
+#[should_panic(expected = "Error(Context, InvalidAction)")]
+fn test_end_in_the_past() {
+    let e = create_env();
+    let test_data = create_airdrop(&e);
+    let root = test_data.tree.last().unwrap()[0].clone();
+
+    e.register(MerkleAirdrop, (test_data.funder.clone(), test_data.token.address.clone(), root, 1_000_i128, START));
+}
+
+#[test]
+fn error_codes_in_registry_range() {
+    use super::Error;
+
+    assert!(soroban_examples_errors::AIRDROP.contains(Error::AlreadyClaimed as u32));
+    assert!(soroban_examples_errors::AIRDROP.contains(Error::InvalidTerms as u32));
+}
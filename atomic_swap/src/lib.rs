@@ -0,0 +1,127 @@
+#![no_std]
+
+//! Hashed timelock contracts (HTLC) for atomic swaps between two tokens, with no trusted intermediary.
+//! Alice picks a secret and locks her tokens for Bob under its SHA-256 hash; Bob locks his tokens for
+//! Alice under the same hash, with an earlier timeout. Alice redeems Bob's lock with the secret, which
+//! reveals it on-chain, and Bob uses it to redeem Alice's lock. If either side stops halfway, both
+//! locks time out and each sender takes its tokens back with `refund`.
+//!
+//! Bob's timeout has to be early enough that he still has time to redeem Alice's lock once the secret
+//! is revealed; the contract cannot check it, since the two locks are independent.
+
+use soroban_examples_events as events;
+use soroban_sdk::{contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Bytes, BytesN, Env, Symbol};
+
+mod storage;
+
+// Topics follow the workspace event schema: (CONTRACT_TAG, action)
+pub const CONTRACT_TAG: Symbol = symbol_short!("HTLC");
+pub const TOPIC_LOCKED: Symbol = symbol_short!("LOCKED");
+pub const TOPIC_REDEEMED: Symbol = symbol_short!("REDEEMED");
+pub const TOPIC_REFUNDED: Symbol = symbol_short!("REFUNDED");
+
+macro_rules! require {
+    ($cond:expr, $err:expr) => {
+        if !$cond {
+            return Err($err);
+        }
+    };
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    SwapNotFound = 1701,
+    SwapSettled = 1702,
+    InvalidPreimage = 1703,
+    SwapExpired = 1704,
+    SwapNotExpired = 1705,
+    InvalidSwap = 1706,
+}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum SwapStatus {
+    Locked = 1,
+    Redeemed = 2,
+    Refunded = 3,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Swap {
+    pub sender: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: i128,
+    /// SHA-256 of the secret that redeems the swap.
+    pub hashlock: BytesN<32>,
+    /// From this timestamp on the swap can no longer be redeemed, only refunded.
+    pub timelock: u64,
+    pub status: SwapStatus,
+    /// The secret, once revealed by the redemption.
+    pub preimage: Option<Bytes>,
+}
+
+fn get_swap(e: &Env, swap_id: u64) -> Result<Swap, Error> {
+    storage::get_swap(e, swap_id).ok_or(Error::SwapNotFound)
+}
+
+#[contract]
+pub struct AtomicSwap;
+
+#[contractimpl]
+impl AtomicSwap {
+    /// `sender` locks `amount` of `token` for `recipient` until `timelock`, redeemable with the secret
+    /// whose SHA-256 is `hashlock`. Returns the swap id.
+    pub fn initiate(e: Env, sender: Address, recipient: Address, token: Address, amount: i128, hashlock: BytesN<32>, timelock: u64) -> Result<u64, Error> {
+        sender.require_auth();
+        require!(amount > 0 && sender != recipient && timelock > e.ledger().timestamp(), Error::InvalidSwap);
+
+        let swap_id = storage::next_swap_id(&e);
+        let swap = Swap { sender: sender.clone(), recipient: recipient.clone(), token: token.clone(), amount, hashlock: hashlock.clone(), timelock, status: SwapStatus::Locked, preimage: None };
+        storage::set_swap(&e, swap_id, &swap);
+        token::Client::new(&e, &token).transfer(&sender, &e.current_contract_address(), &amount);
+        events::publish(&e, CONTRACT_TAG, TOPIC_LOCKED, (swap_id, sender, recipient, amount, hashlock, timelock));
+        Ok(swap_id)
+    }
+
+    /// Pays the locked tokens to the recipient in exchange for the secret, which is published in the
+    /// event and kept with the swap. Anyone holding the secret can trigger it before the timeout.
+    pub fn redeem(e: Env, swap_id: u64, preimage: Bytes) -> Result<bool, Error> {
+        let mut swap = get_swap(&e, swap_id)?;
+        require!(swap.status == SwapStatus::Locked, Error::SwapSettled);
+        require!(e.ledger().timestamp() < swap.timelock, Error::SwapExpired);
+        let hash: BytesN<32> = e.crypto().sha256(&preimage).into();
+        require!(hash == swap.hashlock, Error::InvalidPreimage);
+
+        swap.status = SwapStatus::Redeemed;
+        swap.preimage = Some(preimage.clone());
+        storage::set_swap(&e, swap_id, &swap);
+        token::Client::new(&e, &swap.token).transfer(&e.current_contract_address(), &swap.recipient, &swap.amount);
+        events::publish(&e, CONTRACT_TAG, TOPIC_REDEEMED, (swap_id, preimage));
+        Ok(true)
+    }
+
+    /// Returns the locked tokens to the sender once the swap has timed out unredeemed. Anyone can trigger it.
+    pub fn refund(e: Env, swap_id: u64) -> Result<bool, Error> {
+        let mut swap = get_swap(&e, swap_id)?;
+        require!(swap.status == SwapStatus::Locked, Error::SwapSettled);
+        require!(e.ledger().timestamp() >= swap.timelock, Error::SwapNotExpired);
+
+        swap.status = SwapStatus::Refunded;
+        storage::set_swap(&e, swap_id, &swap);
+        token::Client::new(&e, &swap.token).transfer(&e.current_contract_address(), &swap.sender, &swap.amount);
+        events::publish(&e, CONTRACT_TAG, TOPIC_REFUNDED, swap_id);
+        Ok(true)
+    }
+
+    pub fn swap(e: Env, swap_id: u64) -> Result<Swap, Error> {
+        get_swap(&e, swap_id)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,26 @@
+use crate::Swap;
+use soroban_examples_storage::{StorageCell, StorageMap};
+use soroban_sdk::{contracttype, Env};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    SwapCount,
+    Swap(u64),
+}
+
+// Settled swaps are kept, so the counterparty can still read the revealed preimage
+const SWAP_COUNT: StorageCell<DataKey, u64> = StorageCell::instance(DataKey::SwapCount);
+const SWAPS: StorageMap<u64, Swap, DataKey> = StorageMap::persistent(|id| DataKey::Swap(*id));
+
+pub fn next_swap_id(e: &Env) -> u64 {
+    SWAP_COUNT.update(e, |count| count.unwrap_or(0) + 1)
+}
+
+pub fn get_swap(e: &Env, id: u64) -> Option<Swap> {
+    SWAPS.get(e, &id)
+}
+
+pub fn set_swap(e: &Env, id: u64, swap: &Swap) {
+    SWAPS.set(e, &id, swap);
+}
@@ -0,0 +1,157 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::{AtomicSwap, AtomicSwapClient, SwapStatus, CONTRACT_TAG, TOPIC_REDEEMED};
+use soroban_examples_events::EVENT_VERSION;
+use soroban_examples_testutils::{advance_time, create_env, create_token_contract, mint_to, TokenClient};
+use soroban_sdk::{testutils::{Address as _, Events, Ledger}, Address, Bytes, BytesN, Env, IntoVal};
+
+const START: u64 = 1_000;
+// Bob's lock times out first, so he can still redeem Alice's once she reveals the secret
+const ALICE_TIMELOCK: u64 = START + 48 * 3_600;
+const BOB_TIMELOCK: u64 = START + 24 * 3_600;
+
+struct TestData<'a> {
+    alice: Address,
+    bob: Address,
+    token_a: TokenClient<'a>,
+    token_b: TokenClient<'a>,
+    secret: Bytes,
+    hashlock: BytesN<32>,
+    client: AtomicSwapClient<'a>,
+}
+
+// Alice holds 1000 of token A and Bob 500 of token B; they agreed to swap them
+fn create_swap(e: &Env) -> TestData<'_> {
+    let (alice, bob) = (Address::generate(e), Address::generate(e));
+    let (token_a, token_a_admin) = create_token_contract(e, &Address::generate(e));
+    let (token_b, token_b_admin) = create_token_contract(e, &Address::generate(e));
+    mint_to(&token_a_admin, &[&alice], 1_000);
+    mint_to(&token_b_admin, &[&bob], 500);
+    e.ledger().set_timestamp(START);
+
+    let secret = Bytes::from_slice(e, b"correct horse battery staple");
+    let hashlock = e.crypto().sha256(&secret).into();
+    let client = AtomicSwapClient::new(e, &e.register(AtomicSwap, ()));
+
+    TestData { alice, bob, token_a, token_b, secret, hashlock, client }
+}
+
+// Both sides lock their tokens under the hash of Alice's secret
+fn lock_both(test_data: &TestData) -> (u64, u64) {
+    let alice_lock = test_data.client.initiate(&test_data.alice, &test_data.bob, &test_data.token_a.address, &1_000, &test_data.hashlock, &ALICE_TIMELOCK);
+    let bob_lock = test_data.client.initiate(&test_data.bob, &test_data.alice, &test_data.token_b.address, &500, &test_data.hashlock, &BOB_TIMELOCK);
+    (alice_lock, bob_lock)
+}
+
+#[test]
+fn test_both_sides_settle_with_the_revealed_secret() {
+    let e = create_env();
+    let test_data = create_swap(&e);
+    let (alice_lock, bob_lock) = lock_both(&test_data);
+    assert_eq!(test_data.token_a.balance(&test_data.client.address), 1_000);
+    assert_eq!(test_data.token_b.balance(&test_data.client.address), 500);
+
+    // Alice takes Bob's tokens, revealing the secret
+    advance_time(&e, 3_600);
+    test_data.client.redeem(&bob_lock, &test_data.secret);
+    assert_eq!(
+        e.events().all().last().unwrap(),
+        (test_data.client.address.clone(), (CONTRACT_TAG, TOPIC_REDEEMED).into_val(&e), (EVENT_VERSION, (bob_lock, test_data.secret.clone())).into_val(&e))
+    );
+
+    // Bob reads it from the settled swap and takes Alice's
+    let secret = test_data.client.swap(&bob_lock).preimage.unwrap();
+    advance_time(&e, 3_600);
+    test_data.client.redeem(&alice_lock, &secret);
+
+    assert_eq!(test_data.token_b.balance(&test_data.alice), 500);
+    assert_eq!(test_data.token_a.balance(&test_data.bob), 1_000);
+    assert_eq!(test_data.client.swap(&alice_lock).status, SwapStatus::Redeemed);
+}
+
+#[test]
+fn test_both_sides_are_refunded_if_the_secret_is_never_revealed() {
+    let e = create_env();
+    let test_data = create_swap(&e);
+    let (alice_lock, bob_lock) = lock_both(&test_data);
+
+    e.ledger().set_timestamp(BOB_TIMELOCK);
+    test_data.client.refund(&bob_lock);
+    assert!(test_data.client.try_refund(&alice_lock).is_err());
+
+    e.ledger().set_timestamp(ALICE_TIMELOCK);
+    test_data.client.refund(&alice_lock);
+    assert_eq!(test_data.token_a.balance(&test_data.alice), 1_000);
+    assert_eq!(test_data.token_b.balance(&test_data.bob), 500);
+    assert_eq!(test_data.client.swap(&bob_lock).status, SwapStatus::Refunded);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1701)")]
+fn test_redeem_unknown_swap() {
+    let e = create_env();
+    let test_data = create_swap(&e);
+
+    test_data.client.redeem(&1, &test_data.secret);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1702)")]
+fn test_redeem_twice() {
+    let e = create_env();
+    let test_data = create_swap(&e);
+    let (_, bob_lock) = lock_both(&test_data);
+
+    test_data.client.redeem(&bob_lock, &test_data.secret);
+    test_data.client.redeem(&bob_lock, &test_data.secret);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1703)")]
+fn test_redeem_with_wrong_secret() {
+    let e = create_env();
+    let test_data = create_swap(&e);
+    let (_, bob_lock) = lock_both(&test_data);
+
+    test_data.client.redeem(&bob_lock, &Bytes::from_slice(&e, b"wrong guess"));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1704)")]
+fn test_redeem_after_the_timeout() {
+    let e = create_env();
+    let test_data = create_swap(&e);
+    let (_, bob_lock) = lock_both(&test_data);
+
+    e.ledger().set_timestamp(BOB_TIMELOCK);
+    test_data.client.redeem(&bob_lock, &test_data.secret);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1705)")]
+fn test_refund_before_the_timeout() {
+    let e = create_env();
+    let test_data = create_swap(&e);
+    let (alice_lock, _) = lock_both(&test_data);
+
+    e.ledger().set_timestamp(ALICE_TIMELOCK - 1);
+    test_data.client.refund(&alice_lock);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1706)")]
+fn test_timelock_in_the_past() {
+    let e = create_env();
+    let test_data = create_swap(&e);
+
+    test_data.client.initiate(&test_data.alice, &test_data.bob, &test_data.token_a.address, &1_000, &test_data.hashlock, &START);
+}
+
+#[test]
+fn error_codes_in_registry_range() {
+    use super::Error;
+
+    assert!(soroban_examples_errors::ATOMIC_SWAP.contains(Error::SwapNotFound as u32));
+    assert!(soroban_examples_errors::ATOMIC_SWAP.contains(Error::InvalidSwap as u32));
+}
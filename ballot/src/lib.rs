@@ -1,10 +1,21 @@
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracterror, Env, Symbol, Map, Address, Vec};
+use soroban_examples_access as access;
+use soroban_examples_events as events;
+use soroban_examples_ownership::{self as ownership, OwnershipError, PendingAdmin};
+use soroban_examples_upgrade as upgrade;
+use soroban_sdk::{contract, contractimpl, contracterror, symbol_short, BytesN, Env, Symbol, Map, Address, Vec};
 
 mod storage;
 mod validation;
-use storage::VCounter;
+
+// Version of the contract data layout, bumped whenever a release needs a migration
+pub const CONTRACT_VERSION: u32 = 1;
+
+// Topics follow the workspace event schema: (CONTRACT_TAG, action)
+pub const CONTRACT_TAG: Symbol = symbol_short!("BALLOT");
+pub const TOPIC_VOTED: Symbol = symbol_short!("VOTED");
+pub const TOPIC_DELEGATED: Symbol = symbol_short!("DELEGATED");
 
 struct Voter<'a> {
     id: &'a Symbol
@@ -32,6 +43,10 @@ impl<'a> Voter<'a> {
     }
 }
 
+fn require_admin(env: &Env) -> Result<Address, Error> {
+    access::require_admin(env).map_err(|_| Error::BallotNotInitialized)
+}
+
 fn check_dates(env: &Env) -> bool {
     let cfg = storage::get_config(env);
     let mut valid = true;
@@ -47,13 +62,30 @@ fn check_dates(env: &Env) -> bool {
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
 pub enum Error {
-    VoterHasHisVoteDelegated = 1,
-    VoterHasAlreadyVoted = 2,
-    VoterHasDelegatedVotes = 3,
-    VoterOriginHasAlreadyVotedAndCannotDelegate = 4,
-    VoterTargetHasAlreadyVotedAndCannotDelegate = 5,
-    BallotOutOfDate = 6
+    VoterHasHisVoteDelegated = 101,
+    VoterHasAlreadyVoted = 102,
+    VoterHasDelegatedVotes = 103,
+    VoterOriginHasAlreadyVotedAndCannotDelegate = 104,
+    VoterTargetHasAlreadyVotedAndCannotDelegate = 105,
+    BallotOutOfDate = 106,
+    BallotNotInitialized = 107,
+    AlreadyMigrated = 108,
+    NoPendingAdmin = 109,
+    AdminProposalExpired = 110,
+    StaleAdminProposal = 111,
+    UnknownCandidate = 112,
+    VoterTargetHasHisVoteDelegated = 113
+}
 
+impl From<OwnershipError> for Error {
+    fn from(err: OwnershipError) -> Self {
+        match err {
+            OwnershipError::AdminNotSet => Error::BallotNotInitialized,
+            OwnershipError::NoPendingAdmin => Error::NoPendingAdmin,
+            OwnershipError::ProposalExpired => Error::AdminProposalExpired,
+            OwnershipError::StaleProposal => Error::StaleAdminProposal,
+        }
+    }
 }
 
 #[contract]
@@ -62,14 +94,33 @@ pub struct Ballot;
 #[contractimpl]
 impl Ballot {
 
-    pub fn configure(env: Env, admin: Address, ts_start: u64, ts_end: u64) -> Result<bool, Error> {
-        admin.require_auth();
+    /// The admin is the application that registers votes and delegations on behalf of the voters.
+    pub fn __constructor(env: Env, admin: Address) {
+        access::set_admin(&env, &admin);
+        upgrade::set_version(&env, CONTRACT_VERSION);
+    }
+
+    pub fn configure(env: Env, ts_start: u64, ts_end: u64) -> Result<bool, Error> {
+        require_admin(&env)?;
         storage::store_config(&env, ts_start, ts_end);
         Ok(true)
     }
 
-    pub fn vote(env: Env, admin: Address, voter: Symbol, candidate: Symbol) -> Result<bool, Error> {
-        admin.require_auth();
+    /// Registers the candidates that can be voted for; it can be called again to add more. Until the first
+    /// registration any candidate is accepted, and is added to the ballot by its first vote.
+    pub fn add_candidates(env: Env, candidates: Vec<Symbol>) -> Result<bool, Error> {
+        require_admin(&env)?;
+
+        for candidate in candidates.iter() {
+            storage::store_party(&env, &candidate);
+        }
+        storage::set_candidates_registered(&env);
+
+        Ok(true)
+    }
+
+    pub fn vote(env: Env, voter: Symbol, candidate: Symbol) -> Result<bool, Error> {
+        require_admin(&env)?;
         
         if !check_dates(&env) {
             return Err(Error::BallotOutOfDate);
@@ -83,23 +134,29 @@ impl Ballot {
         if v.has_voted(&env) {
             return Err(Error::VoterHasAlreadyVoted)
         }
-        
-        storage::store_party(&env, &candidate);
+
+        if storage::are_candidates_registered(&env) {
+            if !storage::get_candidates(&env).contains(&candidate) {
+                return Err(Error::UnknownCandidate)
+            }
+        } else {
+            storage::store_party(&env, &candidate);
+        }
 
         let mut votes: Vec<Symbol> = storage::get_votes(&env);
-        let candidate_key = VCounter::Counter(candidate);
         let d_votes: Vec<Symbol> = storage::get_voter_delegated_votes(&env, v.id);
-        let count = 1 + d_votes.len() + storage::get_candidate_votes_count(&env, &candidate_key);
-        votes.push_back(voter);
+        let count = 1 + d_votes.len() + storage::get_candidate_votes_count(&env, &candidate);
+        votes.push_back(voter.clone());
  
-        storage::update_candidate_count(&env, candidate_key, count);
+        storage::update_candidate_count(&env, &candidate, count);
         storage::update_votes(&env, votes);
+        events::publish(&env, CONTRACT_TAG, TOPIC_VOTED, (voter, candidate, count));
 
         Ok(true)
     }
 
-    pub fn delegate(env: Env,  admin: Address, o_voter: Symbol, d_voter: Symbol) -> Result<bool, Error> {
-        admin.require_auth();
+    pub fn delegate(env: Env, o_voter: Symbol, d_voter: Symbol) -> Result<bool, Error> {
+        require_admin(&env)?;
 
         if !check_dates(&env) {
             return Err(Error::BallotOutOfDate);
@@ -121,8 +178,9 @@ impl Ballot {
             return Err(Error::VoterHasHisVoteDelegated)
         }
 
+        // Delegations are not chained: the target must vote with its own vote
         if dv.is_delegated(&env) {
-            return Err(Error::VoterHasHisVoteDelegated)
+            return Err(Error::VoterTargetHasHisVoteDelegated)
         }
 
         if ov.has_delegated_votes(&env) {
@@ -136,24 +194,91 @@ impl Ballot {
         d_vot_delegs.push_back(o_voter.clone());
 
         storage::update_delegated_votes(&env, d_votes);
-        storage::update_voter_delegated_votes(&env, d_voter, d_vot_delegs);
+        storage::update_voter_delegated_votes(&env, d_voter.clone(), d_vot_delegs);
+        events::publish(&env, CONTRACT_TAG, TOPIC_DELEGATED, (o_voter, d_voter));
 
         Ok(true)
 
     }
 
-    pub fn count(env: Env,  admin: Address) -> Map<Symbol, u32> {
-        
-        admin.require_auth();
+    pub fn count(env: Env) -> Result<Map<Symbol, u32>, Error> {
+        require_admin(&env)?;
         let pts = storage::get_candidates(&env);
         let mut count_map: Map<Symbol, u32>= Map::new(&env);
         for party in pts.iter() {
-            let candidate_key = VCounter::Counter(party.clone());
-            let candidate_count: u32 = storage::get_candidate_votes_count(&env, &candidate_key);
+            let candidate_count: u32 = storage::get_candidate_votes_count(&env, &party);
             count_map.set(party, candidate_count);
         }
 
-        count_map
+        Ok(count_map)
+    }
+
+    /// Result getter for other contracts: the candidate with the most votes once the ballot has closed.
+    ///
+    /// Returns `None` while the ballot is open or not configured, if nobody voted, or on a tie.
+    pub fn winner(env: Env) -> Option<Symbol> {
+        let cfg = storage::get_config(&env);
+        if cfg.to == 0 || env.ledger().timestamp() <= cfg.to {
+            return None;
+        }
+
+        let mut winner: Option<Symbol> = None;
+        let mut top: u32 = 0;
+        for party in storage::get_candidates(&env).iter() {
+            let candidate_count = storage::get_candidate_votes_count(&env, &party);
+            if candidate_count > top {
+                top = candidate_count;
+                winner = Some(party);
+            } else if candidate_count == top {
+                winner = None;
+            }
+        }
+
+        winner
+    }
+
+    /// Replaces the contract code with the already uploaded wasm `new_wasm_hash`. The admin must call
+    /// `migrate` on the new code right after.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<bool, Error> {
+        require_admin(&env)?;
+        upgrade::upgrade(&env, &new_wasm_hash);
+        Ok(true)
+    }
+
+    /// Brings the stored data up to the version of the running code, returning the version it had.
+    pub fn migrate(env: Env) -> Result<u32, Error> {
+        require_admin(&env)?;
+
+        // Version 1 is the first versioned layout, so there is nothing to convert yet
+        upgrade::migrate(&env, CONTRACT_VERSION, |_env, _from| {}).map_err(|_| Error::AlreadyMigrated)
+    }
+
+    pub fn version(env: Env) -> u32 {
+        upgrade::version(&env)
+    }
+
+    pub fn admin(env: Env) -> Result<Address, Error> {
+        access::get_admin(&env).ok_or(Error::BallotNotInitialized)
+    }
+
+    /// Proposes `new_admin` as the application registering the votes. It only takes over once it calls
+    /// `accept_admin`, within a week.
+    pub fn propose_admin(env: Env, new_admin: Address) -> Result<PendingAdmin, Error> {
+        Ok(ownership::propose_admin(&env, &new_admin)?)
+    }
+
+    pub fn accept_admin(env: Env) -> Result<bool, Error> {
+        ownership::accept_admin(&env)?;
+        Ok(true)
+    }
+
+    pub fn cancel_admin_transfer(env: Env) -> Result<bool, Error> {
+        ownership::cancel_admin_transfer(&env)?;
+        Ok(true)
+    }
+
+    pub fn pending_admin(env: Env) -> Option<PendingAdmin> {
+        ownership::pending_admin(&env)
     }
 }
 
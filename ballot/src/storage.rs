@@ -1,9 +1,11 @@
+use soroban_examples_storage::{StorageCell, StorageMap};
 use soroban_sdk::{ Env, Symbol, Vec, symbol_short, contracttype};
 
 pub const VOTES: Symbol = symbol_short!("votes");
 pub const PARTIES: Symbol = symbol_short!("parties");
 pub const DVOTES: Symbol = symbol_short!("dvotes");
 pub const CONFIG: Symbol = symbol_short!("config");
+pub const REGISTERED: Symbol = symbol_short!("reg_cands");
 
 #[derive(Debug)]
 #[contracttype]
@@ -23,80 +25,69 @@ pub enum VCounter {
     Counter(Symbol)
 }
 
-pub fn get_candidates(env: &Env) -> Vec<Symbol> {
-    let pts: Vec<Symbol> = env
-        .storage()
-        .instance()
-        .get(&PARTIES)
-        .unwrap_or(Vec::new(env))
-    ;
+// All the ballot data lives in instance storage, bumped on each read and write
+const CANDIDATES: StorageCell<Symbol, Vec<Symbol>> = StorageCell::instance(PARTIES);
+const VOTERS: StorageCell<Symbol, Vec<Symbol>> = StorageCell::instance(VOTES);
+const DELEGATED_VOTERS: StorageCell<Symbol, Vec<Symbol>> = StorageCell::instance(DVOTES);
+const BALLOT_CONFIG: StorageCell<Symbol, Config> = StorageCell::instance(CONFIG);
+// Set once the admin registers candidates; from then on only those can be voted for
+const CANDIDATES_REGISTERED: StorageCell<Symbol, bool> = StorageCell::instance(REGISTERED);
+const CANDIDATE_COUNTS: StorageMap<Symbol, u32, VCounter> = StorageMap::instance(|candidate| VCounter::Counter(candidate.clone()));
+const VOTER_DELEGATIONS: StorageMap<Symbol, Vec<Symbol>, Symbol> = StorageMap::instance(|d_voter| d_voter.clone());
 
-    pts
+pub fn get_candidates(env: &Env) -> Vec<Symbol> {
+    CANDIDATES.get_or_else(env, || Vec::new(env))
 }
 
 pub fn store_party(env: &Env, p: &Symbol) -> bool {
     let mut pts: Vec<Symbol> = get_candidates(env);
     if !pts.contains(p) {
         pts.push_back(p.clone());
-        env.storage().instance().set(&PARTIES, &pts);
+        CANDIDATES.set(env, &pts);
         return true;
     }
 
     false
 }
 
-pub fn get_votes(env: &Env) -> Vec<Symbol>{
-    let vts: Vec<Symbol> = env
-        .storage()
-        .instance()
-        .get(&VOTES)
-        .unwrap_or(Vec::new(env))
-    ;
+pub fn are_candidates_registered(env: &Env) -> bool {
+    CANDIDATES_REGISTERED.get_or_else(env, || false)
+}
 
-    vts
+pub fn set_candidates_registered(env: &Env) {
+    CANDIDATES_REGISTERED.set(env, &true);
 }
 
-pub fn get_voter_delegated_votes(env: &Env, d_voter: &Symbol) -> Vec<Symbol> {
-    let v_dvts: Vec<Symbol> = env
-        .storage()
-        .instance()
-        .get(d_voter)
-        .unwrap_or(Vec::new(env))
-    ;
+pub fn get_votes(env: &Env) -> Vec<Symbol>{
+    VOTERS.get_or_else(env, || Vec::new(env))
+}
 
-    v_dvts
+pub fn get_voter_delegated_votes(env: &Env, d_voter: &Symbol) -> Vec<Symbol> {
+    VOTER_DELEGATIONS.get_or_else(env, d_voter, || Vec::new(env))
 }
 
 pub fn get_delegated_votes(env: &Env) -> Vec<Symbol> {
-    let dvts: Vec<Symbol> = env
-        .storage()
-        .instance()
-        .get(&DVOTES)
-        .unwrap_or(Vec::new(env))
-    ;
-
-    dvts
+    DELEGATED_VOTERS.get_or_else(env, || Vec::new(env))
 }
 
-pub fn get_candidate_votes_count(env: &Env, candidate: &VCounter) -> u32 {
-    let total_votes = env.storage().instance().get(candidate).unwrap_or(0);
-    total_votes
+pub fn get_candidate_votes_count(env: &Env, candidate: &Symbol) -> u32 {
+    CANDIDATE_COUNTS.get_or_else(env, candidate, || 0)
 }
 
-pub fn update_candidate_count(env: &Env, candidate: VCounter, count: u32) {
-    env.storage().instance().set(&candidate, &count);
+pub fn update_candidate_count(env: &Env, candidate: &Symbol, count: u32) {
+    CANDIDATE_COUNTS.set(env, candidate, &count);
 }
 
 pub fn update_votes(env: &Env, votes: Vec<Symbol>) {
-    env.storage().instance().set(&VOTES, &votes);
+    VOTERS.set(env, &votes);
 }
 
 pub fn update_delegated_votes(env: &Env, d_votes: Vec<Symbol>) {
-    env.storage().instance().set(&DVOTES, &d_votes);
+    DELEGATED_VOTERS.set(env, &d_votes);
 }
 
 pub fn update_voter_delegated_votes(env: &Env, d_voter: Symbol, d_vot_delegs: Vec<Symbol>) {
-    env.storage().instance().set(&d_voter, &d_vot_delegs);
+    VOTER_DELEGATIONS.set(env, &d_voter, &d_vot_delegs);
 }
 
 pub fn store_config(env: &Env, ts_start: u64, ts_end: u64) {
@@ -105,16 +96,9 @@ pub fn store_config(env: &Env, ts_start: u64, ts_end: u64) {
         to: ts_end
     };
 
-    env.storage().instance().set(&CONFIG, &cfg);
+    BALLOT_CONFIG.set(env, &cfg);
 }
 
 pub fn get_config(env: &Env) -> Config {
-    let cfg = env
-        .storage()
-        .instance()
-        .get(&CONFIG)
-        .unwrap_or(Config::default()
-    );
-
-    cfg
-}
\ No newline at end of file
+    BALLOT_CONFIG.get_or_else(env, Config::default)
+}
@@ -1,27 +1,37 @@
 #![cfg(test)]
+extern crate std;
 
-use super::{Ballot, BallotClient};
-use soroban_sdk::{Env, symbol_short, testutils::Address as _, Address};
+// Build the contract first with `cargo build --target wasm32-unknown-unknown --release`
+#[cfg(feature = "upgrade-tests")]
+mod ballot_wasm {
+    soroban_sdk::contractimport!(
+        file = "target/wasm32-unknown-unknown/release/ballot.wasm"
+    );
+}
+
+use super::{Ballot, BallotClient, CONTRACT_TAG, TOPIC_DELEGATED, TOPIC_VOTED};
+use soroban_examples_events::EVENT_VERSION;
+use soroban_examples_testutils::EventSnapshot;
+use soroban_sdk::{Env, IntoVal, symbol_short, vec, testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Events, Ledger}, Address};
 
 #[test]
 fn vote_test() {
     let env = Env::default();
     let client = create_client(&env);
-    let addr_admin = Address::generate(&env);
 
-    assert_eq!(client.vote(&addr_admin, &symbol_short!("hyyt76"), &symbol_short!("Laborist")), true);
-    assert_eq!(client.vote(&addr_admin, &symbol_short!("ptft37"), &symbol_short!("Conserv")), true);
-    assert_eq!(client.vote(&addr_admin, &symbol_short!("oo9gt6"), &symbol_short!("Conserv")), true);
+    assert_eq!(client.vote(&symbol_short!("hyyt76"), &symbol_short!("Laborist")), true);
+    assert_eq!(client.vote(&symbol_short!("ptft37"), &symbol_short!("Conserv")), true);
+    assert_eq!(client.vote(&symbol_short!("oo9gt6"), &symbol_short!("Conserv")), true);
 
-    let count = client.count(&addr_admin);
+    let count = client.count();
 
     assert_eq!(count.get(symbol_short!("Laborist")).unwrap(), 1);
     assert_eq!(count.get(symbol_short!("Conserv")).unwrap(), 2);
 
-    client.delegate(&addr_admin, &symbol_short!("ippcxs"), &symbol_short!("oonvv5"));
-    assert_eq!(client.vote(&addr_admin, &symbol_short!("oonvv5"), &symbol_short!("Conserv")), true);
+    client.delegate(&symbol_short!("ippcxs"), &symbol_short!("oonvv5"));
+    assert_eq!(client.vote(&symbol_short!("oonvv5"), &symbol_short!("Conserv")), true);
 
-    let count = client.count(&addr_admin);
+    let count = client.count();
 
     assert_eq!(count.get(symbol_short!("Laborist")).unwrap(), 1);
     assert_eq!(count.get(symbol_short!("Conserv")).unwrap(),4);
@@ -29,89 +39,266 @@ fn vote_test() {
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #6)")]
+#[should_panic(expected = "HostError: Error(Contract, #106)")]
 fn vote_out_of_dates_test() {
     let env = Env::default();
     let client = create_client(&env);
-    let addr_admin = Address::generate(&env);
 
     let ts_start: u64 = 1689238800; // 2023-07-13 09:00:00
     let ts_end: u64 = 1689551999; // 2023-07-16 23:59:59
 
-    client.configure(&addr_admin, &ts_start, &ts_end);
-    client.vote(&addr_admin, &symbol_short!("hyyt76"), &symbol_short!("Laborist"));
+    client.configure(&ts_start, &ts_end);
+    client.vote(&symbol_short!("hyyt76"), &symbol_short!("Laborist"));
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #2)")]
+#[should_panic(expected = "HostError: Error(Contract, #102)")]
 fn vote_test_already_voted() {
     let env = Env::default();
     let client = create_client(&env);
-    let addr_admin = Address::generate(&env);
 
-    client.vote(&addr_admin, &symbol_short!("hyyt76"), &symbol_short!("Laborist"));
-    client.vote(&addr_admin, &symbol_short!("hyyt76"), &symbol_short!("Laborist"));
+    client.vote(&symbol_short!("hyyt76"), &symbol_short!("Laborist"));
+    client.vote(&symbol_short!("hyyt76"), &symbol_short!("Laborist"));
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #1)")]
+#[should_panic(expected = "HostError: Error(Contract, #101)")]
 fn vote_test_delegated_vote() {
     let env = Env::default();
     let client = create_client(&env);
-    let addr_admin = Address::generate(&env);
 
-    client.delegate(&addr_admin, &symbol_short!("ippcxs"), &symbol_short!("oonvv5"));
-    client.vote(&addr_admin, &symbol_short!("ippcxs"), &symbol_short!("Laborist"));
+    client.delegate(&symbol_short!("ippcxs"), &symbol_short!("oonvv5"));
+    client.vote(&symbol_short!("ippcxs"), &symbol_short!("Laborist"));
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #3)")]
+#[should_panic(expected = "HostError: Error(Contract, #103)")]
 fn delegate_test_has_delegated_votes() {
     let env = Env::default();
     let client = create_client(&env);
 
-    let addr_admin = Address::generate(&env);
-    client.delegate(&addr_admin, &symbol_short!("ippcxs"), &symbol_short!("oonvv5"));
-    client.delegate(&addr_admin, &symbol_short!("oonvv5"), &symbol_short!("ppky55"));
+    client.delegate(&symbol_short!("ippcxs"), &symbol_short!("oonvv5"));
+    client.delegate(&symbol_short!("oonvv5"), &symbol_short!("ppky55"));
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #1)")]
-fn delegate_test_has_delegated_his_vote() {
+#[should_panic(expected = "HostError: Error(Contract, #101)")]
+fn delegate_test_o_voter_has_delegated_his_vote() {
     let env = Env::default();
     let client = create_client(&env);
 
-    let addr_admin = Address::generate(&env);
-    client.delegate(&addr_admin, &symbol_short!("ippcxs"), &symbol_short!("oonvv5"));
-    client.delegate(&addr_admin, &symbol_short!("hhvftp"), &symbol_short!("ippcxs"));
+    client.delegate(&symbol_short!("ippcxs"), &symbol_short!("oonvv5"));
+    client.delegate(&symbol_short!("ippcxs"), &symbol_short!("hhvftp"));
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #4)")]
+#[should_panic(expected = "HostError: Error(Contract, #113)")]
+fn delegate_test_d_voter_has_delegated_his_vote() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    client.delegate(&symbol_short!("ippcxs"), &symbol_short!("oonvv5"));
+    client.delegate(&symbol_short!("hhvftp"), &symbol_short!("ippcxs"));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #104)")]
 fn delegate_test_o_voter_has_voted() {
     let env = Env::default();
     let client = create_client(&env);
 
-    let addr_admin = Address::generate(&env);
-    client.vote(&addr_admin, &symbol_short!("ippcxs"), &symbol_short!("Laborist"));
-    client.delegate(&addr_admin, &symbol_short!("ippcxs"), &symbol_short!("hhcfrp"));
+    client.vote(&symbol_short!("ippcxs"), &symbol_short!("Laborist"));
+    client.delegate(&symbol_short!("ippcxs"), &symbol_short!("hhcfrp"));
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #5)")]
+#[should_panic(expected = "HostError: Error(Contract, #105)")]
 fn delegate_test_d_voter_has_voted() {
     let env = Env::default();
     let client = create_client(&env);
 
-    let addr_admin = Address::generate(&env);
-    client.vote(&addr_admin, &symbol_short!("hhcfrp"), &symbol_short!("Laborist"));
-    client.delegate(&addr_admin, &symbol_short!("ippcxs"), &symbol_short!("hhcfrp"));
+    client.vote(&symbol_short!("hhcfrp"), &symbol_short!("Laborist"));
+    client.delegate(&symbol_short!("ippcxs"), &symbol_short!("hhcfrp"));
+}
+
+#[test]
+fn vote_requires_admin_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = BallotClient::new(&env, &env.register(Ballot, (admin.clone(),)));
+    client.vote(&symbol_short!("hyyt76"), &symbol_short!("Laborist"));
+
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            admin,
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    client.address.clone(),
+                    symbol_short!("vote"),
+                    (symbol_short!("hyyt76"), symbol_short!("Laborist")).into_val(&env)
+                )),
+                sub_invocations: std::vec![]
+            }
+        )]
+    );
+}
+
+#[test]
+fn events_test() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    client.delegate(&symbol_short!("ippcxs"), &symbol_short!("oonvv5"));
+    assert_eq!(
+        env.events().all().last().unwrap(),
+        (
+            client.address.clone(),
+            (CONTRACT_TAG, TOPIC_DELEGATED).into_val(&env),
+            (EVENT_VERSION, (symbol_short!("ippcxs"), symbol_short!("oonvv5"))).into_val(&env)
+        )
+    );
+
+    client.vote(&symbol_short!("oonvv5"), &symbol_short!("Conserv"));
+    assert_eq!(
+        env.events().all().last().unwrap(),
+        (
+            client.address.clone(),
+            (CONTRACT_TAG, TOPIC_VOTED).into_val(&env),
+            (EVENT_VERSION, (symbol_short!("oonvv5"), symbol_short!("Conserv"), 2_u32)).into_val(&env)
+        )
+    );
+}
+
+#[test]
+#[cfg(feature = "upgrade-tests")]
+fn upgrade_and_migrate_test() {
+    let env = Env::default();
+    let client = create_client(&env);
+    client.vote(&symbol_short!("hyyt76"), &symbol_short!("Laborist"));
+    assert_eq!(client.version(), 1);
+
+    // Simulate data written by a build that predates versioning
+    env.as_contract(&client.address, || soroban_examples_upgrade::set_version(&env, 0));
+
+    let new_wasm_hash = env.deployer().upload_contract_wasm(ballot_wasm::WASM);
+    assert_eq!(client.upgrade(&new_wasm_hash), true);
+
+    let upgraded = ballot_wasm::Client::new(&env, &client.address);
+    assert_eq!(upgraded.migrate(), 0);
+    assert_eq!(upgraded.version(), 1);
+    assert_eq!(upgraded.count().get(symbol_short!("Laborist")).unwrap(), 1);
+    assert!(upgraded.try_migrate().is_err());
+}
+
+#[test]
+fn admin_transfer_test() {
+    let env = Env::default();
+    let client = create_client(&env);
+    let new_admin = Address::generate(&env);
+
+    client.propose_admin(&new_admin);
+    client.accept_admin();
+    assert_eq!(client.admin(), new_admin);
+    assert!(client.pending_admin().is_none());
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #109)")]
+fn accept_admin_twice_test() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    client.propose_admin(&Address::generate(&env));
+    client.accept_admin();
+    client.accept_admin();
+}
+
+#[test]
+fn winner_test() {
+    let env = Env::default();
+    let client = create_client(&env);
+    client.configure(&1000, &2000);
+    env.ledger().set_timestamp(1500);
+
+    client.vote(&symbol_short!("hhcfrp"), &symbol_short!("Laborist"));
+    client.vote(&symbol_short!("ippcxs"), &symbol_short!("Conserv"));
+    client.vote(&symbol_short!("aaabbb"), &symbol_short!("Conserv"));
+
+    // No result until the ballot has closed
+    assert_eq!(client.winner(), None);
+    env.ledger().set_timestamp(2001);
+    assert_eq!(client.winner(), Some(symbol_short!("Conserv")));
+}
+
+#[test]
+fn winner_tie_test() {
+    let env = Env::default();
+    let client = create_client(&env);
+    client.configure(&1000, &2000);
+    env.ledger().set_timestamp(1500);
+
+    client.vote(&symbol_short!("hhcfrp"), &symbol_short!("Laborist"));
+    client.vote(&symbol_short!("ippcxs"), &symbol_short!("Conserv"));
+
+    env.ledger().set_timestamp(2001);
+    assert_eq!(client.winner(), None);
+}
+
+#[test]
+fn registered_candidates_test() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    client.add_candidates(&vec![&env, symbol_short!("Laborist"), symbol_short!("Conserv")]);
+    client.vote(&symbol_short!("hyyt76"), &symbol_short!("Conserv"));
+
+    // Registered candidates are counted even without votes
+    let count = client.count();
+    assert_eq!(count.get(symbol_short!("Laborist")).unwrap(), 0);
+    assert_eq!(count.get(symbol_short!("Conserv")).unwrap(), 1);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #112)")]
+fn vote_test_unknown_candidate() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    client.add_candidates(&vec![&env, symbol_short!("Laborist"), symbol_short!("Conserv")]);
+    client.vote(&symbol_short!("hyyt76"), &symbol_short!("Liberal"));
 }
 
 fn create_client(env: &Env) -> BallotClient<'_>{
     env.mock_all_auths();
 
-    let contract_id = env.register(Ballot, ());
+    let contract_id = env.register(Ballot, (Address::generate(env),));
     let client = BallotClient::new(&env, &contract_id);
     client
-}
\ No newline at end of file
+}
+
+#[test]
+fn events_match_snapshot() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let mut snapshot = EventSnapshot::new(&env, &client.address);
+    client.delegate(&symbol_short!("alice"), &symbol_short!("bob"));
+    snapshot.capture("delegate");
+    client.vote(&symbol_short!("bob"), &symbol_short!("yes"));
+    snapshot.capture("vote bob");
+    client.vote(&symbol_short!("carol"), &symbol_short!("no"));
+    snapshot.capture("vote carol");
+
+    snapshot.assert_matches("snapshots/events/delegate_and_vote.events");
+}
+
+#[test]
+fn error_codes_in_registry_range() {
+    use super::Error;
+
+    assert!(soroban_examples_errors::BALLOT.contains(Error::VoterHasHisVoteDelegated as u32));
+    assert!(soroban_examples_errors::BALLOT.contains(Error::StaleAdminProposal as u32));
+}
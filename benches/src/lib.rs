@@ -0,0 +1,67 @@
+//! Resource budgets of the hot paths of the example contracts. Each benchmark under `tests/` runs one
+//! call on natively registered contracts, reads what the host metered for it through the `Env` cost
+//! estimate, and checks it against the baseline stored next to it, so a change that makes the call
+//! noticeably more expensive fails the suite.
+//!
+//! Natively registered contracts are not metered as wasm, so the instruction counts cover the host
+//! work (storage, token calls, crypto, events) rather than the whole transaction cost; they are meant
+//! to catch regressions, not to size transactions. Every run prints the measured figures: when a change
+//! moves them on purpose, copy them into the baseline.
+
+use soroban_sdk::Env;
+
+/// Allowed growth of the CPU instructions and memory over their baseline, in percent.
+pub const TOLERANCE_PCT: i64 = 10;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Cost {
+    pub instructions: i64,
+    pub mem_bytes: i64,
+    pub read_entries: u32,
+    pub write_entries: u32,
+}
+
+/// Resources metered for the last contract invocation made in `e`.
+pub fn last_invocation_cost(e: &Env) -> Cost {
+    let resources = e.cost_estimate().resources();
+    Cost {
+        instructions: resources.instructions,
+        mem_bytes: resources.mem_bytes,
+        read_entries: resources.read_entries,
+        write_entries: resources.write_entries,
+    }
+}
+
+/// Fails if `measured` goes over `baseline` by more than `TOLERANCE_PCT` in instructions or memory,
+/// or touches more ledger entries than it (entry counts do not vary between runs, so they get no
+/// tolerance).
+///
+/// Prints the measured cost as the constant declaration of its baseline (`name` in upper case), ready
+/// to be pasted over the old one.
+pub fn assert_within_budget(name: &str, measured: Cost, baseline: Cost) {
+    println!("const {}: Cost = {measured:?};", name.to_uppercase());
+
+    assert!(
+        measured.instructions <= with_tolerance(baseline.instructions),
+        "{name}: {} instructions, baseline {}", measured.instructions, baseline.instructions
+    );
+    assert!(
+        measured.mem_bytes <= with_tolerance(baseline.mem_bytes),
+        "{name}: {} bytes of memory, baseline {}", measured.mem_bytes, baseline.mem_bytes
+    );
+    assert!(
+        measured.read_entries <= baseline.read_entries,
+        "{name}: {} entries read, baseline {}", measured.read_entries, baseline.read_entries
+    );
+    assert!(
+        measured.write_entries <= baseline.write_entries,
+        "{name}: {} entries written, baseline {}", measured.write_entries, baseline.write_entries
+    );
+}
+
+fn with_tolerance(baseline: i64) -> i64 {
+    baseline + baseline * TOLERANCE_PCT / 100
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,21 @@
+use crate::{assert_within_budget, with_tolerance, Cost};
+
+const BASELINE: Cost = Cost { instructions: 1_000_000, mem_bytes: 200_000, read_entries: 4, write_entries: 2 };
+
+#[test]
+fn tolerance_applies_to_instructions_and_memory() {
+    assert_eq!(with_tolerance(1_000_000), 1_100_000);
+    assert_within_budget("within", Cost { instructions: 1_100_000, mem_bytes: 220_000, ..BASELINE }, BASELINE);
+}
+
+#[test]
+#[should_panic(expected = "over: 1100001 instructions, baseline 1000000")]
+fn instructions_over_tolerance() {
+    assert_within_budget("over", Cost { instructions: 1_100_001, ..BASELINE }, BASELINE);
+}
+
+#[test]
+#[should_panic(expected = "extra: 3 entries written, baseline 2")]
+fn entry_counts_have_no_tolerance() {
+    assert_within_budget("extra", Cost { write_entries: 3, ..BASELINE }, BASELINE);
+}
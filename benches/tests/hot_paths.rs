@@ -0,0 +1,67 @@
+// One benchmark per hot path: the setup runs first, then the measured call is the last invocation
+// made in the environment, so `last_invocation_cost` reads exactly that call.
+//
+// Each baseline is meant to be the figure its call was measured at, not a budget: the tolerance in
+// `assert_within_budget` is the only headroom. Each run prints every measured cost as the constant
+// below it belongs to (`cargo test -- --nocapture`); when a change moves a path on purpose, paste the
+// printed line over the old one.
+
+use soroban_examples_benches::{assert_within_budget, last_invocation_cost, Cost};
+use soroban_examples_integration_tests::{deploy_ballot, deploy_crypto_deposit, deploy_investment};
+use soroban_examples_testutils::{advance_days, create_env, create_token_contract, mint_to};
+use soroban_sdk::{symbol_short, testutils::{Address as _, Ledger}, Address};
+
+const INVEST: Cost = Cost { instructions: 4_000_000, mem_bytes: 1_000_000, read_entries: 8, write_entries: 5 };
+const PROCESS_INVESTOR_PAYMENT: Cost = Cost { instructions: 4_000_000, mem_bytes: 1_000_000, read_entries: 8, write_entries: 5 };
+const VOTE: Cost = Cost { instructions: 1_500_000, mem_bytes: 400_000, read_entries: 4, write_entries: 3 };
+const DEPOSIT: Cost = Cost { instructions: 4_000_000, mem_bytes: 1_000_000, read_entries: 12, write_entries: 8 };
+
+#[test]
+fn invest() {
+    let e = create_env();
+    let (admin, project, investor) = (Address::generate(&e), Address::generate(&e), Address::generate(&e));
+    let (token, token_admin) = create_token_contract(&e, &Address::generate(&e));
+    let investment = deploy_investment(&e, &admin, &project, &token.address, 500, 4);
+    mint_to(&token_admin, &[&investor], 100_000);
+
+    investment.invest(&investor, &100_000);
+    assert_within_budget("invest", last_invocation_cost(&e), INVEST);
+}
+
+#[test]
+fn process_investor_payment() {
+    let e = create_env();
+    let (admin, project, investor) = (Address::generate(&e), Address::generate(&e), Address::generate(&e));
+    let (token, token_admin) = create_token_contract(&e, &Address::generate(&e));
+    let investment = deploy_investment(&e, &admin, &project, &token.address, 500, 4);
+    mint_to(&token_admin, &[&investor, &admin], 100_000);
+
+    let position = investment.invest(&investor, &100_000);
+    investment.add_company_transfer(&100_000);
+    advance_days(&e, 8);
+
+    investment.process_investor_payment(&investor, &position.claimable_ts);
+    assert_within_budget("process_investor_payment", last_invocation_cost(&e), PROCESS_INVESTOR_PAYMENT);
+}
+
+#[test]
+fn vote() {
+    let e = create_env();
+    let ballot = deploy_ballot(&e, &Address::generate(&e), 1000, 2000);
+    e.ledger().set_timestamp(1500);
+
+    ballot.vote(&symbol_short!("v1"), &symbol_short!("yes"));
+    assert_within_budget("vote", last_invocation_cost(&e), VOTE);
+}
+
+#[test]
+fn deposit() {
+    let e = create_env();
+    let (admin, depositor) = (Address::generate(&e), Address::generate(&e));
+    let (token, token_admin) = create_token_contract(&e, &Address::generate(&e));
+    let vault = deploy_crypto_deposit(&e, &admin, &token.address);
+    mint_to(&token_admin, &[&depositor], 100_000);
+
+    vault.deposit(&depositor, &100_000, &None, &None);
+    assert_within_budget("deposit", last_invocation_cost(&e), DEPOSIT);
+}
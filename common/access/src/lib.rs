@@ -0,0 +1,70 @@
+#![no_std]
+
+//! Role-based access control shared by the example contracts. Every contract has a single admin, who
+//! implicitly holds every role, and can grant roles (identified by a symbol, e.g. `treasurer`) to other
+//! addresses so they can run that role's operations without the admin key.
+
+use soroban_examples_storage::bump_instance;
+use soroban_sdk::{contracttype, Address, Env, Symbol};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AccessError {
+    AdminNotSet,
+    MissingRole,
+}
+
+#[contracttype]
+enum AccessKey {
+    Admin,
+    Role(Symbol, Address),
+}
+
+pub fn get_admin(e: &Env) -> Option<Address> {
+    e.storage().instance().get(&AccessKey::Admin)
+}
+
+/// Sets (or replaces) the admin. Callers are responsible for authorizing the change.
+pub fn set_admin(e: &Env, admin: &Address) {
+    e.storage().instance().set(&AccessKey::Admin, admin);
+    bump_instance(e);
+}
+
+/// Requires the admin signature, returning the admin address.
+pub fn require_admin(e: &Env) -> Result<Address, AccessError> {
+    let admin = get_admin(e).ok_or(AccessError::AdminNotSet)?;
+    admin.require_auth();
+    Ok(admin)
+}
+
+pub fn has_role(e: &Env, role: &Symbol, addr: &Address) -> bool {
+    e.storage().instance().get(&AccessKey::Role(role.clone(), addr.clone())).unwrap_or(false)
+}
+
+/// Grants `role` to `addr`. Requires the admin signature.
+pub fn grant_role(e: &Env, role: &Symbol, addr: &Address) -> Result<(), AccessError> {
+    require_admin(e)?;
+    e.storage().instance().set(&AccessKey::Role(role.clone(), addr.clone()), &true);
+    bump_instance(e);
+    Ok(())
+}
+
+/// Revokes `role` from `addr`. Requires the admin signature.
+pub fn revoke_role(e: &Env, role: &Symbol, addr: &Address) -> Result<(), AccessError> {
+    require_admin(e)?;
+    e.storage().instance().remove(&AccessKey::Role(role.clone(), addr.clone()));
+    Ok(())
+}
+
+/// Requires the `operator` signature and checks that it is the admin or holds `role`.
+pub fn require_role(e: &Env, role: &Symbol, operator: &Address) -> Result<(), AccessError> {
+    operator.require_auth();
+    let admin = get_admin(e).ok_or(AccessError::AdminNotSet)?;
+    if *operator != admin && !has_role(e, role, operator) {
+        return Err(AccessError::MissingRole);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test;
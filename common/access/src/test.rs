@@ -0,0 +1,71 @@
+#![cfg(test)]
+extern crate std;
+
+use soroban_sdk::{contract, symbol_short, testutils::Address as _, Address, Env, Symbol};
+
+use crate::{get_admin, grant_role, has_role, require_admin, require_role, revoke_role, set_admin, AccessError};
+
+const TREASURER: Symbol = symbol_short!("treasurer");
+
+#[contract]
+struct Dummy;
+
+fn setup() -> (Env, Address, Address) {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let contract_id = e.register(Dummy, ());
+    let admin = Address::generate(&e);
+    e.as_contract(&contract_id, || set_admin(&e, &admin));
+
+    (e, contract_id, admin)
+}
+
+#[test]
+fn admin_not_set() {
+    let e = Env::default();
+    let contract_id = e.register(Dummy, ());
+    e.as_contract(&contract_id, || {
+        assert_eq!(get_admin(&e), None);
+        assert_eq!(require_admin(&e), Err(AccessError::AdminNotSet));
+    });
+}
+
+#[test]
+fn admin_holds_every_role() {
+    let (e, contract_id, admin) = setup();
+    e.as_contract(&contract_id, || {
+        assert_eq!(require_admin(&e), Ok(admin.clone()));
+        assert_eq!(require_role(&e, &TREASURER, &admin), Ok(()));
+        assert!(!has_role(&e, &TREASURER, &admin));
+    });
+}
+
+#[test]
+fn grant_and_revoke_role() {
+    let (e, contract_id, _admin) = setup();
+    let operator = Address::generate(&e);
+    e.as_contract(&contract_id, || {
+        assert_eq!(require_role(&e, &TREASURER, &operator), Err(AccessError::MissingRole));
+
+        grant_role(&e, &TREASURER, &operator).unwrap();
+        assert!(has_role(&e, &TREASURER, &operator));
+        assert_eq!(require_role(&e, &TREASURER, &operator), Ok(()));
+        assert_eq!(require_role(&e, &symbol_short!("other"), &operator), Err(AccessError::MissingRole));
+
+        revoke_role(&e, &TREASURER, &operator).unwrap();
+        assert_eq!(require_role(&e, &TREASURER, &operator), Err(AccessError::MissingRole));
+    });
+}
+
+#[test]
+#[should_panic]
+fn grant_role_requires_admin() {
+    let e = Env::default();
+    let contract_id = e.register(Dummy, ());
+    let (admin, operator) = (Address::generate(&e), Address::generate(&e));
+    e.as_contract(&contract_id, || {
+        set_admin(&e, &admin);
+        grant_role(&e, &TREASURER, &operator).unwrap();
+    });
+}
@@ -0,0 +1,84 @@
+#![no_std]
+
+//! Error-code registry of the example contracts. Each contract numbers its `#[contracterror]` enum
+//! inside its own range of `RANGE_SIZE` codes, so `Error(Contract, #402)` can only be Investment's
+//! variant 2, whichever contract in a cross-contract call raised it.
+//!
+//! `#[contracterror]` only accepts literal discriminants, so the enums spell the codes out and each
+//! contract checks in its tests that they stay within its range.
+//!
+//! The shared crates under `common` return plain error enums instead, which each contract maps into
+//! its own `#[contracterror]` enum, so a contract only ever raises codes from its own range.
+
+pub const RANGE_SIZE: u32 = 100;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ErrorRange {
+    pub contract: &'static str,
+    pub start: u32,
+}
+
+impl ErrorRange {
+    const fn new(contract: &'static str, start: u32) -> Self {
+        ErrorRange { contract, start }
+    }
+
+    pub const fn end(&self) -> u32 {
+        self.start + RANGE_SIZE
+    }
+
+    pub const fn contains(&self, code: u32) -> bool {
+        code > self.start && code < self.end()
+    }
+}
+
+pub const BALLOT: ErrorRange = ErrorRange::new("Ballot", 100);
+pub const CRYPTO_DEPOSIT: ErrorRange = ErrorRange::new("CryptoDeposit", 200);
+pub const RECEIPT_TOKEN: ErrorRange = ErrorRange::new("ReceiptToken", 300);
+pub const INVESTMENT: ErrorRange = ErrorRange::new("Investment", 400);
+pub const HOUSE_ASSET: ErrorRange = ErrorRange::new("HouseAsset", 500);
+pub const HOUSE_PURCHASE: ErrorRange = ErrorRange::new("HousePurchase", 600);
+pub const ENGLISH_AUCTION: ErrorRange = ErrorRange::new("EnglishAuction", 700);
+pub const DUTCH_AUCTION: ErrorRange = ErrorRange::new("DutchAuction", 800);
+pub const VESTING: ErrorRange = ErrorRange::new("Vesting", 900);
+pub const ESCROW: ErrorRange = ErrorRange::new("Escrow", 1000);
+pub const CROWDFUNDING: ErrorRange = ErrorRange::new("Crowdfunding", 1100);
+pub const SUBSCRIPTION: ErrorRange = ErrorRange::new("Subscription", 1200);
+pub const STREAMING: ErrorRange = ErrorRange::new("Streaming", 1300);
+pub const MULTISIG_WALLET: ErrorRange = ErrorRange::new("MultisigWallet", 1400);
+pub const TIMELOCK_CONTROLLER: ErrorRange = ErrorRange::new("TimelockController", 1500);
+pub const GOVERNOR: ErrorRange = ErrorRange::new("Governor", 1600);
+pub const ATOMIC_SWAP: ErrorRange = ErrorRange::new("AtomicSwap", 1700);
+pub const RAFFLE: ErrorRange = ErrorRange::new("Raffle", 1800);
+pub const AIRDROP: ErrorRange = ErrorRange::new("Airdrop", 1900);
+
+// New contracts take the next free range
+pub const REGISTRY: &[ErrorRange] = &[
+    BALLOT,
+    CRYPTO_DEPOSIT,
+    RECEIPT_TOKEN,
+    INVESTMENT,
+    HOUSE_ASSET,
+    HOUSE_PURCHASE,
+    ENGLISH_AUCTION,
+    DUTCH_AUCTION,
+    VESTING,
+    ESCROW,
+    CROWDFUNDING,
+    SUBSCRIPTION,
+    STREAMING,
+    MULTISIG_WALLET,
+    TIMELOCK_CONTROLLER,
+    GOVERNOR,
+    ATOMIC_SWAP,
+    RAFFLE,
+    AIRDROP,
+];
+
+/// Range owning `code`, if any. Codes below the first range come from other contracts, e.g. the token (SAC) ones.
+pub fn lookup(code: u32) -> Option<&'static ErrorRange> {
+    REGISTRY.iter().find(|range| range.contains(code))
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,22 @@
+use crate::{lookup, BALLOT, HOUSE_ASSET, INVESTMENT, RANGE_SIZE, REGISTRY};
+
+#[test]
+fn ranges_do_not_overlap() {
+    for (i, range) in REGISTRY.iter().enumerate() {
+        assert_eq!(range.start % RANGE_SIZE, 0);
+        for other in &REGISTRY[i + 1..] {
+            assert!(range.end() <= other.start || other.end() <= range.start);
+        }
+    }
+}
+
+#[test]
+fn lookup_code() {
+    assert_eq!(lookup(101), Some(&BALLOT));
+    assert_eq!(lookup(402), Some(&INVESTMENT));
+    assert_eq!(lookup(506).map(|range| range.contract), Some(HOUSE_ASSET.contract));
+
+    // The base code of a range is never used, and codes below 100 belong to other contracts
+    assert_eq!(lookup(400), None);
+    assert_eq!(lookup(9), None);
+}
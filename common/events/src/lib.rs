@@ -0,0 +1,19 @@
+#![no_std]
+
+//! Event convention shared by the example contracts, so indexers can decode all of them the same way:
+//!
+//! * topics: `(CONTRACT_TAG, ACTION)`, e.g. `("INVEST", "CBUPDATED")`
+//! * data: `(EVENT_VERSION, payload)`, the payload being a `#[contracttype]` value whose layout only
+//!   changes along with `EVENT_VERSION`
+
+use soroban_sdk::{Env, IntoVal, Symbol, Val};
+
+// Bumped whenever the payload layout of any event changes
+pub const EVENT_VERSION: u32 = 1;
+
+pub fn publish<D: IntoVal<Env, Val>>(e: &Env, contract_tag: Symbol, action: Symbol, payload: D) {
+    e.events().publish((contract_tag, action), (EVENT_VERSION, payload));
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,28 @@
+#![cfg(test)]
+extern crate std;
+
+use soroban_sdk::{contract, symbol_short, testutils::Events, vec, Env, IntoVal};
+
+use crate::{publish, EVENT_VERSION};
+
+#[contract]
+struct Dummy;
+
+#[test]
+fn publish_follows_schema() {
+    let e = Env::default();
+    let contract_id = e.register(Dummy, ());
+
+    e.as_contract(&contract_id, || publish(&e, symbol_short!("TEST"), symbol_short!("DONE"), 42_i128));
+    assert_eq!(
+        e.events().all(),
+        vec![
+            &e,
+            (
+                contract_id.clone(),
+                (symbol_short!("TEST"), symbol_short!("DONE")).into_val(&e),
+                (EVENT_VERSION, 42_i128).into_val(&e)
+            )
+        ]
+    );
+}
@@ -0,0 +1,78 @@
+#![no_std]
+
+//! Fixed-point helpers for the token amounts handled by the example contracts. Every operation is
+//! checked, and every division states how it rounds instead of relying on the implicit truncation of
+//! integer division.
+
+pub const BPS_DENOMINATOR: i128 = 10_000;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MathError {
+    Overflow,
+    DivisionByZero,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Rounding {
+    /// Towards negative infinity (floor). Same as integer division for non-negative operands.
+    Down,
+    /// Towards positive infinity (ceil).
+    Up,
+    /// To the nearest integer, halves away from zero.
+    Nearest,
+}
+
+pub fn add(a: i128, b: i128) -> Result<i128, MathError> {
+    a.checked_add(b).ok_or(MathError::Overflow)
+}
+
+pub fn sub(a: i128, b: i128) -> Result<i128, MathError> {
+    a.checked_sub(b).ok_or(MathError::Overflow)
+}
+
+pub fn mul(a: i128, b: i128) -> Result<i128, MathError> {
+    a.checked_mul(b).ok_or(MathError::Overflow)
+}
+
+/// `numerator / denominator`, rounded as requested.
+pub fn div(numerator: i128, denominator: i128, rounding: Rounding) -> Result<i128, MathError> {
+    if denominator == 0 {
+        return Err(MathError::DivisionByZero);
+    }
+
+    let quotient = numerator.checked_div(denominator).ok_or(MathError::Overflow)?;
+    let remainder = numerator % denominator;
+    if remainder == 0 {
+        return Ok(quotient);
+    }
+
+    // Integer division truncates towards zero, so the exact quotient lies between `quotient` and the
+    // next integer away from zero
+    let negative = (remainder < 0) != (denominator < 0);
+    let away_from_zero = match rounding {
+        Rounding::Down => negative,
+        Rounding::Up => !negative,
+        Rounding::Nearest => remainder.unsigned_abs() * 2 >= denominator.unsigned_abs(),
+    };
+
+    if !away_from_zero {
+        Ok(quotient)
+    } else if negative {
+        Ok(quotient - 1)
+    } else {
+        Ok(quotient + 1)
+    }
+}
+
+/// `a * b / denominator`, rounded as requested. Fails if `a * b` overflows.
+pub fn mul_div(a: i128, b: i128, denominator: i128, rounding: Rounding) -> Result<i128, MathError> {
+    div(mul(a, b)?, denominator, rounding)
+}
+
+/// `bps` basis points of `amount`, rounded as requested.
+pub fn bps_of(amount: i128, bps: u32, rounding: Rounding) -> Result<i128, MathError> {
+    mul_div(amount, bps as i128, BPS_DENOMINATOR, rounding)
+}
+
+#[cfg(test)]
+mod test;
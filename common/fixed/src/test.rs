@@ -0,0 +1,76 @@
+extern crate std;
+
+use crate::{add, bps_of, div, mul, mul_div, sub, MathError, Rounding};
+
+// Exact rounding of n / d computed on f64, fine for the small operands below
+fn reference(n: i128, d: i128, rounding: Rounding) -> i128 {
+    let exact = n as f64 / d as f64;
+    match rounding {
+        Rounding::Down => exact.floor() as i128,
+        Rounding::Up => exact.ceil() as i128,
+        Rounding::Nearest => exact.round() as i128,
+    }
+}
+
+#[test]
+fn div_matches_exact_rounding() {
+    for rounding in [Rounding::Down, Rounding::Up, Rounding::Nearest] {
+        for n in -200_i128..=200 {
+            for d in (-13_i128..=13).filter(|d| *d != 0) {
+                assert_eq!(div(n, d, rounding), Ok(reference(n, d, rounding)), "{n} / {d} {rounding:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn div_halves_round_away_from_zero() {
+    assert_eq!(div(5, 2, Rounding::Nearest), Ok(3));
+    assert_eq!(div(-5, 2, Rounding::Nearest), Ok(-3));
+    assert_eq!(div(5, -2, Rounding::Nearest), Ok(-3));
+    assert_eq!(div(7, 4, Rounding::Nearest), Ok(2));
+    assert_eq!(div(5, 4, Rounding::Nearest), Ok(1));
+}
+
+#[test]
+fn div_by_zero() {
+    assert_eq!(div(1, 0, Rounding::Down), Err(MathError::DivisionByZero));
+    assert_eq!(mul_div(1, 1, 0, Rounding::Up), Err(MathError::DivisionByZero));
+}
+
+#[test]
+fn div_overflow() {
+    assert_eq!(div(i128::MIN, -1, Rounding::Down), Err(MathError::Overflow));
+    assert_eq!(div(i128::MAX, 1, Rounding::Up), Ok(i128::MAX));
+    assert_eq!(div(i128::MIN, 2, Rounding::Down), Ok(i128::MIN / 2));
+}
+
+#[test]
+fn mul_div_keeps_the_precision_of_the_product() {
+    // Dividing before multiplying would lose everything here
+    assert_eq!(mul_div(3, 1_000, 7, Rounding::Down), Ok(428));
+    assert_eq!(mul_div(3, 1_000, 7, Rounding::Up), Ok(429));
+    assert_eq!(mul_div(3, 1_000, 7, Rounding::Nearest), Ok(429));
+    assert_eq!(mul_div(i128::MAX, 2, 2, Rounding::Down), Err(MathError::Overflow));
+}
+
+#[test]
+fn bps() {
+    assert_eq!(bps_of(10_000, 1, Rounding::Down), Ok(1));
+    assert_eq!(bps_of(9_999, 1, Rounding::Down), Ok(0));
+    assert_eq!(bps_of(9_999, 1, Rounding::Up), Ok(1));
+    assert_eq!(bps_of(5_000, 1, Rounding::Nearest), Ok(1));
+    assert_eq!(bps_of(4_999, 1, Rounding::Nearest), Ok(0));
+    assert_eq!(bps_of(123_456, 10_000, Rounding::Down), Ok(123_456));
+    assert_eq!(bps_of(123_456, 0, Rounding::Up), Ok(0));
+}
+
+#[test]
+fn checked_ops() {
+    assert_eq!(add(i128::MAX, 1), Err(MathError::Overflow));
+    assert_eq!(sub(i128::MIN, 1), Err(MathError::Overflow));
+    assert_eq!(mul(i128::MAX, 2), Err(MathError::Overflow));
+    assert_eq!(add(2, 3), Ok(5));
+    assert_eq!(sub(2, 3), Ok(-1));
+    assert_eq!(mul(-2, 3), Ok(-6));
+}
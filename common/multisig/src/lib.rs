@@ -0,0 +1,204 @@
+#![no_std]
+
+//! Multisig approvals shared by the example contracts. A contract configures a set of signers and a
+//! threshold, and every sensitive operation (identified by a symbol, e.g. `withdraw`) goes through its own
+//! request: a signer proposes it with the operation payload and an expiry, the other signers approve it,
+//! and the contract executes it once the threshold is reached. Each operation keeps its own request, so
+//! several of them can be in flight at the same time.
+
+use soroban_examples_storage::{bump_instance, get_persistent, set_persistent};
+use soroban_sdk::{contracttype, Address, Env, IntoVal, Symbol, TryFromVal, Val, Vec};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MultisigConfig {
+    pub signers: Vec<Address>,
+    pub threshold: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MultisigRequest {
+    pub proposer: Address,
+    // The proposer counts as the first approval
+    pub approvals: Vec<Address>,
+    pub expires_at: u64,
+}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum MultisigStatus {
+    None = 0,
+    Pending = 1,
+    Approved = 2,
+    Expired = 3,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MultisigError {
+    InvalidSignerSet,
+    NotConfigured,
+    NotASigner,
+    RequestPending,
+    NoRequest,
+    RequestExpired,
+    AlreadyApproved,
+    NotEnoughApprovals,
+}
+
+#[contracttype]
+enum MultisigKey {
+    Config,
+    Request(Symbol),
+    Payload(Symbol),
+}
+
+/// Sets (or replaces) the signer set. The threshold must be between 1 and the number of signers, which
+/// must not repeat. Callers are responsible for authorizing the change.
+pub fn set_signers(e: &Env, signers: &Vec<Address>, threshold: u32) -> Result<(), MultisigError> {
+    if threshold == 0 || threshold > signers.len() {
+        return Err(MultisigError::InvalidSignerSet);
+    }
+
+    for (i, signer) in signers.iter().enumerate() {
+        if signers.first_index_of(&signer) != Some(i as u32) {
+            return Err(MultisigError::InvalidSignerSet);
+        }
+    }
+
+    e.storage().instance().set(&MultisigKey::Config, &MultisigConfig { signers: signers.clone(), threshold });
+    bump_instance(e);
+    Ok(())
+}
+
+pub fn get_config(e: &Env) -> Option<MultisigConfig> {
+    e.storage().instance().get(&MultisigKey::Config)
+}
+
+pub fn is_configured(e: &Env) -> bool {
+    e.storage().instance().has(&MultisigKey::Config)
+}
+
+pub fn get_request(e: &Env, op: &Symbol) -> Option<MultisigRequest> {
+    get_persistent(e, &MultisigKey::Request(op.clone()))
+}
+
+/// Payload the pending request for `op` was proposed with.
+pub fn get_payload<P: TryFromVal<Env, Val>>(e: &Env, op: &Symbol) -> Option<P> {
+    get_persistent(e, &MultisigKey::Payload(op.clone()))
+}
+
+pub fn status(e: &Env, op: &Symbol) -> MultisigStatus {
+    match (get_request(e, op), get_config(e)) {
+        (Some(request), Some(config)) => request_status(e, &request, &config),
+        _ => MultisigStatus::None,
+    }
+}
+
+/// Opens a request for `op` carrying the payload the operation will be executed with. A pending request
+/// for the same operation must be executed, cancelled or left to expire first.
+pub fn propose<P: IntoVal<Env, Val>>(e: &Env, op: &Symbol, proposer: &Address, payload: &P, expires_at: u64) -> Result<MultisigRequest, MultisigError> {
+    proposer.require_auth();
+    let config = require_signer(e, proposer)?;
+
+    if expires_at <= e.ledger().timestamp() {
+        return Err(MultisigError::RequestExpired);
+    }
+
+    if let Some(request) = get_request(e, op) {
+        if request_status(e, &request, &config) != MultisigStatus::Expired {
+            return Err(MultisigError::RequestPending);
+        }
+    }
+
+    let request = MultisigRequest {
+        proposer: proposer.clone(),
+        approvals: Vec::from_array(e, [proposer.clone()]),
+        expires_at,
+    };
+
+    set_persistent(e, &MultisigKey::Request(op.clone()), &request);
+    set_persistent(e, &MultisigKey::Payload(op.clone()), payload);
+    Ok(request)
+}
+
+/// Adds the approval of `signer` to the request for `op`, returning its new status.
+pub fn approve(e: &Env, op: &Symbol, signer: &Address) -> Result<MultisigStatus, MultisigError> {
+    signer.require_auth();
+    let config = require_signer(e, signer)?;
+    let mut request = get_request(e, op).ok_or(MultisigError::NoRequest)?;
+
+    if request_status(e, &request, &config) == MultisigStatus::Expired {
+        return Err(MultisigError::RequestExpired);
+    }
+
+    if request.approvals.contains(signer) {
+        return Err(MultisigError::AlreadyApproved);
+    }
+
+    request.approvals.push_back(signer.clone());
+    set_persistent(e, &MultisigKey::Request(op.clone()), &request);
+    Ok(request_status(e, &request, &config))
+}
+
+/// Consumes an approved request for `op`, returning the payload it was proposed with. The caller
+/// performs the operation right after.
+pub fn execute<P: TryFromVal<Env, Val>>(e: &Env, op: &Symbol) -> Result<P, MultisigError> {
+    let config = get_config(e).ok_or(MultisigError::NotConfigured)?;
+    let request = get_request(e, op).ok_or(MultisigError::NoRequest)?;
+
+    match request_status(e, &request, &config) {
+        MultisigStatus::Approved => (),
+        MultisigStatus::Expired => return Err(MultisigError::RequestExpired),
+        _ => return Err(MultisigError::NotEnoughApprovals),
+    }
+
+    let payload: P = get_persistent(e, &MultisigKey::Payload(op.clone())).ok_or(MultisigError::NoRequest)?;
+    remove_request(e, op);
+    Ok(payload)
+}
+
+/// Drops the request for `op`. Any signer can cancel it.
+pub fn cancel(e: &Env, op: &Symbol, signer: &Address) -> Result<(), MultisigError> {
+    signer.require_auth();
+    require_signer(e, signer)?;
+
+    if get_request(e, op).is_none() {
+        return Err(MultisigError::NoRequest);
+    }
+
+    remove_request(e, op);
+    Ok(())
+}
+
+fn require_signer(e: &Env, addr: &Address) -> Result<MultisigConfig, MultisigError> {
+    let config = get_config(e).ok_or(MultisigError::NotConfigured)?;
+    if !config.signers.contains(addr) {
+        return Err(MultisigError::NotASigner);
+    }
+
+    Ok(config)
+}
+
+// Approvals of addresses removed from the signer set after they approved do not count
+fn request_status(e: &Env, request: &MultisigRequest, config: &MultisigConfig) -> MultisigStatus {
+    if e.ledger().timestamp() >= request.expires_at {
+        return MultisigStatus::Expired;
+    }
+
+    let approvals = request.approvals.iter().filter(|a| config.signers.contains(a)).count() as u32;
+    if approvals >= config.threshold {
+        MultisigStatus::Approved
+    } else {
+        MultisigStatus::Pending
+    }
+}
+
+fn remove_request(e: &Env, op: &Symbol) {
+    e.storage().persistent().remove(&MultisigKey::Request(op.clone()));
+    e.storage().persistent().remove(&MultisigKey::Payload(op.clone()));
+}
+
+#[cfg(test)]
+mod test;
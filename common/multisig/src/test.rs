@@ -0,0 +1,133 @@
+#![cfg(test)]
+extern crate std;
+
+use soroban_sdk::{contract, symbol_short, testutils::{Address as _, Ledger}, vec, Address, Env, Symbol};
+
+use crate::{approve, cancel, execute, get_payload, get_request, propose, set_signers, status, MultisigError, MultisigStatus};
+
+const OP: Symbol = symbol_short!("withdraw");
+
+#[contract]
+struct Dummy;
+
+struct TestData {
+    e: Env,
+    contract_id: Address,
+    signers: [Address; 3],
+}
+
+fn setup() -> TestData {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let contract_id = e.register(Dummy, ());
+    let signers = [Address::generate(&e), Address::generate(&e), Address::generate(&e)];
+    e.as_contract(&contract_id, || {
+        set_signers(&e, &vec![&e, signers[0].clone(), signers[1].clone(), signers[2].clone()], 2).unwrap();
+    });
+
+    TestData { e, contract_id, signers }
+}
+
+#[test]
+fn propose_approve_execute() {
+    let t = setup();
+    t.e.as_contract(&t.contract_id, || {
+        propose(&t.e, &OP, &t.signers[0], &500_i128, 1000).unwrap();
+        assert_eq!(status(&t.e, &OP), MultisigStatus::Pending);
+        assert_eq!(get_payload::<i128>(&t.e, &OP), Some(500));
+        assert_eq!(execute::<i128>(&t.e, &OP), Err(MultisigError::NotEnoughApprovals));
+
+        assert_eq!(approve(&t.e, &OP, &t.signers[1]), Ok(MultisigStatus::Approved));
+        assert_eq!(execute::<i128>(&t.e, &OP), Ok(500));
+
+        // The request is consumed
+        assert_eq!(status(&t.e, &OP), MultisigStatus::None);
+        assert_eq!(get_payload::<i128>(&t.e, &OP), None);
+        assert_eq!(execute::<i128>(&t.e, &OP), Err(MultisigError::NoRequest));
+    });
+}
+
+#[test]
+fn operations_are_independent() {
+    let t = setup();
+    let other = symbol_short!("sweep");
+    t.e.as_contract(&t.contract_id, || {
+        propose(&t.e, &OP, &t.signers[0], &500_i128, 1000).unwrap();
+        propose(&t.e, &other, &t.signers[1], &t.signers[2], 1000).unwrap();
+        approve(&t.e, &other, &t.signers[2]).unwrap();
+
+        assert_eq!(status(&t.e, &OP), MultisigStatus::Pending);
+        assert_eq!(execute::<Address>(&t.e, &other), Ok(t.signers[2].clone()));
+        assert!(get_request(&t.e, &OP).is_some());
+    });
+}
+
+#[test]
+fn invalid_signer_sets() {
+    let t = setup();
+    let s = &t.signers;
+    t.e.as_contract(&t.contract_id, || {
+        assert_eq!(set_signers(&t.e, &vec![&t.e, s[0].clone()], 0), Err(MultisigError::InvalidSignerSet));
+        assert_eq!(set_signers(&t.e, &vec![&t.e, s[0].clone()], 2), Err(MultisigError::InvalidSignerSet));
+        assert_eq!(set_signers(&t.e, &vec![&t.e, s[0].clone(), s[0].clone()], 2), Err(MultisigError::InvalidSignerSet));
+    });
+}
+
+#[test]
+fn only_signers_take_part() {
+    let t = setup();
+    let outsider = Address::generate(&t.e);
+    t.e.as_contract(&t.contract_id, || {
+        assert_eq!(propose(&t.e, &OP, &outsider, &500_i128, 1000).err(), Some(MultisigError::NotASigner));
+
+        propose(&t.e, &OP, &t.signers[0], &500_i128, 1000).unwrap();
+        assert_eq!(approve(&t.e, &OP, &outsider), Err(MultisigError::NotASigner));
+        assert_eq!(approve(&t.e, &OP, &t.signers[0]), Err(MultisigError::AlreadyApproved));
+        assert_eq!(propose(&t.e, &OP, &t.signers[1], &100_i128, 1000).err(), Some(MultisigError::RequestPending));
+    });
+}
+
+#[test]
+fn expired_request() {
+    let t = setup();
+    t.e.as_contract(&t.contract_id, || {
+        propose(&t.e, &OP, &t.signers[0], &500_i128, 1000).unwrap();
+    });
+
+    t.e.ledger().set_timestamp(1000);
+    t.e.as_contract(&t.contract_id, || {
+        assert_eq!(status(&t.e, &OP), MultisigStatus::Expired);
+        assert_eq!(approve(&t.e, &OP, &t.signers[1]), Err(MultisigError::RequestExpired));
+        assert_eq!(execute::<i128>(&t.e, &OP), Err(MultisigError::RequestExpired));
+
+        // An expired request can be replaced
+        propose(&t.e, &OP, &t.signers[1], &100_i128, 2000).unwrap();
+        approve(&t.e, &OP, &t.signers[2]).unwrap();
+        assert_eq!(execute::<i128>(&t.e, &OP), Ok(100));
+    });
+}
+
+#[test]
+fn removed_signer_approvals_do_not_count() {
+    let t = setup();
+    let s = &t.signers;
+    t.e.as_contract(&t.contract_id, || {
+        propose(&t.e, &OP, &s[0], &500_i128, 1000).unwrap();
+        approve(&t.e, &OP, &s[1]).unwrap();
+
+        set_signers(&t.e, &vec![&t.e, s[1].clone(), s[2].clone()], 2).unwrap();
+        assert_eq!(status(&t.e, &OP), MultisigStatus::Pending);
+    });
+}
+
+#[test]
+fn cancel_request() {
+    let t = setup();
+    t.e.as_contract(&t.contract_id, || {
+        propose(&t.e, &OP, &t.signers[0], &500_i128, 1000).unwrap();
+        cancel(&t.e, &OP, &t.signers[2]).unwrap();
+        assert_eq!(status(&t.e, &OP), MultisigStatus::None);
+        assert_eq!(cancel(&t.e, &OP, &t.signers[2]), Err(MultisigError::NoRequest));
+    });
+}
@@ -0,0 +1,97 @@
+#![no_std]
+
+//! Two-step admin transfer shared by the example contracts. The admin proposes its successor, and the
+//! change only takes effect once the proposed address accepts it, so a typo in the new address can never
+//! lock a contract. The admin itself lives in `soroban-examples-access`.
+//!
+//! A proposal is only valid for `PROPOSAL_WINDOW` seconds and only while the admin who made it is still
+//! in charge, so a forgotten proposal cannot be used to take the contract over later.
+
+use soroban_examples_access as access;
+use soroban_examples_storage::bump_instance;
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol};
+
+pub const TOPIC_ADMIN_PROPOSED: Symbol = symbol_short!("ADMPROP");
+pub const TOPIC_ADMIN_CHANGED: Symbol = symbol_short!("ADMCHANGE");
+pub const TOPIC_ADMIN_CANCELLED: Symbol = symbol_short!("ADMCANCEL");
+
+pub const PROPOSAL_WINDOW: u64 = 7 * 86400;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum OwnershipError {
+    AdminNotSet,
+    NoPendingAdmin,
+    ProposalExpired,
+    StaleProposal,
+}
+
+impl From<access::AccessError> for OwnershipError {
+    fn from(_: access::AccessError) -> Self {
+        OwnershipError::AdminNotSet
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingAdmin {
+    pub new_admin: Address,
+    pub proposed_by: Address,
+    pub expires_at: u64,
+}
+
+#[contracttype]
+enum OwnershipKey {
+    PendingAdmin,
+}
+
+pub fn pending_admin(e: &Env) -> Option<PendingAdmin> {
+    e.storage().instance().get(&OwnershipKey::PendingAdmin)
+}
+
+/// First step: the admin proposes `new_admin`, replacing any previous proposal. Requires the admin signature.
+pub fn propose_admin(e: &Env, new_admin: &Address) -> Result<PendingAdmin, OwnershipError> {
+    let admin = access::require_admin(e)?;
+    let pending = PendingAdmin {
+        new_admin: new_admin.clone(),
+        proposed_by: admin.clone(),
+        expires_at: e.ledger().timestamp() + PROPOSAL_WINDOW,
+    };
+
+    e.storage().instance().set(&OwnershipKey::PendingAdmin, &pending);
+    bump_instance(e);
+    e.events().publish((TOPIC_ADMIN_PROPOSED,), (admin, new_admin.clone()));
+    Ok(pending)
+}
+
+/// Second step: the proposed address takes over. Requires its signature and returns the former admin.
+///
+/// Expired proposals, and proposals made by an admin who is no longer in charge, are rejected.
+pub fn accept_admin(e: &Env) -> Result<Address, OwnershipError> {
+    let pending = pending_admin(e).ok_or(OwnershipError::NoPendingAdmin)?;
+    let admin = access::get_admin(e).ok_or(OwnershipError::AdminNotSet)?;
+    if pending.proposed_by != admin {
+        return Err(OwnershipError::StaleProposal);
+    }
+    if e.ledger().timestamp() >= pending.expires_at {
+        return Err(OwnershipError::ProposalExpired);
+    }
+
+    pending.new_admin.require_auth();
+    access::set_admin(e, &pending.new_admin);
+    e.storage().instance().remove(&OwnershipKey::PendingAdmin);
+    e.events().publish((TOPIC_ADMIN_CHANGED,), (admin.clone(), pending.new_admin));
+    Ok(admin)
+}
+
+/// Withdraws the pending proposal. Requires the admin signature.
+pub fn cancel_admin_transfer(e: &Env) -> Result<(), OwnershipError> {
+    let admin = access::require_admin(e)?;
+    let pending = pending_admin(e).ok_or(OwnershipError::NoPendingAdmin)?;
+
+    e.storage().instance().remove(&OwnershipKey::PendingAdmin);
+    e.events().publish((TOPIC_ADMIN_CANCELLED,), (admin, pending.new_admin));
+    Ok(())
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,119 @@
+#![cfg(test)]
+extern crate std;
+
+use soroban_examples_access::{get_admin, set_admin};
+use soroban_sdk::{contract, testutils::{Address as _, Ledger}, Address, Env};
+
+use crate::{accept_admin, cancel_admin_transfer, pending_admin, propose_admin, OwnershipError, PROPOSAL_WINDOW};
+
+#[contract]
+struct Dummy;
+
+fn setup() -> (Env, Address, Address) {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let contract_id = e.register(Dummy, ());
+    let admin = Address::generate(&e);
+    e.as_contract(&contract_id, || set_admin(&e, &admin));
+
+    (e, contract_id, admin)
+}
+
+#[test]
+fn two_step_transfer() {
+    let (e, contract_id, admin) = setup();
+    let new_admin = Address::generate(&e);
+
+    e.as_contract(&contract_id, || {
+        let pending = propose_admin(&e, &new_admin).unwrap();
+        assert_eq!(pending.proposed_by, admin);
+        assert_eq!(pending.expires_at, e.ledger().timestamp() + PROPOSAL_WINDOW);
+
+        // Nothing changes until the proposed admin accepts
+        assert_eq!(get_admin(&e), Some(admin.clone()));
+        assert_eq!(accept_admin(&e), Ok(admin.clone()));
+        assert_eq!(get_admin(&e), Some(new_admin.clone()));
+        assert_eq!(pending_admin(&e), None);
+    });
+}
+
+#[test]
+fn accept_without_proposal() {
+    let (e, contract_id, _) = setup();
+    e.as_contract(&contract_id, || {
+        assert_eq!(accept_admin(&e), Err(OwnershipError::NoPendingAdmin));
+        assert_eq!(cancel_admin_transfer(&e), Err(OwnershipError::NoPendingAdmin));
+    });
+}
+
+#[test]
+fn new_proposal_replaces_the_previous_one() {
+    let (e, contract_id, _) = setup();
+    let (first, second) = (Address::generate(&e), Address::generate(&e));
+
+    e.as_contract(&contract_id, || {
+        propose_admin(&e, &first).unwrap();
+        propose_admin(&e, &second).unwrap();
+        accept_admin(&e).unwrap();
+        assert_eq!(get_admin(&e), Some(second));
+    });
+}
+
+#[test]
+fn expired_proposal() {
+    let (e, contract_id, admin) = setup();
+    let new_admin = Address::generate(&e);
+
+    e.as_contract(&contract_id, || propose_admin(&e, &new_admin).unwrap());
+    e.ledger().set_timestamp(e.ledger().timestamp() + PROPOSAL_WINDOW);
+
+    e.as_contract(&contract_id, || {
+        assert_eq!(accept_admin(&e), Err(OwnershipError::ProposalExpired));
+        assert_eq!(get_admin(&e), Some(admin));
+    });
+}
+
+#[test]
+fn stale_proposal_cannot_take_over() {
+    let (e, contract_id, admin) = setup();
+    let (stale, successor) = (Address::generate(&e), Address::generate(&e));
+
+    // The old admin's proposal outlives its term: once someone else is in charge it is worthless
+    e.as_contract(&contract_id, || {
+        propose_admin(&e, &stale).unwrap();
+        set_admin(&e, &successor);
+        assert_eq!(accept_admin(&e), Err(OwnershipError::StaleProposal));
+        assert_eq!(get_admin(&e), Some(successor.clone()));
+    });
+
+    // Going back to the proposing admin does not revive it either once it has expired
+    e.ledger().set_timestamp(e.ledger().timestamp() + PROPOSAL_WINDOW);
+    e.as_contract(&contract_id, || {
+        set_admin(&e, &admin);
+        assert_eq!(accept_admin(&e), Err(OwnershipError::ProposalExpired));
+    });
+}
+
+#[test]
+fn cancelled_proposal() {
+    let (e, contract_id, admin) = setup();
+    let new_admin = Address::generate(&e);
+
+    e.as_contract(&contract_id, || {
+        propose_admin(&e, &new_admin).unwrap();
+        cancel_admin_transfer(&e).unwrap();
+        assert_eq!(pending_admin(&e), None);
+        assert_eq!(accept_admin(&e), Err(OwnershipError::NoPendingAdmin));
+        assert_eq!(get_admin(&e), Some(admin));
+    });
+}
+
+#[test]
+fn propose_without_admin() {
+    let e = Env::default();
+    let contract_id = e.register(Dummy, ());
+    e.as_contract(&contract_id, || {
+        assert_eq!(propose_admin(&e, &Address::generate(&e)), Err(OwnershipError::AdminNotSet));
+    });
+}
@@ -0,0 +1,64 @@
+#![no_std]
+
+//! Emergency stop shared by the example contracts. A contract pauses itself from whatever entrypoint
+//! its emergency authority calls, and guards the entrypoints that move funds with `when_not_paused`.
+
+use soroban_examples_storage::bump_instance;
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol};
+
+pub const TOPIC_PAUSED: Symbol = symbol_short!("PAUSED");
+pub const TOPIC_UNPAUSED: Symbol = symbol_short!("UNPAUSED");
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PausableError {
+    Paused,
+    NotPaused,
+}
+
+#[contracttype]
+enum PausableKey {
+    Paused,
+}
+
+pub fn is_paused(e: &Env) -> bool {
+    e.storage().instance().get(&PausableKey::Paused).unwrap_or(false)
+}
+
+/// Pauses the contract on behalf of `by`. Callers are responsible for authorizing it. Pausing an
+/// already paused contract does nothing.
+pub fn pause(e: &Env, by: &Address) {
+    if !is_paused(e) {
+        e.storage().instance().set(&PausableKey::Paused, &true);
+        bump_instance(e);
+        e.events().publish((TOPIC_PAUSED,), by.clone());
+    }
+}
+
+/// Resumes the contract on behalf of `by`. Callers are responsible for authorizing it.
+pub fn unpause(e: &Env, by: &Address) {
+    if is_paused(e) {
+        e.storage().instance().remove(&PausableKey::Paused);
+        e.events().publish((TOPIC_UNPAUSED,), by.clone());
+    }
+}
+
+/// Guard for the entrypoints that must stop while the contract is paused.
+pub fn when_not_paused(e: &Env) -> Result<(), PausableError> {
+    if is_paused(e) {
+        return Err(PausableError::Paused);
+    }
+
+    Ok(())
+}
+
+/// Guard for the entrypoints that are only available while the contract is paused (e.g. emergency exits).
+pub fn when_paused(e: &Env) -> Result<(), PausableError> {
+    if !is_paused(e) {
+        return Err(PausableError::NotPaused);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test;
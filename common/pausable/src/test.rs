@@ -0,0 +1,54 @@
+#![cfg(test)]
+extern crate std;
+
+use soroban_sdk::{contract, testutils::{Address as _, Events}, vec, Address, Env, IntoVal};
+
+use crate::{is_paused, pause, unpause, when_not_paused, when_paused, PausableError, TOPIC_PAUSED, TOPIC_UNPAUSED};
+
+#[contract]
+struct Dummy;
+
+#[test]
+fn pause_and_unpause() {
+    let e = Env::default();
+    let contract_id = e.register(Dummy, ());
+    let guardian = Address::generate(&e);
+
+    e.as_contract(&contract_id, || {
+        assert!(!is_paused(&e));
+        assert_eq!(when_not_paused(&e), Ok(()));
+        assert_eq!(when_paused(&e), Err(PausableError::NotPaused));
+
+        pause(&e, &guardian);
+        assert!(is_paused(&e));
+        assert_eq!(when_not_paused(&e), Err(PausableError::Paused));
+        assert_eq!(when_paused(&e), Ok(()));
+
+        unpause(&e, &guardian);
+        assert!(!is_paused(&e));
+        assert_eq!(when_not_paused(&e), Ok(()));
+    });
+}
+
+#[test]
+fn pause_emits_events() {
+    let e = Env::default();
+    let contract_id = e.register(Dummy, ());
+    let guardian = Address::generate(&e);
+
+    // Pausing twice only emits one event
+    e.as_contract(&contract_id, || {
+        pause(&e, &guardian);
+        pause(&e, &guardian);
+        unpause(&e, &guardian);
+    });
+
+    assert_eq!(
+        e.events().all(),
+        vec![
+            &e,
+            (contract_id.clone(), (TOPIC_PAUSED,).into_val(&e), guardian.into_val(&e)),
+            (contract_id.clone(), (TOPIC_UNPAUSED,).into_val(&e), guardian.into_val(&e))
+        ]
+    );
+}
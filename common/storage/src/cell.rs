@@ -0,0 +1,166 @@
+//! Typed handles over a storage key. A cell knows which storage its value lives in and extends the
+//! TTL of that storage every time the value is read or written, so the contracts using them never
+//! have to remember which accesses need a bump.
+
+use core::marker::PhantomData;
+
+use soroban_sdk::{Env, IntoVal, TryFromVal, Val};
+
+use crate::{bump_instance, bump_persistent, bump_temporary};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Durability {
+    Instance,
+    Persistent,
+    Temporary,
+}
+
+/// A single value of type `T` stored under the key `K`.
+pub struct StorageCell<K, T> {
+    key: K,
+    durability: Durability,
+    _value: PhantomData<T>,
+}
+
+impl<K, T> StorageCell<K, T> {
+    pub const fn new(durability: Durability, key: K) -> Self {
+        StorageCell { key, durability, _value: PhantomData }
+    }
+
+    pub const fn instance(key: K) -> Self {
+        Self::new(Durability::Instance, key)
+    }
+
+    pub const fn persistent(key: K) -> Self {
+        Self::new(Durability::Persistent, key)
+    }
+
+    pub const fn temporary(key: K) -> Self {
+        Self::new(Durability::Temporary, key)
+    }
+
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+}
+
+impl<K: IntoVal<Env, Val>, T: IntoVal<Env, Val> + TryFromVal<Env, Val>> StorageCell<K, T> {
+    /// Reads the value, extending the TTL of the storage it lives in. Instance storage is always
+    /// bumped; persistent and temporary entries only when they exist.
+    pub fn get(&self, e: &Env) -> Option<T> {
+        let value = match self.durability {
+            Durability::Instance => e.storage().instance().get(&self.key),
+            Durability::Persistent => e.storage().persistent().get(&self.key),
+            Durability::Temporary => e.storage().temporary().get(&self.key),
+        };
+
+        if value.is_some() || self.durability == Durability::Instance {
+            self.bump(e);
+        }
+
+        value
+    }
+
+    pub fn get_or_else(&self, e: &Env, default: impl FnOnce() -> T) -> T {
+        self.get(e).unwrap_or_else(default)
+    }
+
+    /// Writes the value and extends its TTL.
+    pub fn set(&self, e: &Env, value: &T) {
+        match self.durability {
+            Durability::Instance => e.storage().instance().set(&self.key, value),
+            Durability::Persistent => e.storage().persistent().set(&self.key, value),
+            Durability::Temporary => e.storage().temporary().set(&self.key, value),
+        }
+
+        self.bump(e);
+    }
+
+    /// Reads the value (or `None`), lets `f` produce the new one and stores it.
+    pub fn update(&self, e: &Env, f: impl FnOnce(Option<T>) -> T) -> T {
+        let value = f(self.get(e));
+        self.set(e, &value);
+        value
+    }
+
+    pub fn has(&self, e: &Env) -> bool {
+        match self.durability {
+            Durability::Instance => e.storage().instance().has(&self.key),
+            Durability::Persistent => e.storage().persistent().has(&self.key),
+            Durability::Temporary => e.storage().temporary().has(&self.key),
+        }
+    }
+
+    pub fn remove(&self, e: &Env) {
+        match self.durability {
+            Durability::Instance => e.storage().instance().remove(&self.key),
+            Durability::Persistent => e.storage().persistent().remove(&self.key),
+            Durability::Temporary => e.storage().temporary().remove(&self.key),
+        }
+    }
+
+    fn bump(&self, e: &Env) {
+        match self.durability {
+            Durability::Instance => bump_instance(e),
+            Durability::Persistent => bump_persistent(e, &self.key),
+            Durability::Temporary => bump_temporary(e, &self.key),
+        }
+    }
+}
+
+/// One value of type `V` per `K`, each stored under its own key built by `key` (e.g. a `DataKey`
+/// variant holding an address). Every entry behaves as a [`StorageCell`].
+pub struct StorageMap<K, V, DK> {
+    key: fn(&K) -> DK,
+    durability: Durability,
+    _value: PhantomData<V>,
+}
+
+impl<K, V, DK> StorageMap<K, V, DK> {
+    pub const fn new(durability: Durability, key: fn(&K) -> DK) -> Self {
+        StorageMap { key, durability, _value: PhantomData }
+    }
+
+    pub const fn instance(key: fn(&K) -> DK) -> Self {
+        Self::new(Durability::Instance, key)
+    }
+
+    pub const fn persistent(key: fn(&K) -> DK) -> Self {
+        Self::new(Durability::Persistent, key)
+    }
+
+    pub const fn temporary(key: fn(&K) -> DK) -> Self {
+        Self::new(Durability::Temporary, key)
+    }
+
+    /// The cell holding the entry of `k`.
+    pub fn cell(&self, k: &K) -> StorageCell<DK, V> {
+        StorageCell::new(self.durability, (self.key)(k))
+    }
+}
+
+impl<K, V: IntoVal<Env, Val> + TryFromVal<Env, Val>, DK: IntoVal<Env, Val>> StorageMap<K, V, DK> {
+    pub fn get(&self, e: &Env, k: &K) -> Option<V> {
+        self.cell(k).get(e)
+    }
+
+    pub fn get_or_else(&self, e: &Env, k: &K, default: impl FnOnce() -> V) -> V {
+        self.cell(k).get_or_else(e, default)
+    }
+
+    pub fn set(&self, e: &Env, k: &K, value: &V) {
+        self.cell(k).set(e, value)
+    }
+
+    pub fn update(&self, e: &Env, k: &K, f: impl FnOnce(Option<V>) -> V) -> V {
+        self.cell(k).update(e, f)
+    }
+
+    pub fn has(&self, e: &Env, k: &K) -> bool {
+        self.cell(k).has(e)
+    }
+
+    pub fn remove(&self, e: &Env, k: &K) {
+        self.cell(k).remove(e)
+    }
+}
@@ -0,0 +1,57 @@
+#![no_std]
+
+//! Storage and TTL helpers shared by the example contracts, so all of them keep their entries alive
+//! the same way.
+
+use soroban_sdk::{Env, IntoVal, TryFromVal, Val};
+
+mod cell;
+pub use cell::{Durability, StorageCell, StorageMap};
+
+pub const DAY_IN_LEDGERS: u32 = 17280;
+
+// Instance storage: config and totals, accessed frequently, moderate TTL
+pub const INSTANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;      // ~30 days
+pub const INSTANCE_LIFETIME_THRESHOLD: u32 = 15 * DAY_IN_LEDGERS; // ~15 days
+
+// Persistent storage: critical user data, long TTL for safety
+pub const PERSISTENT_BUMP_AMOUNT: u32 = 180 * DAY_IN_LEDGERS;    // ~6 months
+pub const PERSISTENT_LIFETIME_THRESHOLD: u32 = 90 * DAY_IN_LEDGERS; // ~3 months
+
+// Temporary storage: short-lived entries (e.g. nonces, pending operations), cheap to let expire
+pub const TEMPORARY_BUMP_AMOUNT: u32 = DAY_IN_LEDGERS;              // ~1 day
+pub const TEMPORARY_LIFETIME_THRESHOLD: u32 = DAY_IN_LEDGERS / 2;   // ~12 hours
+
+/// Extends the TTL of the contract instance (and its instance storage) once it drops below the threshold.
+pub fn bump_instance(e: &Env) {
+    e.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+/// Extends the TTL of a persistent entry once it drops below the threshold. The entry must exist.
+pub fn bump_persistent<K: IntoVal<Env, Val>>(e: &Env, key: &K) {
+    e.storage().persistent().extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+/// Extends the TTL of a temporary entry once it drops below the threshold. The entry must exist.
+pub fn bump_temporary<K: IntoVal<Env, Val>>(e: &Env, key: &K) {
+    e.storage().temporary().extend_ttl(key, TEMPORARY_LIFETIME_THRESHOLD, TEMPORARY_BUMP_AMOUNT);
+}
+
+/// Reads a persistent entry, extending its TTL when it exists.
+pub fn get_persistent<K: IntoVal<Env, Val>, V: TryFromVal<Env, Val>>(e: &Env, key: &K) -> Option<V> {
+    let value = e.storage().persistent().get(key);
+    if value.is_some() {
+        bump_persistent(e, key);
+    }
+
+    value
+}
+
+/// Writes a persistent entry and extends its TTL.
+pub fn set_persistent<K: IntoVal<Env, Val>, V: IntoVal<Env, Val>>(e: &Env, key: &K, value: &V) {
+    e.storage().persistent().set(key, value);
+    bump_persistent(e, key);
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,178 @@
+#![cfg(test)]
+extern crate std;
+
+use soroban_sdk::{contract, contracttype, symbol_short, testutils::{storage::{Instance as _, Persistent as _, Temporary as _}, Address as _, Ledger}, Address, Env, Symbol};
+
+use crate::{bump_instance, bump_persistent, get_persistent, set_persistent, StorageCell, StorageMap, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD, TEMPORARY_BUMP_AMOUNT};
+
+const KEY: Symbol = symbol_short!("KEY");
+
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Total,
+    Balance(Address),
+}
+
+const TOTAL: StorageCell<DataKey, u32> = StorageCell::instance(DataKey::Total);
+const BALANCES: StorageMap<Address, i128, DataKey> = StorageMap::persistent(|addr| DataKey::Balance(addr.clone()));
+const NONCE: StorageCell<Symbol, u64> = StorageCell::temporary(KEY);
+
+#[contract]
+struct Dummy;
+
+fn setup() -> (Env, Address) {
+    let e = Env::default();
+    e.ledger().with_mut(|li| {
+        li.min_persistent_entry_ttl = 100;
+        li.max_entry_ttl = PERSISTENT_BUMP_AMOUNT + 1;
+    });
+
+    let contract_id = e.register(Dummy, ());
+    (e, contract_id)
+}
+
+fn pass_ledgers(e: &Env, ledgers: u32) {
+    e.ledger().with_mut(|li| li.sequence_number += ledgers);
+}
+
+#[test]
+fn set_persistent_extends_ttl() {
+    let (e, contract_id) = setup();
+    e.as_contract(&contract_id, || {
+        set_persistent(&e, &KEY, &7_u32);
+        assert_eq!(e.storage().persistent().get_ttl(&KEY), PERSISTENT_BUMP_AMOUNT);
+    });
+}
+
+#[test]
+fn bump_persistent_only_below_threshold() {
+    let (e, contract_id) = setup();
+    e.as_contract(&contract_id, || set_persistent(&e, &KEY, &7_u32));
+
+    // Still above the threshold: the TTL is left as it is
+    pass_ledgers(&e, PERSISTENT_BUMP_AMOUNT - PERSISTENT_LIFETIME_THRESHOLD - 1);
+    e.as_contract(&contract_id, || {
+        bump_persistent(&e, &KEY);
+        assert_eq!(e.storage().persistent().get_ttl(&KEY), PERSISTENT_LIFETIME_THRESHOLD + 1);
+    });
+
+    pass_ledgers(&e, 2);
+    e.as_contract(&contract_id, || {
+        assert_eq!(e.storage().persistent().get_ttl(&KEY), PERSISTENT_LIFETIME_THRESHOLD - 1);
+        bump_persistent(&e, &KEY);
+        assert_eq!(e.storage().persistent().get_ttl(&KEY), PERSISTENT_BUMP_AMOUNT);
+    });
+}
+
+#[test]
+fn get_persistent_keeps_entry_alive() {
+    let (e, contract_id) = setup();
+    e.as_contract(&contract_id, || set_persistent(&e, &KEY, &7_u32));
+
+    // Reading the entry before it expires extends it, so it outlives its original TTL
+    for _ in 0..3 {
+        pass_ledgers(&e, PERSISTENT_BUMP_AMOUNT - PERSISTENT_LIFETIME_THRESHOLD + 1);
+        e.as_contract(&contract_id, || {
+            assert_eq!(get_persistent::<Symbol, u32>(&e, &KEY), Some(7));
+            assert_eq!(e.storage().persistent().get_ttl(&KEY), PERSISTENT_BUMP_AMOUNT);
+        });
+    }
+
+    assert!(e.ledger().sequence() > PERSISTENT_BUMP_AMOUNT);
+}
+
+#[test]
+fn get_persistent_missing_entry() {
+    let (e, contract_id) = setup();
+    e.as_contract(&contract_id, || {
+        assert_eq!(get_persistent::<Symbol, u32>(&e, &KEY), None);
+    });
+}
+
+#[test]
+fn bump_instance_extends_ttl() {
+    let (e, contract_id) = setup();
+    e.as_contract(&contract_id, || {
+        bump_instance(&e);
+        assert_eq!(e.storage().instance().get_ttl(), INSTANCE_BUMP_AMOUNT);
+    });
+
+    pass_ledgers(&e, INSTANCE_BUMP_AMOUNT - INSTANCE_LIFETIME_THRESHOLD - 1);
+    e.as_contract(&contract_id, || {
+        bump_instance(&e);
+        assert_eq!(e.storage().instance().get_ttl(), INSTANCE_LIFETIME_THRESHOLD + 1);
+    });
+}
+
+#[test]
+#[should_panic]
+fn expired_entry_is_not_readable() {
+    let (e, contract_id) = setup();
+    e.as_contract(&contract_id, || set_persistent(&e, &KEY, &7_u32));
+
+    // Nobody touched the entry during its whole TTL, so it gets archived
+    pass_ledgers(&e, PERSISTENT_BUMP_AMOUNT + 1);
+    e.as_contract(&contract_id, || {
+        get_persistent::<Symbol, u32>(&e, &KEY);
+    });
+}
+
+#[test]
+fn instance_cell_bumps_on_every_access() {
+    let (e, contract_id) = setup();
+    e.as_contract(&contract_id, || {
+        assert_eq!(TOTAL.get(&e), None);
+        assert_eq!(e.storage().instance().get_ttl(), INSTANCE_BUMP_AMOUNT);
+    });
+
+    pass_ledgers(&e, INSTANCE_BUMP_AMOUNT - INSTANCE_LIFETIME_THRESHOLD + 1);
+    e.as_contract(&contract_id, || {
+        TOTAL.set(&e, &3);
+        assert_eq!(e.storage().instance().get_ttl(), INSTANCE_BUMP_AMOUNT);
+    });
+
+    pass_ledgers(&e, INSTANCE_BUMP_AMOUNT - INSTANCE_LIFETIME_THRESHOLD + 1);
+    e.as_contract(&contract_id, || {
+        assert_eq!(TOTAL.update(&e, |total| total.unwrap_or(0) + 1), 4);
+        assert_eq!(TOTAL.get(&e), Some(4));
+        assert_eq!(e.storage().instance().get_ttl(), INSTANCE_BUMP_AMOUNT);
+    });
+}
+
+#[test]
+fn storage_map_keeps_entries_alive() {
+    let (e, contract_id) = setup();
+    let (alice, bob) = (Address::generate(&e), Address::generate(&e));
+    e.as_contract(&contract_id, || {
+        BALANCES.set(&e, &alice, &100);
+        assert_eq!(BALANCES.get(&e, &bob), None);
+        assert!(!BALANCES.has(&e, &bob));
+        assert_eq!(e.storage().persistent().get_ttl(&DataKey::Balance(alice.clone())), PERSISTENT_BUMP_AMOUNT);
+    });
+
+    // Reading an entry before it expires extends it, so it outlives its original TTL
+    for _ in 0..3 {
+        pass_ledgers(&e, PERSISTENT_BUMP_AMOUNT - PERSISTENT_LIFETIME_THRESHOLD + 1);
+        e.as_contract(&contract_id, || {
+            assert_eq!(BALANCES.get_or_else(&e, &alice, || 0), 100);
+            assert_eq!(e.storage().persistent().get_ttl(BALANCES.cell(&alice).key()), PERSISTENT_BUMP_AMOUNT);
+        });
+    }
+
+    e.as_contract(&contract_id, || {
+        BALANCES.remove(&e, &alice);
+        assert_eq!(BALANCES.get(&e, &alice), None);
+    });
+}
+
+#[test]
+fn temporary_cell_extends_ttl() {
+    let (e, contract_id) = setup();
+    e.as_contract(&contract_id, || {
+        assert!(!NONCE.has(&e));
+        NONCE.set(&e, &1);
+        assert_eq!(e.storage().temporary().get_ttl(&KEY), TEMPORARY_BUMP_AMOUNT);
+        assert_eq!(NONCE.get(&e), Some(1));
+    });
+}
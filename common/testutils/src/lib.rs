@@ -0,0 +1,103 @@
+//! Test scaffolding shared by the contracts' test suites: environment setup, token (SAC) registration,
+//! funding and time-travel helpers, a re-entering token, event snapshots and a scenario runner. Only
+//! meant to be used as a dev-dependency.
+
+use std::rc::Rc;
+
+use soroban_sdk::{testutils::Ledger, token, xdr, Address, Bytes, Env, TryFromVal};
+
+mod reentrant;
+mod scenario;
+mod snapshot;
+
+pub use reentrant::{Reentry, ReentrantToken, ReentrantTokenClient};
+pub use scenario::Scenario;
+pub use snapshot::{EventSnapshot, UPDATE_SNAPSHOTS_VAR};
+pub use token::Client as TokenClient;
+pub use token::StellarAssetClient as TokenAdminClient;
+
+pub const SECONDS_IN_DAY: u64 = 86400;
+pub const LEDGERS_IN_DAY: u32 = 17280;
+
+/// A default environment where every `require_auth` succeeds.
+pub fn create_env() -> Env {
+    let e = Env::default();
+    e.mock_all_auths();
+    e
+}
+
+/// Registers a Stellar Asset Contract administered by `admin`, returning its token and admin clients.
+pub fn create_token_contract<'a>(e: &Env, admin: &Address) -> (TokenClient<'a>, TokenAdminClient<'a>) {
+    let sac = e.register_stellar_asset_contract_v2(admin.clone());
+    (
+        TokenClient::new(e, &sac.address()),
+        TokenAdminClient::new(e, &sac.address()),
+    )
+}
+
+/// Deploys the native (XLM) Stellar Asset Contract.
+pub fn create_native_token_contract<'a>(e: &Env) -> TokenClient<'a> {
+    // XDR encoding of `Asset::Native`
+    let native_asset = Bytes::from_array(e, &[0, 0, 0, 0]);
+    let native_sac = e.deployer().with_stellar_asset(native_asset).deploy();
+    TokenClient::new(e, &native_sac)
+}
+
+/// Registers a `ReentrantToken`, disarmed until `arm` is called. Contracts can use it as their token
+/// through the regular `TokenClient`.
+pub fn create_reentrant_token<'a>(e: &Env) -> ReentrantTokenClient<'a> {
+    ReentrantTokenClient::new(e, &e.register(ReentrantToken, ()))
+}
+
+/// Creates a classic account holding `stroops` lumens. The native SAC has no admin and cannot mint, so
+/// lumens have to live in a classic account entry.
+pub fn create_funded_account(e: &Env, stroops: i64) -> Address {
+    let account_id = xdr::AccountId(xdr::PublicKey::PublicKeyTypeEd25519(xdr::Uint256([7; 32])));
+    let key = Rc::new(xdr::LedgerKey::Account(xdr::LedgerKeyAccount {
+        account_id: account_id.clone(),
+    }));
+    let entry = Rc::new(xdr::LedgerEntry {
+        data: xdr::LedgerEntryData::Account(xdr::AccountEntry {
+            account_id: account_id.clone(),
+            balance: stroops,
+            flags: 0,
+            home_domain: Default::default(),
+            inflation_dest: None,
+            num_sub_entries: 0,
+            seq_num: xdr::SequenceNumber(0),
+            thresholds: xdr::Thresholds([1; 4]),
+            signers: xdr::VecM::default(),
+            ext: xdr::AccountEntryExt::V0,
+        }),
+        last_modified_ledger_seq: 0,
+        ext: xdr::LedgerEntryExt::V0,
+    });
+    e.host().add_ledger_entry(&key, &entry, None).unwrap();
+
+    Address::try_from_val(e, &xdr::ScAddress::Account(account_id)).unwrap()
+}
+
+/// Mints `amount` tokens to each of `holders`.
+pub fn mint_to(token_admin: &TokenAdminClient, holders: &[&Address], amount: i128) {
+    for holder in holders {
+        token_admin.mint(holder, &amount);
+    }
+}
+
+/// Moves the ledger clock forward by `seconds`.
+pub fn advance_time(e: &Env, seconds: u64) {
+    e.ledger().set_timestamp(e.ledger().timestamp() + seconds);
+}
+
+/// Moves the ledger clock forward by `days`.
+pub fn advance_days(e: &Env, days: u64) {
+    advance_time(e, days * SECONDS_IN_DAY);
+}
+
+/// Closes `ledgers` ledgers without moving the clock, e.g. to let storage TTLs run down.
+pub fn advance_ledgers(e: &Env, ledgers: u32) {
+    e.ledger().set_sequence_number(e.ledger().sequence() + ledgers);
+}
+
+#[cfg(test)]
+mod test;
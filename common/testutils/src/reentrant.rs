@@ -0,0 +1,104 @@
+//! A hostile token for re-entrancy tests. Once armed, each transfer first calls back into a contract
+//! (usually the one moving the tokens) and records whether that call went through, then moves the
+//! balances as a plain token would.
+
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Symbol, Val, Vec};
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Reentry {
+    pub contract: Address,
+    pub fn_name: Symbol,
+    pub args: Vec<Val>,
+}
+
+#[contracttype]
+enum DataKey {
+    Balance(Address),
+    Reentry,
+    Attempts,
+    Reentered,
+}
+
+fn get_balance(e: &Env, id: &Address) -> i128 {
+    e.storage().persistent().get(&DataKey::Balance(id.clone())).unwrap_or(0)
+}
+
+fn move_balance(e: &Env, from: &Address, to: &Address, amount: i128) {
+    let from_balance = get_balance(e, from);
+    if amount < 0 || from_balance < amount {
+        panic!("insufficient balance");
+    }
+
+    e.storage().persistent().set(&DataKey::Balance(from.clone()), &(from_balance - amount));
+    e.storage().persistent().set(&DataKey::Balance(to.clone()), &(get_balance(e, to) + amount));
+}
+
+fn bump_counter(e: &Env, key: DataKey) {
+    let count: u32 = e.storage().instance().get(&key).unwrap_or(0);
+    e.storage().instance().set(&key, &(count + 1));
+}
+
+// The re-entry is attempted once: the token is disarmed before the call, so a call that does get
+// through cannot loop back into this transfer
+fn reenter(e: &Env) {
+    if let Some(reentry) = e.storage().instance().get::<DataKey, Reentry>(&DataKey::Reentry) {
+        e.storage().instance().remove(&DataKey::Reentry);
+        bump_counter(e, DataKey::Attempts);
+
+        let result = e.try_invoke_contract::<Val, soroban_sdk::Error>(&reentry.contract, &reentry.fn_name, reentry.args);
+        if let Ok(Ok(_)) = result {
+            bump_counter(e, DataKey::Reentered);
+        }
+    }
+}
+
+#[contract]
+pub struct ReentrantToken;
+
+#[contractimpl]
+impl ReentrantToken {
+    pub fn mint(e: Env, to: Address, amount: i128) {
+        e.storage().persistent().set(&DataKey::Balance(to.clone()), &(get_balance(&e, &to) + amount));
+    }
+
+    /// The next transfer calls `fn_name(args)` on `contract` before moving any balance.
+    pub fn arm(e: Env, contract: Address, fn_name: Symbol, args: Vec<Val>) {
+        e.storage().instance().set(&DataKey::Reentry, &Reentry { contract, fn_name, args });
+    }
+
+    /// Re-entries attempted so far.
+    pub fn attempts(e: Env) -> u32 {
+        e.storage().instance().get(&DataKey::Attempts).unwrap_or(0)
+    }
+
+    /// Re-entries that completed without error.
+    pub fn reentered(e: Env) -> u32 {
+        e.storage().instance().get(&DataKey::Reentered).unwrap_or(0)
+    }
+
+    pub fn balance(e: Env, id: Address) -> i128 {
+        get_balance(&e, &id)
+    }
+
+    pub fn decimals(_e: Env) -> u32 {
+        7
+    }
+
+    pub fn transfer(e: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        reenter(&e);
+        move_balance(&e, &from, &to, amount);
+    }
+
+    // Allowances are not tracked: every spender may move any amount, and its signature is enough
+    pub fn allowance(_e: Env, _from: Address, _spender: Address) -> i128 {
+        i128::MAX
+    }
+
+    pub fn transfer_from(e: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        spender.require_auth();
+        reenter(&e);
+        move_balance(&e, &from, &to, amount);
+    }
+}
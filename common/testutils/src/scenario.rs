@@ -0,0 +1,94 @@
+//! Declarative scenarios: a flow is written as a list of named steps and waits, then run in order
+//! against a context (usually the test data of the suite, with clients and accounts). Time only moves
+//! through the waits, so the same scenario always sees the same ledger timestamps.
+//!
+//! Steps get the context mutably, so a step can keep what a later one needs (an investment, an id).
+//! With `trace`, every step is printed with the ledger time it runs at, so the test output shows where
+//! a failing scenario stopped. Fragments shared by several tests are plain functions taking and
+//! returning a `Scenario`, spliced in with `with`.
+
+use std::rc::Rc;
+
+use soroban_sdk::{testutils::Ledger, Env};
+
+use crate::{advance_time, SECONDS_IN_DAY};
+
+type Action<'a, C> = Box<dyn FnOnce(&Env, &mut C) + 'a>;
+
+enum Step<'a, C> {
+    Wait(u64),
+    Run(String, Action<'a, C>),
+}
+
+pub struct Scenario<'a, C> {
+    e: &'a Env,
+    context: C,
+    steps: Vec<Step<'a, C>>,
+    trace: bool,
+}
+
+impl<'a, C: 'a> Scenario<'a, C> {
+    pub fn new(e: &'a Env, context: C) -> Self {
+        Scenario { e, context, steps: Vec::new(), trace: false }
+    }
+
+    /// Runs `action` at this point of the flow.
+    pub fn step(mut self, name: &str, action: impl FnOnce(&Env, &mut C) + 'a) -> Self {
+        self.steps.push(Step::Run(name.into(), Box::new(action)));
+        self
+    }
+
+    /// Runs `action` `times` times, `every` seconds apart, passing the 0-based occurrence.
+    pub fn repeat(mut self, name: &str, times: u32, every: u64, action: impl Fn(&Env, &mut C, u32) + 'a) -> Self {
+        let action = Rc::new(action);
+        for occurrence in 0..times {
+            if occurrence > 0 {
+                self.steps.push(Step::Wait(every));
+            }
+
+            let action = action.clone();
+            let name = format!("{name} #{}", occurrence + 1);
+            self.steps.push(Step::Run(name, Box::new(move |e: &Env, context: &mut C| (*action)(e, context, occurrence))));
+        }
+        self
+    }
+
+    /// Moves the ledger clock forward by `seconds` before the next step.
+    pub fn wait(mut self, seconds: u64) -> Self {
+        self.steps.push(Step::Wait(seconds));
+        self
+    }
+
+    pub fn wait_days(self, days: u64) -> Self {
+        self.wait(days * SECONDS_IN_DAY)
+    }
+
+    /// Prints every step with the ledger time it runs at.
+    pub fn trace(mut self) -> Self {
+        self.trace = true;
+        self
+    }
+
+    /// Appends the steps added by `fragment`.
+    pub fn with(self, fragment: impl FnOnce(Self) -> Self) -> Self {
+        fragment(self)
+    }
+
+    /// Runs every step in order and hands the context back for the final assertions.
+    pub fn run(self) -> C {
+        let Scenario { e, mut context, steps, trace } = self;
+        for step in steps {
+            match step {
+                Step::Wait(seconds) => advance_time(e, seconds),
+                Step::Run(name, action) => {
+                    if trace {
+                        println!("[t={}] {name}", e.ledger().timestamp());
+                    }
+                    action(e, &mut context);
+                }
+            }
+        }
+
+        context
+    }
+}
@@ -0,0 +1,144 @@
+//! Golden-file snapshots of the events a contract emits. A scenario captures the events of the contract
+//! after each step and compares them, as text, against a file committed next to the contract, so a
+//! change to a topic or to the layout of a payload shows up as a failing test instead of a broken
+//! indexer.
+//!
+//! Values are written with their type (`5i128`, `7u32`), symbols bare, strings quoted, vectors as
+//! `[..]` and `#[contracttype]` structs as `{field: value}`. Addresses are written with the label given
+//! through `label`, or as `addr#N` in order of appearance, so snapshots do not depend on how the test
+//! environment generates them.
+
+use std::{fs, path::Path};
+
+use soroban_sdk::{testutils::Events, xdr::{ScAddress, ScVal}, Address, Env, IntoVal, TryFromVal, Val};
+
+/// When set, `assert_matches` rewrites the snapshots instead of comparing against them.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "UPDATE_EVENT_SNAPSHOTS";
+
+pub struct EventSnapshot<'a> {
+    e: &'a Env,
+    contract: Address,
+    labels: Vec<(ScAddress, String)>,
+    unlabeled: u32,
+    lines: Vec<String>,
+}
+
+impl<'a> EventSnapshot<'a> {
+    /// Records the events emitted by `contract`, which is labeled `contract`.
+    pub fn new(e: &'a Env, contract: &Address) -> Self {
+        let mut snapshot = EventSnapshot { e, contract: contract.clone(), labels: Vec::new(), unlabeled: 0, lines: Vec::new() };
+        snapshot.label(contract, "contract");
+        snapshot
+    }
+
+    /// Writes `address` as `name` in the snapshot.
+    pub fn label(&mut self, address: &Address, name: &str) -> &mut Self {
+        let address = to_sc_address(self.e, address);
+        self.labels.push((address, name.into()));
+        self
+    }
+
+    /// Appends the events the contract emitted in the last invocation under a `# step` heading. The
+    /// test environment only keeps the events of the last invocation, so call it after every call of
+    /// the scenario whose events matter.
+    pub fn capture(&mut self, step: &str) {
+        self.lines.push(format!("# {step}"));
+
+        let e = self.e;
+        for (contract, topics, data) in e.events().all().iter() {
+            if contract != self.contract {
+                continue;
+            }
+
+            let topics: Vec<String> = topics.iter().map(|topic| self.render(topic)).collect();
+            let data = self.render(data);
+            self.lines.push(format!("[{}] => {}", topics.join(", "), data));
+        }
+    }
+
+    /// The snapshot as it would be written to a file.
+    pub fn rendered(&self) -> String {
+        let mut rendered = self.lines.join("\n");
+        rendered.push('\n');
+        rendered
+    }
+
+    /// Compares the captured events against the snapshot at `path`, relative to the package being
+    /// tested. A missing snapshot is written and fails the test, so that it gets reviewed and committed;
+    /// run with `UPDATE_EVENT_SNAPSHOTS=1` to rewrite the snapshots after an intended change.
+    pub fn assert_matches(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        let actual = self.rendered();
+        let update = std::env::var_os(UPDATE_SNAPSHOTS_VAR).is_some();
+
+        if update || !path.exists() {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).unwrap();
+            }
+            fs::write(path, &actual).unwrap();
+            assert!(update, "no event snapshot at {}: wrote a new one, review and commit it", path.display());
+            return;
+        }
+
+        let expected = fs::read_to_string(path).unwrap().replace("\r\n", "\n");
+        assert!(
+            expected == actual,
+            "events differ from the snapshot at {} (run with {UPDATE_SNAPSHOTS_VAR}=1 if the change is intended)\n--- snapshot\n{expected}--- emitted\n{actual}",
+            path.display()
+        );
+    }
+
+    fn render(&mut self, val: Val) -> String {
+        let val = ScVal::try_from_val(self.e, &val).unwrap();
+        self.render_sc(&val)
+    }
+
+    fn render_sc(&mut self, val: &ScVal) -> String {
+        match val {
+            ScVal::Void => "()".into(),
+            ScVal::Bool(b) => b.to_string(),
+            ScVal::U32(v) => format!("{v}u32"),
+            ScVal::I32(v) => format!("{v}i32"),
+            ScVal::U64(v) => format!("{v}u64"),
+            ScVal::I64(v) => format!("{v}i64"),
+            ScVal::U128(parts) => format!("{}u128", (u128::from(parts.hi) << 64) | u128::from(parts.lo)),
+            ScVal::I128(parts) => format!("{}i128", (i128::from(parts.hi) << 64) | i128::from(parts.lo)),
+            ScVal::Symbol(symbol) => symbol.0.to_utf8_string_lossy(),
+            ScVal::String(string) => format!("{:?}", string.0.to_utf8_string_lossy()),
+            ScVal::Bytes(bytes) => format!("0x{}", bytes.0.iter().map(|b| format!("{b:02x}")).collect::<String>()),
+            ScVal::Address(address) => self.address_label(address),
+            ScVal::Vec(items) => {
+                let items: Vec<String> = items.iter().flat_map(|items| items.0.iter()).map(|item| self.render_sc(item)).collect();
+                format!("[{}]", items.join(", "))
+            }
+            ScVal::Map(entries) => {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .flat_map(|entries| entries.0.iter())
+                    .map(|entry| format!("{}: {}", self.render_sc(&entry.key), self.render_sc(&entry.val)))
+                    .collect();
+                format!("{{{}}}", entries.join(", "))
+            }
+            other => format!("{other:?}"),
+        }
+    }
+
+    fn address_label(&mut self, address: &ScAddress) -> String {
+        if let Some((_, name)) = self.labels.iter().find(|(known, _)| known == address) {
+            return name.clone();
+        }
+
+        let name = format!("addr#{}", self.unlabeled);
+        self.unlabeled += 1;
+        self.labels.push((address.clone(), name.clone()));
+        name
+    }
+}
+
+fn to_sc_address(e: &Env, address: &Address) -> ScAddress {
+    let val: Val = address.into_val(e);
+    match ScVal::try_from_val(e, &val).unwrap() {
+        ScVal::Address(address) => address,
+        _ => unreachable!(),
+    }
+}
@@ -0,0 +1,154 @@
+use soroban_sdk::{contract, contractimpl, symbol_short, testutils::{Address as _, Ledger}, vec, Address, Env, String};
+
+use crate::{
+    advance_days, advance_ledgers, advance_time, create_env, create_funded_account, create_native_token_contract, create_reentrant_token,
+    create_token_contract, mint_to, EventSnapshot, Scenario, TokenClient, SECONDS_IN_DAY,
+};
+
+#[contract]
+struct Payer;
+
+#[contractimpl]
+impl Payer {
+    pub fn pay(e: Env, token: Address, to: Address, amount: i128) {
+        TokenClient::new(&e, &token).transfer(&e.current_contract_address(), &to, &amount);
+    }
+
+    pub fn ping(_e: Env) -> u32 {
+        1
+    }
+
+    pub fn notify(e: Env, to: Address, amount: i128) {
+        e.events().publish((symbol_short!("PAID"), to.clone()), (amount, 7_u32, String::from_str(&e, "memo"), None::<u32>, to));
+    }
+}
+
+#[test]
+fn token_helpers() {
+    let e = create_env();
+    let (admin, alice, bob) = (Address::generate(&e), Address::generate(&e), Address::generate(&e));
+    let (token, token_admin) = create_token_contract(&e, &admin);
+
+    mint_to(&token_admin, &[&alice, &bob], 100);
+    assert_eq!(token.balance(&alice), 100);
+    assert_eq!(token.balance(&bob), 100);
+}
+
+#[test]
+fn native_token_helpers() {
+    let e = create_env();
+    let native = create_native_token_contract(&e);
+    let account = create_funded_account(&e, 5_000_000);
+
+    assert_eq!(native.balance(&account), 5_000_000);
+}
+
+#[test]
+fn time_travel() {
+    let e = create_env();
+    e.ledger().set_timestamp(1000);
+
+    advance_time(&e, 10);
+    assert_eq!(e.ledger().timestamp(), 1010);
+
+    advance_days(&e, 2);
+    assert_eq!(e.ledger().timestamp(), 1010 + 2 * SECONDS_IN_DAY);
+
+    let sequence = e.ledger().sequence();
+    advance_ledgers(&e, 5);
+    assert_eq!(e.ledger().sequence(), sequence + 5);
+}
+
+#[test]
+fn reentrant_token_calls_back_once() {
+    let e = create_env();
+    let (alice, bob) = (Address::generate(&e), Address::generate(&e));
+    let token = create_reentrant_token(&e);
+    let payer = e.register(Payer, ());
+
+    // A contract that is not on the call stack takes the call
+    token.mint(&alice, &100);
+    token.arm(&payer, &symbol_short!("ping"), &vec![&e]);
+    token.transfer(&alice, &bob, &40);
+    token.transfer(&alice, &bob, &40);
+
+    assert_eq!(token.attempts(), 1);
+    assert_eq!(token.reentered(), 1);
+    assert_eq!(token.balance(&alice), 20);
+    assert_eq!(token.balance(&bob), 80);
+}
+
+#[test]
+fn host_rejects_reentry_into_the_paying_contract() {
+    let e = create_env();
+    let bob = Address::generate(&e);
+    let token = create_reentrant_token(&e);
+    let payer = e.register(Payer, ());
+
+    token.mint(&payer, &100);
+    token.arm(&payer, &symbol_short!("ping"), &vec![&e]);
+    PayerClient::new(&e, &payer).pay(&token.address, &bob, &100);
+
+    // The transfer still completes; only the call back into `Payer` is refused
+    assert_eq!(token.attempts(), 1);
+    assert_eq!(token.reentered(), 0);
+    assert_eq!(token.balance(&bob), 100);
+}
+
+#[test]
+fn event_snapshot_renders_the_contract_events() {
+    let e = create_env();
+    let (alice, bob) = (Address::generate(&e), Address::generate(&e));
+    let payer = PayerClient::new(&e, &e.register(Payer, ()));
+
+    let mut snapshot = EventSnapshot::new(&e, &payer.address);
+    snapshot.label(&alice, "alice");
+    payer.notify(&alice, &-5);
+    snapshot.capture("notify alice");
+    payer.ping();
+    snapshot.capture("ping");
+    payer.notify(&bob, &10);
+    snapshot.capture("notify bob");
+
+    assert_eq!(
+        snapshot.rendered(),
+        "# notify alice\n\
+         [PAID, alice] => [-5i128, 7u32, \"memo\", (), alice]\n\
+         # ping\n\
+         # notify bob\n\
+         [PAID, addr#0] => [10i128, 7u32, \"memo\", (), addr#0]\n"
+    );
+}
+
+#[test]
+#[should_panic(expected = "events differ from the snapshot")]
+fn event_snapshot_mismatch_fails() {
+    let e = create_env();
+    let payer = PayerClient::new(&e, &e.register(Payer, ()));
+    let path = std::env::temp_dir().join("soroban-examples-testutils-mismatch.events");
+    std::fs::write(&path, "# notify\n[PAID, addr#0] => [1i128, 7u32, \"memo\", (), addr#0]\n").unwrap();
+
+    let mut snapshot = EventSnapshot::new(&e, &payer.address);
+    payer.notify(&Address::generate(&e), &2);
+    snapshot.capture("notify");
+    snapshot.assert_matches(&path);
+}
+
+#[test]
+fn scenario_runs_steps_in_order_as_time_passes() {
+    let e = create_env();
+    e.ledger().set_timestamp(1000);
+
+    let log = Scenario::new(&e, std::vec::Vec::new())
+        .step("open", |e, log| log.push(("open", e.ledger().timestamp())))
+        .wait_days(1)
+        .repeat("pay", 3, 10, |e, log, occurrence| {
+            assert_eq!(occurrence as usize, log.len() - 1);
+            log.push(("pay", e.ledger().timestamp()));
+        })
+        .with(|scenario| scenario.wait(5).step("close", |e, log| log.push(("close", e.ledger().timestamp()))))
+        .run();
+
+    let day = 1000 + SECONDS_IN_DAY;
+    assert_eq!(log, [("open", 1000), ("pay", day), ("pay", day + 10), ("pay", day + 20), ("close", day + 25)]);
+}
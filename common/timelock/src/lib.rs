@@ -0,0 +1,119 @@
+#![no_std]
+
+//! Timelock controller shared by the example contracts. A sensitive operation (identified by a symbol,
+//! e.g. `withdraw`, and the payload it runs with) is first queued under the hash of both, and can only
+//! be executed once the delay it was queued with has passed. The delay can never be shorter than the
+//! minimum the contract configured, so users always get that much notice (through the `TLSCHED` event)
+//! before the operation takes effect. A queued operation can be cancelled until it is executed.
+
+use soroban_examples_storage::{StorageCell, StorageMap};
+use soroban_sdk::{contracttype, symbol_short, xdr::ToXdr, BytesN, Env, IntoVal, Symbol, Val};
+
+pub const TOPIC_TIMELOCK_SCHEDULED: Symbol = symbol_short!("TLSCHED");
+pub const TOPIC_TIMELOCK_EXECUTED: Symbol = symbol_short!("TLEXEC");
+pub const TOPIC_TIMELOCK_CANCELLED: Symbol = symbol_short!("TLCANCEL");
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TimelockOperation {
+    pub op: Symbol,
+    pub ready_at: u64,
+}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum OperationState {
+    Unset = 0,
+    Waiting = 1,
+    Ready = 2,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TimelockError {
+    DelayTooShort,
+    AlreadyQueued,
+    NotQueued,
+    NotReady,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum TimelockKey {
+    MinDelay,
+    Operation(BytesN<32>),
+}
+
+const MIN_DELAY: StorageCell<TimelockKey, u64> = StorageCell::instance(TimelockKey::MinDelay);
+const OPERATIONS: StorageMap<BytesN<32>, TimelockOperation, TimelockKey> = StorageMap::persistent(|hash| TimelockKey::Operation(hash.clone()));
+
+/// Sets the minimum delay, in seconds, operations are queued with. Callers are responsible for
+/// authorizing the change; already queued operations keep their delay.
+pub fn set_min_delay(e: &Env, delay: u64) {
+    MIN_DELAY.set(e, &delay);
+}
+
+pub fn min_delay(e: &Env) -> u64 {
+    MIN_DELAY.get(e).unwrap_or(0)
+}
+
+/// Identifies the operation `op` run with `payload`: the SHA-256 of both, XDR encoded.
+pub fn hash_operation<P: IntoVal<Env, Val>>(e: &Env, op: &Symbol, payload: &P) -> BytesN<32> {
+    let payload: Val = payload.into_val(e);
+    e.crypto().sha256(&(op.clone(), payload).to_xdr(e)).into()
+}
+
+pub fn get_operation(e: &Env, hash: &BytesN<32>) -> Option<TimelockOperation> {
+    OPERATIONS.get(e, hash)
+}
+
+pub fn state(e: &Env, hash: &BytesN<32>) -> OperationState {
+    match get_operation(e, hash) {
+        None => OperationState::Unset,
+        Some(operation) if e.ledger().timestamp() < operation.ready_at => OperationState::Waiting,
+        Some(_) => OperationState::Ready,
+    }
+}
+
+/// Queues `op` with `payload`, executable `delay` seconds from now. Returns the operation hash.
+pub fn schedule<P: IntoVal<Env, Val>>(e: &Env, op: &Symbol, payload: &P, delay: u64) -> Result<BytesN<32>, TimelockError> {
+    if delay < min_delay(e) {
+        return Err(TimelockError::DelayTooShort);
+    }
+
+    let hash = hash_operation(e, op, payload);
+    if OPERATIONS.has(e, &hash) {
+        return Err(TimelockError::AlreadyQueued);
+    }
+
+    let operation = TimelockOperation { op: op.clone(), ready_at: e.ledger().timestamp() + delay };
+    OPERATIONS.set(e, &hash, &operation);
+    e.events().publish((TOPIC_TIMELOCK_SCHEDULED,), (operation.op, hash.clone(), operation.ready_at));
+    Ok(hash)
+}
+
+/// Consumes the queued `op` with `payload` once it is ready, returning its hash. The caller performs
+/// the operation right after.
+pub fn execute<P: IntoVal<Env, Val>>(e: &Env, op: &Symbol, payload: &P) -> Result<BytesN<32>, TimelockError> {
+    let hash = hash_operation(e, op, payload);
+    let operation = get_operation(e, &hash).ok_or(TimelockError::NotQueued)?;
+    if e.ledger().timestamp() < operation.ready_at {
+        return Err(TimelockError::NotReady);
+    }
+
+    OPERATIONS.remove(e, &hash);
+    e.events().publish((TOPIC_TIMELOCK_EXECUTED,), (operation.op, hash.clone()));
+    Ok(hash)
+}
+
+/// Drops the queued operation `hash`. Callers are responsible for authorizing the cancellation.
+pub fn cancel(e: &Env, hash: &BytesN<32>) -> Result<TimelockOperation, TimelockError> {
+    let operation = get_operation(e, hash).ok_or(TimelockError::NotQueued)?;
+
+    OPERATIONS.remove(e, hash);
+    e.events().publish((TOPIC_TIMELOCK_CANCELLED,), (operation.op.clone(), hash.clone()));
+    Ok(operation)
+}
+
+#[cfg(test)]
+mod test;
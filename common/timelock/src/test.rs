@@ -0,0 +1,108 @@
+#![cfg(test)]
+extern crate std;
+
+use soroban_sdk::{contract, symbol_short, testutils::{Address as _, Events, Ledger}, vec, Address, Env, IntoVal, Symbol};
+
+use crate::{cancel, execute, get_operation, hash_operation, min_delay, schedule, set_min_delay, state, OperationState, TimelockError, TOPIC_TIMELOCK_SCHEDULED};
+
+const OP: Symbol = symbol_short!("withdraw");
+const DELAY: u64 = 86400;
+
+#[contract]
+struct Dummy;
+
+fn setup() -> (Env, Address) {
+    let e = Env::default();
+    let contract_id = e.register(Dummy, ());
+    e.as_contract(&contract_id, || set_min_delay(&e, DELAY));
+
+    (e, contract_id)
+}
+
+fn pass_time(e: &Env, secs: u64) {
+    e.ledger().with_mut(|li| li.timestamp += secs);
+}
+
+#[test]
+fn queue_then_execute() {
+    let (e, contract_id) = setup();
+    let hash = e.as_contract(&contract_id, || {
+        let hash = schedule(&e, &OP, &500_i128, DELAY).unwrap();
+        assert_eq!(hash, hash_operation(&e, &OP, &500_i128));
+        assert_eq!(state(&e, &hash), OperationState::Waiting);
+        assert_eq!(execute(&e, &OP, &500_i128), Err(TimelockError::NotReady));
+        hash
+    });
+
+    pass_time(&e, DELAY);
+    e.as_contract(&contract_id, || {
+        assert_eq!(state(&e, &hash), OperationState::Ready);
+        assert_eq!(execute(&e, &OP, &500_i128), Ok(hash.clone()));
+
+        // The operation is consumed
+        assert_eq!(state(&e, &hash), OperationState::Unset);
+        assert_eq!(execute(&e, &OP, &500_i128), Err(TimelockError::NotQueued));
+    });
+}
+
+#[test]
+fn scheduling_emits_event() {
+    let (e, contract_id) = setup();
+    let hash = e.as_contract(&contract_id, || schedule(&e, &OP, &500_i128, DELAY).unwrap());
+
+    assert_eq!(
+        e.events().all(),
+        vec![
+            &e,
+            (contract_id.clone(), (TOPIC_TIMELOCK_SCHEDULED,).into_val(&e), (OP, hash, e.ledger().timestamp() + DELAY).into_val(&e))
+        ]
+    );
+}
+
+#[test]
+fn delay_below_minimum() {
+    let (e, contract_id) = setup();
+    e.as_contract(&contract_id, || {
+        assert_eq!(min_delay(&e), DELAY);
+        assert_eq!(schedule(&e, &OP, &500_i128, DELAY - 1), Err(TimelockError::DelayTooShort));
+
+        // A longer delay is fine
+        let hash = schedule(&e, &OP, &500_i128, 2 * DELAY).unwrap();
+        assert_eq!(get_operation(&e, &hash).unwrap().ready_at, e.ledger().timestamp() + 2 * DELAY);
+    });
+}
+
+#[test]
+fn payload_is_part_of_the_operation() {
+    let (e, contract_id) = setup();
+    pass_time(&e, 1000);
+    e.as_contract(&contract_id, || {
+        schedule(&e, &OP, &500_i128, DELAY).unwrap();
+        assert_eq!(schedule(&e, &OP, &500_i128, DELAY), Err(TimelockError::AlreadyQueued));
+
+        // The same operation with another payload is queued on its own
+        let other = schedule(&e, &OP, &Address::generate(&e), DELAY).unwrap();
+        assert_ne!(other, hash_operation(&e, &OP, &500_i128));
+    });
+
+    pass_time(&e, DELAY);
+    e.as_contract(&contract_id, || {
+        assert_eq!(execute(&e, &OP, &400_i128), Err(TimelockError::NotQueued));
+        assert!(execute(&e, &OP, &500_i128).is_ok());
+    });
+}
+
+#[test]
+fn cancelled_operation_cannot_run() {
+    let (e, contract_id) = setup();
+    e.as_contract(&contract_id, || {
+        let hash = schedule(&e, &OP, &500_i128, DELAY).unwrap();
+        assert_eq!(cancel(&e, &hash).unwrap().op, OP);
+        assert_eq!(cancel(&e, &hash), Err(TimelockError::NotQueued));
+    });
+
+    pass_time(&e, DELAY);
+    e.as_contract(&contract_id, || {
+        assert_eq!(execute(&e, &OP, &500_i128), Err(TimelockError::NotQueued));
+    });
+}
@@ -0,0 +1,41 @@
+#![no_std]
+
+//! Pulling funds through token allowances. A smart wallet or a router approves a spender on the
+//! token and then calls the `*_from` entry point of a contract, which moves the funds with
+//! `transfer_from` instead of asking the owner to sign the payment. The allowance and the owner's
+//! balance are checked up front, so a missing approval surfaces as a contract error rather than as
+//! the token's own failure.
+
+use soroban_sdk::{token::TokenClient, Address, Env};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TransferError {
+    InsufficientAllowance,
+    InsufficientBalance,
+    TransferFailed,
+}
+
+/// Moves `amount` of `token` from `from` to `to`, spending the allowance `from` granted to `spender`.
+/// The token requires the signature of `spender`.
+pub fn transfer_from(e: &Env, token: &Address, spender: &Address, from: &Address, to: &Address, amount: i128) -> Result<(), TransferError> {
+    let tk = TokenClient::new(e, token);
+    if tk.allowance(from, spender) < amount {
+        return Err(TransferError::InsufficientAllowance);
+    }
+
+    if tk.balance(from) < amount {
+        return Err(TransferError::InsufficientBalance);
+    }
+
+    tk.try_transfer_from(spender, from, to, &amount)
+        .map_err(|_| TransferError::TransferFailed)?
+        .map_err(|_| TransferError::TransferFailed)
+}
+
+/// Moves `amount` of `token` from `from` into the calling contract through the allowance of `spender`.
+pub fn pull_from(e: &Env, token: &Address, spender: &Address, from: &Address, amount: i128) -> Result<(), TransferError> {
+    transfer_from(e, token, spender, from, &e.current_contract_address(), amount)
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,83 @@
+#![cfg(test)]
+extern crate std;
+
+use soroban_sdk::{contract, testutils::Address as _, token::{StellarAssetClient, TokenClient}, Address, Env};
+
+use crate::{pull_from, transfer_from, TransferError};
+
+#[contract]
+struct Dummy;
+
+fn setup() -> (Env, Address, Address, Address) {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let contract_id = e.register(Dummy, ());
+    let token = e.register_stellar_asset_contract_v2(Address::generate(&e)).address();
+    let owner = Address::generate(&e);
+    StellarAssetClient::new(&e, &token).mint(&owner, &100);
+
+    (e, contract_id, token, owner)
+}
+
+fn approve(e: &Env, token: &Address, owner: &Address, spender: &Address, amount: i128) {
+    TokenClient::new(e, token).approve(owner, spender, &amount, &(e.ledger().sequence() + 100));
+}
+
+#[test]
+fn pulls_into_the_contract() {
+    let (e, contract_id, token, owner) = setup();
+    let router = Address::generate(&e);
+    approve(&e, &token, &owner, &router, 60);
+
+    e.as_contract(&contract_id, || {
+        assert_eq!(pull_from(&e, &token, &router, &owner, 40), Ok(()));
+    });
+
+    let tk = TokenClient::new(&e, &token);
+    assert_eq!(tk.balance(&contract_id), 40);
+    assert_eq!(tk.balance(&owner), 60);
+    assert_eq!(tk.allowance(&owner, &router), 20);
+}
+
+#[test]
+fn transfers_to_a_third_party() {
+    let (e, contract_id, token, owner) = setup();
+    let (router, seller) = (Address::generate(&e), Address::generate(&e));
+    approve(&e, &token, &owner, &router, 100);
+
+    e.as_contract(&contract_id, || {
+        assert_eq!(transfer_from(&e, &token, &router, &owner, &seller, 100), Ok(()));
+    });
+
+    assert_eq!(TokenClient::new(&e, &token).balance(&seller), 100);
+}
+
+#[test]
+fn missing_allowance() {
+    let (e, contract_id, token, owner) = setup();
+    let router = Address::generate(&e);
+
+    e.as_contract(&contract_id, || {
+        assert_eq!(pull_from(&e, &token, &router, &owner, 1), Err(TransferError::InsufficientAllowance));
+    });
+
+    approve(&e, &token, &owner, &router, 30);
+    e.as_contract(&contract_id, || {
+        assert_eq!(pull_from(&e, &token, &router, &owner, 31), Err(TransferError::InsufficientAllowance));
+    });
+}
+
+#[test]
+fn allowance_above_balance() {
+    let (e, contract_id, token, owner) = setup();
+    let router = Address::generate(&e);
+    approve(&e, &token, &owner, &router, 500);
+
+    e.as_contract(&contract_id, || {
+        assert_eq!(pull_from(&e, &token, &router, &owner, 200), Err(TransferError::InsufficientBalance));
+    });
+
+    // Nothing was spent
+    assert_eq!(TokenClient::new(&e, &token).allowance(&owner, &router), 500);
+}
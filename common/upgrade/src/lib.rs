@@ -0,0 +1,57 @@
+#![no_std]
+
+//! Upgradeability shared by the example contracts. Every contract records the version of its data;
+//! `upgrade` swaps the contract code in place, and the new code brings the data up to its own version
+//! with `migrate`, which runs a contract-specific hook once per version change.
+
+use soroban_examples_storage::bump_instance;
+use soroban_sdk::{contracttype, symbol_short, BytesN, Env, Symbol};
+
+pub const TOPIC_UPGRADED: Symbol = symbol_short!("UPGRADED");
+pub const TOPIC_MIGRATED: Symbol = symbol_short!("MIGRATED");
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum UpgradeError {
+    AlreadyMigrated,
+}
+
+#[contracttype]
+enum UpgradeKey {
+    Version,
+}
+
+/// Version of the contract data. Contracts deployed before adopting this crate report 0.
+pub fn version(e: &Env) -> u32 {
+    e.storage().instance().get(&UpgradeKey::Version).unwrap_or(0)
+}
+
+/// Records the version of freshly initialized data. Meant to be called from the constructor.
+pub fn set_version(e: &Env, version: u32) {
+    e.storage().instance().set(&UpgradeKey::Version, &version);
+    bump_instance(e);
+}
+
+/// Replaces the contract code with the already uploaded wasm `new_wasm_hash`. Callers are responsible
+/// for authorizing it. Storage is kept as is and the constructor is not run again, so the new code must
+/// be followed by a call to its `migrate` entrypoint.
+pub fn upgrade(e: &Env, new_wasm_hash: &BytesN<32>) {
+    e.events().publish((TOPIC_UPGRADED,), (version(e), new_wasm_hash.clone()));
+    e.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+}
+
+/// Brings the data up to `to_version` (the version of the running code), calling `hook` with the
+/// version it migrates from. Fails if the data is already at that version or newer.
+pub fn migrate<F: FnOnce(&Env, u32)>(e: &Env, to_version: u32, hook: F) -> Result<u32, UpgradeError> {
+    let from_version = version(e);
+    if from_version >= to_version {
+        return Err(UpgradeError::AlreadyMigrated);
+    }
+
+    hook(e, from_version);
+    set_version(e, to_version);
+    e.events().publish((TOPIC_MIGRATED,), (from_version, to_version));
+    Ok(from_version)
+}
+
+#[cfg(test)]
+mod test;
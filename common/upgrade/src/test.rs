@@ -0,0 +1,42 @@
+#![cfg(test)]
+extern crate std;
+
+use soroban_sdk::{contract, testutils::Events, vec, Env, IntoVal};
+
+use crate::{migrate, set_version, version, UpgradeError, TOPIC_MIGRATED};
+
+#[contract]
+struct Dummy;
+
+#[test]
+fn version_defaults_to_zero() {
+    let e = Env::default();
+    let contract_id = e.register(Dummy, ());
+    e.as_contract(&contract_id, || {
+        assert_eq!(version(&e), 0);
+        set_version(&e, 1);
+        assert_eq!(version(&e), 1);
+    });
+}
+
+#[test]
+fn migrate_runs_hook_once() {
+    let e = Env::default();
+    let contract_id = e.register(Dummy, ());
+    e.as_contract(&contract_id, || {
+        set_version(&e, 1);
+
+        let mut migrated_from = None;
+        assert_eq!(migrate(&e, 3, |_, from| migrated_from = Some(from)), Ok(1));
+        assert_eq!(migrated_from, Some(1));
+        assert_eq!(version(&e), 3);
+
+        assert_eq!(migrate(&e, 3, |_, _| panic!("hook must not run")), Err(UpgradeError::AlreadyMigrated));
+        assert_eq!(migrate(&e, 2, |_, _| panic!("hook must not run")), Err(UpgradeError::AlreadyMigrated));
+    });
+
+    assert_eq!(
+        e.events().all(),
+        vec![&e, (contract_id.clone(), (TOPIC_MIGRATED,).into_val(&e), (1_u32, 3_u32).into_val(&e))]
+    );
+}
@@ -0,0 +1,161 @@
+#![no_std]
+
+//! All-or-nothing crowdfunding. Unlike an `Investment`, pledgers get nothing back but the project
+//! itself: their pledges are escrowed until the deadline, and only count if the campaign reaches its
+//! goal by then. A successful campaign lets the creator withdraw everything pledged; a failed one lets
+//! every pledger reclaim its own pledge.
+//!
+//! The campaign is `Open` until the deadline and then `Succeeded` or `Failed` depending on the total
+//! pledged; a succeeded campaign becomes `Withdrawn` once the creator has taken the funds.
+
+use soroban_examples_events as events;
+use soroban_sdk::{contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Env, Symbol};
+
+mod storage;
+
+// Topics follow the workspace event schema: (CONTRACT_TAG, action)
+pub const CONTRACT_TAG: Symbol = symbol_short!("CROWDFUND");
+pub const TOPIC_PLEDGED: Symbol = symbol_short!("PLEDGED");
+pub const TOPIC_WITHDRAWN: Symbol = symbol_short!("WITHDRAWN");
+pub const TOPIC_RECLAIMED: Symbol = symbol_short!("RECLAIMED");
+
+macro_rules! require {
+    ($cond:expr, $err:expr) => {
+        if !$cond {
+            return Err($err);
+        }
+    };
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    CampaignClosed = 1101,
+    CampaignNotEnded = 1102,
+    GoalNotReached = 1103,
+    GoalReached = 1104,
+    NothingToReclaim = 1105,
+    AlreadyWithdrawn = 1106,
+    InvalidAmount = 1107,
+    InvalidTerms = 1108,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CampaignConfig {
+    pub creator: Address,
+    pub token: Address,
+    pub goal: i128,
+    pub deadline: u64,
+}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum CampaignStatus {
+    Open = 1,
+    Succeeded = 2,
+    Failed = 3,
+    Withdrawn = 4,
+}
+
+fn current_status(e: &Env, config: &CampaignConfig) -> CampaignStatus {
+    if e.ledger().timestamp() < config.deadline {
+        CampaignStatus::Open
+    } else if storage::get_total(e) < config.goal {
+        CampaignStatus::Failed
+    } else if storage::is_withdrawn(e) {
+        CampaignStatus::Withdrawn
+    } else {
+        CampaignStatus::Succeeded
+    }
+}
+
+#[contract]
+pub struct Crowdfunding;
+
+#[contractimpl]
+impl Crowdfunding {
+    /// Raises `goal` of `token` for `creator` until `deadline`.
+    pub fn __constructor(e: Env, creator: Address, token: Address, goal: i128, deadline: u64) -> Result<(), Error> {
+        creator.require_auth();
+        require!(goal > 0 && deadline > e.ledger().timestamp(), Error::InvalidTerms);
+
+        storage::set_config(&e, &CampaignConfig { creator, token, goal, deadline });
+        Ok(())
+    }
+
+    /// `pledger` escrows `amount` towards the goal; pledges can go on past the goal until the deadline.
+    /// Returns the total pledged by `pledger`.
+    pub fn pledge(e: Env, pledger: Address, amount: i128) -> Result<i128, Error> {
+        pledger.require_auth();
+        require!(amount > 0, Error::InvalidAmount);
+        let config = storage::get_config(&e);
+        require!(current_status(&e, &config) == CampaignStatus::Open, Error::CampaignClosed);
+
+        let pledged = storage::add_pledge(&e, &pledger, amount);
+        storage::set_total(&e, storage::get_total(&e) + amount);
+        token::Client::new(&e, &config.token).transfer(&pledger, &e.current_contract_address(), &amount);
+        events::publish(&e, CONTRACT_TAG, TOPIC_PLEDGED, (pledger, amount));
+        Ok(pledged)
+    }
+
+    /// The creator takes everything pledged to a campaign that reached its goal. Returns the amount.
+    pub fn withdraw(e: Env) -> Result<i128, Error> {
+        let config = storage::get_config(&e);
+        config.creator.require_auth();
+        match current_status(&e, &config) {
+            CampaignStatus::Succeeded => {}
+            CampaignStatus::Open => return Err(Error::CampaignNotEnded),
+            CampaignStatus::Failed => return Err(Error::GoalNotReached),
+            CampaignStatus::Withdrawn => return Err(Error::AlreadyWithdrawn),
+        }
+
+        let total = storage::get_total(&e);
+        storage::set_withdrawn(&e);
+        token::Client::new(&e, &config.token).transfer(&e.current_contract_address(), &config.creator, &total);
+        events::publish(&e, CONTRACT_TAG, TOPIC_WITHDRAWN, total);
+        Ok(total)
+    }
+
+    /// Pays `pledger` its pledge back once the campaign has failed. Returns the amount.
+    pub fn reclaim(e: Env, pledger: Address) -> Result<i128, Error> {
+        pledger.require_auth();
+        let config = storage::get_config(&e);
+        match current_status(&e, &config) {
+            CampaignStatus::Failed => {}
+            CampaignStatus::Open => return Err(Error::CampaignNotEnded),
+            _ => return Err(Error::GoalReached),
+        }
+
+        let amount = storage::get_pledge(&e, &pledger);
+        require!(amount > 0, Error::NothingToReclaim);
+
+        // The total is left as it was at the deadline, so the campaign stays failed
+        storage::remove_pledge(&e, &pledger);
+        token::Client::new(&e, &config.token).transfer(&e.current_contract_address(), &pledger, &amount);
+        events::publish(&e, CONTRACT_TAG, TOPIC_RECLAIMED, (pledger, amount));
+        Ok(amount)
+    }
+
+    pub fn config(e: Env) -> CampaignConfig {
+        storage::get_config(&e)
+    }
+
+    pub fn status(e: Env) -> CampaignStatus {
+        current_status(&e, &storage::get_config(&e))
+    }
+
+    /// Total pledged until the deadline.
+    pub fn total_pledged(e: Env) -> i128 {
+        storage::get_total(&e)
+    }
+
+    pub fn pledge_of(e: Env, pledger: Address) -> i128 {
+        storage::get_pledge(&e, &pledger)
+    }
+}
+
+#[cfg(test)]
+mod test;
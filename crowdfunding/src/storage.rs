@@ -0,0 +1,54 @@
+use crate::CampaignConfig;
+use soroban_examples_storage::{StorageCell, StorageMap};
+use soroban_sdk::{contracttype, Address, Env};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Config,
+    Total,
+    Withdrawn,
+    Pledge(Address),
+}
+
+// Each pledger gets its own persistent entry, so the number of pledgers is not bound by the instance size
+const CONFIG: StorageCell<DataKey, CampaignConfig> = StorageCell::instance(DataKey::Config);
+const TOTAL: StorageCell<DataKey, i128> = StorageCell::instance(DataKey::Total);
+const WITHDRAWN: StorageCell<DataKey, bool> = StorageCell::instance(DataKey::Withdrawn);
+const PLEDGES: StorageMap<Address, i128, DataKey> = StorageMap::persistent(|pledger| DataKey::Pledge(pledger.clone()));
+
+pub fn get_config(e: &Env) -> CampaignConfig {
+    CONFIG.get_or_else(e, || panic!("Campaign config has expired"))
+}
+
+pub fn set_config(e: &Env, config: &CampaignConfig) {
+    CONFIG.set(e, config);
+}
+
+pub fn get_total(e: &Env) -> i128 {
+    TOTAL.get_or_else(e, || 0)
+}
+
+pub fn set_total(e: &Env, total: i128) {
+    TOTAL.set(e, &total);
+}
+
+pub fn is_withdrawn(e: &Env) -> bool {
+    WITHDRAWN.get_or_else(e, || false)
+}
+
+pub fn set_withdrawn(e: &Env) {
+    WITHDRAWN.set(e, &true);
+}
+
+pub fn get_pledge(e: &Env, pledger: &Address) -> i128 {
+    PLEDGES.get_or_else(e, pledger, || 0)
+}
+
+pub fn add_pledge(e: &Env, pledger: &Address, amount: i128) -> i128 {
+    PLEDGES.update(e, pledger, |pledged| pledged.unwrap_or(0) + amount)
+}
+
+pub fn remove_pledge(e: &Env, pledger: &Address) {
+    PLEDGES.remove(e, pledger);
+}
@@ -0,0 +1,170 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::{CampaignStatus, Crowdfunding, CrowdfundingClient, CONTRACT_TAG, TOPIC_RECLAIMED};
+use soroban_examples_events::EVENT_VERSION;
+use soroban_examples_testutils::{advance_days, create_env, create_token_contract, mint_to, TokenClient, SECONDS_IN_DAY};
+use soroban_sdk::{testutils::{Address as _, Events, Ledger}, Address, Env, IntoVal};
+
+const START: u64 = 1_000;
+const DEADLINE: u64 = START + 30 * SECONDS_IN_DAY;
+const GOAL: i128 = 10_000;
+
+struct TestData<'a> {
+    creator: Address,
+    alice: Address,
+    bob: Address,
+    token: TokenClient<'a>,
+    client: CrowdfundingClient<'a>,
+}
+
+fn create_campaign(e: &Env) -> TestData<'_> {
+    let (creator, alice, bob) = (Address::generate(e), Address::generate(e), Address::generate(e));
+    let (token, token_admin) = create_token_contract(e, &Address::generate(e));
+    mint_to(&token_admin, &[&alice, &bob], 10_000);
+    e.ledger().set_timestamp(START);
+
+    let client = CrowdfundingClient::new(e, &e.register(Crowdfunding, (creator.clone(), token.address.clone(), GOAL, DEADLINE)));
+
+    TestData { creator, alice, bob, token, client }
+}
+
+fn end_campaign(e: &Env) {
+    e.ledger().set_timestamp(DEADLINE);
+}
+
+#[test]
+fn test_successful_campaign_pays_the_creator() {
+    let e = create_env();
+    let test_data = create_campaign(&e);
+
+    test_data.client.pledge(&test_data.alice, &4_000);
+    advance_days(&e, 10);
+    test_data.client.pledge(&test_data.bob, &5_000);
+    assert_eq!(test_data.client.pledge(&test_data.alice, &2_000), 6_000);
+    assert_eq!(test_data.client.total_pledged(), 11_000);
+    assert_eq!(test_data.client.status(), CampaignStatus::Open);
+
+    end_campaign(&e);
+    assert_eq!(test_data.client.status(), CampaignStatus::Succeeded);
+    assert_eq!(test_data.client.withdraw(), 11_000);
+    assert_eq!(test_data.client.status(), CampaignStatus::Withdrawn);
+    assert_eq!(test_data.token.balance(&test_data.creator), 11_000);
+    assert_eq!(test_data.token.balance(&test_data.client.address), 0);
+}
+
+#[test]
+fn test_failed_campaign_refunds_every_pledger() {
+    let e = create_env();
+    let test_data = create_campaign(&e);
+
+    test_data.client.pledge(&test_data.alice, &4_000);
+    test_data.client.pledge(&test_data.bob, &5_999);
+
+    end_campaign(&e);
+    assert_eq!(test_data.client.status(), CampaignStatus::Failed);
+    assert_eq!(test_data.client.reclaim(&test_data.alice), 4_000);
+    assert_eq!(
+        e.events().all().last().unwrap(),
+        (test_data.client.address.clone(), (CONTRACT_TAG, TOPIC_RECLAIMED).into_val(&e), (EVENT_VERSION, (test_data.alice.clone(), 4_000_i128)).into_val(&e))
+    );
+
+    // Reclaims do not change the outcome of the campaign
+    assert_eq!(test_data.client.status(), CampaignStatus::Failed);
+    assert_eq!(test_data.client.reclaim(&test_data.bob), 5_999);
+    assert_eq!(test_data.token.balance(&test_data.alice), 10_000);
+    assert_eq!(test_data.token.balance(&test_data.bob), 10_000);
+    assert_eq!(test_data.client.pledge_of(&test_data.alice), 0);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1101)")]
+fn test_pledge_after_the_deadline() {
+    let e = create_env();
+    let test_data = create_campaign(&e);
+
+    end_campaign(&e);
+    test_data.client.pledge(&test_data.alice, &1_000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1102)")]
+fn test_withdraw_before_the_deadline() {
+    let e = create_env();
+    let test_data = create_campaign(&e);
+
+    test_data.client.pledge(&test_data.alice, &10_000);
+    test_data.client.withdraw();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1103)")]
+fn test_withdraw_from_a_failed_campaign() {
+    let e = create_env();
+    let test_data = create_campaign(&e);
+
+    test_data.client.pledge(&test_data.alice, &9_999);
+    end_campaign(&e);
+    test_data.client.withdraw();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1104)")]
+fn test_reclaim_from_a_successful_campaign() {
+    let e = create_env();
+    let test_data = create_campaign(&e);
+
+    test_data.client.pledge(&test_data.alice, &10_000);
+    end_campaign(&e);
+    test_data.client.reclaim(&test_data.alice);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1105)")]
+fn test_reclaim_twice() {
+    let e = create_env();
+    let test_data = create_campaign(&e);
+
+    test_data.client.pledge(&test_data.alice, &1_000);
+    end_campaign(&e);
+    test_data.client.reclaim(&test_data.alice);
+    test_data.client.reclaim(&test_data.alice);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1106)")]
+fn test_withdraw_twice() {
+    let e = create_env();
+    let test_data = create_campaign(&e);
+
+    test_data.client.pledge(&test_data.alice, &10_000);
+    end_campaign(&e);
+    test_data.client.withdraw();
+    test_data.client.withdraw();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1107)")]
+fn test_pledge_nothing() {
+    let e = create_env();
+    let test_data = create_campaign(&e);
+
+    test_data.client.pledge(&test_data.alice, &0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Context, InvalidAction)")]
+fn test_deadline_in_the_past() {
+    let e = create_env();
+    let test_data = create_campaign(&e);
+
+    e.register(Crowdfunding, (test_data.creator.clone(), test_data.token.address.clone(), GOAL, START - 1));
+}
+
+#[test]
+fn error_codes_in_registry_range() {
+    use super::Error;
+
+    assert!(soroban_examples_errors::CROWDFUNDING.contains(Error::CampaignClosed as u32));
+    assert!(soroban_examples_errors::CROWDFUNDING.contains(Error::InvalidTerms as u32));
+}
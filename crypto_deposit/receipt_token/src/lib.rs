@@ -0,0 +1,121 @@
+#![no_std]
+
+use soroban_examples_storage::set_persistent;
+use soroban_sdk::{contract, contracterror, contractimpl, contracttype, Address, Env, String};
+
+#[contracttype]
+enum DataKey {
+    Admin,
+    Metadata,
+    TotalSupply,
+    Balance(Address)
+}
+
+#[contracttype]
+#[derive(Clone)]
+struct Metadata {
+    decimals: u32,
+    name: String,
+    symbol: String
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AddressInsufficientBalance = 301,
+    AmountMustBePositive = 302,
+    SharesNotTransferable = 303
+}
+
+fn get_balance(e: &Env, addr: &Address) -> i128 {
+    e.storage().persistent().get(&DataKey::Balance(addr.clone())).unwrap_or(0_i128)
+}
+
+fn update_balance(e: &Env, addr: &Address, balance: &i128) {
+    set_persistent(e, &DataKey::Balance(addr.clone()), balance);
+}
+
+fn get_total_supply(e: &Env) -> i128 {
+    e.storage().instance().get(&DataKey::TotalSupply).unwrap_or(0_i128)
+}
+
+fn require_admin(e: &Env) {
+    let admin: Address = e.storage().instance().get(&DataKey::Admin).unwrap();
+    admin.require_auth();
+}
+
+fn get_metadata(e: &Env) -> Metadata {
+    e.storage().instance().get(&DataKey::Metadata).unwrap()
+}
+
+/// Share token issued by a vault as a receipt for deposits.
+///
+/// Only the admin (the vault contract) can mint and burn shares. Shares cannot be transferred: the vault
+/// pays withdrawals out of the depositor's own balance, so shares held by anyone else could never be
+/// redeemed.
+#[contract]
+pub struct ReceiptToken;
+
+#[contractimpl]
+impl ReceiptToken {
+
+    pub fn __constructor(e: Env, admin: Address, decimals: u32, name: String, symbol: String) {
+        e.storage().instance().set(&DataKey::Admin, &admin);
+        e.storage().instance().set(&DataKey::Metadata, &Metadata { decimals, name, symbol });
+    }
+
+    pub fn mint(e: Env, to: Address, amount: i128) -> Result<i128, Error> {
+        require_admin(&e);
+        if amount <= 0 {
+            return Err(Error::AmountMustBePositive);
+        }
+
+        let balance = get_balance(&e, &to) + amount;
+        update_balance(&e, &to, &balance);
+        e.storage().instance().set(&DataKey::TotalSupply, &(get_total_supply(&e) + amount));
+        Ok(balance)
+    }
+
+    pub fn burn(e: Env, from: Address, amount: i128) -> Result<i128, Error> {
+        require_admin(&e);
+        if amount <= 0 {
+            return Err(Error::AmountMustBePositive);
+        }
+
+        let balance = get_balance(&e, &from);
+        if balance < amount {
+            return Err(Error::AddressInsufficientBalance);
+        }
+
+        update_balance(&e, &from, &(balance - amount));
+        e.storage().instance().set(&DataKey::TotalSupply, &(get_total_supply(&e) - amount));
+        Ok(balance - amount)
+    }
+
+    pub fn transfer(_e: Env, _from: Address, _to: Address, _amount: i128) -> Result<bool, Error> {
+        Err(Error::SharesNotTransferable)
+    }
+
+    pub fn balance(e: Env, id: Address) -> i128 {
+        get_balance(&e, &id)
+    }
+
+    pub fn total_supply(e: Env) -> i128 {
+        get_total_supply(&e)
+    }
+
+    pub fn decimals(e: Env) -> u32 {
+        get_metadata(&e).decimals
+    }
+
+    pub fn name(e: Env) -> String {
+        get_metadata(&e).name
+    }
+
+    pub fn symbol(e: Env) -> String {
+        get_metadata(&e).symbol
+    }
+}
+
+mod test;
@@ -0,0 +1,69 @@
+#![cfg(test)]
+
+use super::{ReceiptToken, ReceiptTokenClient};
+use soroban_sdk::{Env, Address, String, testutils::Address as _};
+
+fn create_client(env: &Env) -> ReceiptTokenClient<'_> {
+    env.mock_all_auths();
+    let admin = Address::generate(env);
+    let contract_id = env.register(
+        ReceiptToken,
+        (admin, 7_u32, String::from_str(env, "Deposit Share"), String::from_str(env, "DSHR"))
+    );
+
+    ReceiptTokenClient::new(env, &contract_id)
+}
+
+#[test]
+fn mint_and_burn() {
+    let env = Env::default();
+    let client = create_client(&env);
+    let holder = Address::generate(&env);
+
+    assert_eq!(client.mint(&holder, &100), 100);
+    assert_eq!(client.burn(&holder, &10), 90);
+
+    assert_eq!(client.balance(&holder), 90);
+    assert_eq!(client.total_supply(), 90);
+    assert_eq!(client.decimals(), 7);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #303)")]
+fn transfer_not_allowed() {
+    let env = Env::default();
+    let client = create_client(&env);
+    let holder = Address::generate(&env);
+
+    client.mint(&holder, &100);
+    client.transfer(&holder, &Address::generate(&env), &40);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #301)")]
+fn burn_insufficient_balance() {
+    let env = Env::default();
+    let client = create_client(&env);
+    let holder = Address::generate(&env);
+
+    client.mint(&holder, &100);
+    client.burn(&holder, &101);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #302)")]
+fn mint_zero_amount() {
+    let env = Env::default();
+    let client = create_client(&env);
+    let holder = Address::generate(&env);
+
+    client.mint(&holder, &0);
+}
+
+#[test]
+fn error_codes_in_registry_range() {
+    use super::Error;
+
+    assert!(soroban_examples_errors::RECEIPT_TOKEN.contains(Error::AddressInsufficientBalance as u32));
+    assert!(soroban_examples_errors::RECEIPT_TOKEN.contains(Error::AmountMustBePositive as u32));
+}
@@ -1,33 +1,1024 @@
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, Address, Env, Symbol, token, symbol_short};
+use soroban_examples_access::{self as access, AccessError};
+use soroban_examples_ownership::{self as ownership, OwnershipError, PendingAdmin};
+use soroban_examples_pausable as pausable;
+use soroban_examples_timelock::{self as timelock, TimelockError, TimelockOperation};
+use soroban_examples_transfer::{self as transfer, TransferError};
+use soroban_examples_upgrade as upgrade;
+use soroban_sdk::{contract, contracterror, contractimpl, vec, Address, BytesN, Env, IntoVal, Map, String, Symbol, Vec, token, symbol_short};
+use soroban_sdk::auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation};
+
+mod receipt;
+mod storage;
+mod strategy;
+pub use storage::{ActivityKind, ActivityPage, ActivityRecord, DepositCaps, DepositRecord, EmergencyRequest, RecurringDeposit, Role, SavingsGoal, TermDeposit};
+pub use strategy::StrategyPosition;
 
-pub const ADMIN: Symbol = symbol_short!("admin");
 pub const TOKEN: Symbol = symbol_short!("token");
 
+pub const MAX_FEE_BPS: u32 = 10_000;
+
+// Timelock operation under which surplus sweeps are queued
+pub const OP_SWEEP: Symbol = symbol_short!("sweep");
+
+// Bumped on every release so clients can tell which code an upgraded instance is running
+pub const CONTRACT_VERSION: u32 = 1;
+
+pub const TOPIC_DEPOSIT: Symbol = symbol_short!("DEPOSIT");
+pub const TOPIC_WITHDRAW: Symbol = symbol_short!("WITHDRAW");
+pub const TOPIC_WITHDRAW_TO: Symbol = symbol_short!("WDRAWTO");
+pub const TOPIC_GOAL_REACHED: Symbol = symbol_short!("GOALRCHD");
+pub const TOPIC_FEE_CHARGED: Symbol = symbol_short!("FEECHRGD");
+pub const TOPIC_FEES_WITHDRAWN: Symbol = symbol_short!("FEEWDRAWN");
+pub const TOPIC_EMERGENCY_SCHEDULED: Symbol = symbol_short!("EMSCHED");
+pub const TOPIC_EMERGENCY_EXECUTED: Symbol = symbol_short!("EMEXEC");
+pub const TOPIC_EMERGENCY_CANCELLED: Symbol = symbol_short!("EMCANCEL");
+pub const TOPIC_STRATEGY_DEPLOYED: Symbol = symbol_short!("STDEPLOY");
+pub const TOPIC_STRATEGY_SYNCED: Symbol = symbol_short!("STSYNC");
+pub const TOPIC_UPGRADED: Symbol = symbol_short!("UPGRADED");
+pub const TOPIC_TERM_OPENED: Symbol = symbol_short!("TERMOPEN");
+pub const TOPIC_TERM_CLOSED: Symbol = symbol_short!("TERMCLOSE");
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AddressInsufficientBalance = 201,
+    DepositStillLocked = 202,
+    AmountExceedsSurplus = 203,
+    WouldExceedMaxPerUser = 204,
+    WouldExceedMaxTotal = 205,
+    AddressNotAllowed = 206,
+    ContractPaused = 207,
+    NoPendingAdmin = 208,
+    FeeBpsTooHigh = 209,
+    FeesInsufficientBalance = 210,
+    ReceiptTokenAlreadySet = 211,
+    NoEmergencyScheduled = 212,
+    EmergencyStillLocked = 213,
+    EmergencyAlreadyScheduled = 214,
+    NotInitialized = 215,
+    AmountMustBePositive = 216,
+    StrategyNotSet = 217,
+    StrategyAlreadySet = 218,
+    ContractInsufficientBalance = 219,
+    NoRecurringAuthorization = 220,
+    AddressIsNotRecurringPuller = 221,
+    RecurringIntervalNotElapsed = 222,
+    AmountExceedsRecurringLimit = 223,
+    AddressMissingRole = 224,
+    AmountBelowMinDeposit = 225,
+    TermProductNotFound = 226,
+    RateBpsTooHigh = 227,
+    TermDepositNotFound = 228,
+    InsufficientInterestReserve = 229,
+    AdminProposalExpired = 230,
+    StaleAdminProposal = 231,
+    InsufficientAllowance = 232,
+    TokenTransferFailed = 233,
+    OperationAlreadyQueued = 234,
+    OperationNotQueued = 235,
+    OperationNotReady = 236,
+    TimelockDelayTooShort = 237,
+    AlreadyMigrated = 238,
+    NegativeDepositCap = 239
+}
+
+macro_rules! require {
+    ($cond:expr, $err:expr) => {
+        if !$cond {
+            return Err($err);
+        }
+    };
+}
+
+impl From<AccessError> for Error {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::AdminNotSet => Error::NotInitialized,
+            AccessError::MissingRole => Error::AddressMissingRole,
+        }
+    }
+}
+
+impl From<TimelockError> for Error {
+    fn from(err: TimelockError) -> Self {
+        match err {
+            TimelockError::DelayTooShort => Error::TimelockDelayTooShort,
+            TimelockError::AlreadyQueued => Error::OperationAlreadyQueued,
+            TimelockError::NotQueued => Error::OperationNotQueued,
+            TimelockError::NotReady => Error::OperationNotReady,
+        }
+    }
+}
+
+impl From<TransferError> for Error {
+    fn from(err: TransferError) -> Self {
+        match err {
+            TransferError::InsufficientAllowance => Error::InsufficientAllowance,
+            TransferError::InsufficientBalance => Error::AddressInsufficientBalance,
+            TransferError::TransferFailed => Error::TokenTransferFailed,
+        }
+    }
+}
+
+impl From<OwnershipError> for Error {
+    fn from(err: OwnershipError) -> Self {
+        match err {
+            OwnershipError::AdminNotSet => Error::NotInitialized,
+            OwnershipError::NoPendingAdmin => Error::NoPendingAdmin,
+            OwnershipError::ProposalExpired => Error::AdminProposalExpired,
+            OwnershipError::StaleProposal => Error::StaleAdminProposal,
+        }
+    }
+}
+
+fn get_admin(env: &Env) -> Result<Address, Error> {
+    access::get_admin(env).ok_or(Error::NotInitialized)
+}
+
+fn get_token(env: &Env) -> Result<Address, Error> {
+    env.storage().instance().get(&TOKEN).ok_or(Error::NotInitialized)
+}
+
+fn require_admin(env: &Env) -> Result<Address, Error> {
+    let admin = access::require_admin(env)?;
+    Ok(admin)
+}
+
+// The admin implicitly holds every role; any other operator needs an explicit grant
+fn require_role(env: &Env, role: Role, operator: &Address) -> Result<(), Error> {
+    access::require_role(env, &role.symbol(env), operator)?;
+    Ok(())
+}
+
+// Checks a deposit of `amount` for `addr` against the allowlist and caps, returning the user's new balance
+fn validate_deposit(env: &Env, addr: &Address, amount: i128, new_total: i128) -> Result<i128, Error> {
+    require!(amount >= storage::get_min_deposit(env), Error::AmountBelowMinDeposit);
+    if storage::is_allowlist_enabled(env) && !storage::is_allowed(env, addr) {
+        return Err(Error::AddressNotAllowed);
+    }
+
+    let new_balance = storage::get_balance(env, addr) + amount;
+
+    // A cap set to 0 means there is no limit
+    let caps = storage::get_deposit_caps(env);
+    if caps.max_per_user > 0 && new_balance > caps.max_per_user {
+        return Err(Error::WouldExceedMaxPerUser);
+    }
+    if caps.max_total > 0 && new_total > caps.max_total {
+        return Err(Error::WouldExceedMaxTotal);
+    }
+
+    Ok(new_balance)
+}
+
+// Books a deposit in the internal ledger. Every storage write happens here, before the caller
+// pulls the tokens and mints the receipt shares
+fn record_deposit(env: &Env, addr: &Address, token: &Address, amount: i128, new_balance: i128, memo: Option<String>) {
+    storage::update_balance(env, addr, &new_balance);
+    storage::push_history(env, addr, DepositRecord { ts: env.ledger().timestamp(), amount, memo: memo.clone() });
+    storage::push_activity(env, addr, ActivityKind::Deposit, amount);
+
+    // The event carries the goal target, if any, so apps can show the progress
+    let goal = storage::get_goal(env, addr);
+    let goal_target = goal.as_ref().map(|g| g.target);
+    env.events().publish((TOPIC_DEPOSIT,), (addr.clone(), token.clone(), amount, new_balance, memo, goal_target));
+
+    if let Some(mut goal) = goal {
+        if !goal.reached && new_balance >= goal.target {
+            goal.reached = true;
+            storage::update_goal(env, addr, &goal);
+            env.events().publish((TOPIC_GOAL_REACHED,), (addr.clone(), goal.target, new_balance));
+        }
+    }
+}
+
+// Vault mode: `new_total` is the pool including this deposit; shares are minted against the pool before it
+fn mint_receipt(env: &Env, addr: &Address, amount: i128, new_total: i128) {
+    if let Some(receipt_token) = storage::get_receipt_token(env) {
+        let receipt = receipt::ReceiptTokenClient::new(env, &receipt_token);
+        let shares = receipt::shares_for_deposit(amount, receipt.total_supply(), new_total - amount);
+        if shares > 0 {
+            receipt.mint(addr, &shares);
+        }
+    }
+}
+
+// Tokens held by the contract that nobody is owed: not user balances, fees or term deposit liabilities
+fn get_surplus(env: &Env, tk: &token::Client) -> i128 {
+    tk.balance(&env.current_contract_address())
+        - storage::get_total_balance(env)
+        - storage::get_fees_balance(env)
+        - storage::get_term_liabilities(env)
+}
+
+// Share of `amount` given in basis points, rounded down: withdrawal fees and term deposit interest
+fn bps_of(amount: i128, bps: u32) -> i128 {
+    amount * bps as i128 / MAX_FEE_BPS as i128
+}
+
+// Deposits stop while the contract is paused; withdrawals only when the pause includes them
+fn when_not_paused(env: &Env) -> Result<(), Error> {
+    pausable::when_not_paused(env).map_err(|_| Error::ContractPaused)
+}
+
+// Debits `amount` from `addr` and pays it, net of the withdrawal fee, to `destination`
+fn process_withdrawal(env: &Env, addr: &Address, destination: &Address, amount: i128) -> Result<(Address, i128), Error> {
+    require!(amount > 0, Error::AmountMustBePositive);
+    if storage::is_withdrawals_paused(env) {
+        return Err(Error::ContractPaused);
+    }
+
+    let balance = storage::get_balance(env, addr);
+    if balance < amount {
+        return Err(Error::AddressInsufficientBalance);
+    }
+
+    if env.ledger().timestamp() < storage::get_unlock_time(env, addr) {
+        return Err(Error::DepositStillLocked);
+    }
+
+    let token = get_token(env)?;
+    let tk = token::Client::new(env, &token);
+
+    // Vault mode: the withdrawn part of the pool is paid for with the depositor's shares, priced
+    // against the pool before the withdrawal
+    let receipt = storage::get_receipt_token(env).map(|receipt_token| {
+        let receipt = receipt::ReceiptTokenClient::new(env, &receipt_token);
+        let shares = receipt::shares_for_withdrawal(amount, receipt.total_supply(), storage::get_total_balance(env));
+        (receipt, shares)
+    });
+
+    // The ledger is updated before any external call. The fee stays in the contract, booked in the
+    // fees balance instead of the user's
+    let fee = bps_of(amount, storage::get_fee_bps(env));
+    let new_balance = balance - amount;
+    storage::update_balance(env, addr, &new_balance);
+    storage::update_total_balance(env, &(storage::get_total_balance(env) - amount));
+    storage::push_activity(env, addr, ActivityKind::Withdrawal, amount);
+    if fee > 0 {
+        let fees_balance = storage::get_fees_balance(env) + fee;
+        storage::update_fees_balance(env, &fees_balance);
+        env.events().publish((TOPIC_FEE_CHARGED,), (addr.clone(), fee, fees_balance));
+    }
+
+    if let Some((receipt, shares)) = receipt {
+        if shares > 0 {
+            receipt.burn(addr, &shares);
+        }
+    }
+    tk.transfer(&env.current_contract_address(), destination, &(amount - fee));
+
+    Ok((token, new_balance))
+}
+
 #[contract]
 pub struct CryptoDeposit;
 
 #[contractimpl]
 impl CryptoDeposit {
 
-    pub fn __constructor(env: Env, admin_addr: Address, token_addr: Address) {
-        env.storage().instance().set(&ADMIN, &admin_addr);
+    /// `token_addr` can be any Stellar Asset Contract, including the native one, so the same
+    /// contract holds lumens as well as issued assets. Amounts are always in the token's smallest
+    /// unit (stroops for XLM, which uses 7 decimals).
+    ///
+    /// `fee_bps` is the fee charged on every withdrawal, in basis points (100 = 1%).
+    /// `emergency_delay` is the time, in seconds, between scheduling and executing an emergency
+    /// withdrawal. It cannot be changed afterwards, so depositors can rely on it.
+    /// `min_deposit` is the smallest amount a single deposit can credit (0 for no minimum), which keeps
+    /// dust entries out of per-user storage.
+    pub fn __constructor(env: Env, admin_addr: Address, token_addr: Address, fee_bps: u32, emergency_delay: u64, min_deposit: i128) -> Result<(), Error> {
+        if fee_bps > MAX_FEE_BPS {
+            return Err(Error::FeeBpsTooHigh);
+        }
+        require!(min_deposit >= 0, Error::AmountMustBePositive);
+
+        access::set_admin(&env, &admin_addr);
         env.storage().instance().set(&TOKEN, &token_addr);
+        storage::update_fee_bps(&env, &fee_bps);
+        storage::update_emergency_delay(&env, &emergency_delay);
+        timelock::set_min_delay(&env, emergency_delay);
+        storage::update_min_deposit(&env, &min_deposit);
+        upgrade::set_version(&env, CONTRACT_VERSION);
+        Ok(())
     }
-    
-    
-    pub fn deposit(env: Env, addr: Address, amount: i128) -> i128 {
+
+
+    /// `memo` is an optional reference (e.g. an invoice id) kept in the user's history and
+    /// included in the `DEPOSIT` event, so business systems can reconcile payments.
+    pub fn deposit(env: Env, addr: Address, amount: i128, lock_secs: Option<u64>, memo: Option<String>) -> Result<i128, Error> {
 
         addr.require_auth();
-        let token: Address = env.storage().instance().get(&TOKEN).unwrap();
-        
+        require!(amount > 0, Error::AmountMustBePositive);
+        when_not_paused(&env)?;
+
+        let new_total = storage::get_total_balance(&env) + amount;
+        let new_balance = validate_deposit(&env, &addr, amount, new_total)?;
+
+        let token = get_token(&env)?;
+
+        storage::update_total_balance(&env, &new_total);
+
+        // A new lock can only push the unlock time forward, never release funds earlier
+        if let Some(secs) = lock_secs {
+            let unlock_ts = env.ledger().timestamp() + secs;
+            if unlock_ts > storage::get_unlock_time(&env, &addr) {
+                storage::update_unlock_time(&env, &addr, &unlock_ts);
+            }
+        }
+
+        record_deposit(&env, &addr, &token, amount, new_balance, memo);
+
         let tk = token::Client::new(&env, &token);
         tk.transfer(&addr, &env.current_contract_address(), &amount);
+        mint_receipt(&env, &addr, amount, new_total);
+
+        let current_contract_balance = tk.balance(&env.current_contract_address());
+        Ok(current_contract_balance)
+    }
+
+    /// Deposits `amount` on behalf of `owner`, pulled through the token allowance `owner` granted to `spender`.
+    ///
+    /// Lets a router contract or a scheduled job fund the user's balance without the user signing
+    /// each deposit: only `spender` authorizes the call. Fails with `InsufficientAllowance` if `owner`
+    /// has not approved `spender` for `amount`.
+    pub fn deposit_from(env: Env, spender: Address, owner: Address, amount: i128) -> Result<i128, Error> {
+
+        spender.require_auth();
+        require!(amount > 0, Error::AmountMustBePositive);
+        when_not_paused(&env)?;
+
+        let new_total = storage::get_total_balance(&env) + amount;
+        let new_balance = validate_deposit(&env, &owner, amount, new_total)?;
+
+        let token = get_token(&env)?;
+
+        storage::update_total_balance(&env, &new_total);
+        record_deposit(&env, &owner, &token, amount, new_balance, None);
+
+        transfer::pull_from(&env, &token, &spender, &owner, amount)?;
+        mint_receipt(&env, &owner, amount, new_total);
+
+        let current_contract_balance = token::Client::new(&env, &token).balance(&env.current_contract_address());
+        Ok(current_contract_balance)
+    }
+
+    /// Lets `puller` move up to `amount` from `addr` into the vault at most once every `interval_secs`.
+    ///
+    /// The tokens are pulled through a token allowance `addr` must grant to this contract. The first
+    /// pull can happen right away; replaces any previous authorization.
+    pub fn authorize_recurring(env: Env, addr: Address, amount: i128, interval_secs: u64, puller: Address) -> Result<RecurringDeposit, Error> {
+        addr.require_auth();
+        require!(amount > 0, Error::AmountMustBePositive);
+
+        let recurring = RecurringDeposit {
+            amount,
+            interval_secs,
+            puller,
+            next_pull_ts: env.ledger().timestamp(),
+        };
+
+        storage::update_recurring(&env, &addr, &recurring);
+        Ok(recurring)
+    }
+
+    pub fn cancel_recurring(env: Env, addr: Address) -> Result<bool, Error> {
+        addr.require_auth();
+        storage::get_recurring(&env, &addr).ok_or(Error::NoRecurringAuthorization)?;
+        storage::remove_recurring(&env, &addr);
+        Ok(true)
+    }
+
+    pub fn get_recurring(env: Env, addr: Address) -> Option<RecurringDeposit> {
+        storage::get_recurring(&env, &addr)
+    }
+
+    /// Executes a recurring deposit for `addr`, called by its designated puller.
+    pub fn pull_recurring(env: Env, puller: Address, addr: Address, amount: i128) -> Result<i128, Error> {
+
+        puller.require_auth();
+        require!(amount > 0, Error::AmountMustBePositive);
+        when_not_paused(&env)?;
+
+        let mut recurring = storage::get_recurring(&env, &addr).ok_or(Error::NoRecurringAuthorization)?;
+        require!(recurring.puller == puller, Error::AddressIsNotRecurringPuller);
+        require!(amount <= recurring.amount, Error::AmountExceedsRecurringLimit);
+        require!(env.ledger().timestamp() >= recurring.next_pull_ts, Error::RecurringIntervalNotElapsed);
+
+        let new_total = storage::get_total_balance(&env) + amount;
+        let new_balance = validate_deposit(&env, &addr, amount, new_total)?;
+
+        let token = get_token(&env)?;
+
+        recurring.next_pull_ts = env.ledger().timestamp() + recurring.interval_secs;
+        storage::update_recurring(&env, &addr, &recurring);
+        storage::update_total_balance(&env, &new_total);
+        record_deposit(&env, &addr, &token, amount, new_balance, None);
+
+        transfer::pull_from(&env, &token, &env.current_contract_address(), &addr, amount)?;
+        mint_receipt(&env, &addr, amount, new_total);
+
+        let current_contract_balance = token::Client::new(&env, &token).balance(&env.current_contract_address());
+        Ok(current_contract_balance)
+    }
+
+    /// Credits deposits to many users in one go, funded by `payer` with a single token transfer.
+    ///
+    /// Meant for payroll or airdrop accounts. Every entry goes through the same allowlist and cap
+    /// checks as a regular deposit, and emits its own `DEPOSIT` event.
+    pub fn deposit_for_many(env: Env, payer: Address, entries: Vec<(Address, i128)>) -> Result<i128, Error> {
+
+        payer.require_auth();
+        when_not_paused(&env)?;
+
+        let token = get_token(&env)?;
+        let initial_total = storage::get_total_balance(&env);
+        let mut new_total = initial_total;
+        let mut batch_amount: i128 = 0;
+
+        for (addr, amount) in entries.iter() {
+            require!(amount > 0, Error::AmountMustBePositive);
+            new_total += amount;
+            batch_amount += amount;
+            let new_balance = validate_deposit(&env, &addr, amount, new_total)?;
+            record_deposit(&env, &addr, &token, amount, new_balance, None);
+        }
+
+        storage::update_total_balance(&env, &new_total);
+
+        let tk = token::Client::new(&env, &token);
+        tk.transfer(&payer, &env.current_contract_address(), &batch_amount);
+
+        // Shares are minted entry by entry, as if every deposit had been made on its own
+        let mut pool = initial_total;
+        for (addr, amount) in entries.iter() {
+            pool += amount;
+            mint_receipt(&env, &addr, amount, pool);
+        }
+
         let current_contract_balance = tk.balance(&env.current_contract_address());
-        current_contract_balance
+        Ok(current_contract_balance)
+    }
+
+    pub fn withdraw(env: Env, addr: Address, amount: i128) -> Result<i128, Error> {
+
+        addr.require_auth();
+        let (token, new_balance) = process_withdrawal(&env, &addr, &addr, amount)?;
+        env.events().publish((TOPIC_WITHDRAW,), (addr, token, amount, new_balance));
+
+        Ok(new_balance)
+    }
+
+    /// Withdraws `amount` from the vault balance of `addr` and pays it straight to `destination`.
+    ///
+    /// Same checks and fee as `withdraw`; only `addr` has to authorize. Returns the new balance of `addr`.
+    pub fn withdraw_to(env: Env, addr: Address, destination: Address, amount: i128) -> Result<i128, Error> {
+
+        addr.require_auth();
+        let (token, new_balance) = process_withdrawal(&env, &addr, &destination, amount)?;
+        env.events().publish((TOPIC_WITHDRAW_TO,), (addr, destination, token, amount, new_balance));
+
+        Ok(new_balance)
+    }
+
+    /// Queues the sweep of `amount` surplus tokens to the admin (admin only).
+    ///
+    /// The sweep can be executed with `admin_sweep` once the emergency delay has passed; the
+    /// `TLSCHED` event warns depositors in the meantime. Returns the hash identifying the sweep.
+    pub fn queue_sweep(env: Env, amount: i128) -> Result<BytesN<32>, Error> {
+        require_admin(&env)?;
+        require!(amount > 0, Error::AmountMustBePositive);
+        let token = get_token(&env)?;
+        require!(amount <= get_surplus(&env, &token::Client::new(&env, &token)), Error::AmountExceedsSurplus);
+
+        Ok(timelock::schedule(&env, &OP_SWEEP, &amount, storage::get_emergency_delay(&env))?)
+    }
+
+    /// Transfers to the admin tokens held by the contract beyond what it owes (balances, fees and term deposits).
+    ///
+    /// The sweep of `amount` must have been queued with `queue_sweep` at least the emergency delay
+    /// before. Only the surplus (e.g. donations or airdrops sent straight to the contract) can be
+    /// swept; user principal, as recorded in the internal ledger, is never touched. Returns the
+    /// surplus left after the sweep.
+    pub fn admin_sweep(env: Env, amount: i128) -> Result<i128, Error> {
+        let admin = require_admin(&env)?;
+        require!(amount > 0, Error::AmountMustBePositive);
+        let token = get_token(&env)?;
+        let tk = token::Client::new(&env, &token);
+
+        // The surplus may have shrunk while the sweep was queued
+        let surplus = get_surplus(&env, &tk);
+        if amount > surplus {
+            return Err(Error::AmountExceedsSurplus);
+        }
+
+        timelock::execute(&env, &OP_SWEEP, &amount)?;
+        tk.transfer(&env.current_contract_address(), &admin, &amount);
+        Ok(surplus - amount)
+    }
+
+    /// Drops the queued sweep `hash` (admin only).
+    pub fn cancel_sweep(env: Env, hash: BytesN<32>) -> Result<bool, Error> {
+        require_admin(&env)?;
+        timelock::cancel(&env, &hash)?;
+        Ok(true)
+    }
+
+    pub fn get_queued_sweep(env: Env, hash: BytesN<32>) -> Option<TimelockOperation> {
+        timelock::get_operation(&env, &hash)
+    }
+
+    /// Transfers collected withdrawal fees to the admin (admin or treasurer). Returns the fees left.
+    pub fn withdraw_fees(env: Env, operator: Address, amount: i128) -> Result<i128, Error> {
+        require_role(&env, Role::Treasurer, &operator)?;
+        require!(amount > 0, Error::AmountMustBePositive);
+        let fees_balance = storage::get_fees_balance(&env);
+        if amount > fees_balance {
+            return Err(Error::FeesInsufficientBalance);
+        }
+
+        let token = get_token(&env)?;
+        let tk = token::Client::new(&env, &token);
+
+        let new_fees_balance = fees_balance - amount;
+        storage::update_fees_balance(&env, &new_fees_balance);
+        let admin = get_admin(&env)?;
+        tk.transfer(&env.current_contract_address(), &admin, &amount);
+        env.events().publish((TOPIC_FEES_WITHDRAWN,), (operator, amount, new_fees_balance));
+
+        Ok(new_fees_balance)
+    }
+
+    pub fn min_deposit(env: Env) -> i128 {
+        storage::get_min_deposit(&env)
+    }
+
+    pub fn fee_bps(env: Env) -> u32 {
+        storage::get_fee_bps(&env)
+    }
+
+    pub fn fees_balance(env: Env) -> i128 {
+        storage::get_fees_balance(&env)
+    }
+
+    /// First phase of the emergency escape hatch (admin only).
+    ///
+    /// Announces that `amount` tokens will be moved to the admin once the emergency delay has
+    /// passed. The `EMSCHED` event gives depositors that time to withdraw their funds first.
+    pub fn schedule_emergency(env: Env, amount: i128) -> Result<EmergencyRequest, Error> {
+        let admin = require_admin(&env)?;
+        require!(amount > 0, Error::AmountMustBePositive);
+        if storage::get_emergency_request(&env).is_some() {
+            return Err(Error::EmergencyAlreadyScheduled);
+        }
+
+        let request = EmergencyRequest {
+            amount,
+            execute_after: env.ledger().timestamp() + storage::get_emergency_delay(&env),
+        };
+
+        storage::update_emergency_request(&env, &request);
+        env.events().publish((TOPIC_EMERGENCY_SCHEDULED,), (admin, request.amount, request.execute_after));
+        Ok(request)
+    }
+
+    /// Second phase of the emergency escape hatch: transfers the scheduled amount to the admin.
+    ///
+    /// The internal ledger is left as it is, since the user balances are still owed to them.
+    pub fn execute_emergency(env: Env) -> Result<i128, Error> {
+        let admin = require_admin(&env)?;
+        let request = storage::get_emergency_request(&env).ok_or(Error::NoEmergencyScheduled)?;
+        if env.ledger().timestamp() < request.execute_after {
+            return Err(Error::EmergencyStillLocked);
+        }
+
+        let token = get_token(&env)?;
+        let tk = token::Client::new(&env, &token);
+
+        storage::remove_emergency_request(&env);
+        tk.transfer(&env.current_contract_address(), &admin, &request.amount);
+        env.events().publish((TOPIC_EMERGENCY_EXECUTED,), (admin, request.amount));
+        Ok(request.amount)
+    }
+
+    pub fn cancel_emergency(env: Env) -> Result<bool, Error> {
+        let admin = require_admin(&env)?;
+        let request = storage::get_emergency_request(&env).ok_or(Error::NoEmergencyScheduled)?;
+
+        storage::remove_emergency_request(&env);
+        env.events().publish((TOPIC_EMERGENCY_CANCELLED,), (admin, request.amount));
+        Ok(true)
+    }
+
+    pub fn get_emergency_request(env: Env) -> Option<EmergencyRequest> {
+        storage::get_emergency_request(&env)
+    }
+
+    /// Configures the fixed-term product lasting `term_days` (admin only).
+    ///
+    /// `rate_bps` is the interest paid over the whole term, not an annual rate. A rate of 0 removes
+    /// the product; deposits already opened keep the interest they were promised.
+    pub fn set_term_product(env: Env, term_days: u32, rate_bps: u32) -> Result<Map<u32, u32>, Error> {
+        require_admin(&env)?;
+        require!(term_days > 0, Error::TermProductNotFound);
+        require!(rate_bps <= MAX_FEE_BPS, Error::RateBpsTooHigh);
+
+        let mut products = storage::get_term_products(&env);
+        if rate_bps == 0 {
+            products.remove(term_days);
+        } else {
+            products.set(term_days, rate_bps);
+        }
+
+        storage::update_term_products(&env, &products);
+        Ok(products)
+    }
+
+    pub fn get_term_products(env: Env) -> Map<u32, u32> {
+        storage::get_term_products(&env)
+    }
+
+    /// Opens a fixed-term deposit of `amount` for `term_days`, kept apart from the flexible balance.
+    ///
+    /// The interest is fixed now and reserved out of the contract surplus, which the admin funds by
+    /// sending tokens to the contract, so every promised payout is covered.
+    pub fn open_term_deposit(env: Env, addr: Address, amount: i128, term_days: u32) -> Result<TermDeposit, Error> {
+
+        addr.require_auth();
+        require!(amount > 0, Error::AmountMustBePositive);
+        require!(amount >= storage::get_min_deposit(&env), Error::AmountBelowMinDeposit);
+        when_not_paused(&env)?;
+        if storage::is_allowlist_enabled(&env) && !storage::is_allowed(&env, &addr) {
+            return Err(Error::AddressNotAllowed);
+        }
+
+        let rate_bps = storage::get_term_products(&env).get(term_days).ok_or(Error::TermProductNotFound)?;
+        let interest = bps_of(amount, rate_bps);
+
+        let token = get_token(&env)?;
+        let tk = token::Client::new(&env, &token);
+        require!(interest <= get_surplus(&env, &tk), Error::InsufficientInterestReserve);
+        if tk.balance(&addr) < amount {
+            return Err(Error::AddressInsufficientBalance);
+        }
+
+        let mut deposits = storage::get_term_deposits(&env, &addr);
+        let start_ts = env.ledger().timestamp();
+        let deposit = TermDeposit {
+            id: storage::next_term_deposit_id(&env, &addr),
+            amount,
+            interest,
+            start_ts,
+            maturity_ts: start_ts + term_days as u64 * 86400,
+        };
+
+        deposits.set(deposit.id, deposit.clone());
+        storage::update_term_deposits(&env, &addr, &deposits);
+        storage::update_term_liabilities(&env, &(storage::get_term_liabilities(&env) + amount + interest));
+        tk.transfer(&addr, &env.current_contract_address(), &amount);
+        env.events().publish((TOPIC_TERM_OPENED,), (addr, deposit.id, amount, interest, deposit.maturity_ts));
+        Ok(deposit)
+    }
+
+    /// Closes the term deposit `id` of `addr` and pays it out. Returns the amount paid.
+    ///
+    /// From maturity on the payout is the principal plus the interest; closing earlier returns
+    /// only the principal and the forfeited interest goes back to the surplus.
+    pub fn close_term_deposit(env: Env, addr: Address, id: u64) -> Result<i128, Error> {
+
+        addr.require_auth();
+        if storage::is_withdrawals_paused(&env) {
+            return Err(Error::ContractPaused);
+        }
+
+        let mut deposits = storage::get_term_deposits(&env, &addr);
+        let deposit = deposits.get(id).ok_or(Error::TermDepositNotFound)?;
+        let interest_paid = if env.ledger().timestamp() >= deposit.maturity_ts { deposit.interest } else { 0 };
+        let payout = deposit.amount + interest_paid;
+
+        deposits.remove(id);
+        storage::update_term_deposits(&env, &addr, &deposits);
+        storage::update_term_liabilities(&env, &(storage::get_term_liabilities(&env) - deposit.amount - deposit.interest));
+
+        let token = get_token(&env)?;
+        let tk = token::Client::new(&env, &token);
+        tk.transfer(&env.current_contract_address(), &addr, &payout);
+        env.events().publish((TOPIC_TERM_CLOSED,), (addr, id, payout, interest_paid));
+        Ok(payout)
+    }
+
+    pub fn get_term_deposits(env: Env, addr: Address) -> Vec<TermDeposit> {
+        storage::get_term_deposits(&env, &addr).values()
+    }
+
+    /// Opts the vault into investing idle funds in the workspace's InvestmentContract (admin only, once).
+    pub fn set_strategy(env: Env, investment: Address) -> Result<bool, Error> {
+        require_admin(&env)?;
+        if storage::get_strategy(&env).is_some() {
+            return Err(Error::StrategyAlreadySet);
+        }
+
+        storage::update_strategy(&env, &StrategyPosition {
+            investment,
+            deployed: 0,
+            returned: 0,
+            credited: 0,
+            positions: Vec::new(&env),
+        });
+        Ok(true)
+    }
+
+    /// Invests `amount` of the contract surplus in the strategy's Investment contract (admin only).
+    ///
+    /// User balances, fees and term deposits are never deployed, so withdrawals are always covered.
+    /// The Investment contract pays the returns straight back to this contract: the deployed amount goes
+    /// back to the surplus and `sync_strategy` credits the yield on top to the depositors.
+    pub fn deploy_to_strategy(env: Env, amount: i128) -> Result<StrategyPosition, Error> {
+        require_admin(&env)?;
+        require!(amount > 0, Error::AmountMustBePositive);
+        let mut strategy = storage::get_strategy(&env).ok_or(Error::StrategyNotSet)?;
+
+        let token = get_token(&env)?;
+        let tk = token::Client::new(&env, &token);
+        require!(amount <= get_surplus(&env, &tk), Error::AmountExceedsSurplus);
+
+        // The Investment contract pulls the tokens from this contract, one call deeper than the
+        // direct invocation, so that transfer has to be authorized explicitly
+        env.authorize_as_current_contract(vec![
+            &env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token,
+                    fn_name: Symbol::new(&env, "transfer"),
+                    args: (env.current_contract_address(), strategy.investment.clone(), amount).into_val(&env),
+                },
+                sub_invocations: vec![&env],
+            }),
+        ]);
+
+        // The deployed amount is booked first; the position it opens is only known once the
+        // Investment contract answers
+        strategy.deployed += amount;
+        storage::update_strategy(&env, &strategy);
+
+        let investment_client = strategy::InvestmentClient::new(&env, &strategy.investment);
+        let investment = investment_client.invest(&env.current_contract_address(), &amount);
+        if !strategy.positions.contains(investment.claimable_ts) {
+            strategy.positions.push_back(investment.claimable_ts);
+            storage::update_strategy(&env, &strategy);
+        }
+        env.events().publish((TOPIC_STRATEGY_DEPLOYED,), (strategy.investment.clone(), amount, strategy.deployed));
+        Ok(strategy)
+    }
+
+    /// Refreshes how much the Investment contract has paid back to the vault (permissionless).
+    ///
+    /// Returns beyond the deployed amount are yield, credited to the depositors in proportion to their
+    /// balances. Yield that cannot be credited yet (e.g. while nobody holds a balance) is left for a later sync.
+    pub fn sync_strategy(env: Env) -> Result<StrategyPosition, Error> {
+        let mut strategy = storage::get_strategy(&env).ok_or(Error::StrategyNotSet)?;
+        let investment_client = strategy::InvestmentClient::new(&env, &strategy.investment);
+
+        let mut returned: i128 = 0;
+        for ts in strategy.positions.iter() {
+            returned += investment_client.get_investment(&env.current_contract_address(), &ts).paid;
+        }
+
+        strategy.returned = returned;
+        let to_credit = (returned - strategy.deployed).max(0) - strategy.credited;
+        if to_credit > 0 {
+            strategy.credited += storage::credit_yield(&env, to_credit);
+        }
+        storage::update_strategy(&env, &strategy);
+        env.events().publish((TOPIC_STRATEGY_SYNCED,), (strategy.investment.clone(), strategy.deployed, strategy.returned));
+        Ok(strategy)
+    }
+
+    pub fn strategy_position(env: Env) -> Option<StrategyPosition> {
+        storage::get_strategy(&env)
+    }
+
+    /// Turns the contract into a tokenized vault (admin only, once).
+    ///
+    /// From then on every deposit mints shares of `receipt_token` proportional to the pool, and
+    /// every withdrawal burns the shares matching its pro-rata part of the pool. The receipt token
+    /// must have this contract as its admin. Shares are not transferable, since withdrawals are paid
+    /// out of the depositor's own balance.
+    pub fn set_receipt_token(env: Env, receipt_token: Address) -> Result<bool, Error> {
+        require_admin(&env)?;
+        if storage::get_receipt_token(&env).is_some() {
+            return Err(Error::ReceiptTokenAlreadySet);
+        }
+
+        storage::update_receipt_token(&env, &receipt_token);
+        Ok(true)
+    }
+
+    pub fn receipt_token(env: Env) -> Option<Address> {
+        storage::get_receipt_token(&env)
+    }
+
+    /// Caps the balance of each user at `max_per_user` and the sum of all balances at `max_total`
+    /// (admin only). A cap of 0 leaves that limit off; negative caps are rejected. Lowering a cap
+    /// below the current balances blocks further deposits but forces no withdrawal.
+    pub fn set_deposit_caps(env: Env, max_per_user: i128, max_total: i128) -> Result<bool, Error> {
+        require_admin(&env)?;
+        require!(max_per_user >= 0 && max_total >= 0, Error::NegativeDepositCap);
+        storage::update_deposit_caps(&env, &DepositCaps { max_per_user, max_total });
+        Ok(true)
+    }
+
+    pub fn get_deposit_caps(env: Env) -> DepositCaps {
+        storage::get_deposit_caps(&env)
+    }
+
+    pub fn set_allowlist_enabled(env: Env, enabled: bool) -> Result<bool, Error> {
+        require_admin(&env)?;
+        storage::update_allowlist_enabled(&env, enabled);
+        Ok(true)
+    }
+
+    pub fn is_allowlist_enabled(env: Env) -> bool {
+        storage::is_allowlist_enabled(&env)
+    }
+
+    pub fn allow(env: Env, addr: Address) -> Result<bool, Error> {
+        require_admin(&env)?;
+        storage::add_allowed(&env, &addr);
+        Ok(true)
+    }
+
+    pub fn disallow(env: Env, addr: Address) -> Result<bool, Error> {
+        require_admin(&env)?;
+        storage::remove_allowed(&env, &addr);
+        Ok(true)
+    }
+
+    pub fn is_allowed(env: Env, addr: Address) -> bool {
+        storage::is_allowed(&env, &addr)
+    }
+
+    /// Grants `role` to `addr` (admin only), letting it run that role's operations without the admin key.
+    pub fn grant_role(env: Env, role: Role, addr: Address) -> Result<bool, Error> {
+        access::grant_role(&env, &role.symbol(&env), &addr)?;
+        Ok(true)
+    }
+
+    pub fn revoke_role(env: Env, role: Role, addr: Address) -> Result<bool, Error> {
+        access::revoke_role(&env, &role.symbol(&env), &addr)?;
+        Ok(true)
+    }
+
+    pub fn has_role(env: Env, role: Role, addr: Address) -> bool {
+        access::has_role(&env, &role.symbol(&env), &addr)
+    }
+
+    /// Stops new deposits (admin or compliance). Withdrawals are stopped too when `include_withdrawals` is true.
+    pub fn pause(env: Env, operator: Address, include_withdrawals: bool) -> Result<bool, Error> {
+        require_role(&env, Role::Compliance, &operator)?;
+        pausable::pause(&env, &operator);
+        storage::update_withdrawals_paused(&env, include_withdrawals);
+        Ok(true)
+    }
+
+    /// Resumes both deposits and withdrawals (admin or compliance).
+    pub fn unpause(env: Env, operator: Address) -> Result<bool, Error> {
+        require_role(&env, Role::Compliance, &operator)?;
+        pausable::unpause(&env, &operator);
+        storage::update_withdrawals_paused(&env, false);
+        Ok(true)
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        pausable::is_paused(&env)
+    }
+
+    pub fn is_withdrawals_paused(env: Env) -> bool {
+        storage::is_withdrawals_paused(&env)
+    }
+
+    /// First step of an admin rotation: the current admin nominates its successor.
+    ///
+    /// The change only takes effect once the nominated address calls `accept_admin`, so a typo
+    /// in the new address can never lock the contract. The proposal expires after a week.
+    pub fn propose_admin(env: Env, new_admin: Address) -> Result<bool, Error> {
+        ownership::propose_admin(&env, &new_admin)?;
+        Ok(true)
+    }
+
+    /// Second step of an admin rotation: the nominated address takes over the admin role.
+    pub fn accept_admin(env: Env) -> Result<bool, Error> {
+        ownership::accept_admin(&env)?;
+        Ok(true)
+    }
+
+    /// Withdraws the pending admin proposal (admin only).
+    pub fn cancel_admin_transfer(env: Env) -> Result<bool, Error> {
+        ownership::cancel_admin_transfer(&env)?;
+        Ok(true)
+    }
+
+    pub fn pending_admin(env: Env) -> Option<PendingAdmin> {
+        ownership::pending_admin(&env)
+    }
+
+    /// Replaces the contract code with the already uploaded wasm `new_wasm_hash` (admin only).
+    ///
+    /// Storage (balances, config, history) is kept as is; the constructor is not run again, so the
+    /// new code must be followed by a call to its `migrate`.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<bool, Error> {
+        let admin = require_admin(&env)?;
+        env.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+        env.events().publish((TOPIC_UPGRADED,), (admin, new_wasm_hash));
+        Ok(true)
+    }
+
+    /// Brings the stored data up to the version of the running code (admin only), returning the
+    /// version it had. Vaults deployed before versioning report version 0.
+    pub fn migrate(env: Env) -> Result<u32, Error> {
+        require_admin(&env)?;
+
+        // Version 1 is the first versioned layout, so there is nothing to convert yet
+        upgrade::migrate(&env, CONTRACT_VERSION, |_env, _from| {}).map_err(|_| Error::AlreadyMigrated)
+    }
+
+    pub fn version(env: Env) -> u32 {
+        upgrade::version(&env)
+    }
+
+    pub fn token(env: Env) -> Result<Address, Error> {
+        get_token(&env)
+    }
+
+    pub fn admin(env: Env) -> Result<Address, Error> {
+        get_admin(&env)
+    }
+
+    /// Registers a savings goal for `addr`, replacing any previous one.
+    ///
+    /// Deposit events include the target from then on, and a `GOALRCHD` event is emitted the first
+    /// time the balance reaches it.
+    pub fn set_goal(env: Env, addr: Address, target: i128) -> Result<SavingsGoal, Error> {
+        addr.require_auth();
+        require!(target > 0, Error::AmountMustBePositive);
+
+        let goal = SavingsGoal { target, reached: storage::get_balance(&env, &addr) >= target };
+        storage::update_goal(&env, &addr, &goal);
+        Ok(goal)
+    }
+
+    pub fn get_goal(env: Env, addr: Address) -> Option<SavingsGoal> {
+        storage::get_goal(&env, &addr)
+    }
+
+    /// Returns up to `limit` of the most recent deposits of `addr`, oldest first.
+    pub fn get_history(env: Env, addr: Address, limit: u32) -> Vec<DepositRecord> {
+        let history = storage::get_history(&env, &addr);
+        let start = history.len().saturating_sub(limit);
+        history.slice(start..)
+    }
+
+    /// Returns up to `limit` deposits and withdrawals of `addr` with `seq >= cursor`, oldest first.
+    ///
+    /// Pass `0` to start from the oldest retained record and keep calling with `next_cursor`
+    /// until it is `None` to rebuild the statement. Only the last `MAX_HISTORY_ENTRIES` movements
+    /// are kept, so a cursor pointing at a dropped record resumes from the oldest one left.
+    /// `limit` is clamped to `1..=MAX_HISTORY_ENTRIES`, so every page moves the cursor forward.
+    pub fn get_activity(env: Env, addr: Address, cursor: u64, limit: u32) -> ActivityPage {
+        let limit = limit.clamp(1, storage::MAX_HISTORY_ENTRIES);
+        let mut records = Vec::new(&env);
+        let mut next_cursor = None;
+        for record in storage::get_activity(&env, &addr).iter().filter(|record| record.seq >= cursor) {
+            if records.len() == limit {
+                next_cursor = Some(record.seq);
+                break;
+            }
+            records.push_back(record);
+        }
+
+        ActivityPage { records, next_cursor }
+    }
+
+    /// Extends the TTL of the entries kept for `addr` (permissionless).
+    ///
+    /// Entries are already bumped whenever they are used, but a depositor who stays inactive for
+    /// months would see them archived. Anyone (the user, a keeper bot, ...) can call this to keep them alive.
+    pub fn extend_ttl_for(env: Env, addr: Address) -> bool {
+        storage::extend_ttl_for(&env, &addr);
+        true
+    }
+
+    pub fn balance(env: Env, addr: Address) -> i128 {
+        storage::get_balance(&env, &addr)
+    }
+
+    pub fn get_unlock_time(env: Env, addr: Address) -> u64 {
+        storage::get_unlock_time(&env, &addr)
     }
 }
 
 mod test;
-
+#[cfg(all(test, feature = "fuzz"))]
+mod prop_test;
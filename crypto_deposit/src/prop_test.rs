@@ -0,0 +1,41 @@
+extern crate std;
+
+use proptest::prelude::*;
+
+use crate::receipt::{shares_for_deposit, shares_for_withdrawal};
+use crate::{bps_of, MAX_FEE_BPS};
+
+proptest! {
+    #[test]
+    fn withdrawal_fee_and_net_amount_sum_up_to_the_amount(amount in 0_i128..10_i128.pow(24), fee_bps in 0_u32..=MAX_FEE_BPS) {
+        let fee = bps_of(amount, fee_bps);
+
+        prop_assert!(fee >= 0 && fee <= amount);
+        prop_assert_eq!((amount - fee) + fee, amount);
+    }
+
+    #[test]
+    fn term_interest_never_exceeds_the_rate(amount in 0_i128..10_i128.pow(24), rate_bps in 0_u32..=MAX_FEE_BPS) {
+        let interest = bps_of(amount, rate_bps);
+
+        // Rounded down, so the promised interest is never more than the exact rate and off by less than a unit
+        prop_assert!(interest * MAX_FEE_BPS as i128 <= amount * rate_bps as i128);
+        prop_assert!(amount * rate_bps as i128 - interest * (MAX_FEE_BPS as i128) < MAX_FEE_BPS as i128);
+    }
+
+    #[test]
+    fn term_interest_grows_with_the_amount(amount in 0_i128..10_i128.pow(24), extra in 0_i128..10_i128.pow(12), rate_bps in 0_u32..=MAX_FEE_BPS) {
+        prop_assert!(bps_of(amount + extra, rate_bps) >= bps_of(amount, rate_bps));
+    }
+
+    #[test]
+    fn vault_shares_never_favour_the_depositor(
+        amount in 1_i128..10_i128.pow(18),
+        supply in 1_i128..10_i128.pow(18),
+        pool in 1_i128..10_i128.pow(18),
+    ) {
+        // Deposits mint at most the proportional shares, withdrawals burn at least them
+        prop_assert!(shares_for_deposit(amount, supply, pool) * pool <= amount * supply);
+        prop_assert!(shares_for_withdrawal(amount, supply, pool) * pool >= amount * supply);
+    }
+}
@@ -0,0 +1,26 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+#[contractclient(name = "ReceiptTokenClient")]
+pub trait ReceiptTokenInterface {
+    fn mint(env: Env, to: Address, amount: i128) -> i128;
+    fn burn(env: Env, from: Address, amount: i128) -> i128;
+    fn total_supply(env: Env) -> i128;
+}
+
+/// Shares minted for `amount`, proportional to the pool the depositor is joining.
+pub fn shares_for_deposit(amount: i128, supply: i128, pool: i128) -> i128 {
+    if supply == 0 || pool == 0 {
+        return amount;
+    }
+
+    amount * supply / pool
+}
+
+/// Shares burned to take `amount` out of the pool, rounded up so the vault never burns too few.
+pub fn shares_for_withdrawal(amount: i128, supply: i128, pool: i128) -> i128 {
+    if pool == 0 {
+        return 0;
+    }
+
+    (amount * supply + pool - 1) / pool
+}
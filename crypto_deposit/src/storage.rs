@@ -0,0 +1,417 @@
+use soroban_sdk::{contracttype, Address, Env, IntoVal, Map, String, Symbol, TryFromVal, Val, Vec};
+
+use soroban_examples_storage::{bump_instance, bump_persistent};
+
+use crate::strategy::StrategyPosition;
+
+// Oldest entries are dropped once a depositor's history (or activity log) reaches this size
+pub const MAX_HISTORY_ENTRIES: u32 = 50;
+
+// Value of the yield index before any strategy yield is credited
+const YIELD_INDEX_ONE: i128 = 1_000_000_000;
+
+#[contracttype]
+pub enum DataKey {
+    Balance(Address),
+    UnlockTime(Address),
+    TotalBalance,
+    DepositCaps,
+    AllowlistEnabled,
+    Allowed(Address),
+    WithdrawalsPaused,
+    History(Address),
+    FeeBps,
+    FeesBalance,
+    ReceiptToken,
+    EmergencyDelay,
+    MinDeposit,
+    EmergencyRequest,
+    Strategy,
+    Goal(Address),
+    Recurring(Address),
+    Activity(Address),
+    TermProducts,
+    TermDeposits(Address),
+    TermLiabilities,
+    YieldIndex,
+    BalanceIndex(Address),
+    NextTermDepositId(Address)
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    Treasurer,
+    Compliance
+}
+
+impl Role {
+    // Roles are kept in the shared access-control storage under these names
+    pub fn symbol(&self, env: &Env) -> Symbol {
+        match self {
+            Role::Treasurer => Symbol::new(env, "treasurer"),
+            Role::Compliance => Symbol::new(env, "compliance"),
+        }
+    }
+}
+
+#[contracttype]
+pub struct DepositCaps {
+    pub max_per_user: i128,
+    pub max_total: i128
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepositRecord {
+    pub ts: u64,
+    pub amount: i128,
+    pub memo: Option<String>
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ActivityKind {
+    Deposit,
+    Withdrawal
+}
+
+// `seq` increases by one per movement of the user and is never reused, so it works as a stable cursor
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ActivityRecord {
+    pub seq: u64,
+    pub ts: u64,
+    pub kind: ActivityKind,
+    pub amount: i128
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ActivityPage {
+    pub records: Vec<ActivityRecord>,
+    pub next_cursor: Option<u64>
+}
+
+// Fixed-term deposit: `interest` is fixed when it is opened and only paid out from `maturity_ts` on
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TermDeposit {
+    pub id: u64,
+    pub amount: i128,
+    pub interest: i128,
+    pub start_ts: u64,
+    pub maturity_ts: u64
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmergencyRequest {
+    pub amount: i128,
+    pub execute_after: u64
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SavingsGoal {
+    pub target: i128,
+    pub reached: bool
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecurringDeposit {
+    pub amount: i128,
+    pub interval_secs: u64,
+    pub puller: Address,
+    pub next_pull_ts: u64
+}
+
+impl Default for DepositCaps {
+    fn default() -> DepositCaps {
+        DepositCaps { max_per_user: 0, max_total: 0 }
+    }
+}
+
+// Balances grow with the yield index: a stored balance is worth `index / index_when_stored` times
+// itself, so crediting yield to every depositor is a single write
+pub fn get_balance(env: &Env, addr: &Address) -> i128 {
+    let stored: i128 = get_persistent(env, &DataKey::Balance(addr.clone())).unwrap_or(0_i128);
+    if stored == 0 {
+        return 0;
+    }
+
+    let stored_index: i128 = get_persistent(env, &DataKey::BalanceIndex(addr.clone())).unwrap_or(YIELD_INDEX_ONE);
+    stored * get_yield_index(env) / stored_index
+}
+
+pub fn update_balance(env: &Env, addr: &Address, balance: &i128) {
+    set_persistent(env, &DataKey::Balance(addr.clone()), balance);
+
+    // Until some yield is credited every balance is stored at the initial index, which is the default
+    let index = get_yield_index(env);
+    if index != YIELD_INDEX_ONE {
+        set_persistent(env, &DataKey::BalanceIndex(addr.clone()), &index);
+    }
+}
+
+fn get_yield_index(env: &Env) -> i128 {
+    env.storage().instance().get(&DataKey::YieldIndex).unwrap_or(YIELD_INDEX_ONE)
+}
+
+/// Shares up to `amount` out among the depositors in proportion to their balances. Returns the amount
+/// credited, which the index precision can round below `amount` (0 if there is nobody to credit).
+pub fn credit_yield(env: &Env, amount: i128) -> i128 {
+    let total = get_total_balance(env);
+    if total == 0 {
+        return 0;
+    }
+
+    let index = get_yield_index(env);
+    let new_index = index * (total + amount) / total;
+
+    // Only what the new index actually adds to the balances is booked, so their sum never exceeds the total
+    let credited = total * new_index / index - total;
+    if credited > 0 {
+        env.storage().instance().set(&DataKey::YieldIndex, &new_index);
+        update_total_balance(env, &(total + credited));
+    }
+    credited
+}
+
+pub fn get_unlock_time(env: &Env, addr: &Address) -> u64 {
+    get_persistent(env, &DataKey::UnlockTime(addr.clone())).unwrap_or(0_u64)
+}
+
+pub fn update_unlock_time(env: &Env, addr: &Address, unlock_ts: &u64) {
+    set_persistent(env, &DataKey::UnlockTime(addr.clone()), unlock_ts);
+}
+
+pub fn get_total_balance(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::TotalBalance)
+        .unwrap_or(0_i128)
+}
+
+pub fn update_total_balance(env: &Env, total: &i128) {
+    env.storage().instance().set(&DataKey::TotalBalance, total);
+}
+
+pub fn get_deposit_caps(env: &Env) -> DepositCaps {
+    env.storage()
+        .instance()
+        .get(&DataKey::DepositCaps)
+        .unwrap_or(DepositCaps::default())
+}
+
+pub fn update_deposit_caps(env: &Env, caps: &DepositCaps) {
+    env.storage().instance().set(&DataKey::DepositCaps, caps);
+}
+
+pub fn is_allowlist_enabled(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::AllowlistEnabled)
+        .unwrap_or(false)
+}
+
+pub fn update_allowlist_enabled(env: &Env, enabled: bool) {
+    env.storage().instance().set(&DataKey::AllowlistEnabled, &enabled);
+}
+
+pub fn is_allowed(env: &Env, addr: &Address) -> bool {
+    get_persistent(env, &DataKey::Allowed(addr.clone())).unwrap_or(false)
+}
+
+pub fn add_allowed(env: &Env, addr: &Address) {
+    set_persistent(env, &DataKey::Allowed(addr.clone()), &true);
+}
+
+pub fn remove_allowed(env: &Env, addr: &Address) {
+    env.storage().persistent().remove(&DataKey::Allowed(addr.clone()));
+}
+
+pub fn is_withdrawals_paused(env: &Env) -> bool {
+    env.storage().instance().get(&DataKey::WithdrawalsPaused).unwrap_or(false)
+}
+
+pub fn update_withdrawals_paused(env: &Env, paused: bool) {
+    env.storage().instance().set(&DataKey::WithdrawalsPaused, &paused);
+}
+
+pub fn get_history(env: &Env, addr: &Address) -> Vec<DepositRecord> {
+    get_persistent(env, &DataKey::History(addr.clone())).unwrap_or(Vec::new(env))
+}
+
+pub fn push_history(env: &Env, addr: &Address, record: DepositRecord) {
+    let mut history = get_history(env, addr);
+    if history.len() >= MAX_HISTORY_ENTRIES {
+        history.pop_front();
+    }
+
+    history.push_back(record);
+    set_persistent(env, &DataKey::History(addr.clone()), &history);
+}
+
+pub fn get_activity(env: &Env, addr: &Address) -> Vec<ActivityRecord> {
+    get_persistent(env, &DataKey::Activity(addr.clone())).unwrap_or(Vec::new(env))
+}
+
+pub fn push_activity(env: &Env, addr: &Address, kind: ActivityKind, amount: i128) {
+    let mut activity = get_activity(env, addr);
+    let seq = activity.last().map(|record| record.seq + 1).unwrap_or(0);
+    if activity.len() >= MAX_HISTORY_ENTRIES {
+        activity.pop_front();
+    }
+
+    activity.push_back(ActivityRecord { seq, ts: env.ledger().timestamp(), kind, amount });
+    set_persistent(env, &DataKey::Activity(addr.clone()), &activity);
+}
+
+pub fn get_fee_bps(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::FeeBps).unwrap_or(0_u32)
+}
+
+pub fn update_fee_bps(env: &Env, fee_bps: &u32) {
+    env.storage().instance().set(&DataKey::FeeBps, fee_bps);
+}
+
+pub fn get_fees_balance(env: &Env) -> i128 {
+    env.storage().instance().get(&DataKey::FeesBalance).unwrap_or(0_i128)
+}
+
+pub fn update_fees_balance(env: &Env, fees: &i128) {
+    env.storage().instance().set(&DataKey::FeesBalance, fees);
+}
+
+pub fn get_receipt_token(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::ReceiptToken)
+}
+
+pub fn update_receipt_token(env: &Env, addr: &Address) {
+    env.storage().instance().set(&DataKey::ReceiptToken, addr);
+}
+
+pub fn get_emergency_delay(env: &Env) -> u64 {
+    env.storage().instance().get(&DataKey::EmergencyDelay).unwrap_or(0_u64)
+}
+
+pub fn update_emergency_delay(env: &Env, delay: &u64) {
+    env.storage().instance().set(&DataKey::EmergencyDelay, delay);
+}
+
+pub fn get_min_deposit(env: &Env) -> i128 {
+    env.storage().instance().get(&DataKey::MinDeposit).unwrap_or(0_i128)
+}
+
+pub fn update_min_deposit(env: &Env, min_deposit: &i128) {
+    env.storage().instance().set(&DataKey::MinDeposit, min_deposit);
+}
+
+pub fn get_emergency_request(env: &Env) -> Option<EmergencyRequest> {
+    env.storage().instance().get(&DataKey::EmergencyRequest)
+}
+
+pub fn update_emergency_request(env: &Env, request: &EmergencyRequest) {
+    env.storage().instance().set(&DataKey::EmergencyRequest, request);
+}
+
+pub fn remove_emergency_request(env: &Env) {
+    env.storage().instance().remove(&DataKey::EmergencyRequest);
+}
+
+pub fn get_goal(env: &Env, addr: &Address) -> Option<SavingsGoal> {
+    get_persistent(env, &DataKey::Goal(addr.clone()))
+}
+
+pub fn update_goal(env: &Env, addr: &Address, goal: &SavingsGoal) {
+    set_persistent(env, &DataKey::Goal(addr.clone()), goal);
+}
+
+pub fn get_recurring(env: &Env, addr: &Address) -> Option<RecurringDeposit> {
+    get_persistent(env, &DataKey::Recurring(addr.clone()))
+}
+
+pub fn update_recurring(env: &Env, addr: &Address, recurring: &RecurringDeposit) {
+    set_persistent(env, &DataKey::Recurring(addr.clone()), recurring);
+}
+
+pub fn remove_recurring(env: &Env, addr: &Address) {
+    env.storage().persistent().remove(&DataKey::Recurring(addr.clone()));
+}
+
+// Term length in days -> interest paid over the whole term, in basis points
+pub fn get_term_products(env: &Env) -> Map<u32, u32> {
+    env.storage().instance().get(&DataKey::TermProducts).unwrap_or(Map::new(env))
+}
+
+pub fn update_term_products(env: &Env, products: &Map<u32, u32>) {
+    env.storage().instance().set(&DataKey::TermProducts, products);
+}
+
+pub fn get_term_deposits(env: &Env, addr: &Address) -> Map<u64, TermDeposit> {
+    get_persistent(env, &DataKey::TermDeposits(addr.clone())).unwrap_or(Map::new(env))
+}
+
+pub fn update_term_deposits(env: &Env, addr: &Address, deposits: &Map<u64, TermDeposit>) {
+    set_persistent(env, &DataKey::TermDeposits(addr.clone()), deposits);
+}
+
+// Ids only ever grow, so a closed deposit's id is never handed out again
+pub fn next_term_deposit_id(env: &Env, addr: &Address) -> u64 {
+    let key = DataKey::NextTermDepositId(addr.clone());
+    let id: u64 = get_persistent(env, &key).unwrap_or(0);
+    set_persistent(env, &key, &(id + 1));
+    id
+}
+
+// Principal plus promised interest of every open term deposit
+pub fn get_term_liabilities(env: &Env) -> i128 {
+    env.storage().instance().get(&DataKey::TermLiabilities).unwrap_or(0_i128)
+}
+
+pub fn update_term_liabilities(env: &Env, liabilities: &i128) {
+    env.storage().instance().set(&DataKey::TermLiabilities, liabilities);
+}
+
+pub fn get_strategy(env: &Env) -> Option<StrategyPosition> {
+    env.storage().instance().get(&DataKey::Strategy)
+}
+
+pub fn update_strategy(env: &Env, strategy: &StrategyPosition) {
+    env.storage().instance().set(&DataKey::Strategy, strategy);
+}
+
+/// Extends the TTL of every entry kept for `addr`, plus the contract instance.
+pub fn extend_ttl_for(env: &Env, addr: &Address) {
+    let keys = [
+        DataKey::Balance(addr.clone()),
+        DataKey::BalanceIndex(addr.clone()),
+        DataKey::UnlockTime(addr.clone()),
+        DataKey::History(addr.clone()),
+        DataKey::Allowed(addr.clone()),
+        DataKey::Goal(addr.clone()),
+        DataKey::Recurring(addr.clone()),
+        DataKey::Activity(addr.clone()),
+        DataKey::TermDeposits(addr.clone()),
+        DataKey::NextTermDepositId(addr.clone()),
+    ];
+
+    for key in keys.iter() {
+        if env.storage().persistent().has(key) {
+            bump_persistent(env, key);
+        }
+    }
+
+    bump_instance(env);
+}
+
+// Per-user entries get their TTL extended every time they are read or written
+fn get_persistent<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+    soroban_examples_storage::get_persistent(env, key)
+}
+
+fn set_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    soroban_examples_storage::set_persistent(env, key, value);
+}
@@ -0,0 +1,45 @@
+use soroban_sdk::{contractclient, contracttype, Address, Env, Vec};
+
+// Mirror of the types exposed by the workspace's InvestmentContract (`investment` crate), so the
+// vault can call it without importing its wasm. Field names and order must match.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum InvestmentStatus {
+    Blocked = 1,
+    Claimable = 2,
+    CashFlowing = 4,
+    Finished = 5,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Investment {
+    pub deposited: i128,
+    pub commission: i128,
+    pub accumulated_interests: i128,
+    pub total: i128,
+    pub claimable_ts: u64,
+    pub last_transfer_ts: u64,
+    pub status: InvestmentStatus,
+    pub regular_payment: i128,
+    pub paid: i128,
+    pub payments_transferred: u32
+}
+
+#[contractclient(name = "InvestmentClient")]
+pub trait InvestmentInterface {
+    fn invest(env: Env, addr: Address, amount: i128) -> Investment;
+    fn get_investment(env: Env, addr: Address, ts: u64) -> Investment;
+}
+
+/// Position held by the vault in the Investment contract. `credited` is the part of the returns beyond
+/// the deployed amount that has been credited to the depositors.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StrategyPosition {
+    pub investment: Address,
+    pub deployed: i128,
+    pub returned: i128,
+    pub credited: i128,
+    pub positions: Vec<u64>
+}
@@ -1,19 +1,45 @@
 #![cfg(test)]
+extern crate std;
 
-use crate::{CryptoDeposit, CryptoDepositClient};
-use soroban_sdk::{Env, testutils::Address as _, Address, token};
-use token::Client as TokenClient;
-use token::StellarAssetClient as TokenAdminClient;
+// Build the contract first with `cargo build --target wasm32-unknown-unknown --release`
+#[cfg(feature = "upgrade-tests")]
+mod crypto_deposit_wasm {
+    soroban_sdk::contractimport!(
+        file = "target/wasm32-unknown-unknown/release/crypto_deposit.wasm"
+    );
+}
 
-fn create_token_contract<'a>(e: &Env, admin: &Address) -> (TokenClient<'a>, TokenAdminClient<'a>) {
-    let sac = e.register_stellar_asset_contract_v2(admin.clone());
-    (
-        TokenClient::new(e, &sac.address()),
-        TokenAdminClient::new(e, &sac.address()),
-    )
+use crate::{ActivityKind, ActivityRecord, CryptoDeposit, CryptoDepositClient, DepositRecord, Role, SavingsGoal, TermDeposit};
+use crate::receipt::{shares_for_deposit, shares_for_withdrawal};
+use crate::storage::{DataKey, MAX_HISTORY_ENTRIES};
+use soroban_examples_storage::{PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use soroban_examples_testutils::{
+    advance_days, advance_ledgers, advance_time, create_funded_account, create_native_token_contract, create_reentrant_token,
+    create_token_contract, EventSnapshot, TokenAdminClient, TokenClient, LEDGERS_IN_DAY,
+};
+use soroban_sdk::{Env, testutils::{Address as _, Ledger, storage::Persistent as _}, Address, IntoVal, String, Symbol, vec};
+use receipt_token::{ReceiptToken, ReceiptTokenClient};
+use investment::contract::{InvestmentContract, InvestmentContractClient};
+const STROOPS_PER_XLM: i128 = 10_000_000;
+const EMERGENCY_DELAY: u64 = 3 * 86400;
+
+struct TestData<'a> {
+    client: CryptoDepositClient<'a>,
+    user: Address,
+    admin: Address,
+    token: TokenClient<'a>,
+    token_admin: TokenAdminClient<'a>,
+}
+
+fn create_contract<'a>(e: &'a Env, amount: &'a i128) -> TestData<'a> {
+    create_contract_with_fee(e, amount, 0)
+}
+
+fn create_contract_with_fee<'a>(e: &'a Env, amount: &'a i128, fee_bps: u32) -> TestData<'a> {
+    create_contract_with_config(e, amount, fee_bps, 0)
 }
 
-fn create_contract<'a>(e: &'a Env, amount: &'a i128) -> (CryptoDepositClient<'a>, Address) {
+fn create_contract_with_config<'a>(e: &'a Env, amount: &'a i128, fee_bps: u32, min_deposit: i128) -> TestData<'a> {
     e.mock_all_auths();
 
     let admin = Address::generate(&e);
@@ -25,16 +51,1076 @@ fn create_contract<'a>(e: &'a Env, amount: &'a i128) -> (CryptoDepositClient<'a>
         e,
         &e.register(
             CryptoDeposit {}, 
-            (admin, token.address)
+            (admin.clone(), token.address.clone(), fee_bps, EMERGENCY_DELAY, min_deposit)
         )
     );
 
-    (client, user.clone())
+    TestData {
+        client,
+        user,
+        admin,
+        token,
+        token_admin,
+    }
 }
 
 #[test]
 fn test_deposit() {
     let e = Env::default();
     let test_data = create_contract(&e, &100_i128);
-    assert_eq!(test_data.0.deposit(&test_data.1, &50), 50);
+    assert_eq!(test_data.client.deposit(&test_data.user, &50, &None, &None), 50);
+}
+
+#[test]
+fn test_withdraw() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.deposit(&test_data.user, &50, &None, &None);
+    assert_eq!(test_data.client.withdraw(&test_data.user, &20), 30);
+    assert_eq!(test_data.client.balance(&test_data.user), 30);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #201)")]
+fn test_withdraw_insufficient_balance() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.deposit(&test_data.user, &50, &None, &None);
+    test_data.client.withdraw(&test_data.user, &60);
+}
+
+#[test]
+fn test_withdraw_after_lock() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.deposit(&test_data.user, &50, &Some(86400_u64), &None);
+    assert_eq!(test_data.client.get_unlock_time(&test_data.user), e.ledger().timestamp() + 86400);
+
+    advance_days(&e, 1);
+    assert_eq!(test_data.client.withdraw(&test_data.user, &50), 0);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #202)")]
+fn test_withdraw_deposit_still_locked() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.deposit(&test_data.user, &50, &Some(86400_u64), &None);
+    test_data.client.withdraw(&test_data.user, &50);
+}
+
+#[test]
+fn test_withdraw_to_destination() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    let destination = Address::generate(&e);
+    test_data.client.deposit(&test_data.user, &50, &None, &None);
+
+    assert_eq!(test_data.client.withdraw_to(&test_data.user, &destination, &20), 30);
+    assert_eq!(test_data.token.balance(&destination), 20);
+    assert_eq!(test_data.token.balance(&test_data.user), 50);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #201)")]
+fn test_withdraw_to_insufficient_balance() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    let destination = Address::generate(&e);
+    test_data.client.deposit(&test_data.user, &50, &None, &None);
+    test_data.client.withdraw_to(&test_data.user, &destination, &60);
+}
+
+#[test]
+fn test_admin_sweep_surplus() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.deposit(&test_data.user, &50, &None, &None);
+    test_data.token_admin.mint(&test_data.client.address, &30);
+
+    assert_eq!(sweep(&e, &test_data, 20), 10);
+    assert_eq!(test_data.token.balance(&test_data.admin), 20);
+    assert_eq!(test_data.token.balance(&test_data.client.address), 60);
+}
+
+// Queues the sweep and executes it once the delay has passed
+fn sweep(e: &Env, test_data: &TestData, amount: i128) -> i128 {
+    let hash = test_data.client.queue_sweep(&amount);
+    assert_eq!(test_data.client.get_queued_sweep(&hash).unwrap().ready_at, e.ledger().timestamp() + EMERGENCY_DELAY);
+    advance_time(e, EMERGENCY_DELAY);
+    test_data.client.admin_sweep(&amount)
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #236)")]
+fn test_admin_sweep_before_delay() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.token_admin.mint(&test_data.client.address, &30);
+
+    test_data.client.queue_sweep(&20);
+    advance_time(&e, EMERGENCY_DELAY - 1);
+    test_data.client.admin_sweep(&20);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #235)")]
+fn test_admin_sweep_cancelled() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.token_admin.mint(&test_data.client.address, &30);
+
+    let hash = test_data.client.queue_sweep(&20);
+    test_data.client.cancel_sweep(&hash);
+    advance_time(&e, EMERGENCY_DELAY);
+    test_data.client.admin_sweep(&20);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #203)")]
+fn test_admin_sweep_cannot_touch_user_balances() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.deposit(&test_data.user, &50, &None, &None);
+    test_data.token_admin.mint(&test_data.client.address, &30);
+
+    test_data.client.queue_sweep(&31);
+}
+
+#[test]
+fn test_deposit_within_caps() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.set_deposit_caps(&60, &80);
+    assert_eq!(test_data.client.deposit(&test_data.user, &60, &None, &None), 60);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #239)")]
+fn test_negative_deposit_cap() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.set_deposit_caps(&60, &-1);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #204)")]
+fn test_deposit_exceeds_max_per_user() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.set_deposit_caps(&60, &0);
+    test_data.client.deposit(&test_data.user, &50, &None, &None);
+    test_data.client.deposit(&test_data.user, &20, &None, &None);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #205)")]
+fn test_deposit_exceeds_max_total() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    let another_user = Address::generate(&e);
+    test_data.token_admin.mint(&another_user, &100);
+    test_data.client.set_deposit_caps(&60, &80);
+
+    test_data.client.deposit(&test_data.user, &50, &None, &None);
+    test_data.client.deposit(&another_user, &40, &None, &None);
+}
+
+#[test]
+fn test_deposit_allowed_address() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.set_allowlist_enabled(&true);
+    test_data.client.allow(&test_data.user);
+    assert_eq!(test_data.client.deposit(&test_data.user, &50, &None, &None), 50);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #206)")]
+fn test_deposit_address_not_allowed() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.set_allowlist_enabled(&true);
+    test_data.client.allow(&test_data.user);
+    test_data.client.disallow(&test_data.user);
+    test_data.client.deposit(&test_data.user, &50, &None, &None);
+}
+
+#[test]
+fn test_pause_and_unpause() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.deposit(&test_data.user, &50, &None, &None);
+
+    test_data.client.pause(&test_data.admin, &false);
+    assert_eq!(test_data.client.is_paused(), true);
+    assert_eq!(test_data.client.is_withdrawals_paused(), false);
+    assert_eq!(test_data.client.withdraw(&test_data.user, &10), 40);
+
+    test_data.client.unpause(&test_data.admin);
+    assert_eq!(test_data.client.is_paused(), false);
+    assert_eq!(test_data.client.deposit(&test_data.user, &10, &None, &None), 50);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #207)")]
+fn test_deposit_contract_paused() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.pause(&test_data.admin, &false);
+    test_data.client.deposit(&test_data.user, &50, &None, &None);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #207)")]
+fn test_withdraw_contract_paused() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.deposit(&test_data.user, &50, &None, &None);
+    test_data.client.pause(&test_data.admin, &true);
+    test_data.client.withdraw(&test_data.user, &50);
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    let new_admin = Address::generate(&e);
+
+    test_data.client.propose_admin(&new_admin);
+    assert_eq!(test_data.client.admin(), test_data.admin);
+
+    test_data.client.accept_admin();
+    assert_eq!(test_data.client.admin(), new_admin);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #208)")]
+fn test_accept_admin_without_proposal() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.accept_admin();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #230)")]
+fn test_accept_admin_after_proposal_expired() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.propose_admin(&Address::generate(&e));
+
+    advance_days(&e, 7);
+    test_data.client.accept_admin();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #208)")]
+fn test_accept_admin_after_cancel() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.propose_admin(&Address::generate(&e));
+    assert!(test_data.client.pending_admin().is_some());
+
+    test_data.client.cancel_admin_transfer();
+    test_data.client.accept_admin();
+}
+
+#[test]
+fn test_deposit_history() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.deposit(&test_data.user, &10, &None, &None);
+    e.ledger().set_timestamp(1000);
+    test_data.client.deposit(&test_data.user, &20, &None, &None);
+    e.ledger().set_timestamp(2000);
+    test_data.client.deposit(&test_data.user, &30, &None, &None);
+
+    let history = test_data.client.get_history(&test_data.user, &2);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).unwrap(), DepositRecord { ts: 1000, amount: 20, memo: None });
+    assert_eq!(history.get(1).unwrap(), DepositRecord { ts: 2000, amount: 30, memo: None });
+    assert_eq!(test_data.client.get_history(&test_data.user, &10).len(), 3);
+}
+
+#[test]
+fn test_deposit_history_is_bounded() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    for _ in 0..(MAX_HISTORY_ENTRIES + 5) {
+        test_data.client.deposit(&test_data.user, &1, &None, &None);
+    }
+
+    assert_eq!(test_data.client.get_history(&test_data.user, &100).len(), MAX_HISTORY_ENTRIES);
+}
+
+#[test]
+fn test_activity_pagination() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.deposit(&test_data.user, &10, &None, &None);
+    e.ledger().set_timestamp(1000);
+    test_data.client.deposit(&test_data.user, &20, &None, &None);
+    e.ledger().set_timestamp(2000);
+    test_data.client.withdraw(&test_data.user, &5);
+
+    let page = test_data.client.get_activity(&test_data.user, &0, &2);
+    assert_eq!(page.records.len(), 2);
+    assert_eq!(page.records.get(1).unwrap(), ActivityRecord { seq: 1, ts: 1000, kind: ActivityKind::Deposit, amount: 20 });
+    assert_eq!(page.next_cursor, Some(2));
+
+    let page = test_data.client.get_activity(&test_data.user, &page.next_cursor.unwrap(), &2);
+    assert_eq!(page.records.len(), 1);
+    assert_eq!(page.records.get(0).unwrap(), ActivityRecord { seq: 2, ts: 2000, kind: ActivityKind::Withdrawal, amount: 5 });
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn test_activity_page_limit_is_clamped() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.deposit(&test_data.user, &10, &None, &None);
+    test_data.client.deposit(&test_data.user, &20, &None, &None);
+
+    // A zero limit still returns one record, so following the cursor always ends
+    let page = test_data.client.get_activity(&test_data.user, &0, &0);
+    assert_eq!(page.records.len(), 1);
+    assert_eq!(page.next_cursor, Some(1));
+    let page = test_data.client.get_activity(&test_data.user, &1, &0);
+    assert_eq!(page.records.len(), 1);
+    assert_eq!(page.next_cursor, None);
+
+    for _ in 0..MAX_HISTORY_ENTRIES {
+        test_data.client.deposit(&test_data.user, &1, &None, &None);
+    }
+    let page = test_data.client.get_activity(&test_data.user, &0, &u32::MAX);
+    assert_eq!(page.records.len(), MAX_HISTORY_ENTRIES);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn test_activity_cursor_survives_dropped_records() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    for _ in 0..(MAX_HISTORY_ENTRIES + 5) {
+        test_data.client.deposit(&test_data.user, &1, &None, &None);
+    }
+
+    let page = test_data.client.get_activity(&test_data.user, &0, &1);
+    assert_eq!(page.records.get(0).unwrap().seq, 5);
+    assert_eq!(page.next_cursor, Some(6));
+}
+
+#[test]
+fn test_native_xlm_deposit_and_withdraw() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user = create_funded_account(&e, (100 * STROOPS_PER_XLM) as i64);
+    let xlm = create_native_token_contract(&e);
+    let client = CryptoDepositClient::new(&e, &e.register(CryptoDeposit {}, (admin, xlm.address.clone(), 0_u32, EMERGENCY_DELAY, 0_i128)));
+
+    assert_eq!(xlm.decimals(), 7);
+    assert_eq!(client.token(), xlm.address);
+
+    client.deposit(&user, &(25 * STROOPS_PER_XLM), &None, &None);
+    assert_eq!(xlm.balance(&client.address), 25 * STROOPS_PER_XLM);
+    assert_eq!(xlm.balance(&user), 75 * STROOPS_PER_XLM);
+
+    // Half a lumen, to make sure sub-unit amounts are handled in stroops
+    client.withdraw(&user, &(STROOPS_PER_XLM / 2));
+    assert_eq!(client.balance(&user), 25 * STROOPS_PER_XLM - STROOPS_PER_XLM / 2);
+    assert_eq!(xlm.balance(&user), 75 * STROOPS_PER_XLM + STROOPS_PER_XLM / 2);
+}
+
+#[test]
+fn test_withdrawal_fee() {
+    let e = Env::default();
+    let test_data = create_contract_with_fee(&e, &10000_i128, 250);
+    test_data.client.deposit(&test_data.user, &10000, &None, &None);
+
+    assert_eq!(test_data.client.withdraw(&test_data.user, &4000), 6000);
+    assert_eq!(test_data.token.balance(&test_data.user), 3900);
+    assert_eq!(test_data.client.fee_bps(), 250);
+    assert_eq!(test_data.client.fees_balance(), 100);
+
+    assert_eq!(test_data.client.withdraw_fees(&test_data.admin, &60), 40);
+    assert_eq!(test_data.token.balance(&test_data.admin), 60);
+    assert_eq!(test_data.token.balance(&test_data.client.address), 6040);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #210)")]
+fn test_withdraw_fees_insufficient_balance() {
+    let e = Env::default();
+    let test_data = create_contract_with_fee(&e, &10000_i128, 250);
+    test_data.client.deposit(&test_data.user, &10000, &None, &None);
+    test_data.client.withdraw(&test_data.user, &4000);
+    test_data.client.withdraw_fees(&test_data.admin, &101);
+}
+
+#[test]
+fn test_operator_roles() {
+    let e = Env::default();
+    let test_data = create_contract_with_fee(&e, &10000_i128, 250);
+    let treasurer = Address::generate(&e);
+    let compliance = Address::generate(&e);
+    test_data.client.grant_role(&Role::Treasurer, &treasurer);
+    test_data.client.grant_role(&Role::Compliance, &compliance);
+    assert_eq!(test_data.client.has_role(&Role::Treasurer, &treasurer), true);
+    assert_eq!(test_data.client.has_role(&Role::Compliance, &treasurer), false);
+
+    test_data.client.deposit(&test_data.user, &10000, &None, &None);
+    test_data.client.withdraw(&test_data.user, &4000);
+    assert_eq!(test_data.client.withdraw_fees(&treasurer, &100), 0);
+    assert_eq!(test_data.token.balance(&test_data.admin), 100);
+
+    test_data.client.pause(&compliance, &false);
+    assert_eq!(test_data.client.is_paused(), true);
+    test_data.client.unpause(&compliance);
+    assert_eq!(test_data.client.is_paused(), false);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #224)")]
+fn test_treasurer_cannot_pause() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    let treasurer = Address::generate(&e);
+    test_data.client.grant_role(&Role::Treasurer, &treasurer);
+    test_data.client.pause(&treasurer, &false);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #224)")]
+fn test_revoked_compliance_cannot_pause() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    let compliance = Address::generate(&e);
+    test_data.client.grant_role(&Role::Compliance, &compliance);
+    test_data.client.revoke_role(&Role::Compliance, &compliance);
+    test_data.client.pause(&compliance, &false);
+}
+
+#[test]
+#[should_panic(expected = "Error(Context, InvalidAction)")]
+fn test_constructor_fee_too_high() {
+    let e = Env::default();
+    create_contract_with_fee(&e, &100_i128, 10_001);
+}
+
+#[test]
+fn test_min_deposit() {
+    let e = Env::default();
+    let test_data = create_contract_with_config(&e, &100_i128, 0, 10);
+    assert_eq!(test_data.client.min_deposit(), 10);
+    assert_eq!(test_data.client.deposit(&test_data.user, &10, &None, &None), 10);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #225)")]
+fn test_deposit_below_min_deposit() {
+    let e = Env::default();
+    let test_data = create_contract_with_config(&e, &100_i128, 0, 10);
+    test_data.client.deposit(&test_data.user, &9, &None, &None);
+}
+
+#[test]
+fn test_deposit_for_many() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    let user_a = Address::generate(&e);
+    let user_b = Address::generate(&e);
+
+    let entries = vec![&e, (user_a.clone(), 30_i128), (user_b.clone(), 20_i128), (user_a.clone(), 5_i128)];
+    assert_eq!(test_data.client.deposit_for_many(&test_data.user, &entries), 55);
+    assert_eq!(test_data.token.balance(&test_data.user), 45);
+    assert_eq!(test_data.client.balance(&user_a), 35);
+    assert_eq!(test_data.client.balance(&user_b), 20);
+    assert_eq!(test_data.client.balance(&test_data.user), 0);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #204)")]
+fn test_deposit_for_many_exceeds_max_per_user() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    let user_a = Address::generate(&e);
+    test_data.client.set_deposit_caps(&30, &0);
+
+    let entries = vec![&e, (user_a.clone(), 20_i128), (user_a.clone(), 20_i128)];
+    test_data.client.deposit_for_many(&test_data.user, &entries);
+}
+
+#[test]
+fn test_deposit_from_allowance() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    let router = Address::generate(&e);
+    test_data.token.approve(&test_data.user, &router, &60, &(e.ledger().sequence() + 100));
+
+    assert_eq!(test_data.client.deposit_from(&router, &test_data.user, &40), 40);
+    assert_eq!(test_data.client.balance(&test_data.user), 40);
+    assert_eq!(test_data.client.balance(&router), 0);
+    assert_eq!(test_data.token.allowance(&test_data.user, &router), 20);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #232)")]
+fn test_deposit_from_exceeds_allowance() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    let router = Address::generate(&e);
+    test_data.token.approve(&test_data.user, &router, &30, &(e.ledger().sequence() + 100));
+
+    test_data.client.deposit_from(&router, &test_data.user, &40);
+}
+
+#[test]
+fn test_deposit_with_memo() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    let memo = String::from_str(&e, "INV-2024-0042");
+    test_data.client.deposit(&test_data.user, &50, &None, &Some(memo.clone()));
+
+    let history = test_data.client.get_history(&test_data.user, &1);
+    assert_eq!(history.get(0).unwrap().memo, Some(memo));
+}
+
+fn create_receipt_token<'a>(e: &Env, test_data: &TestData) -> ReceiptTokenClient<'a> {
+    let receipt = ReceiptTokenClient::new(
+        e,
+        &e.register(
+            ReceiptToken,
+            (
+                test_data.client.address.clone(),
+                7_u32,
+                String::from_str(e, "Deposit Share"),
+                String::from_str(e, "DSHR")
+            )
+        )
+    );
+
+    test_data.client.set_receipt_token(&receipt.address);
+    receipt
+}
+
+#[test]
+fn test_vault_mints_and_burns_shares() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    let receipt = create_receipt_token(&e, &test_data);
+    let another_user = Address::generate(&e);
+    test_data.token_admin.mint(&another_user, &100);
+
+    test_data.client.deposit(&test_data.user, &60, &None, &None);
+    test_data.client.deposit(&another_user, &20, &None, &None);
+    assert_eq!(receipt.balance(&test_data.user), 60);
+    assert_eq!(receipt.balance(&another_user), 20);
+    assert_eq!(receipt.total_supply(), 80);
+
+    test_data.client.withdraw(&test_data.user, &45);
+    assert_eq!(receipt.balance(&test_data.user), 15);
+    assert_eq!(receipt.total_supply(), 35);
+    assert_eq!(test_data.token.balance(&test_data.user), 85);
+}
+
+#[test]
+fn test_vault_shares_stay_with_the_depositor() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    let receipt = create_receipt_token(&e, &test_data);
+    let another_user = Address::generate(&e);
+
+    test_data.client.deposit(&test_data.user, &60, &None, &None);
+    assert!(receipt.try_transfer(&test_data.user, &another_user, &30).is_err());
+    assert_eq!(receipt.balance(&another_user), 0);
+    assert_eq!(test_data.client.try_withdraw(&another_user, &30), Err(Ok(crate::Error::AddressInsufficientBalance)));
+
+    // The shares still back the whole deposit, which the depositor redeems in full
+    test_data.client.withdraw(&test_data.user, &60);
+    assert_eq!(receipt.balance(&test_data.user), 0);
+    assert_eq!(receipt.total_supply(), 0);
+    assert_eq!(test_data.token.balance(&test_data.user), 100);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #211)")]
+fn test_receipt_token_already_set() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    let receipt = create_receipt_token(&e, &test_data);
+    test_data.client.set_receipt_token(&receipt.address);
+}
+
+#[test]
+fn test_shares_calculator() {
+    assert_eq!(shares_for_deposit(50, 0, 0), 50);
+    assert_eq!(shares_for_deposit(50, 100, 200), 25);
+    assert_eq!(shares_for_withdrawal(50, 100, 200), 25);
+    assert_eq!(shares_for_withdrawal(51, 100, 200), 26);
+}
+
+#[test]
+fn test_emergency_withdrawal() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.deposit(&test_data.user, &80, &None, &None);
+
+    let request = test_data.client.schedule_emergency(&50);
+    assert_eq!(request.execute_after, e.ledger().timestamp() + EMERGENCY_DELAY);
+
+    e.ledger().set_timestamp(request.execute_after);
+    assert_eq!(test_data.client.execute_emergency(), 50);
+    assert_eq!(test_data.token.balance(&test_data.admin), 50);
+    assert_eq!(test_data.client.get_emergency_request(), None);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #213)")]
+fn test_emergency_still_locked() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.deposit(&test_data.user, &80, &None, &None);
+
+    test_data.client.schedule_emergency(&50);
+    advance_time(&e, EMERGENCY_DELAY - 1);
+    test_data.client.execute_emergency();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #212)")]
+fn test_emergency_cancelled() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.deposit(&test_data.user, &80, &None, &None);
+
+    test_data.client.schedule_emergency(&50);
+    test_data.client.cancel_emergency();
+    advance_time(&e, EMERGENCY_DELAY);
+    test_data.client.execute_emergency();
+}
+
+fn get_balance_ttl(e: &Env, test_data: &TestData) -> u32 {
+    e.as_contract(&test_data.client.address, || {
+        e.storage().persistent().get_ttl(&DataKey::Balance(test_data.user.clone()))
+    })
+}
+
+#[test]
+fn test_extend_ttl_for() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.deposit(&test_data.user, &50, &None, &None);
+    assert_eq!(get_balance_ttl(&e, &test_data), PERSISTENT_BUMP_AMOUNT);
+
+    // Once the remaining TTL drops below the threshold, anyone can extend it again
+    let ledgers_to_pass = PERSISTENT_BUMP_AMOUNT - PERSISTENT_LIFETIME_THRESHOLD + 1;
+    advance_ledgers(&e, ledgers_to_pass);
+    assert_eq!(get_balance_ttl(&e, &test_data), PERSISTENT_LIFETIME_THRESHOLD - 1);
+
+    test_data.client.extend_ttl_for(&test_data.user);
+    assert_eq!(get_balance_ttl(&e, &test_data), PERSISTENT_BUMP_AMOUNT);
+}
+
+#[test]
+fn test_balance_survives_past_initial_ttl() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.deposit(&test_data.user, &50, &None, &None);
+
+    // Move forward ~20 days at a time, well within the instance TTL
+    for _ in 0..10 {
+        advance_ledgers(&e, 20 * LEDGERS_IN_DAY);
+        test_data.client.extend_ttl_for(&test_data.user);
+    }
+
+    assert!(e.ledger().sequence() > PERSISTENT_BUMP_AMOUNT);
+    assert_eq!(test_data.client.withdraw(&test_data.user, &50), 0);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #216)")]
+fn test_deposit_zero_amount() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.deposit(&test_data.user, &0, &None, &None);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #216)")]
+fn test_withdraw_negative_amount() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.deposit(&test_data.user, &50, &None, &None);
+    test_data.client.withdraw(&test_data.user, &-10);
+}
+
+fn create_investment_contract<'a>(e: &Env, test_data: &TestData) -> InvestmentContractClient<'a> {
+    let project_address = Address::generate(e);
+    InvestmentContractClient::new(
+        e,
+        &e.register(
+            InvestmentContract {},
+            (
+                test_data.admin.clone(),
+                project_address,
+                test_data.token.address.clone(),
+                500_u32,
+                7_u64,
+                1000000_i128,
+                1_u32,
+                4_u32,
+                100_i128,
+            ),
+        ),
+    )
+}
+
+#[test]
+fn test_strategy_deploy_and_sync() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &300000_i128);
+    let investment = create_investment_contract(&e, &test_data);
+    test_data.client.deposit(&test_data.user, &200000, &None, &None);
+
+    // The strategy invests the surplus the admin funded, never the deposits
+    test_data.token_admin.mint(&test_data.client.address, &100000);
+    test_data.client.set_strategy(&investment.address);
+    let position = test_data.client.deploy_to_strategy(&100000);
+    assert_eq!(position.deployed, 100000);
+    assert_eq!(position.positions.len(), 1);
+    assert_eq!(test_data.token.balance(&test_data.client.address), 200000);
+
+    // The project pays the first installment back to the vault, still less than it was lent
+    advance_days(&e, 8);
+    test_data.token_admin.mint(&test_data.admin, &100000);
+    investment.add_company_transfer(&100000);
+    let paid = investment.process_investor_payment(&test_data.client.address, &position.positions.get(0).unwrap()).paid;
+
+    let position = test_data.client.sync_strategy();
+    assert_eq!(position.returned, paid);
+    assert_eq!(position.credited, 0);
+    assert_eq!(test_data.token.balance(&test_data.client.address), 200000 + paid);
+    assert_eq!(test_data.client.withdraw(&test_data.user, &200000), 0);
+}
+
+#[test]
+fn test_strategy_yield_credited_to_depositors() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &300000_i128);
+    let investment = create_investment_contract(&e, &test_data);
+    let another_user = Address::generate(&e);
+    test_data.token_admin.mint(&another_user, &50000);
+    test_data.client.deposit(&test_data.user, &150000, &None, &None);
+    test_data.client.deposit(&another_user, &50000, &None, &None);
+
+    test_data.token_admin.mint(&test_data.client.address, &100000);
+    test_data.client.set_strategy(&investment.address);
+    let claimable_ts = test_data.client.deploy_to_strategy(&100000).positions.get(0).unwrap();
+
+    // The project returns the loan with its interest in 4 monthly installments
+    let regular_payment = investment.get_investment(&test_data.client.address, &claimable_ts).regular_payment;
+    advance_days(&e, 7);
+    for _ in 0..4 {
+        test_data.token_admin.mint(&test_data.admin, &regular_payment);
+        investment.add_company_transfer(&regular_payment);
+        investment.process_investor_payment(&test_data.client.address, &claimable_ts);
+        advance_days(&e, 30);
+    }
+
+    let position = test_data.client.sync_strategy();
+    let yield_amount = position.returned - position.deployed;
+    assert!(yield_amount > 0);
+    assert!(position.credited > 0 && position.credited <= yield_amount);
+    assert!((test_data.client.balance(&test_data.user) - (150000 + position.credited * 3 / 4)).abs() <= 1);
+    assert!((test_data.client.balance(&another_user) - (50000 + position.credited / 4)).abs() <= 1);
+
+    // Syncing again credits nothing more
+    assert_eq!(test_data.client.sync_strategy().credited, position.credited);
+
+    // Depositors take their yield; the principal of the loan is back in the surplus
+    test_data.client.withdraw(&test_data.user, &test_data.client.balance(&test_data.user));
+    test_data.client.withdraw(&another_user, &test_data.client.balance(&another_user));
+    assert!(test_data.token.balance(&test_data.user) > 300000);
+    assert!(test_data.token.balance(&test_data.client.address) >= 100000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #203)")]
+fn test_strategy_deploy_more_than_surplus() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &300000_i128);
+    let investment = create_investment_contract(&e, &test_data);
+    test_data.client.deposit(&test_data.user, &200000, &None, &None);
+    test_data.token_admin.mint(&test_data.client.address, &50000);
+
+    test_data.client.set_strategy(&investment.address);
+    test_data.client.deploy_to_strategy(&50001);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #217)")]
+fn test_strategy_not_set() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &300000_i128);
+    test_data.client.deposit(&test_data.user, &200000, &None, &None);
+    test_data.client.deploy_to_strategy(&100000);
+}
+
+#[test]
+fn test_savings_goal_reached() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+
+    let goal = test_data.client.set_goal(&test_data.user, &70);
+    assert_eq!(goal, SavingsGoal { target: 70, reached: false });
+
+    test_data.client.deposit(&test_data.user, &50, &None, &None);
+    assert_eq!(test_data.client.get_goal(&test_data.user).unwrap().reached, false);
+
+    test_data.client.deposit(&test_data.user, &30, &None, &None);
+    assert_eq!(test_data.client.get_goal(&test_data.user).unwrap().reached, true);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #216)")]
+fn test_savings_goal_must_be_positive() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.set_goal(&test_data.user, &0);
+}
+
+fn authorize_recurring(e: &Env, test_data: &TestData, puller: &Address) {
+    test_data.token.approve(&test_data.user, &test_data.client.address, &100, &(e.ledger().sequence() + 100_000));
+    test_data.client.authorize_recurring(&test_data.user, &10, &(7 * 86400), puller);
+}
+
+#[test]
+fn test_recurring_deposit() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    let puller = Address::generate(&e);
+    authorize_recurring(&e, &test_data, &puller);
+
+    test_data.client.pull_recurring(&puller, &test_data.user, &10);
+    advance_days(&e, 7);
+    test_data.client.pull_recurring(&puller, &test_data.user, &8);
+
+    assert_eq!(test_data.client.balance(&test_data.user), 18);
+    assert_eq!(test_data.token.balance(&test_data.user), 82);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #222)")]
+fn test_recurring_deposit_interval_not_elapsed() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    let puller = Address::generate(&e);
+    authorize_recurring(&e, &test_data, &puller);
+
+    test_data.client.pull_recurring(&puller, &test_data.user, &10);
+    advance_time(&e, 7 * 86400 - 1);
+    test_data.client.pull_recurring(&puller, &test_data.user, &10);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #223)")]
+fn test_recurring_deposit_exceeds_limit() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    let puller = Address::generate(&e);
+    authorize_recurring(&e, &test_data, &puller);
+
+    test_data.client.pull_recurring(&puller, &test_data.user, &11);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #221)")]
+fn test_recurring_deposit_wrong_puller() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    let puller = Address::generate(&e);
+    let another_puller = Address::generate(&e);
+    authorize_recurring(&e, &test_data, &puller);
+
+    test_data.client.pull_recurring(&another_puller, &test_data.user, &10);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #232)")]
+fn test_recurring_deposit_allowance_revoked() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    let puller = Address::generate(&e);
+    authorize_recurring(&e, &test_data, &puller);
+
+    // The authorization outlives the token allowance it relies on
+    test_data.token.approve(&test_data.user, &test_data.client.address, &0, &(e.ledger().sequence() + 100_000));
+    test_data.client.pull_recurring(&puller, &test_data.user, &10);
+}
+
+fn set_term_products(test_data: &TestData) {
+    test_data.client.set_term_product(&30, &50);
+    test_data.client.set_term_product(&90, &200);
+    test_data.client.set_term_product(&180, &500);
+}
+
+#[test]
+fn test_term_deposit_at_maturity() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &1000_i128);
+    set_term_products(&test_data);
+    assert_eq!(test_data.client.get_term_products().len(), 3);
+    test_data.token_admin.mint(&test_data.client.address, &100);
+
+    let deposit = test_data.client.open_term_deposit(&test_data.user, &1000, &90);
+    assert_eq!(deposit, TermDeposit { id: 0, amount: 1000, interest: 20, start_ts: 0, maturity_ts: 90 * 86400 });
+    assert_eq!(sweep(&e, &test_data, 80), 0);
+
+    e.ledger().set_timestamp(deposit.maturity_ts);
+    assert_eq!(test_data.client.close_term_deposit(&test_data.user, &0), 1020);
+    assert_eq!(test_data.token.balance(&test_data.user), 1020);
+    assert_eq!(test_data.client.get_term_deposits(&test_data.user).len(), 0);
+}
+
+#[test]
+fn test_term_deposit_early_withdrawal_forfeits_interest() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &1000_i128);
+    set_term_products(&test_data);
+    test_data.token_admin.mint(&test_data.client.address, &100);
+
+    test_data.client.open_term_deposit(&test_data.user, &400, &30);
+    let deposit = test_data.client.open_term_deposit(&test_data.user, &600, &180);
+    assert_eq!(deposit.id, 1);
+
+    e.ledger().set_timestamp(deposit.maturity_ts - 1);
+    assert_eq!(test_data.client.close_term_deposit(&test_data.user, &1), 600);
+    assert_eq!(test_data.client.get_term_deposits(&test_data.user).len(), 1);
+
+    // Only the interest still promised to the 30 days deposit stays reserved
+    assert_eq!(sweep(&e, &test_data, 98), 0);
+}
+
+#[test]
+fn test_term_deposit_ids_not_reused() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &1000_i128);
+    set_term_products(&test_data);
+    test_data.token_admin.mint(&test_data.client.address, &100);
+
+    test_data.client.open_term_deposit(&test_data.user, &300, &30);
+    test_data.client.open_term_deposit(&test_data.user, &300, &30);
+    test_data.client.close_term_deposit(&test_data.user, &1);
+
+    let deposit = test_data.client.open_term_deposit(&test_data.user, &300, &30);
+    assert_eq!(deposit.id, 2);
+    let deposits = test_data.client.get_term_deposits(&test_data.user);
+    assert_eq!(deposits.len(), 2);
+    assert_eq!(deposits.get(1).unwrap().id, 2);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #226)")]
+fn test_term_deposit_unknown_product() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &1000_i128);
+    set_term_products(&test_data);
+    test_data.client.open_term_deposit(&test_data.user, &1000, &60);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #229)")]
+fn test_term_deposit_interest_not_covered() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &1000_i128);
+    set_term_products(&test_data);
+    test_data.token_admin.mint(&test_data.client.address, &19);
+    test_data.client.open_term_deposit(&test_data.user, &1000, &90);
+}
+
+#[test]
+#[cfg(feature = "upgrade-tests")]
+fn test_upgrade_and_migrate() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.deposit(&test_data.user, &50, &None, &None);
+    assert_eq!(test_data.client.version(), 1);
+
+    // Simulate data written by a build that predates versioning
+    e.as_contract(&test_data.client.address, || soroban_examples_upgrade::set_version(&e, 0));
+
+    let new_wasm_hash = e.deployer().upload_contract_wasm(crypto_deposit_wasm::WASM);
+    assert_eq!(test_data.client.upgrade(&new_wasm_hash), true);
+
+    let upgraded = crypto_deposit_wasm::Client::new(&e, &test_data.client.address);
+    assert_eq!(upgraded.version(), 0);
+    assert_eq!(upgraded.migrate(), 0);
+    assert_eq!(upgraded.version(), 1);
+    assert_eq!(upgraded.balance(&test_data.user), 50);
+    assert_eq!(upgraded.withdraw(&test_data.user, &20), 30);
+
+    // The migration only runs once per version
+    assert!(upgraded.try_migrate().is_err());
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #238)")]
+fn test_migrate_current_version() {
+    let e = Env::default();
+    let test_data = create_contract(&e, &100_i128);
+    test_data.client.migrate();
+}
+
+#[test]
+fn test_reentrant_token_cannot_double_spend() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let user = Address::generate(&e);
+    let token = create_reentrant_token(&e);
+    token.mint(&user, &1000);
+    let client = CryptoDepositClient::new(&e, &e.register(CryptoDeposit {}, (admin, token.address.clone(), 0_u32, EMERGENCY_DELAY, 0_i128)));
+
+    // The token calls `deposit` again while the first deposit pulls the tokens
+    token.arm(&client.address, &Symbol::new(&e, "deposit"), &(user.clone(), 500_i128, None::<u64>, None::<String>).into_val(&e));
+    client.deposit(&user, &500, &None, &None);
+    assert_eq!(token.reentered(), 0);
+    assert_eq!(client.balance(&user), 500);
+    assert_eq!(token.balance(&client.address), 500);
+
+    // And `withdraw` while the withdrawal pays the user
+    token.arm(&client.address, &Symbol::new(&e, "withdraw"), &(user.clone(), 300_i128).into_val(&e));
+    client.withdraw(&user, &300);
+    assert_eq!(token.attempts(), 2);
+    assert_eq!(token.reentered(), 0);
+    assert_eq!(client.balance(&user), 200);
+    assert_eq!(token.balance(&user), 800);
+    assert_eq!(token.balance(&client.address), 200);
+}
+
+#[test]
+fn events_match_snapshot() {
+    let e = Env::default();
+    let test_data = create_contract_with_fee(&e, &1000, 100);
+
+    let mut snapshot = EventSnapshot::new(&e, &test_data.client.address);
+    snapshot.label(&test_data.user, "user").label(&test_data.token.address, "token");
+    test_data.client.set_goal(&test_data.user, &1000);
+    snapshot.capture("set_goal");
+    test_data.client.deposit(&test_data.user, &1000, &None, &Some(String::from_str(&e, "rent")));
+    snapshot.capture("deposit");
+    test_data.client.withdraw(&test_data.user, &400);
+    snapshot.capture("withdraw");
+
+    snapshot.assert_matches("snapshots/events/goal_deposit_withdraw.events");
+}
+
+#[test]
+fn error_codes_in_registry_range() {
+    use crate::Error;
+
+    assert!(soroban_examples_errors::CRYPTO_DEPOSIT.contains(Error::AddressInsufficientBalance as u32));
+    assert!(soroban_examples_errors::CRYPTO_DEPOSIT.contains(Error::NegativeDepositCap as u32));
 }
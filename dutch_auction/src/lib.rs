@@ -0,0 +1,154 @@
+#![no_std]
+
+//! Dutch (descending-price) auction of a lot of tokens. The seller escrows the lot when deploying the
+//! auction; from the start ledger the asking price drops by a fixed amount every `interval_ledgers`
+//! ledgers until it reaches the floor, and the first buyer to accept the current price with `buy`
+//! gets the whole lot. Both the lot and the payment are Stellar Asset Contract tokens.
+
+use soroban_examples_events as events;
+use soroban_sdk::{contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Env, Symbol};
+
+mod storage;
+
+// Topics follow the workspace event schema: (CONTRACT_TAG, action)
+pub const CONTRACT_TAG: Symbol = symbol_short!("DUTCH");
+pub const TOPIC_SOLD: Symbol = symbol_short!("SOLD");
+pub const TOPIC_CANCELLED: Symbol = symbol_short!("CANCELLED");
+
+macro_rules! require {
+    ($cond:expr, $err:expr) => {
+        if !$cond {
+            return Err($err);
+        }
+    };
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AuctionNotStarted = 801,
+    AuctionClosed = 802,
+    PriceAboveLimit = 803,
+    InvalidTerms = 804,
+    SellerCannotBuy = 805,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DutchTerms {
+    /// Asking price for the whole lot at `start_ledger`, in the payment token.
+    pub start_price: i128,
+    /// The price never drops below it.
+    pub floor_price: i128,
+    /// Drop of the price every `interval_ledgers`.
+    pub decay: i128,
+    pub interval_ledgers: u32,
+    pub start_ledger: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionConfig {
+    pub seller: Address,
+    pub lot_token: Address,
+    pub lot_amount: i128,
+    pub payment_token: Address,
+    pub terms: DutchTerms,
+}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum AuctionStatus {
+    Scheduled = 1,
+    Open = 2,
+    Sold = 3,
+    Cancelled = 4,
+}
+
+fn current_status(e: &Env, config: &AuctionConfig) -> AuctionStatus {
+    match storage::get_outcome(e) {
+        Some(outcome) => outcome,
+        None if e.ledger().sequence() < config.terms.start_ledger => AuctionStatus::Scheduled,
+        None => AuctionStatus::Open,
+    }
+}
+
+/// Asking price `ledgers` ledgers after the start.
+pub fn price_after(terms: &DutchTerms, ledgers: u32) -> i128 {
+    let intervals = (ledgers / terms.interval_ledgers) as i128;
+    let price = terms.start_price.saturating_sub(terms.decay.saturating_mul(intervals));
+    price.max(terms.floor_price)
+}
+
+fn current_price(e: &Env, config: &AuctionConfig) -> i128 {
+    price_after(&config.terms, e.ledger().sequence().saturating_sub(config.terms.start_ledger))
+}
+
+#[contract]
+pub struct DutchAuction;
+
+#[contractimpl]
+impl DutchAuction {
+    /// Sells `lot_amount` of `lot_token`, escrowed from `seller` right away, for `payment_token`.
+    pub fn __constructor(e: Env, seller: Address, lot_token: Address, lot_amount: i128, payment_token: Address, terms: DutchTerms) -> Result<(), Error> {
+        seller.require_auth();
+        require!(lot_amount > 0 && terms.floor_price > 0 && terms.decay > 0, Error::InvalidTerms);
+        require!(terms.start_price >= terms.floor_price && terms.interval_ledgers > 0, Error::InvalidTerms);
+
+        storage::set_config(&e, &AuctionConfig { seller: seller.clone(), lot_token: lot_token.clone(), lot_amount, payment_token, terms });
+        token::Client::new(&e, &lot_token).transfer(&seller, &e.current_contract_address(), &lot_amount);
+        Ok(())
+    }
+
+    /// Current asking price for the whole lot. Before the start it is the start price.
+    pub fn price(e: Env) -> i128 {
+        current_price(&e, &storage::get_config(&e))
+    }
+
+    /// `buyer` takes the lot at the current price, as long as it is not above `max_price` (the price
+    /// the buyer saw when signing, so a late inclusion never makes it pay more). Returns the price paid.
+    pub fn buy(e: Env, buyer: Address, max_price: i128) -> Result<i128, Error> {
+        buyer.require_auth();
+        let config = storage::get_config(&e);
+        match current_status(&e, &config) {
+            AuctionStatus::Open => {}
+            AuctionStatus::Scheduled => return Err(Error::AuctionNotStarted),
+            _ => return Err(Error::AuctionClosed),
+        }
+        require!(buyer != config.seller, Error::SellerCannotBuy);
+
+        let price = current_price(&e, &config);
+        require!(price <= max_price, Error::PriceAboveLimit);
+
+        storage::set_outcome(&e, AuctionStatus::Sold);
+        token::Client::new(&e, &config.payment_token).transfer(&buyer, &config.seller, &price);
+        token::Client::new(&e, &config.lot_token).transfer(&e.current_contract_address(), &buyer, &config.lot_amount);
+        events::publish(&e, CONTRACT_TAG, TOPIC_SOLD, (buyer, config.lot_amount, price));
+        Ok(price)
+    }
+
+    /// The seller withdraws the lot while nobody has bought it.
+    pub fn cancel(e: Env) -> Result<bool, Error> {
+        let config = storage::get_config(&e);
+        config.seller.require_auth();
+        require!(storage::get_outcome(&e).is_none(), Error::AuctionClosed);
+
+        storage::set_outcome(&e, AuctionStatus::Cancelled);
+        token::Client::new(&e, &config.lot_token).transfer(&e.current_contract_address(), &config.seller, &config.lot_amount);
+        events::publish(&e, CONTRACT_TAG, TOPIC_CANCELLED, config.lot_amount);
+        Ok(true)
+    }
+
+    pub fn config(e: Env) -> AuctionConfig {
+        storage::get_config(&e)
+    }
+
+    pub fn status(e: Env) -> AuctionStatus {
+        current_status(&e, &storage::get_config(&e))
+    }
+}
+
+#[cfg(test)]
+mod test;
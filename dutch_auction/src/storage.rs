@@ -0,0 +1,29 @@
+use crate::{AuctionConfig, AuctionStatus};
+use soroban_examples_storage::StorageCell;
+use soroban_sdk::{contracttype, Env};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Config,
+    Outcome,
+}
+
+const CONFIG: StorageCell<DataKey, AuctionConfig> = StorageCell::instance(DataKey::Config);
+const OUTCOME: StorageCell<DataKey, AuctionStatus> = StorageCell::instance(DataKey::Outcome);
+
+pub fn get_config(e: &Env) -> AuctionConfig {
+    CONFIG.get_or_else(e, || panic!("Auction config has expired"))
+}
+
+pub fn set_config(e: &Env, config: &AuctionConfig) {
+    CONFIG.set(e, config);
+}
+
+pub fn get_outcome(e: &Env) -> Option<AuctionStatus> {
+    OUTCOME.get(e)
+}
+
+pub fn set_outcome(e: &Env, outcome: AuctionStatus) {
+    OUTCOME.set(e, &outcome);
+}
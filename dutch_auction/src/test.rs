@@ -0,0 +1,164 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::{price_after, AuctionStatus, DutchAuction, DutchAuctionClient, DutchTerms, CONTRACT_TAG, TOPIC_SOLD};
+use soroban_examples_events::EVENT_VERSION;
+use soroban_examples_testutils::{advance_ledgers, create_env, create_token_contract, mint_to, TokenClient};
+use soroban_sdk::{testutils::{Address as _, Events, Ledger}, Address, Env, IntoVal};
+
+const START_LEDGER: u32 = 100;
+const LOT: i128 = 1_000_000;
+
+struct TestData<'a> {
+    seller: Address,
+    buyer: Address,
+    lot_token: TokenClient<'a>,
+    payment_token: TokenClient<'a>,
+    client: DutchAuctionClient<'a>,
+}
+
+// 10000 at the start, 500 less every 10 ledgers, down to 6000
+fn terms() -> DutchTerms {
+    DutchTerms { start_price: 10_000, floor_price: 6_000, decay: 500, interval_ledgers: 10, start_ledger: START_LEDGER }
+}
+
+fn create_auction(e: &Env) -> TestData<'_> {
+    let (seller, buyer) = (Address::generate(e), Address::generate(e));
+    let (lot_token, lot_admin) = create_token_contract(e, &Address::generate(e));
+    let (payment_token, payment_admin) = create_token_contract(e, &Address::generate(e));
+    mint_to(&lot_admin, &[&seller], LOT);
+    mint_to(&payment_admin, &[&buyer], 20_000);
+
+    let client = DutchAuctionClient::new(
+        e,
+        &e.register(DutchAuction, (seller.clone(), lot_token.address.clone(), LOT, payment_token.address.clone(), terms())),
+    );
+
+    TestData { seller, buyer, lot_token, payment_token, client }
+}
+
+#[test]
+fn test_price_decays_down_to_the_floor() {
+    assert_eq!(price_after(&terms(), 0), 10_000);
+    assert_eq!(price_after(&terms(), 9), 10_000);
+    assert_eq!(price_after(&terms(), 10), 9_500);
+    assert_eq!(price_after(&terms(), 75), 6_500);
+    assert_eq!(price_after(&terms(), 80), 6_000);
+    assert_eq!(price_after(&terms(), u32::MAX), 6_000);
+}
+
+#[test]
+fn test_lot_is_escrowed_until_bought() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+    assert_eq!(test_data.lot_token.balance(&test_data.client.address), LOT);
+    assert_eq!(test_data.client.status(), AuctionStatus::Scheduled);
+    assert_eq!(test_data.client.price(), 10_000);
+
+    e.ledger().set_sequence_number(START_LEDGER);
+    advance_ledgers(&e, 25);
+    assert_eq!(test_data.client.status(), AuctionStatus::Open);
+    assert_eq!(test_data.client.price(), 9_000);
+
+    assert_eq!(test_data.client.buy(&test_data.buyer, &9_000), 9_000);
+    assert_eq!(test_data.client.status(), AuctionStatus::Sold);
+    assert_eq!(test_data.lot_token.balance(&test_data.buyer), LOT);
+    assert_eq!(test_data.lot_token.balance(&test_data.client.address), 0);
+    assert_eq!(test_data.payment_token.balance(&test_data.seller), 9_000);
+    assert_eq!(
+        e.events().all().last().unwrap(),
+        (test_data.client.address.clone(), (CONTRACT_TAG, TOPIC_SOLD).into_val(&e), (EVENT_VERSION, (test_data.buyer.clone(), LOT, 9_000_i128)).into_val(&e))
+    );
+}
+
+#[test]
+fn test_buy_at_the_floor() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    e.ledger().set_sequence_number(START_LEDGER + 10_000);
+    assert_eq!(test_data.client.buy(&test_data.buyer, &10_000), 6_000);
+    assert_eq!(test_data.payment_token.balance(&test_data.buyer), 14_000);
+}
+
+#[test]
+fn test_seller_cancels_and_gets_the_lot_back() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    e.ledger().set_sequence_number(START_LEDGER + 30);
+    assert!(test_data.client.cancel());
+    assert_eq!(test_data.client.status(), AuctionStatus::Cancelled);
+    assert_eq!(test_data.lot_token.balance(&test_data.seller), LOT);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #801)")]
+fn test_buy_before_start() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    e.ledger().set_sequence_number(START_LEDGER - 1);
+    test_data.client.buy(&test_data.buyer, &10_000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #802)")]
+fn test_buy_twice() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+    e.ledger().set_sequence_number(START_LEDGER);
+
+    test_data.client.buy(&test_data.buyer, &10_000);
+    test_data.client.buy(&test_data.buyer, &10_000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #802)")]
+fn test_buy_after_cancel() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+    e.ledger().set_sequence_number(START_LEDGER);
+
+    test_data.client.cancel();
+    test_data.client.buy(&test_data.buyer, &10_000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #803)")]
+fn test_price_above_limit() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    // The price only drops once the first interval is over
+    e.ledger().set_sequence_number(START_LEDGER + 9);
+    test_data.client.buy(&test_data.buyer, &9_500);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #805)")]
+fn test_seller_cannot_buy() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+    e.ledger().set_sequence_number(START_LEDGER);
+
+    test_data.client.buy(&test_data.seller, &10_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Context, InvalidAction)")]
+fn test_floor_above_start_price() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    let terms = DutchTerms { floor_price: 11_000, ..terms() };
+    e.register(DutchAuction, (test_data.seller.clone(), test_data.lot_token.address.clone(), LOT, test_data.payment_token.address.clone(), terms));
+}
+
+#[test]
+fn error_codes_in_registry_range() {
+    use super::Error;
+
+    assert!(soroban_examples_errors::DUTCH_AUCTION.contains(Error::AuctionNotStarted as u32));
+    assert!(soroban_examples_errors::DUTCH_AUCTION.contains(Error::SellerCannotBuy as u32));
+}
@@ -0,0 +1,10 @@
+use soroban_sdk::{contractclient, Address, Env, String};
+
+// Functions of the `HouseAsset` registry called by the auction
+#[contractclient(name = "HouseAssetClient")]
+pub trait HouseAssetInterface {
+    fn owner_of(e: Env, id: String) -> Address;
+    fn operator(e: Env, id: String) -> Option<Address>;
+    fn transfer(e: Env, id: String, to: Address) -> bool;
+    fn transfer_by_operator(e: Env, id: String, operator: Address, to: Address) -> bool;
+}
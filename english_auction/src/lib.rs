@@ -0,0 +1,283 @@
+#![no_std]
+
+//! English auction of a `HouseAsset`: bidders escrow their bids in a token, each bid has to beat the
+//! highest one by a minimum increment, and the outbid bidders take their tokens back with `withdraw`.
+//! Once the auction has ended anyone can `close` it, which hands the asset over to the highest bidder
+//! and pays the seller. The seller approves the auction as operator of the asset in the registry, the
+//! same approval `HousePurchase` relies on, and the first bid uses it to move the asset into the
+//! auction: from then on the seller can neither revoke the approval nor sell the asset elsewhere.
+//!
+//! The auction is a time-bound state machine: `Scheduled` until `start_ts`, `Open` until the end,
+//! `Ended` until someone closes it, and then `Settled`, `Unsold` or `Cancelled`.
+
+use soroban_examples_events as events;
+use soroban_sdk::{contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Env, String, Symbol};
+
+mod asset;
+mod storage;
+
+// Topics follow the workspace event schema: (CONTRACT_TAG, action)
+pub const CONTRACT_TAG: Symbol = symbol_short!("AUCTION");
+pub const TOPIC_BID: Symbol = symbol_short!("BID");
+pub const TOPIC_EXTENDED: Symbol = symbol_short!("EXTENDED");
+pub const TOPIC_WITHDRAWN: Symbol = symbol_short!("WITHDRAWN");
+pub const TOPIC_SETTLED: Symbol = symbol_short!("SETTLED");
+pub const TOPIC_UNSOLD: Symbol = symbol_short!("UNSOLD");
+pub const TOPIC_CANCELLED: Symbol = symbol_short!("CANCELLED");
+
+macro_rules! require {
+    ($cond:expr, $err:expr) => {
+        if !$cond {
+            return Err($err);
+        }
+    };
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AuctionNotOpen = 701,
+    BidTooLow = 702,
+    SellerCannotBid = 703,
+    AssetNotApproved = 704,
+    NothingToWithdraw = 705,
+    AuctionNotEnded = 706,
+    AuctionAlreadyClosed = 707,
+    AuctionHasBids = 708,
+    InvalidTerms = 709,
+    AssetNotOwnedBySeller = 710,
+    AssetNotHeld = 711,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionTerms {
+    /// Smallest first bid.
+    pub reserve_price: i128,
+    /// How much each bid has to add to the highest one.
+    pub min_increment: i128,
+    pub start_ts: u64,
+    pub end_ts: u64,
+    /// A bid placed less than `extension` seconds before the end moves the end to `extension` seconds
+    /// after the bid, so nobody can win by bidding at the last second (0 to disable).
+    pub extension: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionConfig {
+    pub seller: Address,
+    pub asset: Address,
+    pub asset_id: String,
+    pub token: Address,
+    pub terms: AuctionTerms,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Bid {
+    pub bidder: Address,
+    pub amount: i128,
+    pub ts: u64,
+}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum AuctionStatus {
+    Scheduled = 1,
+    Open = 2,
+    Ended = 3,
+    /// Closed with a winner: the asset and the price have changed hands.
+    Settled = 4,
+    /// Closed without any bid.
+    Unsold = 5,
+    /// Cancelled by the seller before the first bid, or closed while the registry had the asset frozen;
+    /// the highest bid, if any, is returned to its bidder and the asset with `return_asset`.
+    Cancelled = 6,
+}
+
+fn current_status(e: &Env, config: &AuctionConfig) -> AuctionStatus {
+    if let Some(outcome) = storage::get_outcome(e) {
+        return outcome;
+    }
+
+    let now = e.ledger().timestamp();
+    if now < config.terms.start_ts {
+        AuctionStatus::Scheduled
+    } else if now < storage::get_end_ts(e) {
+        AuctionStatus::Open
+    } else {
+        AuctionStatus::Ended
+    }
+}
+
+fn is_approved_operator(e: &Env, config: &AuctionConfig) -> bool {
+    asset::HouseAssetClient::new(e, &config.asset).operator(&config.asset_id) == Some(e.current_contract_address())
+}
+
+#[contract]
+pub struct EnglishAuction;
+
+#[contractimpl]
+impl EnglishAuction {
+    /// Auctions the asset `asset_id` of the `asset` registry, owned by `seller`, for bids in `token`.
+    ///
+    /// Bids are only accepted once the seller has approved this contract as operator of the asset, which
+    /// the first bid uses to take the asset into escrow.
+    pub fn __constructor(e: Env, seller: Address, asset: Address, asset_id: String, token: Address, terms: AuctionTerms) -> Result<(), Error> {
+        seller.require_auth();
+        require!(terms.reserve_price > 0 && terms.min_increment > 0, Error::InvalidTerms);
+        require!(terms.start_ts < terms.end_ts && terms.end_ts > e.ledger().timestamp(), Error::InvalidTerms);
+        require!(asset::HouseAssetClient::new(&e, &asset).owner_of(&asset_id) == seller, Error::AssetNotOwnedBySeller);
+
+        storage::set_end_ts(&e, terms.end_ts);
+        storage::set_config(&e, &AuctionConfig { seller, asset, asset_id, token, terms });
+        Ok(())
+    }
+
+    /// `bidder` bids `amount`, which is escrowed by the contract. The first bid must reach the reserve
+    /// price and every later one beat the highest bid by the minimum increment. The bid it replaces
+    /// becomes withdrawable by its bidder.
+    ///
+    /// The first bid also moves the asset from the seller to the auction, so the bidders know it will be
+    /// there when the auction closes. It fails with `AssetNotApproved` if the auction is not the
+    /// operator of the asset or the registry does not let the asset change hands.
+    pub fn bid(e: Env, bidder: Address, amount: i128) -> Result<Bid, Error> {
+        bidder.require_auth();
+        let config = storage::get_config(&e);
+        require!(current_status(&e, &config) == AuctionStatus::Open, Error::AuctionNotOpen);
+        require!(bidder != config.seller, Error::SellerCannotBid);
+
+        let highest = storage::get_highest_bid(&e);
+        let min_amount = match &highest {
+            Some(highest) => highest.amount + config.terms.min_increment,
+            None => config.terms.reserve_price,
+        };
+        require!(amount >= min_amount, Error::BidTooLow);
+        if highest.is_none() {
+            require!(is_approved_operator(&e, &config), Error::AssetNotApproved);
+            let asset = asset::HouseAssetClient::new(&e, &config.asset);
+            let escrowed = asset.try_transfer_by_operator(&config.asset_id, &e.current_contract_address(), &e.current_contract_address());
+            require!(matches!(escrowed, Ok(Ok(true))), Error::AssetNotApproved);
+        }
+
+        let now = e.ledger().timestamp();
+        let bid = Bid { bidder: bidder.clone(), amount, ts: now };
+        if let Some(highest) = highest {
+            storage::add_pending_return(&e, &highest.bidder, highest.amount);
+        }
+        storage::set_highest_bid(&e, &bid);
+
+        let end_ts = storage::get_end_ts(&e);
+        if end_ts - now < config.terms.extension {
+            storage::set_end_ts(&e, now + config.terms.extension);
+            events::publish(&e, CONTRACT_TAG, TOPIC_EXTENDED, now + config.terms.extension);
+        }
+
+        token::Client::new(&e, &config.token).transfer(&bidder, &e.current_contract_address(), &amount);
+        events::publish(&e, CONTRACT_TAG, TOPIC_BID, (bidder, amount));
+        Ok(bid)
+    }
+
+    /// Pays `bidder` back the bids it had escrowed and that were outbid (or the winning bid, if the
+    /// auction got cancelled). Returns the amount paid.
+    pub fn withdraw(e: Env, bidder: Address) -> Result<i128, Error> {
+        bidder.require_auth();
+        let amount = storage::get_pending_return(&e, &bidder);
+        require!(amount > 0, Error::NothingToWithdraw);
+
+        storage::remove_pending_return(&e, &bidder);
+        let config = storage::get_config(&e);
+        token::Client::new(&e, &config.token).transfer(&e.current_contract_address(), &bidder, &amount);
+        events::publish(&e, CONTRACT_TAG, TOPIC_WITHDRAWN, (bidder, amount));
+        Ok(amount)
+    }
+
+    /// Closes an ended auction; anyone can call it. The highest bidder gets the escrowed asset and the
+    /// seller the winning bid. If the registry has frozen the asset, the auction is cancelled, the
+    /// winning bid returned, and the asset goes back to the seller with `return_asset` once unfrozen.
+    pub fn close(e: Env) -> Result<AuctionStatus, Error> {
+        let config = storage::get_config(&e);
+        match current_status(&e, &config) {
+            AuctionStatus::Ended => {}
+            AuctionStatus::Scheduled | AuctionStatus::Open => return Err(Error::AuctionNotEnded),
+            _ => return Err(Error::AuctionAlreadyClosed),
+        }
+
+        let Some(winner) = storage::get_highest_bid(&e) else {
+            storage::set_outcome(&e, AuctionStatus::Unsold);
+            events::publish(&e, CONTRACT_TAG, TOPIC_UNSOLD, config.asset_id);
+            return Ok(AuctionStatus::Unsold);
+        };
+
+        // The outcome is written before calling out, so a call back into the auction finds it closed
+        storage::set_outcome(&e, AuctionStatus::Settled);
+        let asset = asset::HouseAssetClient::new(&e, &config.asset);
+        let transferred = asset.try_transfer(&config.asset_id, &winner.bidder);
+        if !matches!(transferred, Ok(Ok(true))) {
+            storage::set_outcome(&e, AuctionStatus::Cancelled);
+            storage::add_pending_return(&e, &winner.bidder, winner.amount);
+            events::publish(&e, CONTRACT_TAG, TOPIC_CANCELLED, (config.asset_id, Some(winner.bidder)));
+            return Ok(AuctionStatus::Cancelled);
+        }
+
+        token::Client::new(&e, &config.token).transfer(&e.current_contract_address(), &config.seller, &winner.amount);
+        events::publish(&e, CONTRACT_TAG, TOPIC_SETTLED, (config.asset_id, winner.bidder, winner.amount));
+        Ok(AuctionStatus::Settled)
+    }
+
+    /// The seller calls the auction off. Only possible before the first bid.
+    pub fn cancel(e: Env) -> Result<bool, Error> {
+        let config = storage::get_config(&e);
+        config.seller.require_auth();
+        match current_status(&e, &config) {
+            AuctionStatus::Scheduled | AuctionStatus::Open => {}
+            AuctionStatus::Ended => return Err(Error::AuctionNotOpen),
+            _ => return Err(Error::AuctionAlreadyClosed),
+        }
+        require!(storage::get_highest_bid(&e).is_none(), Error::AuctionHasBids);
+
+        storage::set_outcome(&e, AuctionStatus::Cancelled);
+        events::publish(&e, CONTRACT_TAG, TOPIC_CANCELLED, (config.asset_id, None::<Address>));
+        Ok(true)
+    }
+
+    /// Hands the escrowed asset back to the seller after the auction was cancelled with bids on it;
+    /// anyone can call it. Fails while the registry keeps the asset frozen.
+    pub fn return_asset(e: Env) -> Result<bool, Error> {
+        let config = storage::get_config(&e);
+        require!(current_status(&e, &config) == AuctionStatus::Cancelled, Error::AssetNotHeld);
+        let asset = asset::HouseAssetClient::new(&e, &config.asset);
+        require!(asset.owner_of(&config.asset_id) == e.current_contract_address(), Error::AssetNotHeld);
+
+        asset.transfer(&config.asset_id, &config.seller);
+        Ok(true)
+    }
+
+    pub fn config(e: Env) -> AuctionConfig {
+        storage::get_config(&e)
+    }
+
+    pub fn status(e: Env) -> AuctionStatus {
+        current_status(&e, &storage::get_config(&e))
+    }
+
+    /// Current end of the auction, later than the scheduled one if last-minute bids extended it.
+    pub fn end_ts(e: Env) -> u64 {
+        storage::get_end_ts(&e)
+    }
+
+    pub fn highest_bid(e: Env) -> Option<Bid> {
+        storage::get_highest_bid(&e)
+    }
+
+    /// Amount `bidder` can take back with `withdraw`.
+    pub fn pending_return(e: Env, bidder: Address) -> i128 {
+        storage::get_pending_return(&e, &bidder)
+    }
+}
+
+#[cfg(test)]
+mod test;
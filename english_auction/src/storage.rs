@@ -0,0 +1,64 @@
+use crate::{AuctionConfig, AuctionStatus, Bid};
+use soroban_examples_storage::{StorageCell, StorageMap};
+use soroban_sdk::{contracttype, Address, Env};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Config,
+    EndTs,
+    HighestBid,
+    Outcome,
+    PendingReturn(Address),
+}
+
+// The auction itself lives in instance storage; the amounts owed to outbid bidders get an entry each
+const CONFIG: StorageCell<DataKey, AuctionConfig> = StorageCell::instance(DataKey::Config);
+const END_TS: StorageCell<DataKey, u64> = StorageCell::instance(DataKey::EndTs);
+const HIGHEST_BID: StorageCell<DataKey, Bid> = StorageCell::instance(DataKey::HighestBid);
+const OUTCOME: StorageCell<DataKey, AuctionStatus> = StorageCell::instance(DataKey::Outcome);
+const PENDING_RETURNS: StorageMap<Address, i128, DataKey> = StorageMap::persistent(|bidder| DataKey::PendingReturn(bidder.clone()));
+
+pub fn get_config(e: &Env) -> AuctionConfig {
+    CONFIG.get_or_else(e, || panic!("Auction config has expired"))
+}
+
+pub fn set_config(e: &Env, config: &AuctionConfig) {
+    CONFIG.set(e, config);
+}
+
+pub fn get_end_ts(e: &Env) -> u64 {
+    END_TS.get_or_else(e, || 0)
+}
+
+pub fn set_end_ts(e: &Env, end_ts: u64) {
+    END_TS.set(e, &end_ts);
+}
+
+pub fn get_highest_bid(e: &Env) -> Option<Bid> {
+    HIGHEST_BID.get(e)
+}
+
+pub fn set_highest_bid(e: &Env, bid: &Bid) {
+    HIGHEST_BID.set(e, bid);
+}
+
+pub fn get_outcome(e: &Env) -> Option<AuctionStatus> {
+    OUTCOME.get(e)
+}
+
+pub fn set_outcome(e: &Env, outcome: AuctionStatus) {
+    OUTCOME.set(e, &outcome);
+}
+
+pub fn get_pending_return(e: &Env, bidder: &Address) -> i128 {
+    PENDING_RETURNS.get_or_else(e, bidder, || 0)
+}
+
+pub fn add_pending_return(e: &Env, bidder: &Address, amount: i128) {
+    PENDING_RETURNS.update(e, bidder, |pending| pending.unwrap_or(0) + amount);
+}
+
+pub fn remove_pending_return(e: &Env, bidder: &Address) {
+    PENDING_RETURNS.remove(e, bidder);
+}
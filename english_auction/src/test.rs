@@ -0,0 +1,310 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::{AuctionStatus, AuctionTerms, Bid, EnglishAuction, EnglishAuctionClient, CONTRACT_TAG, TOPIC_BID, TOPIC_EXTENDED};
+use house_asset::{Asset, AssetClient, Metadata};
+use soroban_examples_events::EVENT_VERSION;
+use soroban_examples_testutils::{advance_time, create_env, create_token_contract, mint_to, TokenClient};
+use soroban_sdk::{testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke}, Address, BytesN, Env, IntoVal, String};
+
+const START: u64 = 1_000;
+const END: u64 = 1_000 + 3 * 86400;
+const EXTENSION: u64 = 600;
+
+struct TestData<'a> {
+    seller: Address,
+    alice: Address,
+    bob: Address,
+    asset_id: String,
+    asset: AssetClient<'a>,
+    token: TokenClient<'a>,
+    client: EnglishAuctionClient<'a>,
+}
+
+fn terms() -> AuctionTerms {
+    AuctionTerms { reserve_price: 1000, min_increment: 100, start_ts: START, end_ts: END, extension: EXTENSION }
+}
+
+// The seller has approved the auction as operator of the asset, and the auction is open
+fn create_auction(e: &Env) -> TestData<'_> {
+    let (seller, alice, bob) = (Address::generate(e), Address::generate(e), Address::generate(e));
+    let asset = AssetClient::new(e, &e.register(Asset, (Address::generate(e),)));
+    let asset_id = String::from_str(e, "399fg7u6h69965h6");
+    let metadata = Metadata {
+        postal_address: String::from_str(e, "Calle Mayor 1, 28013 Madrid"),
+        legal_id: String::from_str(e, "9872023VH5797S0001WX"),
+        deed_hash: BytesN::from_array(e, &[1; 32]),
+    };
+    asset.register_asset(&asset_id, &seller, &metadata);
+
+    let (token, token_admin) = create_token_contract(e, &Address::generate(e));
+    mint_to(&token_admin, &[&alice, &bob], 10_000);
+
+    let client = EnglishAuctionClient::new(
+        e,
+        &e.register(EnglishAuction, (seller.clone(), asset.address.clone(), asset_id.clone(), token.address.clone(), terms())),
+    );
+    asset.approve_operator(&asset_id, &client.address);
+    e.ledger().set_timestamp(START);
+
+    TestData { seller, alice, bob, asset_id, asset, token, client }
+}
+
+fn end_auction(e: &Env, test_data: &TestData) {
+    e.ledger().set_timestamp(test_data.client.end_ts());
+}
+
+#[test]
+fn test_highest_bidder_wins_the_asset() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    test_data.client.bid(&test_data.alice, &1000);
+    test_data.client.bid(&test_data.bob, &1200);
+    assert_eq!(test_data.token.balance(&test_data.client.address), 2200);
+    assert_eq!(test_data.client.pending_return(&test_data.alice), 1000);
+
+    end_auction(&e, &test_data);
+    assert_eq!(test_data.client.status(), AuctionStatus::Ended);
+    assert_eq!(test_data.client.close(), AuctionStatus::Settled);
+    assert_eq!(test_data.asset.owner_of(&test_data.asset_id), test_data.bob);
+    assert_eq!(test_data.token.balance(&test_data.seller), 1200);
+
+    // The outbid bidder takes its bid back, even after the close
+    assert_eq!(test_data.client.withdraw(&test_data.alice), 1000);
+    assert_eq!(test_data.token.balance(&test_data.alice), 10_000);
+    assert_eq!(test_data.token.balance(&test_data.client.address), 0);
+}
+
+#[test]
+fn test_outbid_bidder_can_withdraw_while_open() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    test_data.client.bid(&test_data.alice, &1000);
+    test_data.client.bid(&test_data.bob, &1100);
+    test_data.client.bid(&test_data.alice, &1500);
+
+    assert_eq!(test_data.client.withdraw(&test_data.alice), 1000);
+    assert_eq!(test_data.client.withdraw(&test_data.bob), 1100);
+    assert_eq!(test_data.client.highest_bid(), Some(Bid { bidder: test_data.alice.clone(), amount: 1500, ts: START }));
+    assert_eq!(test_data.token.balance(&test_data.client.address), 1500);
+}
+
+#[test]
+fn test_last_minute_bid_extends_the_auction() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+    test_data.client.bid(&test_data.alice, &1000);
+
+    e.ledger().set_timestamp(END - 60);
+    test_data.client.bid(&test_data.bob, &1100);
+    assert_eq!(test_data.client.end_ts(), END - 60 + EXTENSION);
+    assert_eq!(
+        e.events().all().get(0).unwrap(),
+        (test_data.client.address.clone(), (CONTRACT_TAG, TOPIC_EXTENDED).into_val(&e), (EVENT_VERSION, END - 60 + EXTENSION).into_val(&e))
+    );
+    assert_eq!(
+        e.events().all().last().unwrap(),
+        (test_data.client.address.clone(), (CONTRACT_TAG, TOPIC_BID).into_val(&e), (EVENT_VERSION, (test_data.bob.clone(), 1100_i128)).into_val(&e))
+    );
+
+    // Still open at the scheduled end
+    e.ledger().set_timestamp(END);
+    assert_eq!(test_data.client.status(), AuctionStatus::Open);
+    test_data.client.bid(&test_data.alice, &1200);
+}
+
+#[test]
+fn test_close_without_bids() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    end_auction(&e, &test_data);
+    assert_eq!(test_data.client.close(), AuctionStatus::Unsold);
+    assert_eq!(test_data.asset.owner_of(&test_data.asset_id), test_data.seller);
+}
+
+#[test]
+fn test_first_bid_escrows_the_asset() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+    test_data.client.bid(&test_data.alice, &1000);
+    assert_eq!(test_data.asset.owner_of(&test_data.asset_id), test_data.client.address);
+
+    // The seller can no longer take the asset away from the bidders
+    e.mock_auths(&[MockAuth {
+        address: &test_data.seller,
+        invoke: &MockAuthInvoke {
+            contract: &test_data.asset.address,
+            fn_name: "transfer",
+            args: (test_data.asset_id.clone(), test_data.seller.clone()).into_val(&e),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(test_data.asset.try_transfer(&test_data.asset_id, &test_data.seller).is_err());
+    e.mock_all_auths();
+
+    test_data.client.bid(&test_data.bob, &1100);
+    end_auction(&e, &test_data);
+    assert_eq!(test_data.client.close(), AuctionStatus::Settled);
+    assert_eq!(test_data.asset.owner_of(&test_data.asset_id), test_data.bob);
+}
+
+#[test]
+fn test_frozen_asset_returns_the_winning_bid() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+    test_data.client.bid(&test_data.alice, &1000);
+
+    test_data.asset.freeze(&test_data.asset_id);
+    end_auction(&e, &test_data);
+    assert_eq!(test_data.client.close(), AuctionStatus::Cancelled);
+    assert_eq!(test_data.client.withdraw(&test_data.alice), 1000);
+
+    // The asset stays in the auction until the registry unfreezes it
+    assert!(test_data.client.try_return_asset().is_err());
+    test_data.asset.unfreeze(&test_data.asset_id);
+    assert!(test_data.client.return_asset());
+    assert_eq!(test_data.asset.owner_of(&test_data.asset_id), test_data.seller);
+}
+
+#[test]
+fn test_seller_cancels_before_bids() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    assert!(test_data.client.cancel());
+    assert_eq!(test_data.client.status(), AuctionStatus::Cancelled);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #701)")]
+fn test_bid_before_start() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    e.ledger().set_timestamp(START - 1);
+    test_data.client.bid(&test_data.alice, &1000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #701)")]
+fn test_bid_after_end() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    end_auction(&e, &test_data);
+    test_data.client.bid(&test_data.alice, &1000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #702)")]
+fn test_bid_below_reserve() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    test_data.client.bid(&test_data.alice, &999);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #702)")]
+fn test_bid_below_increment() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    test_data.client.bid(&test_data.alice, &1000);
+    test_data.client.bid(&test_data.bob, &1099);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #703)")]
+fn test_seller_cannot_bid() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    test_data.client.bid(&test_data.seller, &1000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #704)")]
+fn test_bid_requires_operator_approval() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    test_data.asset.revoke_operator(&test_data.asset_id);
+    test_data.client.bid(&test_data.alice, &1000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #705)")]
+fn test_highest_bidder_cannot_withdraw() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    test_data.client.bid(&test_data.alice, &1000);
+    test_data.client.withdraw(&test_data.alice);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #706)")]
+fn test_close_while_open() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    test_data.client.bid(&test_data.alice, &1000);
+    advance_time(&e, 60);
+    test_data.client.close();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #707)")]
+fn test_close_twice() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    test_data.client.bid(&test_data.alice, &1000);
+    end_auction(&e, &test_data);
+    test_data.client.close();
+    test_data.client.close();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #708)")]
+fn test_cancel_after_a_bid() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    test_data.client.bid(&test_data.alice, &1000);
+    test_data.client.cancel();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #711)")]
+fn test_return_asset_that_was_never_escrowed() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    test_data.client.cancel();
+    test_data.client.return_asset();
+}
+
+#[test]
+#[should_panic(expected = "Error(Context, InvalidAction)")]
+fn test_only_the_owner_can_auction() {
+    let e = create_env();
+    let test_data = create_auction(&e);
+
+    e.register(
+        EnglishAuction,
+        (test_data.alice.clone(), test_data.asset.address.clone(), test_data.asset_id.clone(), test_data.token.address.clone(), terms()),
+    );
+}
+
+#[test]
+fn error_codes_in_registry_range() {
+    use super::Error;
+
+    assert!(soroban_examples_errors::ENGLISH_AUCTION.contains(Error::AuctionNotOpen as u32));
+    assert!(soroban_examples_errors::ENGLISH_AUCTION.contains(Error::AssetNotHeld as u32));
+}
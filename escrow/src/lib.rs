@@ -0,0 +1,169 @@
+#![no_std]
+
+//! Two-party escrow with a neutral arbiter. The payer deposits the agreed amount with `fund`; from then
+//! on the payer can `release` it to the payee, the payee can `refund` it to the payer, and once the
+//! deadline has passed the payer can take it back with `refund` as well. Either party can instead
+//! `dispute` the deal, which freezes the funds until the arbiter `resolve`s it by splitting them
+//! between both sides. It is the escrow a `HousePurchase` keeps for its installments, reduced to a
+//! single payment.
+//!
+//! `AwaitingFunding` -> `Funded` -> `Released` | `Refunded` | `Disputed` -> `Resolved`
+
+use soroban_examples_events as events;
+use soroban_sdk::{contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Env, Symbol};
+
+mod storage;
+
+// Topics follow the workspace event schema: (CONTRACT_TAG, action)
+pub const CONTRACT_TAG: Symbol = symbol_short!("ESCROW");
+pub const TOPIC_FUNDED: Symbol = symbol_short!("FUNDED");
+pub const TOPIC_RELEASED: Symbol = symbol_short!("RELEASED");
+pub const TOPIC_REFUNDED: Symbol = symbol_short!("REFUNDED");
+pub const TOPIC_DISPUTED: Symbol = symbol_short!("DISPUTED");
+pub const TOPIC_RESOLVED: Symbol = symbol_short!("RESOLVED");
+
+macro_rules! require {
+    ($cond:expr, $err:expr) => {
+        if !$cond {
+            return Err($err);
+        }
+    };
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    /// The action is not possible in the current status of the escrow.
+    InvalidState = 1001,
+    NotAParty = 1002,
+    DeadlineNotReached = 1003,
+    InvalidSplit = 1004,
+    InvalidTerms = 1005,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EscrowConfig {
+    pub payer: Address,
+    pub payee: Address,
+    pub arbiter: Address,
+    pub token: Address,
+    pub amount: i128,
+    /// From this timestamp on the payer can take an undisputed deposit back.
+    pub deadline: u64,
+}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum EscrowStatus {
+    AwaitingFunding = 1,
+    Funded = 2,
+    Disputed = 3,
+    /// Paid to the payee.
+    Released = 4,
+    /// Paid back to the payer.
+    Refunded = 5,
+    /// Split by the arbiter.
+    Resolved = 6,
+}
+
+fn transfer(e: &Env, config: &EscrowConfig, to: &Address, amount: i128) {
+    if amount > 0 {
+        token::Client::new(e, &config.token).transfer(&e.current_contract_address(), to, &amount);
+    }
+}
+
+#[contract]
+pub struct Escrow;
+
+#[contractimpl]
+impl Escrow {
+    /// Escrows `amount` of `token` from `payer` to `payee`, with `arbiter` settling any dispute.
+    pub fn __constructor(e: Env, payer: Address, payee: Address, arbiter: Address, token: Address, amount: i128, deadline: u64) -> Result<(), Error> {
+        payer.require_auth();
+        require!(amount > 0 && deadline > e.ledger().timestamp(), Error::InvalidTerms);
+        require!(payer != payee && arbiter != payer && arbiter != payee, Error::InvalidTerms);
+
+        storage::set_config(&e, &EscrowConfig { payer, payee, arbiter, token, amount, deadline });
+        Ok(())
+    }
+
+    /// The payer deposits the agreed amount.
+    pub fn fund(e: Env) -> Result<EscrowStatus, Error> {
+        let config = storage::get_config(&e);
+        config.payer.require_auth();
+        require!(storage::get_status(&e) == EscrowStatus::AwaitingFunding, Error::InvalidState);
+
+        storage::set_status(&e, EscrowStatus::Funded);
+        token::Client::new(&e, &config.token).transfer(&config.payer, &e.current_contract_address(), &config.amount);
+        events::publish(&e, CONTRACT_TAG, TOPIC_FUNDED, config.amount);
+        Ok(EscrowStatus::Funded)
+    }
+
+    /// The payer is satisfied and pays the deposit to the payee.
+    pub fn release(e: Env) -> Result<EscrowStatus, Error> {
+        let config = storage::get_config(&e);
+        config.payer.require_auth();
+        require!(storage::get_status(&e) == EscrowStatus::Funded, Error::InvalidState);
+
+        storage::set_status(&e, EscrowStatus::Released);
+        transfer(&e, &config, &config.payee, config.amount);
+        events::publish(&e, CONTRACT_TAG, TOPIC_RELEASED, config.amount);
+        Ok(EscrowStatus::Released)
+    }
+
+    /// Pays the deposit back to the payer. The payee can do it at any time, the payer only once the
+    /// deadline has passed.
+    pub fn refund(e: Env, caller: Address) -> Result<EscrowStatus, Error> {
+        caller.require_auth();
+        let config = storage::get_config(&e);
+        require!(caller == config.payer || caller == config.payee, Error::NotAParty);
+        require!(storage::get_status(&e) == EscrowStatus::Funded, Error::InvalidState);
+        require!(caller == config.payee || e.ledger().timestamp() >= config.deadline, Error::DeadlineNotReached);
+
+        storage::set_status(&e, EscrowStatus::Refunded);
+        transfer(&e, &config, &config.payer, config.amount);
+        events::publish(&e, CONTRACT_TAG, TOPIC_REFUNDED, (caller, config.amount));
+        Ok(EscrowStatus::Refunded)
+    }
+
+    /// The payer or the payee hands the deal over to the arbiter, even past the deadline.
+    pub fn dispute(e: Env, caller: Address) -> Result<EscrowStatus, Error> {
+        caller.require_auth();
+        let config = storage::get_config(&e);
+        require!(caller == config.payer || caller == config.payee, Error::NotAParty);
+        require!(storage::get_status(&e) == EscrowStatus::Funded, Error::InvalidState);
+
+        storage::set_status(&e, EscrowStatus::Disputed);
+        events::publish(&e, CONTRACT_TAG, TOPIC_DISPUTED, caller);
+        Ok(EscrowStatus::Disputed)
+    }
+
+    /// The arbiter settles a dispute: `payee_amount` goes to the payee and the rest back to the payer.
+    pub fn resolve(e: Env, payee_amount: i128) -> Result<EscrowStatus, Error> {
+        let config = storage::get_config(&e);
+        config.arbiter.require_auth();
+        require!(storage::get_status(&e) == EscrowStatus::Disputed, Error::InvalidState);
+        require!(payee_amount >= 0 && payee_amount <= config.amount, Error::InvalidSplit);
+
+        storage::set_status(&e, EscrowStatus::Resolved);
+        let payer_amount = config.amount - payee_amount;
+        transfer(&e, &config, &config.payee, payee_amount);
+        transfer(&e, &config, &config.payer, payer_amount);
+        events::publish(&e, CONTRACT_TAG, TOPIC_RESOLVED, (payee_amount, payer_amount));
+        Ok(EscrowStatus::Resolved)
+    }
+
+    pub fn config(e: Env) -> EscrowConfig {
+        storage::get_config(&e)
+    }
+
+    pub fn status(e: Env) -> EscrowStatus {
+        storage::get_status(&e)
+    }
+}
+
+#[cfg(test)]
+mod test;
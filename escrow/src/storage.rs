@@ -0,0 +1,29 @@
+use crate::{EscrowConfig, EscrowStatus};
+use soroban_examples_storage::StorageCell;
+use soroban_sdk::{contracttype, Env};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Config,
+    Status,
+}
+
+const CONFIG: StorageCell<DataKey, EscrowConfig> = StorageCell::instance(DataKey::Config);
+const STATUS: StorageCell<DataKey, EscrowStatus> = StorageCell::instance(DataKey::Status);
+
+pub fn get_config(e: &Env) -> EscrowConfig {
+    CONFIG.get_or_else(e, || panic!("Escrow config has expired"))
+}
+
+pub fn set_config(e: &Env, config: &EscrowConfig) {
+    CONFIG.set(e, config);
+}
+
+pub fn get_status(e: &Env) -> EscrowStatus {
+    STATUS.get(e).unwrap_or(EscrowStatus::AwaitingFunding)
+}
+
+pub fn set_status(e: &Env, status: EscrowStatus) {
+    STATUS.set(e, &status);
+}
@@ -0,0 +1,198 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::{Error, Escrow, EscrowClient, EscrowStatus, CONTRACT_TAG, TOPIC_RESOLVED};
+use soroban_examples_events::EVENT_VERSION;
+use soroban_examples_testutils::{advance_days, create_env, create_token_contract, mint_to, TokenClient, SECONDS_IN_DAY};
+use soroban_sdk::{testutils::{Address as _, Events, Ledger}, Address, Env, IntoVal};
+
+const START: u64 = 1_000;
+const DEADLINE: u64 = START + 30 * SECONDS_IN_DAY;
+const AMOUNT: i128 = 1_000;
+
+struct TestData<'a> {
+    payer: Address,
+    payee: Address,
+    arbiter: Address,
+    token: TokenClient<'a>,
+    client: EscrowClient<'a>,
+}
+
+fn create_escrow(e: &Env) -> TestData<'_> {
+    let (payer, payee, arbiter) = (Address::generate(e), Address::generate(e), Address::generate(e));
+    let (token, token_admin) = create_token_contract(e, &Address::generate(e));
+    mint_to(&token_admin, &[&payer], AMOUNT);
+    e.ledger().set_timestamp(START);
+
+    let client = EscrowClient::new(
+        e,
+        &e.register(Escrow, (payer.clone(), payee.clone(), arbiter.clone(), token.address.clone(), AMOUNT, DEADLINE)),
+    );
+
+    TestData { payer, payee, arbiter, token, client }
+}
+
+// Drives a new escrow into `status` through its regular flow
+fn escrow_in(e: &Env, status: EscrowStatus) -> TestData<'_> {
+    let test_data = create_escrow(e);
+    if status != EscrowStatus::AwaitingFunding {
+        test_data.client.fund();
+    }
+    match status {
+        EscrowStatus::Released => {
+            test_data.client.release();
+        }
+        EscrowStatus::Refunded => {
+            test_data.client.refund(&test_data.payee);
+        }
+        EscrowStatus::Disputed | EscrowStatus::Resolved => {
+            test_data.client.dispute(&test_data.payer);
+            if status == EscrowStatus::Resolved {
+                test_data.client.resolve(&(AMOUNT / 2));
+            }
+        }
+        _ => {}
+    }
+    assert_eq!(test_data.client.status(), status);
+    test_data
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Action {
+    Fund,
+    Release,
+    RefundByPayee,
+    Dispute,
+    Resolve,
+}
+
+fn attempt(test_data: &TestData, action: Action) -> Result<EscrowStatus, Error> {
+    let result = match action {
+        Action::Fund => test_data.client.try_fund(),
+        Action::Release => test_data.client.try_release(),
+        Action::RefundByPayee => test_data.client.try_refund(&test_data.payee),
+        Action::Dispute => test_data.client.try_dispute(&test_data.payee),
+        Action::Resolve => test_data.client.try_resolve(&0),
+    };
+    match result {
+        Ok(status) => Ok(status.unwrap()),
+        Err(err) => Err(err.unwrap()),
+    }
+}
+
+#[test]
+fn test_state_machine_transitions() {
+    use Action::*;
+    use EscrowStatus::*;
+
+    // Every action in every status: where it leads, or None if the status does not allow it
+    let transitions = [
+        (AwaitingFunding, [Some(Funded), None, None, None, None]),
+        (Funded, [None, Some(Released), Some(Refunded), Some(Disputed), None]),
+        (Disputed, [None, None, None, None, Some(Resolved)]),
+        (Released, [None, None, None, None, None]),
+        (Refunded, [None, None, None, None, None]),
+        (Resolved, [None, None, None, None, None]),
+    ];
+
+    for (status, outcomes) in transitions {
+        for (action, outcome) in [Fund, Release, RefundByPayee, Dispute, Resolve].into_iter().zip(outcomes) {
+            let e = create_env();
+            let test_data = escrow_in(&e, status);
+            let expected = outcome.ok_or(Error::InvalidState);
+            assert_eq!(attempt(&test_data, action), expected, "{action:?} while {status:?}");
+            assert_eq!(test_data.client.status(), outcome.unwrap_or(status), "{action:?} while {status:?}");
+        }
+    }
+}
+
+#[test]
+fn test_release_pays_the_payee() {
+    let e = create_env();
+    let test_data = escrow_in(&e, EscrowStatus::Funded);
+    assert_eq!(test_data.token.balance(&test_data.client.address), AMOUNT);
+
+    test_data.client.release();
+    assert_eq!(test_data.token.balance(&test_data.payee), AMOUNT);
+    assert_eq!(test_data.token.balance(&test_data.client.address), 0);
+}
+
+#[test]
+fn test_payee_refunds_before_the_deadline() {
+    let e = create_env();
+    let test_data = escrow_in(&e, EscrowStatus::Funded);
+
+    test_data.client.refund(&test_data.payee);
+    assert_eq!(test_data.token.balance(&test_data.payer), AMOUNT);
+}
+
+#[test]
+fn test_payer_reclaims_after_the_deadline() {
+    let e = create_env();
+    let test_data = escrow_in(&e, EscrowStatus::Funded);
+
+    advance_days(&e, 30);
+    assert_eq!(test_data.client.refund(&test_data.payer), EscrowStatus::Refunded);
+    assert_eq!(test_data.token.balance(&test_data.payer), AMOUNT);
+}
+
+#[test]
+fn test_arbiter_splits_a_dispute() {
+    let e = create_env();
+    let test_data = escrow_in(&e, EscrowStatus::Disputed);
+
+    // A dispute freezes the deposit, even past the deadline
+    advance_days(&e, 31);
+    assert_eq!(test_data.client.try_refund(&test_data.payer), Err(Ok(Error::InvalidState)));
+
+    test_data.client.resolve(&700);
+    assert_eq!(test_data.token.balance(&test_data.payee), 700);
+    assert_eq!(test_data.token.balance(&test_data.payer), 300);
+    assert_eq!(
+        e.events().all().last().unwrap(),
+        (test_data.client.address.clone(), (CONTRACT_TAG, TOPIC_RESOLVED).into_val(&e), (EVENT_VERSION, (700_i128, 300_i128)).into_val(&e))
+    );
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1002)")]
+fn test_arbiter_cannot_dispute() {
+    let e = create_env();
+    let test_data = escrow_in(&e, EscrowStatus::Funded);
+
+    test_data.client.dispute(&test_data.arbiter);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1003)")]
+fn test_payer_cannot_reclaim_before_the_deadline() {
+    let e = create_env();
+    let test_data = escrow_in(&e, EscrowStatus::Funded);
+
+    e.ledger().set_timestamp(DEADLINE - 1);
+    test_data.client.refund(&test_data.payer);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1004)")]
+fn test_split_above_the_deposit() {
+    let e = create_env();
+    let test_data = escrow_in(&e, EscrowStatus::Disputed);
+
+    test_data.client.resolve(&(AMOUNT + 1));
+}
+
+#[test]
+#[should_panic(expected = "Error(Context, InvalidAction)")]
+fn test_payer_cannot_be_the_arbiter() {
+    let e = create_env();
+    let test_data = create_escrow(&e);
+
+    e.register(Escrow, (test_data.payer.clone(), test_data.payee.clone(), test_data.payer.clone(), test_data.token.address.clone(), AMOUNT, DEADLINE));
+}
+
+#[test]
+fn error_codes_in_registry_range() {
+    assert!(soroban_examples_errors::ESCROW.contains(Error::InvalidState as u32));
+    assert!(soroban_examples_errors::ESCROW.contains(Error::InvalidTerms as u32));
+}
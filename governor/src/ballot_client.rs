@@ -0,0 +1,10 @@
+use soroban_sdk::{contractclient, Env, Symbol};
+
+// Functions of the workspace's Ballot contract (`ballot` crate) used by the governor, which is the
+// admin of the ballots it opens and registers the votes of its members on them
+#[contractclient(name = "BallotClient")]
+pub trait BallotInterface {
+    fn configure(env: Env, ts_start: u64, ts_end: u64) -> bool;
+    fn vote(env: Env, voter: Symbol, candidate: Symbol) -> bool;
+    fn winner(env: Env) -> Option<Symbol>;
+}
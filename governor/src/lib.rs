@@ -0,0 +1,223 @@
+#![no_std]
+
+//! DAO governor: its members decide on proposals by voting in a `Ballot`, and passed proposals are
+//! executed on-chain by the governor itself. A proposal is a call to any function of a contract (e.g.
+//! an admin operation of a contract the governor administers). For every proposal the governor deploys
+//! a fresh Ballot, from the wasm hash it was configured with and with itself as admin, and opens it for
+//! the voting period. Members vote through the governor, which registers their votes in the ballot.
+//!
+//! Proposers never pick the ballot: a contract of their own that claims `yes` won would otherwise let
+//! a single member pass any call.
+//!
+//! A proposal passes if `yes` wins the ballot once it has closed (a tie is not a win). It can then be
+//! executed by anyone after the execution delay, so whoever disagrees has time to react before the
+//! call takes effect.
+
+use soroban_examples_events as events;
+use soroban_sdk::{contract, contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env, Symbol, Val, Vec};
+
+mod ballot_client;
+mod storage;
+
+use ballot_client::BallotClient;
+
+// Topics follow the workspace event schema: (CONTRACT_TAG, action)
+pub const CONTRACT_TAG: Symbol = symbol_short!("GOVERNOR");
+pub const TOPIC_PROPOSED: Symbol = symbol_short!("PROPOSED");
+pub const TOPIC_VOTED: Symbol = symbol_short!("VOTED");
+pub const TOPIC_EXECUTED: Symbol = symbol_short!("EXECUTED");
+
+// Ballot options
+pub const YES: Symbol = symbol_short!("yes");
+pub const NO: Symbol = symbol_short!("no");
+
+macro_rules! require {
+    ($cond:expr, $err:expr) => {
+        if !$cond {
+            return Err($err);
+        }
+    };
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    InvalidConfig = 1601,
+    NotAMember = 1602,
+    ProposalNotFound = 1603,
+    VotingClosed = 1606,
+    VotingNotEnded = 1607,
+    ProposalDefeated = 1608,
+    ExecutionDelayNotElapsed = 1609,
+    AlreadyExecuted = 1610,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GovernorConfig {
+    pub members: Vec<Address>,
+    /// Seconds a ballot stays open.
+    pub voting_period: u64,
+    /// Seconds between the end of the voting and the earliest execution of a passed proposal.
+    pub execution_delay: u64,
+    /// Code of the Ballot contract deployed for every proposal.
+    pub ballot_wasm: BytesN<32>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Call {
+    pub contract: Address,
+    pub function: Symbol,
+    pub args: Vec<Val>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub proposer: Address,
+    pub call: Call,
+    pub ballot: Address,
+    pub voting_end: u64,
+    pub executed: bool,
+}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum ProposalStatus {
+    Active = 1,
+    Defeated = 2,
+    /// Passed, waiting for the execution delay.
+    Queued = 3,
+    Ready = 4,
+    Executed = 5,
+}
+
+fn get_proposal(e: &Env, proposal_id: u32) -> Result<Proposal, Error> {
+    storage::get_proposal(e, proposal_id).ok_or(Error::ProposalNotFound)
+}
+
+fn proposal_status(e: &Env, config: &GovernorConfig, proposal: &Proposal) -> ProposalStatus {
+    let now = e.ledger().timestamp();
+    if proposal.executed {
+        ProposalStatus::Executed
+    } else if now <= proposal.voting_end {
+        ProposalStatus::Active
+    } else if BallotClient::new(e, &proposal.ballot).winner() != Some(YES) {
+        ProposalStatus::Defeated
+    } else if now < proposal.voting_end + config.execution_delay {
+        ProposalStatus::Queued
+    } else {
+        ProposalStatus::Ready
+    }
+}
+
+// Every proposal gets its own ballot, deployed at an address derived from the proposal id
+fn ballot_salt(e: &Env, proposal_id: u32) -> BytesN<32> {
+    let mut salt = [0u8; 32];
+    salt[28..].copy_from_slice(&proposal_id.to_be_bytes());
+    BytesN::from_array(e, &salt)
+}
+
+// Ballots identify voters by a symbol: `member_` followed by the zero-padded position of the member
+fn voter_id(e: &Env, config: &GovernorConfig, member: &Address) -> Result<Symbol, Error> {
+    let position = config.members.first_index_of(member).ok_or(Error::NotAMember)?;
+    let mut id = *b"member_0000000000";
+    let mut rest = position;
+    for digit in id.iter_mut().rev().take(10) {
+        *digit = b'0' + (rest % 10) as u8;
+        rest /= 10;
+    }
+    Ok(Symbol::new(e, core::str::from_utf8(&id).unwrap()))
+}
+
+#[contract]
+pub struct Governor;
+
+#[contractimpl]
+impl Governor {
+    /// `ballot_wasm` is the hash of the uploaded Ballot code the governor deploys its ballots from.
+    pub fn __constructor(e: Env, members: Vec<Address>, voting_period: u64, execution_delay: u64, ballot_wasm: BytesN<32>) -> Result<(), Error> {
+        require!(!members.is_empty() && voting_period > 0, Error::InvalidConfig);
+        for (i, member) in members.iter().enumerate() {
+            require!(members.first_index_of(&member) == Some(i as u32), Error::InvalidConfig);
+        }
+
+        storage::set_config(&e, &GovernorConfig { members, voting_period, execution_delay, ballot_wasm });
+        Ok(())
+    }
+
+    /// `proposer` puts `call` to the vote of the members in a Ballot the governor deploys for it. The
+    /// voting starts right away. Returns the proposal id.
+    pub fn propose(e: Env, proposer: Address, call: Call) -> Result<u32, Error> {
+        proposer.require_auth();
+        let config = storage::get_config(&e);
+        require!(config.members.contains(&proposer), Error::NotAMember);
+
+        let proposal_id = storage::next_proposal_id(&e);
+        let ballot = e
+            .deployer()
+            .with_current_contract(ballot_salt(&e, proposal_id))
+            .deploy_v2(config.ballot_wasm, (e.current_contract_address(),));
+
+        let now = e.ledger().timestamp();
+        let voting_end = now + config.voting_period;
+        BallotClient::new(&e, &ballot).configure(&now, &voting_end);
+
+        storage::set_proposal(&e, proposal_id, &Proposal { proposer: proposer.clone(), call, ballot: ballot.clone(), voting_end, executed: false });
+        events::publish(&e, CONTRACT_TAG, TOPIC_PROPOSED, (proposal_id, proposer, ballot, voting_end));
+        Ok(proposal_id)
+    }
+
+    /// Registers the vote of `member` on the proposal, for it (`support`) or against it. The ballot
+    /// rejects a second vote of the same member.
+    pub fn vote(e: Env, member: Address, proposal_id: u32, support: bool) -> Result<bool, Error> {
+        member.require_auth();
+        let config = storage::get_config(&e);
+        let voter = voter_id(&e, &config, &member)?;
+        let proposal = get_proposal(&e, proposal_id)?;
+        require!(proposal_status(&e, &config, &proposal) == ProposalStatus::Active, Error::VotingClosed);
+
+        BallotClient::new(&e, &proposal.ballot).vote(&voter, &if support { YES } else { NO });
+        events::publish(&e, CONTRACT_TAG, TOPIC_VOTED, (proposal_id, member, support));
+        Ok(true)
+    }
+
+    /// Makes the call of a passed proposal once the execution delay has elapsed; anyone can trigger
+    /// it. Returns what the called function returned.
+    pub fn execute(e: Env, proposal_id: u32) -> Result<Val, Error> {
+        let config = storage::get_config(&e);
+        let mut proposal = get_proposal(&e, proposal_id)?;
+        match proposal_status(&e, &config, &proposal) {
+            ProposalStatus::Ready => {}
+            ProposalStatus::Active => return Err(Error::VotingNotEnded),
+            ProposalStatus::Defeated => return Err(Error::ProposalDefeated),
+            ProposalStatus::Queued => return Err(Error::ExecutionDelayNotElapsed),
+            ProposalStatus::Executed => return Err(Error::AlreadyExecuted),
+        }
+
+        // Marked before calling out, so the call cannot execute the proposal again
+        proposal.executed = true;
+        storage::set_proposal(&e, proposal_id, &proposal);
+        let result = e.invoke_contract::<Val>(&proposal.call.contract, &proposal.call.function, proposal.call.args);
+        events::publish(&e, CONTRACT_TAG, TOPIC_EXECUTED, proposal_id);
+        Ok(result)
+    }
+
+    pub fn proposal(e: Env, proposal_id: u32) -> Result<Proposal, Error> {
+        get_proposal(&e, proposal_id)
+    }
+
+    pub fn status(e: Env, proposal_id: u32) -> Result<ProposalStatus, Error> {
+        Ok(proposal_status(&e, &storage::get_config(&e), &get_proposal(&e, proposal_id)?))
+    }
+
+    pub fn config(e: Env) -> GovernorConfig {
+        storage::get_config(&e)
+    }
+}
+
+#[cfg(all(test, feature = "ballot-wasm"))]
+mod test;
@@ -0,0 +1,35 @@
+use crate::{GovernorConfig, Proposal};
+use soroban_examples_storage::{StorageCell, StorageMap};
+use soroban_sdk::{contracttype, Env};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Config,
+    ProposalCount,
+    Proposal(u32),
+}
+
+const CONFIG: StorageCell<DataKey, GovernorConfig> = StorageCell::instance(DataKey::Config);
+const PROPOSAL_COUNT: StorageCell<DataKey, u32> = StorageCell::instance(DataKey::ProposalCount);
+const PROPOSALS: StorageMap<u32, Proposal, DataKey> = StorageMap::persistent(|id| DataKey::Proposal(*id));
+
+pub fn get_config(e: &Env) -> GovernorConfig {
+    CONFIG.get_or_else(e, || panic!("Governor config has expired"))
+}
+
+pub fn set_config(e: &Env, config: &GovernorConfig) {
+    CONFIG.set(e, config);
+}
+
+pub fn next_proposal_id(e: &Env) -> u32 {
+    PROPOSAL_COUNT.update(e, |count| count.unwrap_or(0) + 1)
+}
+
+pub fn get_proposal(e: &Env, id: u32) -> Option<Proposal> {
+    PROPOSALS.get(e, &id)
+}
+
+pub fn set_proposal(e: &Env, id: u32, proposal: &Proposal) {
+    PROPOSALS.set(e, &id, proposal);
+}
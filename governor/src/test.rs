@@ -0,0 +1,232 @@
+extern crate std;
+
+// Build the Ballot contract first with `cargo build --target wasm32-unknown-unknown --release` in `ballot`
+mod ballot_wasm {
+    soroban_sdk::contractimport!(
+        file = "../ballot/target/wasm32-unknown-unknown/release/ballot.wasm"
+    );
+}
+
+use crate::ballot_client::BallotClient;
+use crate::{Call, Governor, GovernorClient, ProposalStatus, CONTRACT_TAG, TOPIC_EXECUTED, YES};
+use soroban_examples_events::EVENT_VERSION;
+use soroban_examples_testutils::{advance_days, create_env, create_token_contract, TokenClient, SECONDS_IN_DAY};
+use soroban_sdk::{contract, contractimpl, symbol_short, testutils::{Address as _, Events, Ledger}, vec, Address, Env, IntoVal, Symbol};
+
+const START: u64 = 1_000;
+const VOTING_PERIOD: u64 = 7 * SECONDS_IN_DAY;
+const EXECUTION_DELAY: u64 = 2 * SECONDS_IN_DAY;
+
+struct TestData<'a> {
+    members: [Address; 3],
+    treasury: Address,
+    token: TokenClient<'a>,
+    client: GovernorClient<'a>,
+}
+
+// The governor is the admin of a token: minting needs a passed proposal
+fn create_governor(e: &Env) -> TestData<'_> {
+    let members = [Address::generate(e), Address::generate(e), Address::generate(e)];
+    let ballot_wasm = e.deployer().upload_contract_wasm(ballot_wasm::WASM);
+    let client = GovernorClient::new(
+        e,
+        &e.register(Governor, (vec![e, members[0].clone(), members[1].clone(), members[2].clone()], VOTING_PERIOD, EXECUTION_DELAY, ballot_wasm)),
+    );
+    let (token, _) = create_token_contract(e, &client.address);
+    e.ledger().set_timestamp(START);
+
+    TestData { members, treasury: Address::generate(e), token, client }
+}
+
+// Ballot a member could deploy to pass anything: `yes` always wins
+#[contract]
+struct FakeBallot;
+
+#[contractimpl]
+impl FakeBallot {
+    pub fn configure(_e: Env, _ts_start: u64, _ts_end: u64) -> bool {
+        true
+    }
+
+    pub fn vote(_e: Env, _voter: Symbol, _candidate: Symbol) -> bool {
+        true
+    }
+
+    pub fn winner(_e: Env) -> Option<Symbol> {
+        Some(YES)
+    }
+}
+
+fn propose_mint(e: &Env, test_data: &TestData, amount: i128) -> u32 {
+    let call = Call {
+        contract: test_data.token.address.clone(),
+        function: symbol_short!("mint"),
+        args: vec![e, test_data.treasury.into_val(e), amount.into_val(e)],
+    };
+    test_data.client.propose(&test_data.members[0], &call)
+}
+
+fn vote_and_close(e: &Env, test_data: &TestData, proposal_id: u32, votes: [bool; 3]) {
+    for (member, support) in test_data.members.iter().zip(votes) {
+        test_data.client.vote(member, &proposal_id, &support);
+    }
+    e.ledger().set_timestamp(START + VOTING_PERIOD + 1);
+}
+
+#[test]
+fn test_passed_proposal_is_executed_after_the_delay() {
+    let e = create_env();
+    let test_data = create_governor(&e);
+
+    let proposal_id = propose_mint(&e, &test_data, 1_000);
+    assert_eq!(test_data.client.status(&proposal_id), ProposalStatus::Active);
+
+    vote_and_close(&e, &test_data, proposal_id, [true, true, false]);
+    assert_eq!(test_data.client.status(&proposal_id), ProposalStatus::Queued);
+    assert_eq!(
+        BallotClient::new(&e, &test_data.client.proposal(&proposal_id).ballot).winner(),
+        Some(symbol_short!("yes"))
+    );
+
+    advance_days(&e, 2);
+    assert_eq!(test_data.client.status(&proposal_id), ProposalStatus::Ready);
+    test_data.client.execute(&proposal_id);
+    assert_eq!(
+        e.events().all().last().unwrap(),
+        (test_data.client.address.clone(), (CONTRACT_TAG, TOPIC_EXECUTED).into_val(&e), (EVENT_VERSION, proposal_id).into_val(&e))
+    );
+    assert_eq!(test_data.token.balance(&test_data.treasury), 1_000);
+    assert_eq!(test_data.client.status(&proposal_id), ProposalStatus::Executed);
+}
+
+#[test]
+fn test_rejected_proposal_is_defeated() {
+    let e = create_env();
+    let test_data = create_governor(&e);
+
+    let proposal_id = propose_mint(&e, &test_data, 1_000);
+    vote_and_close(&e, &test_data, proposal_id, [true, false, false]);
+    assert_eq!(test_data.client.status(&proposal_id), ProposalStatus::Defeated);
+}
+
+#[test]
+fn test_proposal_without_votes_is_defeated() {
+    let e = create_env();
+    let test_data = create_governor(&e);
+
+    let proposal_id = propose_mint(&e, &test_data, 1_000);
+    e.ledger().set_timestamp(START + VOTING_PERIOD + 1);
+    assert_eq!(test_data.client.status(&proposal_id), ProposalStatus::Defeated);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1602)")]
+fn test_outsider_cannot_vote() {
+    let e = create_env();
+    let test_data = create_governor(&e);
+
+    let proposal_id = propose_mint(&e, &test_data, 1_000);
+    test_data.client.vote(&Address::generate(&e), &proposal_id, &true);
+}
+
+#[test]
+fn test_every_proposal_gets_its_own_ballot() {
+    let e = create_env();
+    let test_data = create_governor(&e);
+
+    let first = test_data.client.proposal(&propose_mint(&e, &test_data, 1_000)).ballot;
+    let second = test_data.client.proposal(&propose_mint(&e, &test_data, 1_000)).ballot;
+    assert_ne!(first, second);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1608)")]
+fn test_fake_ballot_cannot_pass_a_proposal() {
+    let e = create_env();
+    let test_data = create_governor(&e);
+    let fake_ballot = e.register(FakeBallot, ());
+    assert_eq!(BallotClient::new(&e, &fake_ballot).winner(), Some(YES));
+
+    // The proposal is decided in the ballot the governor deployed, where nobody voted
+    let proposal_id = propose_mint(&e, &test_data, 1_000);
+    assert_ne!(test_data.client.proposal(&proposal_id).ballot, fake_ballot);
+    e.ledger().set_timestamp(START + VOTING_PERIOD + EXECUTION_DELAY + 1);
+    test_data.client.execute(&proposal_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1606)")]
+fn test_vote_after_the_voting_period() {
+    let e = create_env();
+    let test_data = create_governor(&e);
+
+    let proposal_id = propose_mint(&e, &test_data, 1_000);
+    e.ledger().set_timestamp(START + VOTING_PERIOD + 1);
+    test_data.client.vote(&test_data.members[0], &proposal_id, &true);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1607)")]
+fn test_execute_while_voting() {
+    let e = create_env();
+    let test_data = create_governor(&e);
+
+    let proposal_id = propose_mint(&e, &test_data, 1_000);
+    test_data.client.vote(&test_data.members[0], &proposal_id, &true);
+    test_data.client.execute(&proposal_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1608)")]
+fn test_execute_defeated_proposal() {
+    let e = create_env();
+    let test_data = create_governor(&e);
+
+    let proposal_id = propose_mint(&e, &test_data, 1_000);
+    vote_and_close(&e, &test_data, proposal_id, [false, false, true]);
+    advance_days(&e, 2);
+    test_data.client.execute(&proposal_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1609)")]
+fn test_execute_before_the_delay() {
+    let e = create_env();
+    let test_data = create_governor(&e);
+
+    let proposal_id = propose_mint(&e, &test_data, 1_000);
+    vote_and_close(&e, &test_data, proposal_id, [true, true, true]);
+    test_data.client.execute(&proposal_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1610)")]
+fn test_execute_twice() {
+    let e = create_env();
+    let test_data = create_governor(&e);
+
+    let proposal_id = propose_mint(&e, &test_data, 1_000);
+    vote_and_close(&e, &test_data, proposal_id, [true, true, true]);
+    advance_days(&e, 2);
+    test_data.client.execute(&proposal_id);
+    test_data.client.execute(&proposal_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #102)")]
+fn test_member_votes_once() {
+    let e = create_env();
+    let test_data = create_governor(&e);
+
+    let proposal_id = propose_mint(&e, &test_data, 1_000);
+    test_data.client.vote(&test_data.members[0], &proposal_id, &true);
+    test_data.client.vote(&test_data.members[0], &proposal_id, &false);
+}
+
+#[test]
+fn error_codes_in_registry_range() {
+    use super::Error;
+
+    assert!(soroban_examples_errors::GOVERNOR.contains(Error::InvalidConfig as u32));
+    assert!(soroban_examples_errors::GOVERNOR.contains(Error::AlreadyExecuted as u32));
+}
@@ -1,27 +1,281 @@
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, contracterror, Env, String, Address};
+use soroban_examples_access as access;
+use soroban_examples_ownership::{self as ownership, OwnershipError, PendingAdmin};
+use soroban_sdk::{contract, contractimpl, contracttype, contracterror, symbol_short, token, Env, String, Address, Bytes, BytesN, IntoVal, Map, Symbol, Val, Vec};
 
+pub const TOPIC_METADATA_UPDATED: Symbol = symbol_short!("METAUPD");
+pub const TOPIC_DOCUMENT_APPENDED: Symbol = symbol_short!("DOCADDED");
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Metadata {
+    pub postal_address: String,
+    // Cadastral reference or any other legal identifier of the property. It cannot be changed.
+    pub legal_id: String,
+    // Hash of the deed document, kept off-chain
+    pub deed_hash: BytesN<32>,
+}
+
+// A change of ownership. `shares` holds the fractional holders when the asset was split among several
+// addresses (`to` being the managing owner) and is empty otherwise.
 #[contracttype]
-struct Metadada {
-    asset_id: String,
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OwnershipRecord {
+    pub ts: u64,
+    pub from: Address,
+    pub to: Address,
+    pub shares: Map<Address, u32>,
 }
 
+// Every asset of the registry is stored under its own keys
 #[contracttype]
 enum DataKey {
-    Owner,
-    Metadata,
-    Allowance
+    Frozen(String),
+    Owner(String),
+    Metadata(String),
+    Allowance(String),
+    Shares(String),
+    Operator(String),
+    History(String),
+    Lien(String),
+    Supply(String),
+    Balance(String, Address),
+    Holders(String),
+    RentTokens(String),
+    Rent(String, Address),
+    RentClaimed(String, Address, Address),
+    Document(String, BytesN<32>),
+    ChainHead(String)
 }
 
+// Fractional shares are expressed in basis points of the whole asset
+pub const TOTAL_SHARES: u32 = 10_000;
+
+// Only the latest ownership changes of each asset are kept
+pub const MAX_HISTORY_ENTRIES: u32 = 50;
+
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
 pub enum Error {
-    AssetAlreadyInitialized = 1,
-    AssetNotInitialized = 2,
-    AddressNotApproved = 3,
-    AssetWithoutTransferAllowance = 4
+    AssetAlreadyRegistered = 501,
+    AssetNotRegistered = 502,
+    AddressNotApproved = 503,
+    AssetWithoutTransferAllowance = 504,
+    InvalidShares = 505,
+    OperatorNotApproved = 506,
+    AssetUnderLien = 507,
+    NoLien = 508,
+    AssetFrozen = 509,
+    AssetTokenized = 510,
+    AssetNotTokenized = 511,
+    InsufficientBalance = 512,
+    NoMajority = 513,
+    InvalidAmount = 514,
+    NoRentToClaim = 515,
+    ImmutableMetadata = 516,
+    DocumentAlreadyAnchored = 517,
+    NoPendingAdmin = 518,
+    AdminProposalExpired = 519,
+    StaleAdminProposal = 520,
+    RegistryAdminNotSet = 521
+}
+
+impl From<OwnershipError> for Error {
+    fn from(err: OwnershipError) -> Self {
+        match err {
+            OwnershipError::AdminNotSet => Error::RegistryAdminNotSet,
+            OwnershipError::NoPendingAdmin => Error::NoPendingAdmin,
+            OwnershipError::ProposalExpired => Error::AdminProposalExpired,
+            OwnershipError::StaleProposal => Error::StaleAdminProposal,
+        }
+    }
+}
+
+fn get_owner(e: &Env, id: &String) -> Option<Address> {
+    e.storage().persistent().get::<DataKey, Address>(&DataKey::Owner(id.clone()))
+}
+
+fn set_persistent<V: IntoVal<Env, Val>>(e: &Env, key: &DataKey, value: &V) {
+    soroban_examples_storage::set_persistent(e, key, value);
+}
+
+fn get_history(e: &Env, id: &String) -> Vec<OwnershipRecord> {
+    e.storage().persistent().get::<DataKey, Vec<OwnershipRecord>>(&DataKey::History(id.clone())).unwrap_or(Vec::new(e))
+}
+
+// Neither the asset nor its tokens can change hands while it is pledged or frozen
+fn check_transferable(e: &Env, id: &String) -> Result<(), Error> {
+    if e.storage().persistent().has(&DataKey::Lien(id.clone())) {
+        return Err(Error::AssetUnderLien);
+    }
+
+    if e.storage().persistent().has(&DataKey::Frozen(id.clone())) {
+        return Err(Error::AssetFrozen);
+    }
+
+    Ok(())
+}
+
+// A new owner starts without an approved operator. `shares` is empty unless the asset is fractional.
+// A tokenized asset only changes hands through its tokens.
+fn set_owner(e: &Env, id: &String, owner: &Address, shares: &Map<Address, u32>) -> Result<(), Error> {
+    check_transferable(e, id)?;
+    if e.storage().persistent().has(&DataKey::Supply(id.clone())) {
+        return Err(Error::AssetTokenized);
+    }
+
+    let mut holders = Vec::from_array(e, [get_owner(e, id).unwrap(), owner.clone()]);
+    holders.append(&Asset::shares(e.clone(), id.clone()).keys());
+    holders.append(&shares.keys());
+    keep_pending_rent(e, id, &holders, || record_owner(e, id, owner, shares))
+}
+
+fn record_owner(e: &Env, id: &String, owner: &Address, shares: &Map<Address, u32>) -> Result<(), Error> {
+    let mut history = get_history(e, id);
+    if history.len() >= MAX_HISTORY_ENTRIES {
+        history.pop_front();
+    }
+    history.push_back(OwnershipRecord {
+        ts: e.ledger().timestamp(),
+        from: get_owner(e, id).unwrap(),
+        to: owner.clone(),
+        shares: shares.clone(),
+    });
+    set_persistent(e, &DataKey::History(id.clone()), &history);
+
+    set_persistent(e, &DataKey::Owner(id.clone()), owner);
+    if shares.is_empty() {
+        e.storage().persistent().remove(&DataKey::Shares(id.clone()));
+    } else {
+        set_persistent(e, &DataKey::Shares(id.clone()), shares);
+    }
+    e.storage().persistent().remove(&DataKey::Operator(id.clone()));
+    Ok(())
+}
+
+fn set_shares(e: &Env, id: &String, shares: &Map<Address, u32>) -> Result<(), Error> {
+    let mut total: u32 = 0;
+    let mut managing_owner: Option<(Address, u32)> = None;
+    for (holder, share) in shares.iter() {
+        if share == 0 {
+            return Err(Error::InvalidShares);
+        }
+
+        total += share;
+        if managing_owner.as_ref().map(|(_, max_share)| share > *max_share).unwrap_or(true) {
+            managing_owner = Some((holder, share));
+        }
+    }
+
+    if total != TOTAL_SHARES {
+        return Err(Error::InvalidShares);
+    }
+
+    set_owner(e, id, &managing_owner.unwrap().0, shares)?;
+    Ok(())
+}
+
+fn get_supply(e: &Env, id: &String) -> Option<i128> {
+    e.storage().persistent().get::<DataKey, i128>(&DataKey::Supply(id.clone()))
+}
+
+fn get_balance(e: &Env, id: &String, holder: &Address) -> i128 {
+    e.storage().persistent().get::<DataKey, i128>(&DataKey::Balance(id.clone(), holder.clone())).unwrap_or(0)
+}
+
+fn get_holders(e: &Env, id: &String) -> Vec<Address> {
+    e.storage().persistent().get::<DataKey, Vec<Address>>(&DataKey::Holders(id.clone())).unwrap_or(Vec::new(e))
+}
+
+// Keeps the list of holders in step with the balances: a holder is added with its first tokens and
+// removed once it has none left
+fn set_balance(e: &Env, id: &String, holder: &Address, balance: i128) {
+    let mut holders = get_holders(e, id);
+    let key = DataKey::Balance(id.clone(), holder.clone());
+    if balance == 0 {
+        e.storage().persistent().remove(&key);
+        if let Some(index) = holders.first_index_of(holder) {
+            holders.remove(index);
+        }
+    } else {
+        set_persistent(e, &key, &balance);
+        if !holders.contains(holder) {
+            holders.push_back(holder.clone());
+        }
+    }
+    set_persistent(e, &DataKey::Holders(id.clone()), &holders);
+}
+
+fn get_rent_tokens(e: &Env, id: &String) -> Vec<Address> {
+    e.storage().persistent().get::<DataKey, Vec<Address>>(&DataKey::RentTokens(id.clone())).unwrap_or(Vec::new(e))
+}
+
+fn get_rent_claimed(e: &Env, id: &String, token: &Address, holder: &Address) -> i128 {
+    e.storage().persistent().get::<DataKey, i128>(&DataKey::RentClaimed(id.clone(), token.clone(), holder.clone())).unwrap_or(0)
+}
+
+// Part of the rent deposited in `token` earned by `holder` with its current share of the asset and not
+// claimed yet. Balances of a tokenized asset are used instead of basis points to avoid rounding them.
+fn get_pending_rent(e: &Env, id: &String, token: &Address, holder: &Address) -> i128 {
+    let deposited = e.storage().persistent().get::<DataKey, i128>(&DataKey::Rent(id.clone(), token.clone())).unwrap_or(0);
+    let earned = match get_supply(e, id) {
+        Some(supply) => deposited * get_balance(e, id, holder) / supply,
+        None => deposited * Asset::share_of(e.clone(), id.clone(), holder.clone()) as i128 / TOTAL_SHARES as i128
+    };
+
+    earned - get_rent_claimed(e, id, token, holder)
+}
+
+// The rent is shared according to the current shares, so a change of ownership would move the rent
+// already earned from the sellers to the buyers. The claimed counters of the `holders` affected by
+// `change` are rebased so that each one keeps what it had pending before.
+fn keep_pending_rent<F: FnOnce() -> Result<(), Error>>(e: &Env, id: &String, holders: &Vec<Address>, change: F) -> Result<(), Error> {
+    let tokens = get_rent_tokens(e, id);
+    if tokens.is_empty() {
+        return change();
+    }
+
+    let mut pending: Vec<(Address, Address, i128)> = Vec::new(e);
+    for holder in holders.iter() {
+        for token in tokens.iter() {
+            if !pending.iter().any(|(t, h, _)| t == token && h == holder) {
+                pending.push_back((token.clone(), holder.clone(), get_pending_rent(e, id, &token, &holder)));
+            }
+        }
+    }
+
+    change()?;
+    for (token, holder, amount) in pending.iter() {
+        let claimed = get_rent_claimed(e, id, &token, &holder) + get_pending_rent(e, id, &token, &holder) - amount;
+        set_persistent(e, &DataKey::RentClaimed(id.clone(), token, holder), &claimed);
+    }
+    Ok(())
+}
+
+// Anchors the document `hash` to the provenance chain of the asset, whose head is the hash of the previous
+// head followed by the new document, starting from the deed registered with the asset
+fn append_to_chain(e: &Env, id: &String, hash: &BytesN<32>) -> BytesN<32> {
+    let key = DataKey::ChainHead(id.clone());
+    let head = e.storage().persistent().get::<DataKey, BytesN<32>>(&key).unwrap_or(BytesN::from_array(e, &[0; 32]));
+    let mut data: Bytes = head.into();
+    data.append(&hash.clone().into());
+    let head = e.crypto().sha256(&data).to_bytes();
+
+    set_persistent(e, &DataKey::Document(id.clone(), hash.clone()), &true);
+    set_persistent(e, &key, &head);
+    head
+}
+
+fn check_operator(e: &Env, id: &String, operator: &Address) -> Result<(), Error> {
+    match e.storage().persistent().get::<DataKey, Address>(&DataKey::Operator(id.clone())) {
+        Some(approved) if approved == *operator => {
+            operator.require_auth();
+            Ok(())
+        },
+        _ => Err(Error::OperatorNotApproved)
+    }
 }
 
 #[contract]
@@ -30,74 +284,463 @@ pub struct Asset;
 #[contractimpl]
 impl Asset {
 
-    pub fn initialize(e: Env, owner: Address, asset_id: String) -> Result<bool, Error> {
-        if let Some(_owner) = e.storage().instance().get::<DataKey, Address>(&DataKey::Owner) {
-            return Err(Error::AssetAlreadyInitialized);
+    /// `admin` manages the registry: it can freeze assets under legal dispute.
+    pub fn __constructor(e: Env, admin: Address) {
+        access::set_admin(&e, &admin);
+    }
+
+    /// Fails with `RegistryAdminNotSet` if the admin entry is missing, e.g. once the instance has expired.
+    pub fn admin(e: Env) -> Result<Address, Error> {
+        access::get_admin(&e).ok_or(Error::RegistryAdminNotSet)
+    }
+
+    /// The registry admin proposes its successor, who takes over once it calls `accept_admin` (within a week).
+    pub fn propose_admin(e: Env, new_admin: Address) -> Result<PendingAdmin, Error> {
+        Ok(ownership::propose_admin(&e, &new_admin)?)
+    }
+
+    pub fn accept_admin(e: Env) -> Result<bool, Error> {
+        ownership::accept_admin(&e)?;
+        Ok(true)
+    }
+
+    pub fn cancel_admin_transfer(e: Env) -> Result<bool, Error> {
+        ownership::cancel_admin_transfer(&e)?;
+        Ok(true)
+    }
+
+    pub fn pending_admin(e: Env) -> Option<PendingAdmin> {
+        ownership::pending_admin(&e)
+    }
+
+    /// The registry admin freezes the asset `id` (e.g. under legal dispute). Its ownership cannot
+    /// change until the admin calls `unfreeze`.
+    pub fn freeze(e: Env, id: String) -> Result<bool, Error> {
+        if let Some(_owner) = get_owner(&e, &id) {
+            access::require_admin(&e).map_err(|_| Error::RegistryAdminNotSet)?;
+            set_persistent(&e, &DataKey::Frozen(id), &true);
+            Ok(true)
+        } else {
+            return Err(Error::AssetNotRegistered);
         }
+    }
 
-        let metadata = Metadada {
-            asset_id,
-        };
+    pub fn unfreeze(e: Env, id: String) -> Result<bool, Error> {
+        if let Some(_owner) = get_owner(&e, &id) {
+            access::require_admin(&e).map_err(|_| Error::RegistryAdminNotSet)?;
+            e.storage().persistent().remove(&DataKey::Frozen(id));
+            Ok(true)
+        } else {
+            return Err(Error::AssetNotRegistered);
+        }
+    }
 
-        e.storage().instance().set(&DataKey::Owner, &owner);
-        e.storage().instance().set(&DataKey::Metadata, &metadata);
+    pub fn is_frozen(e: Env, id: String) -> bool {
+        e.storage().persistent().has(&DataKey::Frozen(id))
+    }
+
+    /// Registers the asset `id` (e.g. its cadastral reference) owned by `owner`, who must sign it.
+    pub fn register_asset(e: Env, id: String, owner: Address, metadata: Metadata) -> Result<bool, Error> {
+        if let Some(_owner) = get_owner(&e, &id) {
+            return Err(Error::AssetAlreadyRegistered);
+        }
+
+        owner.require_auth();
+        set_persistent(&e, &DataKey::Owner(id.clone()), &owner);
+        append_to_chain(&e, &id, &metadata.deed_hash);
+        set_persistent(&e, &DataKey::Metadata(id), &metadata);
         Ok(true)
     }
 
-    pub fn approve(e: Env, addr_to_allow: Address, ts: u64) -> Result<bool, Error> {
+    /// The owner updates the mutable metadata of the asset, e.g. the deed hash after a renovation
+    /// permit. The legal id must stay the same. Emits the old and new deed hashes, and a new deed is
+    /// anchored to the provenance chain of the asset.
+    pub fn update_metadata(e: Env, id: String, metadata: Metadata) -> Result<bool, Error> {
+        if let Some(owner) = get_owner(&e, &id) {
+            let current = Self::metadata(e.clone(), id.clone())?;
+            if metadata.legal_id != current.legal_id {
+                return Err(Error::ImmutableMetadata);
+            }
+
+            owner.require_auth();
+            if !e.storage().persistent().has(&DataKey::Document(id.clone(), metadata.deed_hash.clone())) {
+                append_to_chain(&e, &id, &metadata.deed_hash);
+            }
+            set_persistent(&e, &DataKey::Metadata(id.clone()), &metadata);
+            e.events().publish((TOPIC_METADATA_UPDATED,), (id, owner, current.deed_hash, metadata.deed_hash));
+            Ok(true)
+        }
+        else {
+            return Err(Error::AssetNotRegistered);
+        }
+    }
+
+    /// The owner anchors the legal document `hash` (e.g. a permit or an inspection report) to the
+    /// provenance chain of the asset. Returns the new head of the chain.
+    pub fn append_document(e: Env, id: String, hash: BytesN<32>) -> Result<BytesN<32>, Error> {
+        if let Some(owner) = get_owner(&e, &id) {
+            if e.storage().persistent().has(&DataKey::Document(id.clone(), hash.clone())) {
+                return Err(Error::DocumentAlreadyAnchored);
+            }
+
+            owner.require_auth();
+            let head = append_to_chain(&e, &id, &hash);
+            e.events().publish((TOPIC_DOCUMENT_APPENDED,), (id, hash, head.clone()));
+            Ok(head)
+        } else {
+            return Err(Error::AssetNotRegistered);
+        }
+    }
+
+    /// Whether the document `hash` is anchored to the provenance chain of the asset.
+    pub fn verify_document(e: Env, id: String, hash: BytesN<32>) -> bool {
+        e.storage().persistent().has(&DataKey::Document(id, hash))
+    }
+
+    /// Head of the provenance chain, which lets anyone holding the documents check their whole sequence.
+    pub fn document_chain_head(e: Env, id: String) -> Option<BytesN<32>> {
+        e.storage().persistent().get::<DataKey, BytesN<32>>(&DataKey::ChainHead(id))
+    }
+
+    pub fn metadata(e: Env, id: String) -> Result<Metadata, Error> {
+        e.storage().persistent().get::<DataKey, Metadata>(&DataKey::Metadata(id)).ok_or(Error::AssetNotRegistered)
+    }
+
+    pub fn approve(e: Env, id: String, addr_to_allow: Address, ts: u64) -> Result<bool, Error> {
         
-        if let Some(owner) = e.storage().instance().get::<DataKey, Address>(&DataKey::Owner) { 
+        if let Some(owner) = get_owner(&e, &id) { 
             owner.require_auth();
-            e.storage().temporary().set(&DataKey::Allowance, &addr_to_allow);
+            e.storage().temporary().set(&DataKey::Allowance(id.clone()), &addr_to_allow);
             let next_ledger = e.ledger().sequence() + (ts / 5) as u32;
             let live_for = next_ledger
                 .checked_sub(e.ledger().sequence())
                 .unwrap();
 
-                e.storage().temporary().extend_ttl(&DataKey::Allowance, live_for, live_for);
+                e.storage().temporary().extend_ttl(&DataKey::Allowance(id), live_for, live_for);
                 Ok(true)
         }
         else {
-            return Err(Error::AssetNotInitialized);
+            return Err(Error::AssetNotRegistered);
         } 
     }
     
-    pub fn transfer(e: Env, to: Address) -> Result<bool, Error>  {
-        if let Some(owner) = e.storage().instance().get::<DataKey, Address>(&DataKey::Owner) {
+    pub fn transfer(e: Env, id: String, to: Address) -> Result<bool, Error>  {
+        if let Some(owner) = get_owner(&e, &id) {
             owner.require_auth();
-            e.storage().instance().set(&DataKey::Owner, &to);
+            set_owner(&e, &id, &to, &Map::new(&e))?;
             Ok(true)
         }
         else{
-            return Err(Error::AssetNotInitialized);
+            return Err(Error::AssetNotRegistered);
         }
     }
 
-    pub fn transfer_from(e: Env, allowed_addr: Address, to: Address) -> Result<bool, Error>  {
-        if let Some(_owner) = e.storage().instance().get::<DataKey, Address>(&DataKey::Owner) {
-            if let Some(allowed_a) = e.storage().temporary().get(&DataKey::Allowance) {
+    /// Transfers the asset to several holders, each one owning `shares[holder]` basis points of it.
+    ///
+    /// The shares must add up to `TOTAL_SHARES`. The holder with the largest share becomes the
+    /// `owner`, the address managing the asset (approvals and further transfers).
+    pub fn transfer_shares(e: Env, id: String, shares: Map<Address, u32>) -> Result<bool, Error> {
+        if let Some(owner) = get_owner(&e, &id) {
+            owner.require_auth();
+            set_shares(&e, &id, &shares)?;
+            Ok(true)
+        }
+        else{
+            return Err(Error::AssetNotRegistered);
+        }
+    }
+
+    pub fn transfer_from(e: Env, id: String, allowed_addr: Address, to: Address) -> Result<bool, Error>  {
+        if let Some(_owner) = get_owner(&e, &id) {
+            if let Some(allowed_a) = e.storage().temporary().get(&DataKey::Allowance(id.clone())) {
                 if allowed_addr != allowed_a {
                     return Err(Error::AddressNotApproved);
                 }
 
                 allowed_addr.require_auth();
-                e.storage().instance().set(&DataKey::Owner, &to);
+                set_owner(&e, &id, &to, &Map::new(&e))?;
                 Ok(true)
             } else {
                 return Err(Error::AssetWithoutTransferAllowance);
             }
         } else {
-            return Err(Error::AssetNotInitialized);
+            return Err(Error::AssetNotRegistered);
+        }
+    }
+
+    /// The owner allows `operator` (e.g. the `HousePurchase` contract selling the asset) to change its
+    /// ownership through `transfer_by_operator` and `transfer_shares_by_operator`. The approval is
+    /// dropped as soon as the asset changes hands.
+    pub fn approve_operator(e: Env, id: String, operator: Address) -> Result<bool, Error> {
+        if let Some(owner) = get_owner(&e, &id) {
+            owner.require_auth();
+            set_persistent(&e, &DataKey::Operator(id), &operator);
+            Ok(true)
+        } else {
+            return Err(Error::AssetNotRegistered);
+        }
+    }
+
+    pub fn revoke_operator(e: Env, id: String) -> Result<bool, Error> {
+        if let Some(owner) = get_owner(&e, &id) {
+            owner.require_auth();
+            e.storage().persistent().remove(&DataKey::Operator(id));
+            Ok(true)
+        } else {
+            return Err(Error::AssetNotRegistered);
+        }
+    }
+
+    pub fn operator(e: Env, id: String) -> Option<Address> {
+        e.storage().persistent().get::<DataKey, Address>(&DataKey::Operator(id))
+    }
+
+    pub fn transfer_by_operator(e: Env, id: String, operator: Address, to: Address) -> Result<bool, Error> {
+        if let Some(_owner) = get_owner(&e, &id) {
+            check_operator(&e, &id, &operator)?;
+            set_owner(&e, &id, &to, &Map::new(&e))?;
+            Ok(true)
+        } else {
+            return Err(Error::AssetNotRegistered);
+        }
+    }
+
+    pub fn transfer_shares_by_operator(e: Env, id: String, operator: Address, shares: Map<Address, u32>) -> Result<bool, Error> {
+        if let Some(_owner) = get_owner(&e, &id) {
+            check_operator(&e, &id, &operator)?;
+            set_shares(&e, &id, &shares)?;
+            Ok(true)
+        } else {
+            return Err(Error::AssetNotRegistered);
+        }
+    }
+
+    /// The owner pledges the asset to `lender` (e.g. the lender financing its purchase). The asset
+    /// cannot change hands until the lender calls `release_lien`.
+    pub fn place_lien(e: Env, id: String, lender: Address) -> Result<bool, Error> {
+        if let Some(owner) = get_owner(&e, &id) {
+            if e.storage().persistent().has(&DataKey::Lien(id.clone())) {
+                return Err(Error::AssetUnderLien);
+            }
+
+            owner.require_auth();
+            set_persistent(&e, &DataKey::Lien(id), &lender);
+            Ok(true)
+        } else {
+            return Err(Error::AssetNotRegistered);
+        }
+    }
+
+    pub fn release_lien(e: Env, id: String) -> Result<bool, Error> {
+        if let Some(lender) = e.storage().persistent().get::<DataKey, Address>(&DataKey::Lien(id.clone())) {
+            lender.require_auth();
+            e.storage().persistent().remove(&DataKey::Lien(id));
+            Ok(true)
+        } else {
+            return Err(Error::NoLien);
+        }
+    }
+
+    pub fn lien(e: Env, id: String) -> Option<Address> {
+        e.storage().persistent().get::<DataKey, Address>(&DataKey::Lien(id))
+    }
+
+    /// Splits the asset into `supply` fungible tokens (at least `TOTAL_SHARES`), all of them held by the
+    /// owner at first, or by the fractional holders in proportion to their shares (rounding leftovers
+    /// go to the owner).
+    ///
+    /// From then on the asset changes hands through its tokens (`transfer_tokens`) instead of
+    /// `transfer` and the other ownership transfers, and the holders of more than half of the supply
+    /// elect the managing owner with `elect_owner`.
+    pub fn fractionalize(e: Env, id: String, supply: i128) -> Result<bool, Error> {
+        if let Some(owner) = get_owner(&e, &id) {
+            if get_supply(&e, &id).is_some() {
+                return Err(Error::AssetTokenized);
+            }
+
+            if supply < TOTAL_SHARES as i128 {
+                return Err(Error::InvalidShares);
+            }
+
+            check_transferable(&e, &id)?;
+            owner.require_auth();
+            let shares = Self::shares(e.clone(), id.clone());
+            let mut holders = shares.keys();
+            holders.push_back(owner.clone());
+            keep_pending_rent(&e, &id, &holders, || {
+                let mut assigned: i128 = 0;
+                for (holder, share) in shares.iter() {
+                    let balance = supply * share as i128 / TOTAL_SHARES as i128;
+                    set_balance(&e, &id, &holder, balance);
+                    assigned += balance;
+                }
+                set_balance(&e, &id, &owner, get_balance(&e, &id, &owner) + supply - assigned);
+
+                e.storage().persistent().remove(&DataKey::Shares(id.clone()));
+                set_persistent(&e, &DataKey::Supply(id.clone()), &supply);
+                Ok(())
+            })?;
+            Ok(true)
+        } else {
+            return Err(Error::AssetNotRegistered);
+        }
+    }
+
+    /// `from` sends `amount` tokens of the asset to `to`.
+    pub fn transfer_tokens(e: Env, id: String, from: Address, to: Address, amount: i128) -> Result<bool, Error> {
+        if let Some(_supply) = get_supply(&e, &id) {
+            check_transferable(&e, &id)?;
+            let balance = get_balance(&e, &id, &from);
+            if amount <= 0 || amount > balance {
+                return Err(Error::InsufficientBalance);
+            }
+
+            from.require_auth();
+            keep_pending_rent(&e, &id, &Vec::from_array(&e, [from.clone(), to.clone()]), || {
+                set_balance(&e, &id, &from, balance - amount);
+                set_balance(&e, &id, &to, get_balance(&e, &id, &to) + amount);
+                Ok(())
+            })?;
+            Ok(true)
+        } else {
+            return Err(Error::AssetNotTokenized);
+        }
+    }
+
+    /// Holders of more than half of the supply, all of them signing, make `new_owner` the managing
+    /// owner of a tokenized asset. The balances are left unchanged.
+    pub fn elect_owner(e: Env, id: String, voters: Vec<Address>, new_owner: Address) -> Result<bool, Error> {
+        if let Some(supply) = get_supply(&e, &id) {
+            check_transferable(&e, &id)?;
+            let mut votes: i128 = 0;
+            for (index, voter) in voters.iter().enumerate() {
+                // A voter listed twice only counts once
+                if voters.first_index_of(&voter) == Some(index as u32) {
+                    voter.require_auth();
+                    votes += get_balance(&e, &id, &voter);
+                }
+            }
+
+            if votes * 2 <= supply {
+                return Err(Error::NoMajority);
+            }
+
+            record_owner(&e, &id, &new_owner, &Map::new(&e))?;
+            Ok(true)
+        } else {
+            return Err(Error::AssetNotTokenized);
+        }
+    }
+
+    /// The holder of the whole supply turns the asset back into a single-owner asset. Its share of the
+    /// asset, and so of the rent, does not change.
+    pub fn defractionalize(e: Env, id: String, holder: Address) -> Result<bool, Error> {
+        if let Some(supply) = get_supply(&e, &id) {
+            if get_balance(&e, &id, &holder) != supply {
+                return Err(Error::InsufficientBalance);
+            }
+
+            check_transferable(&e, &id)?;
+            holder.require_auth();
+            set_balance(&e, &id, &holder, 0);
+            e.storage().persistent().remove(&DataKey::Holders(id.clone()));
+            e.storage().persistent().remove(&DataKey::Supply(id.clone()));
+            if get_owner(&e, &id) != Some(holder.clone()) {
+                record_owner(&e, &id, &holder, &Map::new(&e))?;
+            }
+            Ok(true)
+        } else {
+            return Err(Error::AssetNotTokenized);
         }
     }
 
-    pub fn owner(e: Env) -> Address {
-        if let Some(owner) = e.storage().instance().get::<DataKey, Address>(&DataKey::Owner) {
-            owner
+    /// `from` (e.g. the tenant or the managing owner) deposits `amount` of `token` as income of the asset,
+    /// to be shared among its holders in proportion to their current shares.
+    pub fn deposit_rent(e: Env, id: String, from: Address, token: Address, amount: i128) -> Result<bool, Error> {
+        if let Some(_owner) = get_owner(&e, &id) {
+            if amount <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+
+            from.require_auth();
+            let mut tokens = get_rent_tokens(&e, &id);
+            if !tokens.contains(&token) {
+                tokens.push_back(token.clone());
+                set_persistent(&e, &DataKey::RentTokens(id.clone()), &tokens);
+            }
+            let key = DataKey::Rent(id, token.clone());
+            let deposited = e.storage().persistent().get::<DataKey, i128>(&key).unwrap_or(0);
+            set_persistent(&e, &key, &(deposited + amount));
+
+            token::Client::new(&e, &token).transfer(&from, &e.current_contract_address(), &amount);
+            Ok(true)
         } else {
-            panic!("Asset not Initialized");
+            return Err(Error::AssetNotRegistered);
         }
     }
+
+    /// Pays `holder` its part of the rent deposited in `token` not claimed yet and returns the amount paid.
+    pub fn claim_rent(e: Env, id: String, token: Address, holder: Address) -> Result<i128, Error> {
+        let pending = get_pending_rent(&e, &id, &token, &holder);
+        if pending <= 0 {
+            return Err(Error::NoRentToClaim);
+        }
+
+        holder.require_auth();
+        let claimed = get_rent_claimed(&e, &id, &token, &holder) + pending;
+        set_persistent(&e, &DataKey::RentClaimed(id, token.clone(), holder.clone()), &claimed);
+        token::Client::new(&e, &token).transfer(&e.current_contract_address(), &holder, &pending);
+        Ok(pending)
+    }
+
+    pub fn pending_rent(e: Env, id: String, token: Address, holder: Address) -> i128 {
+        get_pending_rent(&e, &id, &token, &holder)
+    }
+
+    /// Number of tokens of the asset, 0 while it is not tokenized.
+    pub fn total_supply(e: Env, id: String) -> i128 {
+        get_supply(&e, &id).unwrap_or(0)
+    }
+
+    pub fn balance(e: Env, id: String, holder: Address) -> i128 {
+        get_balance(&e, &id, &holder)
+    }
+
+    /// Basis points of the asset owned by `holder`. A non fractional asset belongs entirely to its owner.
+    pub fn share_of(e: Env, id: String, holder: Address) -> u32 {
+        if let Some(supply) = get_supply(&e, &id) {
+            (get_balance(&e, &id, &holder) * TOTAL_SHARES as i128 / supply) as u32
+        } else if let Some(shares) = e.storage().persistent().get::<DataKey, Map<Address, u32>>(&DataKey::Shares(id.clone())) {
+            shares.get(holder).unwrap_or(0)
+        } else if Some(holder) == get_owner(&e, &id) {
+            TOTAL_SHARES
+        } else {
+            0
+        }
+    }
+
+    /// Fractional holders of the asset, empty while it is owned by a single address. The shares of a
+    /// tokenized asset are rounded down.
+    pub fn shares(e: Env, id: String) -> Map<Address, u32> {
+        if let Some(_supply) = get_supply(&e, &id) {
+            let mut shares = Map::new(&e);
+            for holder in get_holders(&e, &id).iter() {
+                shares.set(holder.clone(), Self::share_of(e.clone(), id.clone(), holder));
+            }
+            return shares;
+        }
+
+        e.storage().persistent().get::<DataKey, Map<Address, u32>>(&DataKey::Shares(id)).unwrap_or(Map::new(&e))
+    }
+
+    /// Latest ownership changes of the asset, oldest first (up to `MAX_HISTORY_ENTRIES`).
+    pub fn get_history(e: Env, id: String) -> Vec<OwnershipRecord> {
+        get_history(&e, &id)
+    }
+
+    pub fn owner_of(e: Env, id: String) -> Result<Address, Error> {
+        get_owner(&e, &id).ok_or(Error::AssetNotRegistered)
+    }
 }
 
 mod test;
@@ -1,39 +1,61 @@
 #![cfg(test)]
 
-use super::{Asset, AssetClient};
-use soroban_sdk::{Env, Address, String, testutils::{Address as _}};
+use super::{Asset, AssetClient, Metadata, OwnershipRecord};
+use soroban_examples_testutils::create_token_contract;
+use soroban_sdk::{Env, Address, Bytes, BytesN, String, map, vec, testutils::{Address as _, Ledger}};
 
 #[test]
-fn initialize() {
+fn register_asset() {
     let env = Env::default();
     let client = create_client(&env);
 
     let owner = Address::generate(&env);
     let asset_id = String::from_str(&env, "399fg7u6h69965h6");
-    assert_eq!(client.initialize(&owner, &asset_id), true);
+    let metadata = create_metadata(&env);
+    assert_eq!(client.register_asset(&asset_id, &owner, &metadata), true);
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #1)")]
-fn already_initialized() {
+#[should_panic(expected = "HostError: Error(Contract, #501)")]
+fn already_registered() {
     let env = Env::default();
     let client = create_client(&env);
 
     let owner = Address::generate(&env);
     let asset_id = String::from_str(&env, "399fg7u6h69965h6");
-    client.initialize(&owner, &asset_id);
-    client.initialize(&owner, &asset_id);
+    let metadata = create_metadata(&env);
+    client.register_asset(&asset_id, &owner, &metadata);
+    client.register_asset(&asset_id, &owner, &metadata);
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #2)")]
-fn non_initialized() {
+#[should_panic(expected = "HostError: Error(Contract, #502)")]
+fn non_registered() {
 
     let env = Env::default();
     let client = create_client(&env);    
     let allowed_addr = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+
+    client.approve(&asset_id, &allowed_addr, &86400_u64);
+}
+
+#[test]
+fn register_several_assets() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner_a = Address::generate(&env);
+    let owner_b = Address::generate(&env);
+    let asset_a = String::from_str(&env, "399fg7u6h69965h6");
+    let asset_b = String::from_str(&env, "7h4k2p9q1x0z3m8n");
+    client.register_asset(&asset_a, &owner_a, &create_metadata(&env));
+    client.register_asset(&asset_b, &owner_b, &create_metadata(&env));
 
-    client.approve(&allowed_addr, &86400_u64);
+    client.transfer(&asset_a, &owner_b);
+    assert_eq!(client.owner_of(&asset_a), owner_b);
+    assert_eq!(client.owner_of(&asset_b), owner_b);
+    assert_eq!(client.share_of(&asset_b, &owner_a), 0);
 }
 
 #[test]
@@ -44,11 +66,12 @@ fn transfer() {
     let owner = Address::generate(&env);
     let new_owner = Address::generate(&env);
     let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+    let metadata = create_metadata(&env);
 
-    client.initialize(&owner, &asset_id);
-    client.transfer(&new_owner);
+    client.register_asset(&asset_id, &owner, &metadata);
+    client.transfer(&asset_id, &new_owner);
 
-    assert_eq!(client.owner(), new_owner);
+    assert_eq!(client.owner_of(&asset_id), new_owner);
 }
 
 #[test]
@@ -60,16 +83,17 @@ fn transfer_from() {
     let new_owner = Address::generate(&env);
     let allowed_addr = Address::generate(&env);
     let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+    let metadata = create_metadata(&env);
 
-    client.initialize(&owner, &asset_id);
-    client.approve(&allowed_addr, &86400_u64);
-    client.transfer_from(&allowed_addr, &new_owner);
+    client.register_asset(&asset_id, &owner, &metadata);
+    client.approve(&asset_id, &allowed_addr, &86400_u64);
+    client.transfer_from(&asset_id, &allowed_addr, &new_owner);
 
-    assert_eq!(client.owner(), new_owner);
+    assert_eq!(client.owner_of(&asset_id), new_owner);
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #3)")]
+#[should_panic(expected = "HostError: Error(Contract, #503)")]
 fn transfer_from_address_not_approved() {
     let env = Env::default();
     let client = create_client(&env);
@@ -79,14 +103,15 @@ fn transfer_from_address_not_approved() {
     let allowed_addr = Address::generate(&env);
     let not_allowed_addr = Address::generate(&env);
     let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+    let metadata = create_metadata(&env);
 
-    client.initialize(&owner, &asset_id);
-    client.approve(&allowed_addr, &86400_u64);
-    client.transfer_from(&not_allowed_addr, &new_owner);
+    client.register_asset(&asset_id, &owner, &metadata);
+    client.approve(&asset_id, &allowed_addr, &86400_u64);
+    client.transfer_from(&asset_id, &not_allowed_addr, &new_owner);
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #4)")]
+#[should_panic(expected = "HostError: Error(Contract, #504)")]
 fn transfer_from_non_approvals() {
     let env = Env::default();
     let client = create_client(&env);
@@ -95,15 +120,437 @@ fn transfer_from_non_approvals() {
     let new_owner = Address::generate(&env);
     let not_allowed_addr = Address::generate(&env);
     let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+    let metadata = create_metadata(&env);
+
+    client.register_asset(&asset_id, &owner, &metadata);
+    client.transfer_from(&asset_id, &not_allowed_addr, &new_owner);
+}
+
+#[test]
+fn transfer_shares() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner = Address::generate(&env);
+    let holder_a = Address::generate(&env);
+    let holder_b = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+    let metadata = create_metadata(&env);
+
+    client.register_asset(&asset_id, &owner, &metadata);
+    client.transfer_shares(&asset_id, &map![&env, (holder_a.clone(), 4000_u32), (holder_b.clone(), 6000_u32)]);
+
+    assert_eq!(client.owner_of(&asset_id), holder_b);
+    assert_eq!(client.share_of(&asset_id, &holder_a), 4000);
+    assert_eq!(client.share_of(&asset_id, &owner), 0);
+    assert_eq!(client.shares(&asset_id).len(), 2);
+
+    client.transfer(&asset_id, &holder_a);
+    assert_eq!(client.share_of(&asset_id, &holder_a), 10000);
+    assert_eq!(client.shares(&asset_id).len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #505)")]
+fn transfer_shares_not_adding_up() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+    let metadata = create_metadata(&env);
+
+    client.register_asset(&asset_id, &owner, &metadata);
+    client.transfer_shares(&asset_id, &map![&env, (Address::generate(&env), 4000_u32), (Address::generate(&env), 5000_u32)]);
+}
+
+#[test]
+fn update_metadata() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+    let mut metadata = create_metadata(&env);
+    client.register_asset(&asset_id, &owner, &metadata);
+    assert_eq!(client.metadata(&asset_id), metadata);
+
+    metadata.deed_hash = BytesN::from_array(&env, &[2; 32]);
+    client.update_metadata(&asset_id, &metadata);
+    assert_eq!(client.metadata(&asset_id).deed_hash, BytesN::from_array(&env, &[2; 32]));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #516)")]
+fn update_legal_id() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+    let mut metadata = create_metadata(&env);
+    client.register_asset(&asset_id, &owner, &metadata);
+
+    metadata.legal_id = String::from_str(&env, "0000000AA0000A0000AA");
+    client.update_metadata(&asset_id, &metadata);
+}
+
+#[test]
+fn document_chain() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+    let metadata = create_metadata(&env);
+    client.register_asset(&asset_id, &owner, &metadata);
+    assert!(client.verify_document(&asset_id, &metadata.deed_hash));
+
+    let permit = BytesN::from_array(&env, &[5; 32]);
+    assert!(!client.verify_document(&asset_id, &permit));
+    let head = client.append_document(&asset_id, &permit);
+    assert!(client.verify_document(&asset_id, &permit));
+
+    let mut data = Bytes::from_array(&env, &[0; 32]);
+    data.append(&metadata.deed_hash.clone().into());
+    let mut expected: Bytes = env.crypto().sha256(&data).to_bytes().into();
+    expected.append(&permit.into());
+    assert_eq!(head, env.crypto().sha256(&expected).to_bytes());
+    assert_eq!(client.document_chain_head(&asset_id), Some(head));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #517)")]
+fn append_document_twice() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+    client.register_asset(&asset_id, &owner, &create_metadata(&env));
+    client.append_document(&asset_id, &BytesN::from_array(&env, &[5; 32]));
+    client.append_document(&asset_id, &BytesN::from_array(&env, &[5; 32]));
+}
 
-    client.initialize(&owner, &asset_id);
-    client.transfer_from(&not_allowed_addr, &new_owner);
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #502)")]
+fn metadata_non_registered() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    client.metadata(&String::from_str(&env, "399fg7u6h69965h6"));
+}
+
+#[test]
+fn transfer_by_operator() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner = Address::generate(&env);
+    let operator = Address::generate(&env);
+    let new_owner = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+
+    client.register_asset(&asset_id, &owner, &create_metadata(&env));
+    client.approve_operator(&asset_id, &operator);
+    client.transfer_by_operator(&asset_id, &operator, &new_owner);
+
+    assert_eq!(client.owner_of(&asset_id), new_owner);
+    assert_eq!(client.operator(&asset_id), None);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #506)")]
+fn transfer_by_operator_not_approved() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+
+    client.register_asset(&asset_id, &owner, &create_metadata(&env));
+    client.approve_operator(&asset_id, &Address::generate(&env));
+    client.transfer_by_operator(&asset_id, &Address::generate(&env), &Address::generate(&env));
+}
+
+#[test]
+fn ownership_history() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner = Address::generate(&env);
+    let holder_a = Address::generate(&env);
+    let holder_b = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+
+    client.register_asset(&asset_id, &owner, &create_metadata(&env));
+    assert_eq!(client.get_history(&asset_id).len(), 0);
+
+    env.ledger().set_timestamp(1000);
+    client.transfer(&asset_id, &holder_a);
+    env.ledger().set_timestamp(2000);
+    let shares = map![&env, (holder_a.clone(), 4000_u32), (holder_b.clone(), 6000_u32)];
+    client.transfer_shares(&asset_id, &shares);
+
+    let history = client.get_history(&asset_id);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).unwrap(), OwnershipRecord { ts: 1000, from: owner, to: holder_a.clone(), shares: map![&env] });
+    assert_eq!(history.get(1).unwrap(), OwnershipRecord { ts: 2000, from: holder_a, to: holder_b, shares });
+}
+
+#[test]
+fn lien() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner = Address::generate(&env);
+    let lender = Address::generate(&env);
+    let new_owner = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+
+    client.register_asset(&asset_id, &owner, &create_metadata(&env));
+    client.place_lien(&asset_id, &lender);
+    assert_eq!(client.lien(&asset_id), Some(lender));
+
+    client.release_lien(&asset_id);
+    client.transfer(&asset_id, &new_owner);
+    assert_eq!(client.owner_of(&asset_id), new_owner);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #507)")]
+fn transfer_under_lien() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+
+    client.register_asset(&asset_id, &owner, &create_metadata(&env));
+    client.place_lien(&asset_id, &Address::generate(&env));
+    client.transfer(&asset_id, &Address::generate(&env));
+}
+
+#[test]
+fn freeze() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner = Address::generate(&env);
+    let new_owner = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+
+    client.register_asset(&asset_id, &owner, &create_metadata(&env));
+    client.freeze(&asset_id);
+    assert_eq!(client.is_frozen(&asset_id), true);
+
+    client.unfreeze(&asset_id);
+    client.transfer(&asset_id, &new_owner);
+    assert_eq!(client.owner_of(&asset_id), new_owner);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #509)")]
+fn transfer_frozen() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+
+    client.register_asset(&asset_id, &owner, &create_metadata(&env));
+    client.freeze(&asset_id);
+    client.transfer(&asset_id, &Address::generate(&env));
+}
+
+#[test]
+fn fractionalize() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner = Address::generate(&env);
+    let holder_a = Address::generate(&env);
+    let holder_b = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+
+    client.register_asset(&asset_id, &owner, &create_metadata(&env));
+    client.transfer_shares(&asset_id, &map![&env, (holder_a.clone(), 4000_u32), (holder_b.clone(), 6000_u32)]);
+    client.fractionalize(&asset_id, &1_000_000);
+    assert_eq!(client.total_supply(&asset_id), 1_000_000);
+    assert_eq!(client.balance(&asset_id, &holder_a), 400_000);
+    assert_eq!(client.balance(&asset_id, &holder_b), 600_000);
+
+    client.transfer_tokens(&asset_id, &holder_b, &owner, &200_000);
+    assert_eq!(client.share_of(&asset_id, &owner), 2000);
+    assert_eq!(client.shares(&asset_id).len(), 3);
+
+    env.ledger().set_timestamp(1000);
+    client.elect_owner(&asset_id, &vec![&env, holder_a.clone(), owner.clone()], &holder_a);
+    assert_eq!(client.owner_of(&asset_id), holder_a);
+    assert_eq!(client.get_history(&asset_id).last().unwrap(), OwnershipRecord { ts: 1000, from: holder_b.clone(), to: holder_a.clone(), shares: map![&env] });
+
+    client.transfer_tokens(&asset_id, &holder_b, &holder_a, &400_000);
+    client.transfer_tokens(&asset_id, &owner, &holder_a, &200_000);
+    client.defractionalize(&asset_id, &holder_a);
+    assert_eq!(client.total_supply(&asset_id), 0);
+    assert_eq!(client.share_of(&asset_id, &holder_a), 10000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #513)")]
+fn elect_owner_without_majority() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+
+    client.register_asset(&asset_id, &owner, &create_metadata(&env));
+    client.fractionalize(&asset_id, &1_000_000);
+    client.transfer_tokens(&asset_id, &owner, &holder, &500_000);
+    client.elect_owner(&asset_id, &vec![&env, holder.clone(), holder.clone()], &holder);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #509)")]
+fn elect_owner_frozen() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+
+    client.register_asset(&asset_id, &owner, &create_metadata(&env));
+    client.fractionalize(&asset_id, &1_000_000);
+    client.transfer_tokens(&asset_id, &owner, &holder, &600_000);
+    client.freeze(&asset_id);
+    client.elect_owner(&asset_id, &vec![&env, holder.clone()], &holder);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #507)")]
+fn elect_owner_under_lien() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+
+    client.register_asset(&asset_id, &owner, &create_metadata(&env));
+    client.fractionalize(&asset_id, &1_000_000);
+    client.transfer_tokens(&asset_id, &owner, &holder, &600_000);
+    client.place_lien(&asset_id, &Address::generate(&env));
+    client.elect_owner(&asset_id, &vec![&env, holder.clone()], &holder);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #510)")]
+fn transfer_tokenized() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+
+    client.register_asset(&asset_id, &owner, &create_metadata(&env));
+    client.fractionalize(&asset_id, &1_000_000);
+    client.transfer(&asset_id, &Address::generate(&env));
+}
+
+#[test]
+fn rent_distribution() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner = Address::generate(&env);
+    let holder_a = Address::generate(&env);
+    let holder_b = Address::generate(&env);
+    let tenant = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+    let (token_client, token_admin) = create_token_contract(&env, &Address::generate(&env));
+    let token = token_client.address.clone();
+    token_admin.mint(&tenant, &3000);
+
+    client.register_asset(&asset_id, &owner, &create_metadata(&env));
+    client.transfer_shares(&asset_id, &map![&env, (holder_a.clone(), 4000_u32), (holder_b.clone(), 6000_u32)]);
+    client.deposit_rent(&asset_id, &tenant, &token, &1000);
+    assert_eq!(client.claim_rent(&asset_id, &token, &holder_a), 400);
+    assert_eq!(client.pending_rent(&asset_id, &token, &holder_b), 600);
+
+    // Rent earned before a change of ownership stays with the former holders
+    client.fractionalize(&asset_id, &1_000_000);
+    client.transfer_tokens(&asset_id, &holder_b, &holder_a, &600_000);
+    client.deposit_rent(&asset_id, &tenant, &token, &2000);
+    assert_eq!(client.claim_rent(&asset_id, &token, &holder_a), 2000);
+    assert_eq!(client.claim_rent(&asset_id, &token, &holder_b), 600);
+    assert_eq!(token_client.balance(&holder_a), 2400);
+    assert_eq!(client.pending_rent(&asset_id, &token, &owner), 0);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #515)")]
+fn claim_rent_twice() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    let owner = Address::generate(&env);
+    let asset_id = String::from_str(&env, "399fg7u6h69965h6");
+    let (token_client, token_admin) = create_token_contract(&env, &Address::generate(&env));
+    let token = token_client.address;
+    token_admin.mint(&owner, &1000);
+
+    client.register_asset(&asset_id, &owner, &create_metadata(&env));
+    client.deposit_rent(&asset_id, &owner, &token, &1000);
+    client.claim_rent(&asset_id, &token, &owner);
+    client.claim_rent(&asset_id, &token, &owner);
+}
+
+#[test]
+fn registry_admin_transfer() {
+    let env = Env::default();
+    let client = create_client(&env);
+    let new_admin = Address::generate(&env);
+
+    client.propose_admin(&new_admin);
+    assert_ne!(client.admin(), new_admin);
+    client.accept_admin();
+    assert_eq!(client.admin(), new_admin);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #518)")]
+fn accept_admin_after_cancel() {
+    let env = Env::default();
+    let client = create_client(&env);
+
+    client.propose_admin(&Address::generate(&env));
+    client.cancel_admin_transfer();
+    client.accept_admin();
+}
+
+fn create_metadata(env: &Env) -> Metadata {
+    Metadata {
+        postal_address: String::from_str(env, "Calle Mayor 1, 28013 Madrid"),
+        legal_id: String::from_str(env, "9872023VH5797S0001WX"),
+        deed_hash: BytesN::from_array(env, &[1; 32]),
+    }
 }
 
 fn create_client(env: &Env) -> AssetClient<'_> {
     env.mock_all_auths();
-    let contract_id = env.register(Asset, () );
+    let contract_id = env.register(Asset, (Address::generate(env),));
     let client = AssetClient::new(&env, &contract_id);
 
     client
-}
\ No newline at end of file
+}
+
+#[test]
+fn error_codes_in_registry_range() {
+    use super::Error;
+
+    assert!(soroban_examples_errors::HOUSE_ASSET.contains(Error::AssetAlreadyRegistered as u32));
+    assert!(soroban_examples_errors::HOUSE_ASSET.contains(Error::RegistryAdminNotSet as u32));
+}
@@ -0,0 +1,12 @@
+use soroban_sdk::{contractclient, Address, Env, Map, String};
+
+// Functions of the `HouseAsset` registry called by the purchase
+#[contractclient(name = "HouseAssetClient")]
+pub trait HouseAssetInterface {
+    fn admin(e: Env) -> Address;
+    fn owner_of(e: Env, id: String) -> Address;
+    fn is_frozen(e: Env, id: String) -> bool;
+    fn transfer_by_operator(e: Env, id: String, operator: Address, to: Address) -> bool;
+    fn transfer_shares_by_operator(e: Env, id: String, operator: Address, shares: Map<Address, u32>) -> bool;
+    fn place_lien(e: Env, id: String, lender: Address) -> bool;
+}
@@ -1,42 +1,824 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracterror, contracttype, token, Env, Address};
+use soroban_sdk::{contract, contractclient, contractimpl, contracterror, contracttype, symbol_short, token, Env, Address, BytesN, Map, String, Symbol, Vec};
+use soroban_examples_pausable as pausable;
+use soroban_examples_transfer::{self as transfer, TransferError};
+use soroban_examples_storage::bump_instance;
 
-mod asset {
-    soroban_sdk::contractimport!(
-        file = "../HouseAsset/target/wasm32-unknown-unknown/release/house_asset.wasm"
-    );
+mod asset;
+
+pub const TOPIC_INITIALIZED: Symbol = symbol_short!("INIT");
+pub const TOPIC_OFFER_SUBMITTED: Symbol = symbol_short!("OFFER");
+pub const TOPIC_OFFER_ACCEPTED: Symbol = symbol_short!("OFFERACPT");
+pub const TOPIC_OFFER_WITHDRAWN: Symbol = symbol_short!("OFFERWDRW");
+pub const TOPIC_INSPECTION: Symbol = symbol_short!("INSPECTED");
+pub const TOPIC_PAYMENT: Symbol = symbol_short!("PAYMENT");
+pub const TOPIC_RENT_PAID: Symbol = symbol_short!("RENTPAID");
+pub const TOPIC_FINANCED: Symbol = symbol_short!("FINANCED");
+pub const TOPIC_LOAN_REPAID: Symbol = symbol_short!("LOANREPAY");
+pub const TOPIC_PRICE_CHANGED: Symbol = symbol_short!("PRICECHG");
+pub const TOPIC_OWNER_CHANGED: Symbol = symbol_short!("OWNERCHG");
+pub const TOPIC_CANCELLED: Symbol = symbol_short!("CANCELLED");
+pub const TOPIC_RETENTION_RELEASED: Symbol = symbol_short!("RETAINREL");
+pub const TOPIC_INSURED: Symbol = symbol_short!("INSURED");
+pub const TOPIC_NOTARIZED: Symbol = symbol_short!("NOTARIZED");
+pub const TOPIC_BUYER_ASSIGNED: Symbol = symbol_short!("ASSIGNED");
+pub const TOPIC_DISPUTE_OPENED: Symbol = symbol_short!("DISPUTE");
+pub const TOPIC_DISPUTE_RESOLVED: Symbol = symbol_short!("RULING");
+
+// On-chain appraisal feed: value of a `HouseAsset` registry asset, in units of the payment token
+#[contractclient(name = "OracleClient")]
+pub trait AppraisalOracle {
+    fn appraisal(e: Env, asset: Address, asset_id: String) -> i128;
+}
+
+// Last price of a token, in units of the token worth one unit of a fiat reference currency (e.g. one
+// USD cent), and the time it was published at
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64
+}
+
+// Price feed converting fiat reference amounts into token amounts
+#[contractclient(name = "PriceFeedClient")]
+pub trait PriceFeed {
+    fn lastprice(e: Env, token: Address) -> Option<PriceData>;
 }
 
 #[contracttype]
 enum DataKey {
     Asset,
-    State,
-    Buyer,
+    AssetId,
+    Status,
+    Buyers,
+    Contribution(Address),
     Token,
     Admin,
-    FirstPaymentAmount,
-    Amount
+    Installments,
+    PaidInstallments,
+    InstallmentContributed,
+    ClosingDeadline,
+    CancelPolicy,
+    Inspector,
+    Inspection,
+    PriceProposal,
+    AppraisalCheck,
+    Loan,
+    RentToOwn,
+    PaymentTokens,
+    QuotedPrice,
+    MilestoneToken(u32),
+    TokenContribution(Address, Address),
+    ClosingCosts,
+    Retention,
+    CoSigner(Address),
+    Bidders,
+    Offer(Address),
+    Insurance,
+    Notarization,
+    ClosingSchedule,
+    FiatPricing,
+    Arbiter,
+    Dispute
+}
+
+// Lifecycle of the purchase. Every change goes through `set_status`, which only allows these moves:
+// Created -> Accepted -> PaymentsInProgress -> FullyPaid -> Closed, skipping PaymentsInProgress when
+// the plan is completed at once, and to Cancelled from any status before Closed. A purchase opened
+// to offers starts at Bidding instead of Created.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PurchaseStatus {
+    Bidding,
+    Created,
+    Accepted,
+    PaymentsInProgress,
+    FullyPaid,
+    Closed,
+    Cancelled
+}
+
+// What happens to the paid installments when a partially paid purchase is cancelled. With `Penalty` the
+// seller keeps the given basis points of them, or all of them once the buyers waived the inspection.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CancelPolicy {
+    Refund,
+    Forfeit,
+    Penalty(u32)
+}
+
+// Why a purchase is cancelled with `cancel`
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CancelReason {
+    // A buyer walks away from the deal
+    Withdrawal,
+    InspectionFailed,
+    InstallmentOverdue,
+    DeadlinePassed,
+    // The scheduled closing date and its grace period have passed
+    LateClosing
 }
 
 #[contracttype]
-#[derive(Eq, PartialEq)]
-pub enum State {
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InspectionStatus {
     Pending,
-    FirstPaymentTransferred,
-    RestOfPaymentTransferred,
-    Finished
+    Approved,
+    Failed,
+    Waived
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Installment {
+    // What the payment stands for, e.g. "reservation fee", "down payment" or "completion"
+    pub label: String,
+    pub amount: i128,
+    pub due_ts: u64
+}
+
+// New plan for the installments from `from_installment` on, waiting for the other party to accept it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceProposal {
+    pub proposer: Address,
+    pub from_installment: u32,
+    pub installments: Vec<Installment>
+}
+
+// The price must not deviate from the oracle appraisal more than `max_deviation_bps` basis points
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AppraisalCheck {
+    pub oracle: Address,
+    pub max_deviation_bps: u32
+}
+
+// Mortgage granted by `lender`, who paid `principal` of the price on behalf of the buyers
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Loan {
+    pub lender: Address,
+    pub principal: i128,
+    pub repaid: i128
+}
+
+// Rent-to-own terms: `rent` is due every `RENT_PERIOD` from `start_ts`, and `credit_bps` of every
+// rent paid on time is credited towards the price, lowering the final (balloon) payment
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RentToOwn {
+    pub rent: i128,
+    pub credit_bps: u32,
+    pub start_ts: u64,
+    pub paid_rents: u32,
+    pub credit: i128
+}
+
+// Snapshot of the purchase returned by `get_purchase_state`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PurchaseInfo {
+    pub asset: Address,
+    pub asset_id: String,
+    pub buyers: Vec<Address>,
+    pub inspector: Address,
+    pub token: Address,
+    pub installments: Vec<Installment>,
+    pub closing_deadline: u64,
+    pub cancel_policy: CancelPolicy,
+    pub paid_installments: u32,
+    // Paid so far towards the current installment
+    pub installment_contributed: i128,
+    // Funds held by the contract in each payment token until the purchase is closed or cancelled (the
+    // retention, after closing); tokens holding nothing are left out
+    pub escrowed: Map<Address, i128>,
+    pub inspection: InspectionStatus,
+    pub status: PurchaseStatus
+}
+
+// Fee paid at closing out of the seller proceeds, e.g. taxes, notary or registry fees
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClosingCost {
+    pub label: String,
+    pub recipient: Address,
+    pub amount: i128
+}
+
+// Itemized settlement statement of the purchase, in the price token
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Settlement {
+    pub price: i128,
+    pub closing_costs: Vec<ClosingCost>,
+    pub seller_proceeds: i128
+}
+
+// Part of the final installment (`retention_bps`) kept in escrow after closing until
+// `dispute_window` seconds have passed. `retained`, `seller` and `release_ts` are set at closing.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Retention {
+    pub retention_bps: u32,
+    pub dispute_window: u64,
+    pub retained: i128,
+    pub seller: Option<Address>,
+    pub release_ts: u64
+}
+
+// Guarantor of a buyer, who must also sign every contribution of that buyer above `threshold`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CoSigner {
+    pub cosigner: Address,
+    pub threshold: i128
+}
+
+// Offer of a prospective buyer during the bidding phase. The first installment is escrowed as
+// deposit when the offer is submitted.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Offer {
+    pub bidder: Address,
+    pub installments: Vec<Installment>
+}
+
+// The house must be insured at closing: `insurer` confirms the policy (identified by the hash of its
+// document) or the buyers waive the requirement
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Insurance {
+    pub insurer: Address,
+    pub policy_hash: Option<BytesN<32>>,
+    pub waived: bool
+}
+
+// Sign-off of the deal by `notary` once the price is fully paid. `document_hash` (the hash of the
+// notarized deed) and `ts` are set by `notarize`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Notarization {
+    pub notary: Address,
+    pub document_hash: Option<BytesN<32>>,
+    pub ts: u64
+}
+
+// Agreed closing date: the purchase cannot be closed before `closing_ts`, and once `grace_period` seconds
+// have passed after it without closing, either party can cancel it. The seller then keeps `penalty_bps`
+// of what the buyers paid, unless the whole price was paid.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClosingSchedule {
+    pub closing_ts: u64,
+    pub grace_period: u64,
+    pub penalty_bps: u32
+}
+
+// The installments are quoted in a fiat reference unit and every payment is converted into the price
+// token at the last price of `feed`, which must not be older than `max_staleness` seconds
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FiatPricing {
+    pub feed: Address,
+    pub max_staleness: u64
+}
+
+// Decision of the arbiter: the seller gets `seller_bps` of the buyers' payments and the rest is refunded,
+// and the asset goes to the buyers if `transfer_ownership`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Ruling {
+    pub seller_bps: u32,
+    pub transfer_ownership: bool
+}
+
+// Dispute raised by a buyer or the seller. `reason_hash` is the hash of the claim, kept off-chain.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Dispute {
+    pub raised_by: Address,
+    pub reason_hash: BytesN<32>,
+    pub ts: u64,
+    pub ruling: Option<Ruling>
 }
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
 pub enum Error {
-    ContractAlreadyInitialized = 1,
-    ContractNotInitialized = 2,
-    FirstPaymentNotTransferred = 3,
-    RestOfPaymentNotTransferred = 4
+    ContractAlreadyInitialized = 601,
+    ContractNotInitialized = 602,
+    InvalidPaymentSchedule = 603,
+    PaymentScheduleNotCompleted = 604,
+    ClosingDeadlineNotReached = 605,
+    ClosingDeadlinePassed = 606,
+    PurchaseCannotBeCancelled = 607,
+    AddressNotAllowed = 608,
+    OfferNotAccepted = 609,
+    OfferAlreadyAccepted = 610,
+    InstallmentOverdue = 611,
+    InspectionNotCleared = 612,
+    InspectionAlreadyResolved = 613,
+    InvalidContribution = 614,
+    NoBuyers = 615,
+    NoPriceProposal = 616,
+    PriceProposalOutdated = 617,
+    PriceCannotBeChanged = 618,
+    PriceOutOfAppraisalRange = 619,
+    FinancingNotAllowed = 620,
+    NoLoan = 621,
+    InvalidRepayment = 622,
+    PurchaseNotFinished = 623,
+    NotRentToOwn = 624,
+    InvalidRentTerms = 625,
+    AssetFrozen = 626,
+    InvalidStatusTransition = 627,
+    MilestoneOutOfOrder = 628,
+    TokenNotAccepted = 629,
+    TokenMismatch = 630,
+    InvalidClosingCosts = 631,
+    ClosingCostsNotCovered = 632,
+    PaymentsAlreadyMade = 633,
+    RetentionCannotBeSet = 634,
+    RetentionNotReleasable = 635,
+    BiddingNotOpen = 636,
+    NoOffer = 637,
+    OfferAlreadySubmitted = 638,
+    InsuranceCannotBeRequired = 639,
+    NoInsurance = 640,
+    InsuranceAlreadyResolved = 641,
+    InsuranceNotConfirmed = 642,
+    NotaryCannotBeAppointed = 643,
+    NoNotary = 644,
+    AlreadyNotarized = 645,
+    NotNotarized = 646,
+    InvalidClosingSchedule = 647,
+    ClosingDateNotReached = 648,
+    FiatPricingCannotBeSet = 649,
+    StalePrice = 650,
+    BuyerCannotBeAssigned = 651,
+    InvalidCancelPolicy = 652,
+    CancelReasonNotMet = 653,
+    ArbiterCannotBeAppointed = 654,
+    NoArbiter = 655,
+    DisputeOpen = 656,
+    NoDispute = 657,
+    InvalidRuling = 658,
+    PurchasePaused = 659,
+    InsufficientAllowance = 660,
+    InsufficientBalance = 661,
+    TokenTransferFailed = 662
+}
+
+impl From<TransferError> for Error {
+    fn from(err: TransferError) -> Self {
+        match err {
+            TransferError::InsufficientAllowance => Error::InsufficientAllowance,
+            TransferError::InsufficientBalance => Error::InsufficientBalance,
+            TransferError::TransferFailed => Error::TokenTransferFailed,
+        }
+    }
+}
+
+// Ownership shares are expressed in basis points, as in the HouseAsset contract
+const TOTAL_SHARES: u32 = 10_000;
+
+const RENT_PERIOD: u64 = 30 * 86400;
+
+fn get_status(e: &Env) -> PurchaseStatus {
+    e.storage().instance().get::<DataKey, PurchaseStatus>(&DataKey::Status).unwrap()
+}
+
+fn set_status(e: &Env, status: PurchaseStatus) -> Result<(), Error> {
+    let allowed = match (get_status(e), status) {
+        (PurchaseStatus::Created, PurchaseStatus::Accepted) => true,
+        (PurchaseStatus::Bidding, PurchaseStatus::Accepted) => true,
+        (PurchaseStatus::Accepted, PurchaseStatus::PaymentsInProgress) => true,
+        (PurchaseStatus::Accepted, PurchaseStatus::FullyPaid) => true,
+        (PurchaseStatus::PaymentsInProgress, PurchaseStatus::PaymentsInProgress) => true,
+        (PurchaseStatus::PaymentsInProgress, PurchaseStatus::FullyPaid) => true,
+        (PurchaseStatus::FullyPaid, PurchaseStatus::Closed) => true,
+        (PurchaseStatus::Closed, _) | (PurchaseStatus::Cancelled, _) => false,
+        (_, PurchaseStatus::Cancelled) => true,
+        _ => false
+    };
+
+    if !allowed {
+        return Err(Error::InvalidStatusTransition);
+    }
+
+    e.storage().instance().set(&DataKey::Status, &status);
+    bump_instance(e);
+    Ok(())
+}
+
+// Books a contribution of `buyer` towards the current installment. The buyer pays it, or `spender`
+// does through the allowance the buyer granted it
+fn pay_contribution(e: Env, spender: Option<Address>, buyer: Address, token: Address, amount: i128) -> Result<u32, Error> {
+    when_not_paused(&e)?;
+
+    if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+        let status = get_status(&e);
+        if status != PurchaseStatus::Accepted && status != PurchaseStatus::PaymentsInProgress {
+            return Err(Error::OfferNotAccepted);
+        }
+
+        if !get_buyers(&e).contains(&buyer) {
+            return Err(Error::AddressNotAllowed);
+        }
+
+        if deadline_passed(&e) {
+            return Err(Error::ClosingDeadlinePassed);
+        }
+
+        if next_installment_overdue(&e) {
+            return Err(Error::InstallmentOverdue);
+        }
+
+        if dispute_open(&e) {
+            return Err(Error::DisputeOpen);
+        }
+
+        if get_paid_installments(&e) > 0 && !inspection_cleared(&e) {
+            return Err(Error::InspectionNotCleared);
+        }
+
+        // Convert the amount at the price quoted in the token, rounding in favour of the seller
+        let price_token = e.storage().instance().get::<DataKey, Address>(&DataKey::Token).unwrap();
+        let charged = if let Some(pricing) = e.storage().instance().get::<DataKey, FiatPricing>(&DataKey::FiatPricing) {
+            if token != price_token {
+                return Err(Error::TokenNotAccepted);
+            }
+
+            match PriceFeedClient::new(&e, &pricing.feed).lastprice(&token) {
+                Some(data) if data.price > 0 && data.timestamp + pricing.max_staleness >= e.ledger().timestamp() => amount * data.price,
+                _ => return Err(Error::StalePrice)
+            }
+        } else if token == price_token {
+            amount
+        } else {
+            let alt_tokens = e.storage().instance().get::<DataKey, Map<Address, i128>>(&DataKey::PaymentTokens).unwrap();
+            let token_price = alt_tokens.get(token.clone()).ok_or(Error::TokenNotAccepted)?;
+            let quoted_price = e.storage().instance().get::<DataKey, i128>(&DataKey::QuotedPrice).unwrap();
+            (amount * token_price + quoted_price - 1) / quoted_price
+        };
+
+        let installments = get_installments(&e);
+        let mut paid_installments = get_paid_installments(&e);
+        let installment = installments.get(paid_installments).unwrap();
+        let mut contributed = e.storage().instance().get::<DataKey, i128>(&DataKey::InstallmentContributed).unwrap_or(0);
+        if amount <= 0 || contributed + amount > installment.amount {
+            return Err(Error::InvalidContribution);
+        }
+
+        let milestone_token = DataKey::MilestoneToken(paid_installments);
+        match e.storage().instance().get::<DataKey, Address>(&milestone_token) {
+            Some(used) if used != token => return Err(Error::TokenMismatch),
+            Some(_) => {},
+            None => e.storage().instance().set(&milestone_token, &token)
+        }
+
+        match &spender {
+            Some(spender) => spender.require_auth(),
+            None => buyer.require_auth(),
+        }
+        if let Some(cosigner) = e.storage().instance().get::<DataKey, CoSigner>(&DataKey::CoSigner(buyer.clone())) {
+            if amount > cosigner.threshold {
+                cosigner.cosigner.require_auth();
+            }
+        }
+
+        e.storage().instance().set(&DataKey::Contribution(buyer.clone()), &(get_contribution(&e, &buyer) + amount));
+        e.storage().instance().set(&DataKey::TokenContribution(buyer.clone(), token.clone()), &(get_token_contribution(&e, &buyer, &token) + charged));
+
+        contributed += amount;
+        if contributed == installment.amount {
+            paid_installments += 1;
+            contributed = 0;
+        }
+
+        let new_status = if paid_installments == installments.len() { PurchaseStatus::FullyPaid } else { PurchaseStatus::PaymentsInProgress };
+        e.storage().instance().set(&DataKey::PaidInstallments, &paid_installments);
+        e.storage().instance().set(&DataKey::InstallmentContributed, &contributed);
+        set_status(&e, new_status)?;
+        match spender {
+            Some(spender) => transfer::pull_from(&e, &token, &spender, &buyer, charged)?,
+            None => token::Client::new(&e, &token).transfer(&buyer, &e.current_contract_address(), &charged),
+        }
+        e.events().publish((TOPIC_PAYMENT,), (asset, buyer, token, charged, paid_installments));
+        Ok(paid_installments)
+    } else {
+        return Err(Error::ContractNotInitialized);
+    }
+}
+
+// What is left to pay of the milestone `index`, which must be the next one
+fn milestone_remainder(e: &Env, index: u32) -> Result<i128, Error> {
+    let installments = e.storage().instance().get::<DataKey, Vec<Installment>>(&DataKey::Installments).ok_or(Error::ContractNotInitialized)?;
+    if index != get_paid_installments(e) || index >= installments.len() {
+        return Err(Error::MilestoneOutOfOrder);
+    }
+
+    let contributed = e.storage().instance().get::<DataKey, i128>(&DataKey::InstallmentContributed).unwrap_or(0);
+    Ok(installments.get(index).unwrap().amount - contributed)
+}
+
+// Payments and closing stop while the registry admin has the purchase paused
+fn when_not_paused(e: &Env) -> Result<(), Error> {
+    pausable::when_not_paused(e).map_err(|_| Error::PurchasePaused)
+}
+
+fn deadline_passed(e: &Env) -> bool {
+    let closing_deadline = e.storage().instance().get::<DataKey, u64>(&DataKey::ClosingDeadline).unwrap();
+    e.ledger().timestamp() > closing_deadline
+}
+
+// The scheduled closing date and its grace period have passed
+fn closing_overdue(e: &Env) -> bool {
+    match e.storage().instance().get::<DataKey, ClosingSchedule>(&DataKey::ClosingSchedule) {
+        Some(schedule) => e.ledger().timestamp() > schedule.closing_ts + schedule.grace_period,
+        None => false
+    }
+}
+
+fn check_cancel_policy(cancel_policy: CancelPolicy) -> Result<(), Error> {
+    match cancel_policy {
+        CancelPolicy::Penalty(bps) if bps > 10_000 => Err(Error::InvalidCancelPolicy),
+        _ => Ok(())
+    }
+}
+
+// Basis points of the buyers' payments the seller keeps when the purchase is cancelled for `reason`.
+// Nothing is kept before the first payment, after a failed inspection, or when a fully paid purchase
+// was not closed in time.
+fn cancel_penalty_bps(e: &Env, reason: CancelReason, status: PurchaseStatus) -> u32 {
+    if status == PurchaseStatus::Created || status == PurchaseStatus::Accepted || reason == CancelReason::InspectionFailed
+        || (status == PurchaseStatus::FullyPaid && reason != CancelReason::Withdrawal) {
+        return 0;
+    }
+
+    let policy_bps = match e.storage().instance().get::<DataKey, CancelPolicy>(&DataKey::CancelPolicy).unwrap() {
+        CancelPolicy::Refund => 0,
+        CancelPolicy::Forfeit => 10_000,
+        CancelPolicy::Penalty(_) if get_inspection(e) == InspectionStatus::Waived => 10_000,
+        CancelPolicy::Penalty(bps) => bps
+    };
+
+    if policy_bps == 0 && reason == CancelReason::LateClosing {
+        e.storage().instance().get::<DataKey, ClosingSchedule>(&DataKey::ClosingSchedule).unwrap().penalty_bps
+    } else {
+        policy_bps
+    }
+}
+
+fn dispute_open(e: &Env) -> bool {
+    match e.storage().instance().get::<DataKey, Dispute>(&DataKey::Dispute) {
+        Some(dispute) => dispute.ruling.is_none(),
+        None => false
+    }
+}
+
+// Buyers who paid towards the price and the sum of their contributions. The rent credit of a
+// rent-to-own tenant can cover the whole price, leaving every buyer as contributor.
+fn get_contributors(e: &Env) -> (Vec<Address>, i128) {
+    let mut contributors: Vec<Address> = Vec::new(e);
+    let mut contributed: i128 = 0;
+    for buyer in get_buyers(e).iter() {
+        let contribution = get_contribution(e, &buyer);
+        if contribution > 0 {
+            contributors.push_back(buyer);
+            contributed += contribution;
+        }
+    }
+
+    if contributors.is_empty() {
+        contributors = get_buyers(e);
+    }
+    (contributors, contributed)
+}
+
+// Rules on a dispute raised after closing: the retention is split between the seller and the
+// contributors, rounding leftovers going to the first contributor
+fn resolve_retention_dispute(e: &Env, mut dispute: Dispute, seller_bps: u32, transfer_ownership: bool) -> Result<Ruling, Error> {
+    if seller_bps > 10_000 || transfer_ownership {
+        return Err(Error::InvalidRuling);
+    }
+
+    let arbiter = e.storage().instance().get::<DataKey, Address>(&DataKey::Arbiter).unwrap();
+    arbiter.require_auth();
+
+    // The ruling and the retention are booked before any token leaves the contract
+    let ruling = Ruling { seller_bps, transfer_ownership };
+    dispute.ruling = Some(ruling.clone());
+    e.storage().instance().set(&DataKey::Dispute, &dispute);
+    let mut retention = e.storage().instance().get::<DataKey, Retention>(&DataKey::Retention).unwrap();
+    let retained = retention.retained;
+    retention.retained = 0;
+    e.storage().instance().set(&DataKey::Retention, &retention);
+
+    let seller = retention.seller.unwrap();
+    let seller_part = retained * seller_bps as i128 / 10_000;
+    let buyers_part = retained - seller_part;
+    let tk = token::Client::new(e, &e.storage().instance().get::<DataKey, Address>(&DataKey::Token).unwrap());
+    if seller_part > 0 {
+        tk.transfer(&e.current_contract_address(), &seller, &seller_part);
+    }
+
+    let (contributors, contributed) = get_contributors(e);
+    let mut refunded: i128 = 0;
+    if contributed > 0 {
+        for buyer in contributors.iter().skip(1) {
+            let refund = buyers_part * get_contribution(e, &buyer) / contributed;
+            if refund > 0 {
+                tk.transfer(&e.current_contract_address(), &buyer, &refund);
+                refunded += refund;
+            }
+        }
+    }
+    if buyers_part > refunded {
+        tk.transfer(&e.current_contract_address(), &contributors.get(0).unwrap(), &(buyers_part - refunded));
+    }
+
+    let asset = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset).unwrap();
+    e.events().publish((TOPIC_DISPUTE_RESOLVED,), (asset, seller_bps, transfer_ownership));
+    Ok(ruling)
+}
+
+// Transfers the asset to the contributors, each one owning a share proportional to what they paid,
+// and pledges it to the lender of a financed purchase
+fn hand_over_asset(e: &Env, asset_contract: &asset::HouseAssetClient, contributors: &Vec<Address>, contributed: i128) {
+    if contributors.len() == 1 {
+        asset_contract.transfer_by_operator(&get_asset_id(e), &e.current_contract_address(), &contributors.get(0).unwrap());
+    } else {
+        // Rounding leftovers go to the first contributor; a share rounding down to 0 is left out
+        let mut shares: Map<Address, u32> = Map::new(e);
+        let mut assigned: u32 = 0;
+        for buyer in contributors.iter() {
+            let share = (get_contribution(e, &buyer) * TOTAL_SHARES as i128 / contributed) as u32;
+            if share > 0 {
+                shares.set(buyer, share);
+                assigned += share;
+            }
+        }
+
+        let first = contributors.get(0).unwrap();
+        shares.set(first.clone(), shares.get(first).unwrap_or(0) + TOTAL_SHARES - assigned);
+        asset_contract.transfer_shares_by_operator(&get_asset_id(e), &e.current_contract_address(), &shares);
+    }
+
+    // A financed house stays pledged to the lender until the loan is repaid
+    if let Some(loan) = e.storage().instance().get::<DataKey, Loan>(&DataKey::Loan) {
+        asset_contract.place_lien(&get_asset_id(e), &loan.lender);
+    }
+}
+
+fn get_installments(e: &Env) -> Vec<Installment> {
+    e.storage().instance().get::<DataKey, Vec<Installment>>(&DataKey::Installments).unwrap()
+}
+
+fn get_paid_installments(e: &Env) -> u32 {
+    e.storage().instance().get::<DataKey, u32>(&DataKey::PaidInstallments).unwrap_or(0)
+}
+
+// Installments must have a positive amount and be due in order, no later than the closing deadline
+fn check_installments(installments: &Vec<Installment>, closing_deadline: u64) -> Result<(), Error> {
+    if installments.is_empty() {
+        return Err(Error::InvalidPaymentSchedule);
+    }
+
+    let mut previous_due_ts = 0;
+    for installment in installments.iter() {
+        if installment.amount <= 0 || installment.due_ts < previous_due_ts || installment.due_ts > closing_deadline {
+            return Err(Error::InvalidPaymentSchedule);
+        }
+        previous_due_ts = installment.due_ts;
+    }
+
+    Ok(())
+}
+
+fn get_asset_id(e: &Env) -> String {
+    e.storage().instance().get::<DataKey, String>(&DataKey::AssetId).unwrap()
+}
+
+fn get_buyers(e: &Env) -> Vec<Address> {
+    e.storage().instance().get::<DataKey, Vec<Address>>(&DataKey::Buyers).unwrap()
+}
+
+fn get_bidders(e: &Env) -> Vec<Address> {
+    e.storage().instance().get::<DataKey, Vec<Address>>(&DataKey::Bidders).unwrap_or(Vec::new(e))
+}
+
+fn get_contribution(e: &Env, buyer: &Address) -> i128 {
+    e.storage().instance().get::<DataKey, i128>(&DataKey::Contribution(buyer.clone())).unwrap_or(0)
+}
+
+// What `buyer` paid in `token`, in units of that token
+fn get_token_contribution(e: &Env, buyer: &Address, token: &Address) -> i128 {
+    e.storage().instance().get::<DataKey, i128>(&DataKey::TokenContribution(buyer.clone(), token.clone())).unwrap_or(0)
+}
+
+// The price token first, followed by the alternative payment tokens
+fn get_payment_tokens(e: &Env) -> Vec<Address> {
+    let mut tokens = Vec::from_array(e, [e.storage().instance().get::<DataKey, Address>(&DataKey::Token).unwrap()]);
+    if let Some(alt_tokens) = e.storage().instance().get::<DataKey, Map<Address, i128>>(&DataKey::PaymentTokens) {
+        tokens.append(&alt_tokens.keys());
+    }
+    tokens
+}
+
+// Funds held in `token`: the buyers' payments, plus the loan for the price token
+fn escrowed_in(e: &Env, token: &Address) -> i128 {
+    let mut escrowed: i128 = get_buyers(e).iter().map(|buyer| get_token_contribution(e, &buyer, token)).sum();
+    if *token == e.storage().instance().get::<DataKey, Address>(&DataKey::Token).unwrap() {
+        if let Some(loan) = e.storage().instance().get::<DataKey, Loan>(&DataKey::Loan) {
+            escrowed += loan.principal;
+        }
+    }
+    escrowed
+}
+
+fn get_closing_costs(e: &Env) -> Vec<ClosingCost> {
+    e.storage().instance().get::<DataKey, Vec<ClosingCost>>(&DataKey::ClosingCosts).unwrap_or(Vec::new(e))
+}
+
+fn closing_costs_total(closing_costs: &Vec<ClosingCost>) -> i128 {
+    closing_costs.iter().map(|cost| cost.amount).sum()
+}
+
+fn get_inspection(e: &Env) -> InspectionStatus {
+    e.storage().instance().get::<DataKey, InspectionStatus>(&DataKey::Inspection).unwrap()
 }
 
+fn inspection_cleared(e: &Env) -> bool {
+    let inspection = get_inspection(e);
+    inspection == InspectionStatus::Approved || inspection == InspectionStatus::Waived
+}
+
+fn resolve_inspection(e: &Env, resolver: &Address, status: InspectionStatus) -> Result<bool, Error> {
+    if get_inspection(e) != InspectionStatus::Pending {
+        return Err(Error::InspectionAlreadyResolved);
+    }
+
+    resolver.require_auth();
+    e.storage().instance().set(&DataKey::Inspection, &status);
+    let asset = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset).unwrap();
+    e.events().publish((TOPIC_INSPECTION,), (asset, resolver.clone(), status));
+    Ok(true)
+}
+
+// No insurance was required, or it has been confirmed or waived
+fn insurance_cleared(e: &Env) -> bool {
+    match e.storage().instance().get::<DataKey, Insurance>(&DataKey::Insurance) {
+        Some(insurance) => insurance.policy_hash.is_some() || insurance.waived,
+        None => true
+    }
+}
+
+fn resolve_insurance(e: &Env, resolver: &Address, policy_hash: Option<BytesN<32>>) -> Result<bool, Error> {
+    let mut insurance = e.storage().instance().get::<DataKey, Insurance>(&DataKey::Insurance).ok_or(Error::NoInsurance)?;
+    if insurance.policy_hash.is_some() || insurance.waived {
+        return Err(Error::InsuranceAlreadyResolved);
+    }
+
+    resolver.require_auth();
+    insurance.waived = policy_hash.is_none();
+    insurance.policy_hash = policy_hash.clone();
+    e.storage().instance().set(&DataKey::Insurance, &insurance);
+    let asset = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset).unwrap();
+    e.events().publish((TOPIC_INSURED,), (asset, resolver.clone(), policy_hash));
+    Ok(true)
+}
+
+// Sum of the first `count` installments of the plan
+fn installments_total(installments: &Vec<Installment>, count: u32) -> i128 {
+    installments.iter().take(count as usize).map(|installment| installment.amount).sum()
+}
+
+fn check_appraisal(e: &Env, installments: &Vec<Installment>) -> Result<(), Error> {
+    if let Some(check) = e.storage().instance().get::<DataKey, AppraisalCheck>(&DataKey::AppraisalCheck) {
+        let asset = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset).unwrap();
+        let appraisal = OracleClient::new(e, &check.oracle).appraisal(&asset, &get_asset_id(e));
+        let price = installments_total(installments, installments.len());
+        if appraisal <= 0 || (price - appraisal).abs() * 10_000 > appraisal * check.max_deviation_bps as i128 {
+            return Err(Error::PriceOutOfAppraisalRange);
+        }
+    }
+
+    Ok(())
+}
+
+// The next installment to pay is overdue, so the buyer can no longer complete the plan
+fn next_installment_overdue(e: &Env) -> bool {
+    match get_installments(e).get(get_paid_installments(e)) {
+        Some(installment) => e.ledger().timestamp() > installment.due_ts,
+        None => false
+    }
+}
 
 #[contract]
 pub struct HousePurchaseContract;
@@ -44,93 +826,1360 @@ pub struct HousePurchaseContract;
 #[contractimpl]
 impl HousePurchaseContract {
 
-    pub fn initialize(e: Env, asset: Address, buyer: Address, token: Address, first_payment: i128, amount: i128) -> Result<bool, Error> {
+    /// The house sold is the asset `asset_id` of the `asset` registry.
+    ///
+    /// `buyers` purchase the house together, each one owning a share proportional to what they
+    /// contribute. `installments` is the payment plan: the price is the sum of their amounts and
+    /// they must be paid in order, each one by its `due_ts`. Only the first installment can be paid
+    /// before `inspector` approves the inspection of the house (or a buyer waives it).
+    ///
+    /// `closing_deadline` is the timestamp by which the purchase must be closed. Once it has passed,
+    /// or an installment is overdue, either party can call `cancel_purchase`, and the paid
+    /// installments are refunded to the buyer or kept by the seller depending on `cancel_policy`.
+    ///
+    /// Installment amounts are quoted in `token`. `alt_tokens` lists other tokens the buyers can pay
+    /// with (e.g. the native XLM contract), each one with the whole price quoted in it, and
+    /// `contribute_in` converts the installment amounts at those prices.
+    ///
+    /// With an `appraisal_check`, the price (and any price agreed later on) must be within the
+    /// allowed deviation from the appraisal of the asset published by the oracle.
+    ///
+    /// `closing_costs` are paid to their recipients at closing, out of the price paid in `token`,
+    /// and the seller receives the rest.
+    pub fn initialize(e: Env, asset: Address, asset_id: String, buyers: Vec<Address>, inspector: Address, token: Address, alt_tokens: Map<Address, i128>, installments: Vec<Installment>, closing_deadline: u64, cancel_policy: CancelPolicy, appraisal_check: Option<AppraisalCheck>, closing_costs: Vec<ClosingCost>) -> Result<bool, Error> {
 
         if let Some(_asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
             return Err(Error::ContractAlreadyInitialized);
         } else {
+            if buyers.is_empty() {
+                return Err(Error::NoBuyers);
+            }
+
+            check_installments(&installments, closing_deadline)?;
+            check_cancel_policy(cancel_policy)?;
+            for (_, price) in alt_tokens.iter() {
+                if price <= 0 {
+                    return Err(Error::InvalidPaymentSchedule);
+                }
+            }
+
+            if closing_costs.iter().any(|cost| cost.amount <= 0) || closing_costs_total(&closing_costs) >= installments_total(&installments, installments.len()) {
+                return Err(Error::InvalidClosingCosts);
+            }
+
             e.storage().instance().set(&DataKey::Asset, &asset);
-            e.storage().instance().set(&DataKey::Buyer, &buyer);
+            e.storage().instance().set(&DataKey::AssetId, &asset_id);
+            e.storage().instance().set(&DataKey::Buyers, &buyers);
+            e.storage().instance().set(&DataKey::Inspector, &inspector);
+            e.storage().instance().set(&DataKey::Inspection, &InspectionStatus::Pending);
             e.storage().instance().set(&DataKey::Token, &token);
-            e.storage().instance().set(&DataKey::FirstPaymentAmount, &first_payment);
-            e.storage().instance().set(&DataKey::Amount, &amount);
-            e.storage().instance().set(&DataKey::State, &State::Pending);
+            e.storage().instance().set(&DataKey::PaymentTokens, &alt_tokens);
+            e.storage().instance().set(&DataKey::QuotedPrice, &installments_total(&installments, installments.len()));
+            e.storage().instance().set(&DataKey::Installments, &installments);
+            e.storage().instance().set(&DataKey::ClosingDeadline, &closing_deadline);
+            e.storage().instance().set(&DataKey::CancelPolicy, &cancel_policy);
+            e.storage().instance().set(&DataKey::ClosingCosts, &closing_costs);
+            e.storage().instance().set(&DataKey::Status, &PurchaseStatus::Created);
+            if let Some(check) = appraisal_check {
+                e.storage().instance().set(&DataKey::AppraisalCheck, &check);
+                check_appraisal(&e, &installments)?;
+            }
 
+            let price = installments_total(&installments, installments.len());
+            e.events().publish((TOPIC_INITIALIZED,), (asset, asset_id, buyers, token, price));
             Ok(true)
         }
     }
 
-    pub fn transfer_first_payment(e: Env) -> Result<bool, Error> {
+    /// Rent-to-own variant: `tenant` rents the house from `start_ts` on, paying `rent` to the asset
+    /// owner every 30 days with `pay_rent`, and `credit_bps` of each rent paid on time is credited
+    /// towards `price`. Before `closing_deadline` the tenant can buy the house by paying the rest of
+    /// the price (the balloon payment) with `contribute` and closing the purchase with `change_owner`.
+    ///
+    /// As the tenant already lives in the house, no inspection is required. The asset owner accepts
+    /// the deal with `accept_offer`, as for any other purchase.
+    pub fn initialize_rent_to_own(e: Env, asset: Address, asset_id: String, tenant: Address, token: Address, price: i128, rent: i128, credit_bps: u32, start_ts: u64, closing_deadline: u64) -> Result<bool, Error> {
+        if rent <= 0 || credit_bps > 10_000 || start_ts >= closing_deadline {
+            return Err(Error::InvalidRentTerms);
+        }
+
+        let installments = Vec::from_array(&e, [Installment { label: String::from_str(&e, "balloon payment"), amount: price, due_ts: closing_deadline }]);
+        Self::initialize(e.clone(), asset, asset_id, Vec::from_array(&e, [tenant.clone()]), tenant, token, Map::new(&e), installments, closing_deadline, CancelPolicy::Refund, None, Vec::new(&e))?;
 
-        if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+        e.storage().instance().set(&DataKey::Inspection, &InspectionStatus::Waived);
+        e.storage().instance().set(&DataKey::RentToOwn, &RentToOwn { rent, credit_bps, start_ts, paid_rents: 0, credit: 0 });
+        Ok(true)
+    }
+
+    /// The tenant pays the next rent to the asset owner. Returns the credit accumulated so far.
+    ///
+    /// A rent paid after its due date does not add any credit. The credit lowers the balloon
+    /// payment, and once it covers the whole price the purchase is fully paid.
+    pub fn pay_rent(e: Env, tenant: Address) -> Result<i128, Error> {
+        when_not_paused(&e)?;
+
+        if let Some(mut terms) = e.storage().instance().get::<DataKey, RentToOwn>(&DataKey::RentToOwn) {
+            let status = get_status(&e);
+            if status != PurchaseStatus::Accepted && status != PurchaseStatus::PaymentsInProgress {
+                return Err(Error::OfferNotAccepted);
+            }
+
+            if !get_buyers(&e).contains(&tenant) {
+                return Err(Error::AddressNotAllowed);
+            }
+
+            if deadline_passed(&e) {
+                return Err(Error::ClosingDeadlinePassed);
+            }
+
+            tenant.require_auth();
+            let asset = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset).unwrap();
+            let landlord = asset::HouseAssetClient::new(&e, &asset).owner_of(&get_asset_id(&e));
             let token = e.storage().instance().get::<DataKey, Address>(&DataKey::Token).unwrap();
-            let buyer = e.storage().instance().get::<DataKey, Address>(&DataKey::Buyer).unwrap();
-            let first_payment_amount = e.storage().instance().get::<DataKey, i128>(&DataKey::FirstPaymentAmount).unwrap();
-            let asset_contract = asset::Client::new(&e, &asset);
 
-            buyer.require_auth();
-            let tk = token::Client::new(&e, &token);
-            tk.transfer(&buyer, &asset_contract.owner(), &first_payment_amount);
-            e.storage().instance().set(&DataKey::State, &State::FirstPaymentTransferred);
-            Ok(true)  
+            let due_ts = terms.start_ts + terms.paid_rents as u64 * RENT_PERIOD;
+            terms.paid_rents += 1;
+            if e.ledger().timestamp() <= due_ts {
+                // The credit lowers the balloon payment, never below what has already been paid towards it
+                let mut installments = get_installments(&e);
+                let mut balloon = installments.get(0).unwrap();
+                let contributed = e.storage().instance().get::<DataKey, i128>(&DataKey::InstallmentContributed).unwrap_or(0);
+                let credit = (terms.rent * terms.credit_bps as i128 / 10_000).min(balloon.amount - contributed);
+                terms.credit += credit;
+                balloon.amount -= credit;
+                installments.set(0, balloon.clone());
+                e.storage().instance().set(&DataKey::Installments, &installments);
 
+                if balloon.amount == contributed {
+                    e.storage().instance().set(&DataKey::PaidInstallments, &1_u32);
+                    e.storage().instance().set(&DataKey::InstallmentContributed, &0_i128);
+                    set_status(&e, PurchaseStatus::FullyPaid)?;
+                }
+            }
+
+            e.storage().instance().set(&DataKey::RentToOwn, &terms);
+            token::Client::new(&e, &token).transfer(&tenant, &landlord, &terms.rent);
+            e.events().publish((TOPIC_RENT_PAID,), (asset, tenant, terms.rent, terms.credit));
+            Ok(terms.credit)
         } else {
-            return Err(Error::ContractNotInitialized);
+            return Err(Error::NotRentToOwn);
         }
-        
     }
 
-    pub fn transfer_rest_of_payment(e: Env) -> Result<bool, Error> {
+    pub fn rent_to_own(e: Env) -> Option<RentToOwn> {
+        e.storage().instance().get::<DataKey, RentToOwn>(&DataKey::RentToOwn)
+    }
 
+    /// The asset owner accepts the buyer and payment plan set at initialization. No payment can be made before.
+    pub fn accept_offer(e: Env) -> Result<bool, Error> {
         if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
-            let state: State = e.storage().instance().get(&DataKey::State).unwrap();
-            if state != State::FirstPaymentTransferred {
-                return Err(Error::FirstPaymentNotTransferred);
-            } 
+            let status = get_status(&e);
+            if status != PurchaseStatus::Created {
+                return Err(Error::OfferAlreadyAccepted);
+            }
 
-            let token = e.storage().instance().get::<DataKey, Address>(&DataKey::Token).unwrap();
-            let buyer = e.storage().instance().get::<DataKey, Address>(&DataKey::Buyer).unwrap();
-            let first_payment_amount = e.storage().instance().get::<DataKey, i128>(&DataKey::FirstPaymentAmount).unwrap();
-            let amount = e.storage().instance().get::<DataKey, i128>(&DataKey::Amount).unwrap();
+            let asset_contract = asset::HouseAssetClient::new(&e, &asset);
+            let seller = asset_contract.owner_of(&get_asset_id(&e));
+            seller.require_auth();
+            set_status(&e, PurchaseStatus::Accepted)?;
+            e.events().publish((TOPIC_OFFER_ACCEPTED,), (asset, seller));
+            Ok(true)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
 
-            let asset_contract = asset::Client::new(&e, &asset);
-            let rest_of_payment_amount = amount - first_payment_amount;
+    /// Opens the sale of the asset `asset_id` of the `asset` registry to offers: instead of a buyer and
+    /// payment plan set at initialization, prospective buyers submit their own plans with `submit_offer`
+    /// and the asset owner picks one of them with `accept_bid`.
+    ///
+    /// `inspector`, `token`, `closing_deadline` and `cancel_policy` work as in `initialize`.
+    pub fn open_bidding(e: Env, asset: Address, asset_id: String, inspector: Address, token: Address, closing_deadline: u64, cancel_policy: CancelPolicy) -> Result<bool, Error> {
+        if let Some(_asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+            return Err(Error::ContractAlreadyInitialized);
+        } else {
+            check_cancel_policy(cancel_policy)?;
+            e.storage().instance().set(&DataKey::Asset, &asset);
+            e.storage().instance().set(&DataKey::AssetId, &asset_id);
+            e.storage().instance().set(&DataKey::Buyers, &Vec::<Address>::new(&e));
+            e.storage().instance().set(&DataKey::Inspector, &inspector);
+            e.storage().instance().set(&DataKey::Inspection, &InspectionStatus::Pending);
+            e.storage().instance().set(&DataKey::Token, &token);
+            e.storage().instance().set(&DataKey::PaymentTokens, &Map::<Address, i128>::new(&e));
+            e.storage().instance().set(&DataKey::Installments, &Vec::<Installment>::new(&e));
+            e.storage().instance().set(&DataKey::ClosingDeadline, &closing_deadline);
+            e.storage().instance().set(&DataKey::CancelPolicy, &cancel_policy);
+            e.storage().instance().set(&DataKey::ClosingCosts, &Vec::<ClosingCost>::new(&e));
+            e.storage().instance().set(&DataKey::Status, &PurchaseStatus::Bidding);
 
-            buyer.require_auth();
-            let tk = token::Client::new(&e, &token);
-            tk.transfer(&buyer, &asset_contract.owner(), &rest_of_payment_amount);
-            e.storage().instance().set(&DataKey::State, &State::RestOfPaymentTransferred);
+            e.events().publish((TOPIC_INITIALIZED,), (asset, asset_id, Vec::<Address>::new(&e), token, 0_i128));
             Ok(true)
+        }
+    }
+
+    /// `bidder` offers to buy the house paying `installments`, and pays the first one as deposit,
+    /// which the contract escrows. An offer can be withdrawn with `withdraw_offer` until it is accepted.
+    pub fn submit_offer(e: Env, bidder: Address, installments: Vec<Installment>) -> Result<Offer, Error> {
+        when_not_paused(&e)?;
+
+        if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+            if get_status(&e) != PurchaseStatus::Bidding || deadline_passed(&e) {
+                return Err(Error::BiddingNotOpen);
+            }
+
+            let key = DataKey::Offer(bidder.clone());
+            if e.storage().instance().has(&key) {
+                return Err(Error::OfferAlreadySubmitted);
+            }
+
+            let closing_deadline = e.storage().instance().get::<DataKey, u64>(&DataKey::ClosingDeadline).unwrap();
+            check_installments(&installments, closing_deadline)?;
+
+            bidder.require_auth();
+            let deposit = installments.get(0).unwrap().amount;
+            let token = e.storage().instance().get::<DataKey, Address>(&DataKey::Token).unwrap();
+
+            let offer = Offer { bidder: bidder.clone(), installments };
+            let mut bidders = get_bidders(&e);
+            bidders.push_back(bidder.clone());
+            e.storage().instance().set(&DataKey::Bidders, &bidders);
+            e.storage().instance().set(&key, &offer);
+            token::Client::new(&e, &token).transfer(&bidder, &e.current_contract_address(), &deposit);
+            e.events().publish((TOPIC_OFFER_SUBMITTED,), (asset, bidder, deposit, installments_total(&offer.installments, offer.installments.len())));
+            Ok(offer)
         } else {
             return Err(Error::ContractNotInitialized);
         }
-        
     }
 
-    pub fn change_owner(e: Env) -> Result<bool, Error> {
+    /// The asset owner accepts the offer of `bidder`, who becomes the buyer. The offered installments
+    /// become the payment plan, with the deposit as the first installment paid. The other offers stay
+    /// escrowed until their bidders withdraw them.
+    pub fn accept_bid(e: Env, bidder: Address) -> Result<bool, Error> {
         if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
-            let state: State = e.storage().instance().get(&DataKey::State).unwrap();
+            if get_status(&e) != PurchaseStatus::Bidding {
+                return Err(Error::BiddingNotOpen);
+            }
 
-            if state != State::RestOfPaymentTransferred {
-                return Err(Error::RestOfPaymentNotTransferred);
-            } 
+            let key = DataKey::Offer(bidder.clone());
+            let offer = e.storage().instance().get::<DataKey, Offer>(&key).ok_or(Error::NoOffer)?;
+            let asset_contract = asset::HouseAssetClient::new(&e, &asset);
+            let seller = asset_contract.owner_of(&get_asset_id(&e));
+            seller.require_auth();
 
-            let asset_contract = asset::Client::new(&e, &asset);
-            
-            let buyer = e.storage().instance().get::<DataKey, Address>(&DataKey::Buyer).unwrap();
-            asset_contract.owner().require_auth();
-            asset_contract.transfer(&buyer); // change the asset owner
-            e.storage().instance().set(&DataKey::State, &State::Finished);
-            Ok(true)
+            let token = e.storage().instance().get::<DataKey, Address>(&DataKey::Token).unwrap();
+            let deposit = offer.installments.get(0).unwrap().amount;
+            e.storage().instance().set(&DataKey::Buyers, &Vec::from_array(&e, [bidder.clone()]));
+            e.storage().instance().set(&DataKey::QuotedPrice, &installments_total(&offer.installments, offer.installments.len()));
+            e.storage().instance().set(&DataKey::Installments, &offer.installments);
+            e.storage().instance().set(&DataKey::Contribution(bidder.clone()), &deposit);
+            e.storage().instance().set(&DataKey::TokenContribution(bidder.clone(), token.clone()), &deposit);
+            e.storage().instance().set(&DataKey::MilestoneToken(0), &token);
+            e.storage().instance().set(&DataKey::PaidInstallments, &1_u32);
+
+            let mut bidders = get_bidders(&e);
+            bidders.remove(bidders.first_index_of(&bidder).unwrap());
+            e.storage().instance().set(&DataKey::Bidders, &bidders);
+            e.storage().instance().remove(&key);
+
+            set_status(&e, PurchaseStatus::Accepted)?;
+            let new_status = if offer.installments.len() == 1 { PurchaseStatus::FullyPaid } else { PurchaseStatus::PaymentsInProgress };
+            set_status(&e, new_status)?;
+            e.events().publish((TOPIC_OFFER_ACCEPTED,), (asset, seller));
+            Ok(true)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    /// `bidder` withdraws an offer that has not been accepted, e.g. after the seller accepted another
+    /// one, and gets the deposit back. Returns the refunded amount.
+    pub fn withdraw_offer(e: Env, bidder: Address) -> Result<i128, Error> {
+        if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+            let key = DataKey::Offer(bidder.clone());
+            let offer = e.storage().instance().get::<DataKey, Offer>(&key).ok_or(Error::NoOffer)?;
+
+            bidder.require_auth();
+            let deposit = offer.installments.get(0).unwrap().amount;
+            let token = e.storage().instance().get::<DataKey, Address>(&DataKey::Token).unwrap();
+
+            let mut bidders = get_bidders(&e);
+            bidders.remove(bidders.first_index_of(&bidder).unwrap());
+            e.storage().instance().set(&DataKey::Bidders, &bidders);
+            e.storage().instance().remove(&key);
+            token::Client::new(&e, &token).transfer(&e.current_contract_address(), &bidder, &deposit);
+            e.events().publish((TOPIC_OFFER_WITHDRAWN,), (asset, bidder, deposit));
+            Ok(deposit)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    /// Offers not accepted nor withdrawn yet, in the order they were submitted.
+    pub fn offers(e: Env) -> Vec<Offer> {
+        let mut offers = Vec::new(&e);
+        for bidder in get_bidders(&e).iter() {
+            offers.push_back(e.storage().instance().get::<DataKey, Offer>(&DataKey::Offer(bidder)).unwrap());
+        }
+        offers
+    }
+
+    /// The inspector approves the house, which unlocks the rest of the payment plan.
+    pub fn approve_inspection(e: Env) -> Result<bool, Error> {
+        if let Some(inspector) = e.storage().instance().get::<DataKey, Address>(&DataKey::Inspector) {
+            resolve_inspection(&e, &inspector, InspectionStatus::Approved)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    /// The inspector rejects the house. The purchase can then be cancelled right away and the paid
+    /// installments are refunded to the buyer.
+    pub fn fail_inspection(e: Env) -> Result<bool, Error> {
+        if let Some(inspector) = e.storage().instance().get::<DataKey, Address>(&DataKey::Inspector) {
+            resolve_inspection(&e, &inspector, InspectionStatus::Failed)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    /// One of the buyers gives up the inspection contingency on behalf of all of them.
+    pub fn waive_inspection(e: Env, buyer: Address) -> Result<bool, Error> {
+        if let Some(buyers) = e.storage().instance().get::<DataKey, Vec<Address>>(&DataKey::Buyers) {
+            if !buyers.contains(&buyer) {
+                return Err(Error::AddressNotAllowed);
+            }
+
+            resolve_inspection(&e, &buyer, InspectionStatus::Waived)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    pub fn inspection(e: Env) -> Result<InspectionStatus, Error> {
+        if let Some(inspection) = e.storage().instance().get::<DataKey, InspectionStatus>(&DataKey::Inspection) {
+            Ok(inspection)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    /// `buyer` pays `amount` towards the current installment, which is completed once the buyers'
+    /// contributions add up to its amount. Returns the number of installments paid so far.
+    ///
+    /// Payments are escrowed by the contract and only released to the seller by `change_owner`,
+    /// in the same call that hands over the asset.
+    pub fn contribute(e: Env, buyer: Address, amount: i128) -> Result<u32, Error> {
+        let token = e.storage().instance().get::<DataKey, Address>(&DataKey::Token).ok_or(Error::ContractNotInitialized)?;
+        Self::contribute_in(e, buyer, token, amount)
+    }
+
+    /// Like `contribute`, but the payment is pulled through the token allowance `buyer` granted to
+    /// `spender` (e.g. a smart wallet or a payment router), which signs the call instead of the buyer.
+    /// A cosigner of the buyer must still sign contributions above its threshold.
+    pub fn contribute_from(e: Env, spender: Address, buyer: Address, amount: i128) -> Result<u32, Error> {
+        let token = e.storage().instance().get::<DataKey, Address>(&DataKey::Token).ok_or(Error::ContractNotInitialized)?;
+        pay_contribution(e, Some(spender), buyer, token, amount)
+    }
+
+    /// Like `contribute`, but paying with one of the accepted tokens. `amount` is still expressed in
+    /// the price token, and `buyer` is charged its equivalent at the price quoted in `token`.
+    ///
+    /// Each installment is paid in a single token, the one used by its first contribution.
+    pub fn contribute_in(e: Env, buyer: Address, token: Address, amount: i128) -> Result<u32, Error> {
+        pay_contribution(e, None, buyer, token, amount)
+    }
+
+    /// Registers `cosigner` as guarantor of `buyer`: any contribution of the buyer above `threshold`
+    /// (in the price token) also needs the cosigner's signature. Both must sign the registration,
+    /// and replacing or removing the cosigner needs the current one to sign as well.
+    pub fn set_cosigner(e: Env, buyer: Address, cosigner: Option<CoSigner>) -> Result<bool, Error> {
+        if let Some(buyers) = e.storage().instance().get::<DataKey, Vec<Address>>(&DataKey::Buyers) {
+            if !buyers.contains(&buyer) {
+                return Err(Error::AddressNotAllowed);
+            }
+
+            buyer.require_auth();
+            let key = DataKey::CoSigner(buyer);
+            if let Some(current) = e.storage().instance().get::<DataKey, CoSigner>(&key) {
+                current.cosigner.require_auth();
+            }
+
+            match cosigner {
+                Some(cosigner) => {
+                    cosigner.cosigner.require_auth();
+                    e.storage().instance().set(&key, &cosigner);
+                },
+                None => e.storage().instance().remove(&key)
+            }
+            Ok(true)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    pub fn cosigner(e: Env, buyer: Address) -> Option<CoSigner> {
+        e.storage().instance().get::<DataKey, CoSigner>(&DataKey::CoSigner(buyer))
+    }
+
+    /// `buyer` assigns its position in the purchase to `new_buyer` (e.g. a company set up for the
+    /// purchase), who takes over what the buyer paid so far, the remaining payments and the right to
+    /// close. Both must sign, and so must the seller once the offer has been accepted. A cosigner of
+    /// `buyer` does not guarantee `new_buyer`, so it is dropped.
+    pub fn assign_buyer(e: Env, buyer: Address, new_buyer: Address) -> Result<bool, Error> {
+        if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+            let mut buyers = get_buyers(&e);
+            let index = buyers.first_index_of(&buyer).ok_or(Error::AddressNotAllowed)?;
+            let status = get_status(&e);
+            if status == PurchaseStatus::Closed || status == PurchaseStatus::Cancelled || buyers.contains(&new_buyer) {
+                return Err(Error::BuyerCannotBeAssigned);
+            }
+
+            buyer.require_auth();
+            new_buyer.require_auth();
+            if status != PurchaseStatus::Created {
+                asset::HouseAssetClient::new(&e, &asset).owner_of(&get_asset_id(&e)).require_auth();
+            }
+
+            let contribution = get_contribution(&e, &buyer);
+            e.storage().instance().remove(&DataKey::Contribution(buyer.clone()));
+            if contribution > 0 {
+                e.storage().instance().set(&DataKey::Contribution(new_buyer.clone()), &contribution);
+            }
+
+            for token in get_payment_tokens(&e).iter() {
+                let paid = get_token_contribution(&e, &buyer, &token);
+                e.storage().instance().remove(&DataKey::TokenContribution(buyer.clone(), token.clone()));
+                if paid > 0 {
+                    e.storage().instance().set(&DataKey::TokenContribution(new_buyer.clone(), token), &paid);
+                }
+            }
+
+            e.storage().instance().remove(&DataKey::CoSigner(buyer.clone()));
+            buyers.set(index, new_buyer.clone());
+            e.storage().instance().set(&DataKey::Buyers, &buyers);
+            e.events().publish((TOPIC_BUYER_ASSIGNED,), (asset, buyer, new_buyer, contribution));
+            Ok(true)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    /// `buyer` pays what is left of the milestone (installment) `index`, which must be the next one
+    /// to pay. Returns the number of installments paid so far.
+    pub fn pay_milestone(e: Env, buyer: Address, index: u32) -> Result<u32, Error> {
+        let amount = milestone_remainder(&e, index)?;
+        Self::contribute(e, buyer, amount)
+    }
+
+    /// Like `pay_milestone`, but pulling the payment through the token allowance `buyer` granted to
+    /// `spender`, who signs instead of the buyer.
+    pub fn pay_milestone_from(e: Env, spender: Address, buyer: Address, index: u32) -> Result<u32, Error> {
+        let amount = milestone_remainder(&e, index)?;
+        Self::contribute_from(e, spender, buyer, amount)
+    }
+
+    /// Mortgage mode: `lender` pays the rest of the price on behalf of the buyers, completing the
+    /// payment plan. The buyers must have paid the first installment as down payment, the inspection
+    /// must be cleared, and the lender and every buyer must sign.
+    ///
+    /// On closing the asset is pledged to the lender, and the buyers pay the loan back with `repay_lender`.
+    pub fn finance(e: Env, lender: Address) -> Result<Loan, Error> {
+        when_not_paused(&e)?;
+
+        if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+            let status = get_status(&e);
+            let paid_installments = get_paid_installments(&e);
+            if status != PurchaseStatus::PaymentsInProgress || paid_installments == 0 || e.storage().instance().has(&DataKey::FiatPricing) {
+                return Err(Error::FinancingNotAllowed);
+            }
+
+            if deadline_passed(&e) {
+                return Err(Error::ClosingDeadlinePassed);
+            }
+
+            if next_installment_overdue(&e) {
+                return Err(Error::InstallmentOverdue);
+            }
+
+            if !inspection_cleared(&e) {
+                return Err(Error::InspectionNotCleared);
+            }
+
+            let installments = get_installments(&e);
+            let contributed = e.storage().instance().get::<DataKey, i128>(&DataKey::InstallmentContributed).unwrap_or(0);
+            let principal = installments_total(&installments, installments.len()) - installments_total(&installments, paid_installments) - contributed;
+
+            lender.require_auth();
+            for buyer in get_buyers(&e).iter() {
+                buyer.require_auth();
+            }
+
+            let token = e.storage().instance().get::<DataKey, Address>(&DataKey::Token).unwrap();
+            for index in paid_installments..installments.len() {
+                if !e.storage().instance().has(&DataKey::MilestoneToken(index)) {
+                    e.storage().instance().set(&DataKey::MilestoneToken(index), &token);
+                }
+            }
+
+            let loan = Loan { lender: lender.clone(), principal, repaid: 0 };
+            e.storage().instance().set(&DataKey::Loan, &loan);
+            e.storage().instance().set(&DataKey::PaidInstallments, &installments.len());
+            e.storage().instance().set(&DataKey::InstallmentContributed, &0_i128);
+            set_status(&e, PurchaseStatus::FullyPaid)?;
+
+            token::Client::new(&e, &token).transfer(&lender, &e.current_contract_address(), &principal);
+            e.events().publish((TOPIC_FINANCED,), (asset, lender, principal));
+            Ok(loan)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    /// A buyer pays `amount` of the loan back to the lender once the purchase is closed. Returns
+    /// what is left to repay; the lender releases the lien on the asset when it reaches 0.
+    pub fn repay_lender(e: Env, payer: Address, amount: i128) -> Result<i128, Error> {
+        when_not_paused(&e)?;
+
+        if let Some(mut loan) = e.storage().instance().get::<DataKey, Loan>(&DataKey::Loan) {
+            let status = get_status(&e);
+            if status != PurchaseStatus::Closed {
+                return Err(Error::PurchaseNotFinished);
+            }
+
+            if !get_buyers(&e).contains(&payer) {
+                return Err(Error::AddressNotAllowed);
+            }
+
+            let outstanding = loan.principal - loan.repaid;
+            if amount <= 0 || amount > outstanding {
+                return Err(Error::InvalidRepayment);
+            }
+
+            payer.require_auth();
+            loan.repaid += amount;
+            e.storage().instance().set(&DataKey::Loan, &loan);
+
+            let token = e.storage().instance().get::<DataKey, Address>(&DataKey::Token).unwrap();
+            token::Client::new(&e, &token).transfer(&payer, &loan.lender, &amount);
+            let asset = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset).unwrap();
+            e.events().publish((TOPIC_LOAN_REPAID,), (asset, payer, amount, outstanding - amount));
+            Ok(outstanding - amount)
+        } else {
+            return Err(Error::NoLoan);
+        }
+    }
+
+    pub fn loan(e: Env) -> Option<Loan> {
+        e.storage().instance().get::<DataKey, Loan>(&DataKey::Loan)
+    }
+
+    /// Closes the purchase: hands the asset over to the buyers and releases the escrowed price to the seller.
+    ///
+    /// Only possible once the whole payment plan has been paid. Every contributing buyer and the
+    /// seller must sign this invocation, so no party can finalize the purchase alone. With several
+    /// contributors the asset records each one's share of the price as its ownership share. If the
+    /// purchase was financed, a lien in favour of the lender is placed on the asset.
+    ///
+    /// The seller must have approved this contract as operator of the asset in the `HouseAsset` registry,
+    /// and the purchase cannot be closed while the registry admin keeps the asset frozen.
+    pub fn change_owner(e: Env) -> Result<bool, Error> {
+        when_not_paused(&e)?;
+
+        if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+            let status = get_status(&e);
+
+            if status != PurchaseStatus::FullyPaid {
+                return Err(Error::PaymentScheduleNotCompleted);
+            }
+
+            if dispute_open(&e) {
+                return Err(Error::DisputeOpen);
+            }
+
+            if !inspection_cleared(&e) {
+                return Err(Error::InspectionNotCleared);
+            }
+
+            if !insurance_cleared(&e) {
+                return Err(Error::InsuranceNotConfirmed);
+            }
+
+            if let Some(notarization) = e.storage().instance().get::<DataKey, Notarization>(&DataKey::Notarization) {
+                if notarization.document_hash.is_none() {
+                    return Err(Error::NotNotarized);
+                }
+            }
+
+            if let Some(schedule) = e.storage().instance().get::<DataKey, ClosingSchedule>(&DataKey::ClosingSchedule) {
+                if e.ledger().timestamp() < schedule.closing_ts {
+                    return Err(Error::ClosingDateNotReached);
+                }
+            }
+
+            let asset_contract = asset::HouseAssetClient::new(&e, &asset);
+            if asset_contract.is_frozen(&get_asset_id(&e)) {
+                return Err(Error::AssetFrozen);
+            }
+
+            let price_token = e.storage().instance().get::<DataKey, Address>(&DataKey::Token).unwrap();
+            let closing_costs = get_closing_costs(&e);
+            if escrowed_in(&e, &price_token) < closing_costs_total(&closing_costs) {
+                return Err(Error::ClosingCostsNotCovered);
+            }
+
+            let installments = get_installments(&e);
+            let amount = installments_total(&installments, installments.len());
+            let seller = asset_contract.owner_of(&get_asset_id(&e));
+
+            // Ownership shares follow what each buyer paid, leaving aside any part financed by a lender
+            let (contributors, contributed) = get_contributors(&e);
+            for buyer in contributors.iter() {
+                buyer.require_auth();
+            }
+            seller.require_auth();
+
+            // The purchase is closed and the retention booked before any asset or token leaves the contract
+            let mut retained: i128 = 0;
+            if let Some(mut retention) = e.storage().instance().get::<DataKey, Retention>(&DataKey::Retention) {
+                let final_installment = installments.get(installments.len() - 1).unwrap();
+                retained = (final_installment.amount * retention.retention_bps as i128 / 10_000)
+                    .min(escrowed_in(&e, &price_token) - closing_costs_total(&closing_costs));
+                retention.retained = retained;
+                retention.seller = Some(seller.clone());
+                retention.release_ts = e.ledger().timestamp() + retention.dispute_window;
+                e.storage().instance().set(&DataKey::Retention, &retention);
+            }
+            set_status(&e, PurchaseStatus::Closed)?;
+
+            hand_over_asset(&e, &asset_contract, &contributors, contributed);
+
+            // The escrowed price is released only together with the ownership change: closing costs
+            // first, then the rest to the seller except the retention
+            let tk = token::Client::new(&e, &price_token);
+            for cost in closing_costs.iter() {
+                tk.transfer(&e.current_contract_address(), &cost.recipient, &cost.amount);
+            }
+
+            for token in get_payment_tokens(&e).iter() {
+                let mut escrowed = escrowed_in(&e, &token);
+                if token == price_token {
+                    escrowed -= closing_costs_total(&closing_costs) + retained;
+                }
+                if escrowed > 0 {
+                    token::Client::new(&e, &token).transfer(&e.current_contract_address(), &seller, &escrowed);
+                }
+            }
+            e.events().publish((TOPIC_OWNER_CHANGED,), (asset, get_asset_id(&e), seller, contributors, amount));
+            Ok(true)
+
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    /// The buyers ask for `retention_bps` of the final installment to stay escrowed after closing,
+    /// until `dispute_window` seconds have passed. Only possible before the seller accepts the offer,
+    /// so the seller accepts the retention along with the rest of the terms.
+    pub fn set_retention(e: Env, retention_bps: u32, dispute_window: u64) -> Result<Retention, Error> {
+        if let Some(buyers) = e.storage().instance().get::<DataKey, Vec<Address>>(&DataKey::Buyers) {
+            if get_status(&e) != PurchaseStatus::Created || retention_bps > 10_000 || e.storage().instance().has(&DataKey::FiatPricing) {
+                return Err(Error::RetentionCannotBeSet);
+            }
+
+            for buyer in buyers.iter() {
+                buyer.require_auth();
+            }
+
+            let retention = Retention { retention_bps, dispute_window, retained: 0, seller: None, release_ts: 0 };
+            e.storage().instance().set(&DataKey::Retention, &retention);
+            Ok(retention)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    /// The seller collects the retention once the purchase is closed and the dispute window has passed,
+    /// unless a dispute raised within the window is still waiting for its ruling.
+    pub fn release_retention(e: Env) -> Result<i128, Error> {
+        if let Some(mut retention) = e.storage().instance().get::<DataKey, Retention>(&DataKey::Retention) {
+            if get_status(&e) != PurchaseStatus::Closed || retention.retained == 0 || e.ledger().timestamp() < retention.release_ts {
+                return Err(Error::RetentionNotReleasable);
+            }
+
+            if dispute_open(&e) {
+                return Err(Error::DisputeOpen);
+            }
+
+            let seller = retention.seller.clone().unwrap();
+            seller.require_auth();
+            let released = retention.retained;
+            retention.retained = 0;
+            e.storage().instance().set(&DataKey::Retention, &retention);
+
+            let token = e.storage().instance().get::<DataKey, Address>(&DataKey::Token).unwrap();
+            token::Client::new(&e, &token).transfer(&e.current_contract_address(), &seller, &released);
+            let asset = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset).unwrap();
+            e.events().publish((TOPIC_RETENTION_RELEASED,), (asset, seller, released));
+            Ok(released)
+        } else {
+            return Err(Error::RetentionNotReleasable);
+        }
+    }
+
+    pub fn retention(e: Env) -> Option<Retention> {
+        e.storage().instance().get::<DataKey, Retention>(&DataKey::Retention)
+    }
+
+    /// The buyers require the house to be insured before closing: `change_owner` fails until `insurer`
+    /// confirms the policy with `confirm_policy` or a buyer waives the requirement with `waive_insurance`.
+    /// Only possible before the seller accepts the offer, as `set_retention`.
+    pub fn require_insurance(e: Env, insurer: Address) -> Result<Insurance, Error> {
+        if let Some(buyers) = e.storage().instance().get::<DataKey, Vec<Address>>(&DataKey::Buyers) {
+            if get_status(&e) != PurchaseStatus::Created {
+                return Err(Error::InsuranceCannotBeRequired);
+            }
+
+            for buyer in buyers.iter() {
+                buyer.require_auth();
+            }
+
+            let insurance = Insurance { insurer, policy_hash: None, waived: false };
+            e.storage().instance().set(&DataKey::Insurance, &insurance);
+            Ok(insurance)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    /// The insurer confirms that the house is covered by the policy whose document hashes to `policy_hash`.
+    pub fn confirm_policy(e: Env, policy_hash: BytesN<32>) -> Result<bool, Error> {
+        let insurance = e.storage().instance().get::<DataKey, Insurance>(&DataKey::Insurance).ok_or(Error::NoInsurance)?;
+        resolve_insurance(&e, &insurance.insurer, Some(policy_hash))
+    }
+
+    /// One of the buyers closes the purchase without the insurance, on behalf of all of them.
+    pub fn waive_insurance(e: Env, buyer: Address) -> Result<bool, Error> {
+        if let Some(buyers) = e.storage().instance().get::<DataKey, Vec<Address>>(&DataKey::Buyers) {
+            if !buyers.contains(&buyer) {
+                return Err(Error::AddressNotAllowed);
+            }
+
+            resolve_insurance(&e, &buyer, None)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    pub fn insurance(e: Env) -> Option<Insurance> {
+        e.storage().instance().get::<DataKey, Insurance>(&DataKey::Insurance)
+    }
+
+    /// The buyers and the seller appoint `notary`, who must sign off the deal with `notarize` once the
+    /// price is fully paid and before `change_owner`. Only possible before the seller accepts the offer.
+    pub fn appoint_notary(e: Env, notary: Address) -> Result<bool, Error> {
+        if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+            if get_status(&e) != PurchaseStatus::Created {
+                return Err(Error::NotaryCannotBeAppointed);
+            }
+
+            for buyer in get_buyers(&e).iter() {
+                buyer.require_auth();
+            }
+            asset::HouseAssetClient::new(&e, &asset).owner_of(&get_asset_id(&e)).require_auth();
+
+            e.storage().instance().set(&DataKey::Notarization, &Notarization { notary, document_hash: None, ts: 0 });
+            Ok(true)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    /// The notary signs off the fully paid purchase, recording the hash of the notarized deed. The
+    /// record stays with the deal after closing.
+    pub fn notarize(e: Env, document_hash: BytesN<32>) -> Result<Notarization, Error> {
+        let mut notarization = e.storage().instance().get::<DataKey, Notarization>(&DataKey::Notarization).ok_or(Error::NoNotary)?;
+        if notarization.document_hash.is_some() {
+            return Err(Error::AlreadyNotarized);
+        }
+
+        if get_status(&e) != PurchaseStatus::FullyPaid {
+            return Err(Error::PaymentScheduleNotCompleted);
+        }
+
+        notarization.notary.require_auth();
+        notarization.document_hash = Some(document_hash.clone());
+        notarization.ts = e.ledger().timestamp();
+        e.storage().instance().set(&DataKey::Notarization, &notarization);
+        let asset = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset).unwrap();
+        e.events().publish((TOPIC_NOTARIZED,), (asset, notarization.notary.clone(), document_hash));
+        Ok(notarization)
+    }
+
+    pub fn notarization(e: Env) -> Option<Notarization> {
+        e.storage().instance().get::<DataKey, Notarization>(&DataKey::Notarization)
+    }
+
+    /// The buyers and the seller agree on the closing date `closing_ts`, which must fall between the last
+    /// installment due date and the closing deadline. `change_owner` fails before that date, and once
+    /// `grace_period` seconds have passed after it either party can call `cancel_purchase`: the seller keeps
+    /// `penalty_bps` of the paid installments and the rest is refunded, unless the whole price was paid,
+    /// which is always refunded. Only possible before the seller accepts the offer.
+    pub fn schedule_closing(e: Env, closing_ts: u64, grace_period: u64, penalty_bps: u32) -> Result<ClosingSchedule, Error> {
+        if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+            let installments = get_installments(&e);
+            let closing_deadline = e.storage().instance().get::<DataKey, u64>(&DataKey::ClosingDeadline).unwrap();
+            let last_due_ts = installments.last().map(|installment| installment.due_ts).unwrap_or(0);
+            if get_status(&e) != PurchaseStatus::Created || closing_ts < last_due_ts || closing_ts > closing_deadline || penalty_bps > 10_000 {
+                return Err(Error::InvalidClosingSchedule);
+            }
+
+            for buyer in get_buyers(&e).iter() {
+                buyer.require_auth();
+            }
+            asset::HouseAssetClient::new(&e, &asset).owner_of(&get_asset_id(&e)).require_auth();
+
+            let schedule = ClosingSchedule { closing_ts, grace_period, penalty_bps };
+            e.storage().instance().set(&DataKey::ClosingSchedule, &schedule);
+            Ok(schedule)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    pub fn closing_schedule(e: Env) -> Option<ClosingSchedule> {
+        e.storage().instance().get::<DataKey, ClosingSchedule>(&DataKey::ClosingSchedule)
+    }
+
+    /// The buyers and the seller agree to read the installment amounts in a fiat reference unit (e.g.
+    /// USD cents): each payment is charged in the price token at the last price of `feed`, which must
+    /// be at most `max_staleness` seconds old, so a long escrow is not exposed to the token price drift.
+    ///
+    /// The closing costs are still paid in the price token. Fiat pricing cannot be combined with
+    /// alternative payment tokens, a retention or financing by a lender. Only possible before the
+    /// seller accepts the offer.
+    pub fn set_fiat_pricing(e: Env, feed: Address, max_staleness: u64) -> Result<FiatPricing, Error> {
+        if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+            if get_status(&e) != PurchaseStatus::Created || !Self::payment_tokens(e.clone()).is_empty() || e.storage().instance().has(&DataKey::Retention) {
+                return Err(Error::FiatPricingCannotBeSet);
+            }
+
+            for buyer in get_buyers(&e).iter() {
+                buyer.require_auth();
+            }
+            asset::HouseAssetClient::new(&e, &asset).owner_of(&get_asset_id(&e)).require_auth();
+
+            let pricing = FiatPricing { feed, max_staleness };
+            e.storage().instance().set(&DataKey::FiatPricing, &pricing);
+            Ok(pricing)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    pub fn fiat_pricing(e: Env) -> Option<FiatPricing> {
+        e.storage().instance().get::<DataKey, FiatPricing>(&DataKey::FiatPricing)
+    }
+
+    /// A buyer abandons the purchase before any payment has been made. The terms of the deal are
+    /// removed from storage, leaving only the cancelled status, so the contract can be initialized again.
+    pub fn cancel_by_buyer(e: Env, buyer: Address) -> Result<bool, Error> {
+        if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+            if !get_buyers(&e).contains(&buyer) {
+                return Err(Error::AddressNotAllowed);
+            }
+
+            let contributed = e.storage().instance().get::<DataKey, i128>(&DataKey::InstallmentContributed).unwrap_or(0);
+            let paid_rents = e.storage().instance().get::<DataKey, RentToOwn>(&DataKey::RentToOwn).map(|terms| terms.paid_rents).unwrap_or(0);
+            if get_paid_installments(&e) > 0 || contributed > 0 || paid_rents > 0 {
+                return Err(Error::PaymentsAlreadyMade);
+            }
+
+            // An open dispute can only be settled by the arbiter
+            if dispute_open(&e) {
+                return Err(Error::DisputeOpen);
+            }
+
+            buyer.require_auth();
+            set_status(&e, PurchaseStatus::Cancelled)?;
+
+            // No offer can be pending here: an accepted bid comes with its deposit paid
+            for bidder in get_bidders(&e).iter() {
+                e.storage().instance().remove(&DataKey::Offer(bidder));
+            }
+            for co_buyer in get_buyers(&e).iter() {
+                e.storage().instance().remove(&DataKey::CoSigner(co_buyer));
+            }
+            for key in [
+                DataKey::Asset, DataKey::AssetId, DataKey::Buyers, DataKey::Token, DataKey::PaymentTokens,
+                DataKey::QuotedPrice, DataKey::Installments, DataKey::ClosingDeadline, DataKey::CancelPolicy,
+                DataKey::ClosingCosts, DataKey::Inspector, DataKey::Inspection, DataKey::PriceProposal,
+                DataKey::AppraisalCheck, DataKey::RentToOwn, DataKey::Retention, DataKey::Insurance,
+                DataKey::Notarization, DataKey::ClosingSchedule, DataKey::FiatPricing,
+                DataKey::Arbiter, DataKey::Dispute, DataKey::Bidders
+            ] {
+                e.storage().instance().remove(&key);
+            }
+
+            e.events().publish((TOPIC_CANCELLED,), (asset, buyer, 0_i128, false));
+            Ok(true)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    /// Emergency stop: the admin of the `HouseAsset` registry suspends payments, offers, financing and
+    /// closing until it calls `unpause`. Cancellations and refunds are still possible.
+    pub fn pause(e: Env) -> Result<bool, Error> {
+        if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+            let admin = asset::HouseAssetClient::new(&e, &asset).admin();
+            admin.require_auth();
+            pausable::pause(&e, &admin);
+            Ok(true)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    pub fn unpause(e: Env) -> Result<bool, Error> {
+        if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+            let admin = asset::HouseAssetClient::new(&e, &asset).admin();
+            admin.require_auth();
+            pausable::unpause(&e, &admin);
+            Ok(true)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    pub fn is_paused(e: Env) -> bool {
+        pausable::is_paused(&e)
+    }
+
+    /// The buyers and the seller appoint `arbiter` to settle any dispute raised with `open_dispute`. Only
+    /// possible before the seller accepts the offer.
+    pub fn appoint_arbiter(e: Env, arbiter: Address) -> Result<bool, Error> {
+        if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+            if get_status(&e) != PurchaseStatus::Created {
+                return Err(Error::ArbiterCannotBeAppointed);
+            }
+
+            for buyer in get_buyers(&e).iter() {
+                buyer.require_auth();
+            }
+            asset::HouseAssetClient::new(&e, &asset).owner_of(&get_asset_id(&e)).require_auth();
+
+            e.storage().instance().set(&DataKey::Arbiter, &arbiter);
+            Ok(true)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    pub fn arbiter(e: Env) -> Option<Address> {
+        e.storage().instance().get::<DataKey, Address>(&DataKey::Arbiter)
+    }
+
+    /// A buyer or the seller raises a dispute, `reason_hash` being the hash of the claim. Payments,
+    /// closing and cancellation are suspended until the arbiter rules on it with `resolve_dispute`.
+    ///
+    /// Once the purchase is closed a dispute can only be raised while part of the price is retained,
+    /// before the dispute window ends; it then holds the retention until the ruling.
+    pub fn open_dispute(e: Env, caller: Address, reason_hash: BytesN<32>) -> Result<Dispute, Error> {
+        if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+            if !e.storage().instance().has(&DataKey::Arbiter) {
+                return Err(Error::NoArbiter);
+            }
+
+            let status = get_status(&e);
+            let retention = e.storage().instance().get::<DataKey, Retention>(&DataKey::Retention)
+                .filter(|retention| retention.retained > 0 && e.ledger().timestamp() < retention.release_ts);
+            if (status == PurchaseStatus::Closed && retention.is_none()) || status == PurchaseStatus::Cancelled || e.storage().instance().has(&DataKey::Dispute) {
+                return Err(Error::DisputeOpen);
+            }
+
+            // After closing the buyers own the asset, and the seller is the one the retention is kept for
+            let seller = match retention {
+                Some(retention) if status == PurchaseStatus::Closed => retention.seller.unwrap(),
+                _ => asset::HouseAssetClient::new(&e, &asset).owner_of(&get_asset_id(&e)),
+            };
+            if !get_buyers(&e).contains(&caller) && caller != seller {
+                return Err(Error::AddressNotAllowed);
+            }
+
+            caller.require_auth();
+            let dispute = Dispute { raised_by: caller.clone(), reason_hash: reason_hash.clone(), ts: e.ledger().timestamp(), ruling: None };
+            e.storage().instance().set(&DataKey::Dispute, &dispute);
+            e.events().publish((TOPIC_DISPUTE_OPENED,), (asset, caller, reason_hash));
+            Ok(dispute)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    /// The arbiter settles the open dispute, which ends the purchase. The seller gets `seller_bps` of
+    /// what the buyers paid and the rest is refunded to them.
+    ///
+    /// With `transfer_ownership`, only possible on a fully paid purchase of an asset that is not frozen,
+    /// the asset goes to the buyers as in `change_owner` and a loan funding the purchase is paid to the
+    /// seller; the buyers then pledge the house to the lender, so they have to sign such a ruling too.
+    /// Otherwise the purchase is cancelled and the loan is returned to the lender. Closing costs and
+    /// retention do not apply to a ruling.
+    ///
+    /// A dispute raised after closing only splits the retention: the seller gets `seller_bps` of it and
+    /// the buyers the rest, in proportion to what they paid. `transfer_ownership` must be false.
+    pub fn resolve_dispute(e: Env, seller_bps: u32, transfer_ownership: bool) -> Result<Ruling, Error> {
+        if let Some(mut dispute) = e.storage().instance().get::<DataKey, Dispute>(&DataKey::Dispute) {
+            if dispute.ruling.is_some() {
+                return Err(Error::NoDispute);
+            }
+
+            if get_status(&e) == PurchaseStatus::Closed {
+                return resolve_retention_dispute(&e, dispute, seller_bps, transfer_ownership);
+            }
+
+            if seller_bps > 10_000 || (transfer_ownership && get_status(&e) != PurchaseStatus::FullyPaid) {
+                return Err(Error::InvalidRuling);
+            }
+
+            let arbiter = e.storage().instance().get::<DataKey, Address>(&DataKey::Arbiter).unwrap();
+            arbiter.require_auth();
+
+            let asset = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset).unwrap();
+            let asset_contract = asset::HouseAssetClient::new(&e, &asset);
+            if transfer_ownership && asset_contract.is_frozen(&get_asset_id(&e)) {
+                return Err(Error::AssetFrozen);
+            }
+
+            let seller = asset_contract.owner_of(&get_asset_id(&e));
+            let price_token = e.storage().instance().get::<DataKey, Address>(&DataKey::Token).unwrap();
+            let (contributors, contributed) = get_contributors(&e);
+            if transfer_ownership && e.storage().instance().has(&DataKey::Loan) {
+                for buyer in contributors.iter() {
+                    buyer.require_auth();
+                }
+            }
+
+            // The ruling ends the purchase before any asset or token leaves the contract
+            let ruling = Ruling { seller_bps, transfer_ownership };
+            dispute.ruling = Some(ruling.clone());
+            e.storage().instance().set(&DataKey::Dispute, &dispute);
+            set_status(&e, if transfer_ownership { PurchaseStatus::Closed } else { PurchaseStatus::Cancelled })?;
+
+            if transfer_ownership {
+                hand_over_asset(&e, &asset_contract, &contributors, contributed);
+            }
+            if let Some(loan) = e.storage().instance().get::<DataKey, Loan>(&DataKey::Loan) {
+                let receiver = if transfer_ownership { seller.clone() } else { loan.lender };
+                token::Client::new(&e, &price_token).transfer(&e.current_contract_address(), &receiver, &loan.principal);
+            }
+
+            for buyer in get_buyers(&e).iter() {
+                for token in get_payment_tokens(&e).iter() {
+                    let paid = get_token_contribution(&e, &buyer, &token);
+                    let seller_part = paid * seller_bps as i128 / 10_000;
+                    let tk = token::Client::new(&e, &token);
+                    if seller_part > 0 {
+                        tk.transfer(&e.current_contract_address(), &seller, &seller_part);
+                    }
+                    if paid > seller_part {
+                        tk.transfer(&e.current_contract_address(), &buyer, &(paid - seller_part));
+                    }
+                }
+            }
+
+            e.events().publish((TOPIC_DISPUTE_RESOLVED,), (asset, seller_bps, transfer_ownership));
+            Ok(ruling)
+        } else {
+            return Err(Error::NoDispute);
+        }
+    }
+
+    pub fn dispute(e: Env) -> Option<Dispute> {
+        e.storage().instance().get::<DataKey, Dispute>(&DataKey::Dispute)
+    }
+
+    /// Unwinds a purchase that has not been closed by the closing deadline (or within the grace period
+    /// of the scheduled closing date), whose next installment is overdue or whose inspection failed,
+    /// picking the reason for `cancel` that applies.
+    pub fn cancel_purchase(e: Env, caller: Address) -> Result<bool, Error> {
+        if let Some(_asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+            let reason = if get_inspection(&e) == InspectionStatus::Failed {
+                CancelReason::InspectionFailed
+            } else if closing_overdue(&e) {
+                CancelReason::LateClosing
+            } else if next_installment_overdue(&e) {
+                CancelReason::InstallmentOverdue
+            } else if deadline_passed(&e) {
+                CancelReason::DeadlinePassed
+            } else {
+                return Err(Error::ClosingDeadlineNotReached);
+            };
+
+            Self::cancel(e, caller, reason)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    /// Cancels the purchase for `reason`, which must hold: a buyer can always withdraw before closing,
+    /// and any buyer or the asset owner can cancel once the inspection failed, the next installment is
+    /// overdue, the closing deadline passed or the scheduled closing is late.
+    ///
+    /// The asset ownership is left unchanged and a loan funding the purchase is returned to the lender.
+    /// The contributions made so far are refunded to each buyer, except the part the seller keeps:
+    /// - nothing before the first payment, after a failed inspection, or when the whole price was paid
+    ///   and the purchase was not closed in time;
+    /// - otherwise what the cancel policy says (everything with `Forfeit`, the penalty with `Penalty`
+    ///   or everything once the buyers waived the inspection), and with `Refund` the penalty of the
+    ///   closing schedule for a late closing.
+    pub fn cancel(e: Env, caller: Address, reason: CancelReason) -> Result<bool, Error> {
+        if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+            let status = get_status(&e);
+            if status == PurchaseStatus::Closed || status == PurchaseStatus::Cancelled {
+                return Err(Error::PurchaseCannotBeCancelled);
+            }
+
+            if dispute_open(&e) {
+                return Err(Error::DisputeOpen);
+            }
+
+            let reason_met = match reason {
+                CancelReason::Withdrawal => true,
+                CancelReason::InspectionFailed => get_inspection(&e) == InspectionStatus::Failed,
+                CancelReason::InstallmentOverdue => next_installment_overdue(&e),
+                CancelReason::DeadlinePassed => deadline_passed(&e),
+                CancelReason::LateClosing => closing_overdue(&e)
+            };
+            if !reason_met {
+                return Err(Error::CancelReasonNotMet);
+            }
+
+            let asset_contract = asset::HouseAssetClient::new(&e, &asset);
+            let buyers = get_buyers(&e);
+            let seller = asset_contract.owner_of(&get_asset_id(&e));
+            if !buyers.contains(&caller) && (caller != seller || reason == CancelReason::Withdrawal) {
+                return Err(Error::AddressNotAllowed);
+            }
+
+            caller.require_auth();
+            let penalty_bps = cancel_penalty_bps(&e, reason, status);
+            set_status(&e, PurchaseStatus::Cancelled)?;
+
+            let token = e.storage().instance().get::<DataKey, Address>(&DataKey::Token).unwrap();
+            let tk = token::Client::new(&e, &token);
+            if let Some(loan) = e.storage().instance().get::<DataKey, Loan>(&DataKey::Loan) {
+                tk.transfer(&e.current_contract_address(), &loan.lender, &loan.principal);
+            }
+
+            let mut returned: i128 = 0;
+            for buyer in buyers.iter() {
+                for token in get_payment_tokens(&e).iter() {
+                    let paid = get_token_contribution(&e, &buyer, &token);
+                    let penalty = paid * penalty_bps as i128 / 10_000;
+                    let tk = token::Client::new(&e, &token);
+                    if penalty > 0 {
+                        tk.transfer(&e.current_contract_address(), &seller, &penalty);
+                    }
+                    if paid > penalty {
+                        tk.transfer(&e.current_contract_address(), &buyer, &(paid - penalty));
+                    }
+                }
+                returned += get_contribution(&e, &buyer);
+            }
+
+            e.events().publish((TOPIC_CANCELLED,), (asset, caller, returned, penalty_bps > 0));
+            Ok(true)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    /// A buyer or the seller proposes new terms for the part of the plan not paid yet (e.g. after
+    /// the inspection): `installments` replaces every installment from the current one on.
+    ///
+    /// Nothing changes until the other side calls `accept_price_change`. A new proposal replaces
+    /// any previous one.
+    pub fn propose_price_change(e: Env, proposer: Address, installments: Vec<Installment>) -> Result<PriceProposal, Error> {
+        if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+            let status = get_status(&e);
+            if status != PurchaseStatus::Created && status != PurchaseStatus::Accepted && status != PurchaseStatus::PaymentsInProgress {
+                return Err(Error::PriceCannotBeChanged);
+            }
+
+            let asset_contract = asset::HouseAssetClient::new(&e, &asset);
+            if !get_buyers(&e).contains(&proposer) && proposer != asset_contract.owner_of(&get_asset_id(&e)) {
+                return Err(Error::AddressNotAllowed);
+            }
+
+            let closing_deadline = e.storage().instance().get::<DataKey, u64>(&DataKey::ClosingDeadline).unwrap();
+            check_installments(&installments, closing_deadline)?;
+
+            // What the buyers already paid towards the current installment must still fit in it
+            let contributed = e.storage().instance().get::<DataKey, i128>(&DataKey::InstallmentContributed).unwrap_or(0);
+            if installments.get(0).unwrap().amount < contributed {
+                return Err(Error::InvalidPaymentSchedule);
+            }
+
+            proposer.require_auth();
+            let proposal = PriceProposal {
+                proposer,
+                from_installment: get_paid_installments(&e),
+                installments,
+            };
+
+            e.storage().instance().set(&DataKey::PriceProposal, &proposal);
+            Ok(proposal)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    /// The other side accepts the pending price proposal: the seller if a buyer proposed it, or
+    /// every buyer if the seller did. Returns the new payment plan.
+    pub fn accept_price_change(e: Env) -> Result<Vec<Installment>, Error> {
+        if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+            let proposal = e.storage().instance().get::<DataKey, PriceProposal>(&DataKey::PriceProposal).ok_or(Error::NoPriceProposal)?;
+            let status = get_status(&e);
+            if status != PurchaseStatus::Created && status != PurchaseStatus::Accepted && status != PurchaseStatus::PaymentsInProgress {
+                return Err(Error::PriceCannotBeChanged);
+            }
+
+            let paid_installments = get_paid_installments(&e);
+            if paid_installments != proposal.from_installment {
+                return Err(Error::PriceProposalOutdated);
+            }
+
+            let asset_contract = asset::HouseAssetClient::new(&e, &asset);
+            let seller = asset_contract.owner_of(&get_asset_id(&e));
+            if proposal.proposer == seller {
+                for buyer in get_buyers(&e).iter() {
+                    buyer.require_auth();
+                }
+            } else {
+                seller.require_auth();
+            }
+
+            let mut installments = get_installments(&e).slice(0..paid_installments);
+            installments.append(&proposal.installments);
+            check_appraisal(&e, &installments)?;
+            e.storage().instance().set(&DataKey::Installments, &installments);
+            e.storage().instance().remove(&DataKey::PriceProposal);
+            e.events().publish((TOPIC_PRICE_CHANGED,), (asset, installments_total(&installments, installments.len())));
+            Ok(installments)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    pub fn price_proposal(e: Env) -> Option<PriceProposal> {
+        e.storage().instance().get::<DataKey, PriceProposal>(&DataKey::PriceProposal)
+    }
+
+    pub fn installments(e: Env) -> Result<Vec<Installment>, Error> {
+        if let Some(installments) = e.storage().instance().get::<DataKey, Vec<Installment>>(&DataKey::Installments) {
+            Ok(installments)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    /// Itemized statement of the price, the closing costs paid out of it and the seller proceeds.
+    pub fn settlement(e: Env) -> Result<Settlement, Error> {
+        if let Some(installments) = e.storage().instance().get::<DataKey, Vec<Installment>>(&DataKey::Installments) {
+            let price = installments_total(&installments, installments.len());
+            let closing_costs = get_closing_costs(&e);
+            let seller_proceeds = price - closing_costs_total(&closing_costs);
+            Ok(Settlement { price, closing_costs, seller_proceeds })
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    /// Token used to pay the installment `index`, once it has received a payment.
+    pub fn milestone_token(e: Env, index: u32) -> Option<Address> {
+        e.storage().instance().get::<DataKey, Address>(&DataKey::MilestoneToken(index))
+    }
+
+    /// Alternative payment tokens, each one with the whole price quoted in it.
+    pub fn payment_tokens(e: Env) -> Map<Address, i128> {
+        e.storage().instance().get::<DataKey, Map<Address, i128>>(&DataKey::PaymentTokens).unwrap_or(Map::new(&e))
+    }
+
+    pub fn paid_installments(e: Env) -> u32 {
+        get_paid_installments(&e)
+    }
+
+    pub fn buyers(e: Env) -> Result<Vec<Address>, Error> {
+        if let Some(buyers) = e.storage().instance().get::<DataKey, Vec<Address>>(&DataKey::Buyers) {
+            Ok(buyers)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    pub fn contribution(e: Env, buyer: Address) -> i128 {
+        get_contribution(&e, &buyer)
+    }
+
+    /// Share of the house, in basis points of the price, that the contributions of `buyer` pay for so far.
+    pub fn share_of(e: Env, buyer: Address) -> Result<u32, Error> {
+        if let Some(buyers) = e.storage().instance().get::<DataKey, Vec<Address>>(&DataKey::Buyers) {
+            let contributed: i128 = buyers.iter().map(|b| get_contribution(&e, &b)).sum();
+            if contributed == 0 {
+                return Ok(0);
+            }
+            Ok((get_contribution(&e, &buyer) * TOTAL_SHARES as i128 / contributed) as u32)
+        } else {
+            return Err(Error::ContractNotInitialized);
+        }
+    }
+
+    /// Everything a client needs to know about the purchase in a single call.
+    pub fn get_purchase_state(e: Env) -> Result<PurchaseInfo, Error> {
+        if let Some(asset) = e.storage().instance().get::<DataKey, Address>(&DataKey::Asset) {
+            let status = get_status(&e);
+            let buyers = get_buyers(&e);
+            let token = e.storage().instance().get::<DataKey, Address>(&DataKey::Token).unwrap();
+            let mut escrowed: Map<Address, i128> = Map::new(&e);
+            if status == PurchaseStatus::Closed {
+                let retained = e.storage().instance().get::<DataKey, Retention>(&DataKey::Retention).map(|retention| retention.retained).unwrap_or(0);
+                if retained > 0 {
+                    escrowed.set(token.clone(), retained);
+                }
+            } else if status != PurchaseStatus::Cancelled {
+                // Contributions are counted in the token they were paid in, not in price units
+                for payment_token in get_payment_tokens(&e).iter() {
+                    let held = escrowed_in(&e, &payment_token);
+                    if held > 0 {
+                        escrowed.set(payment_token, held);
+                    }
+                }
+            }
 
+            Ok(PurchaseInfo {
+                asset,
+                asset_id: get_asset_id(&e),
+                buyers,
+                inspector: e.storage().instance().get::<DataKey, Address>(&DataKey::Inspector).unwrap(),
+                token,
+                installments: get_installments(&e),
+                closing_deadline: e.storage().instance().get::<DataKey, u64>(&DataKey::ClosingDeadline).unwrap(),
+                cancel_policy: e.storage().instance().get::<DataKey, CancelPolicy>(&DataKey::CancelPolicy).unwrap(),
+                paid_installments: get_paid_installments(&e),
+                installment_contributed: e.storage().instance().get::<DataKey, i128>(&DataKey::InstallmentContributed).unwrap_or(0),
+                escrowed,
+                inspection: get_inspection(&e),
+                status
+            })
         } else {
             return Err(Error::ContractNotInitialized);
         }
     }
 
-    pub fn state(e: Env) -> Result<State, Error> {
-        if let Some(state) = e.storage().instance().get::<DataKey, State>(&DataKey::State) {
-            Ok(state)
+    pub fn status(e: Env) -> Result<PurchaseStatus, Error> {
+        if let Some(status) = e.storage().instance().get::<DataKey, PurchaseStatus>(&DataKey::Status) {
+            Ok(status)
         } else {
             return Err(Error::ContractNotInitialized);
         }
@@ -138,4 +2187,4 @@ impl HousePurchaseContract {
 
 }
 
-mod test;
\ No newline at end of file
+mod test;
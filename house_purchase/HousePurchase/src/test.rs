@@ -1,35 +1,75 @@
 #![cfg(test)]
+extern crate std;
 
-mod asset {
-    soroban_sdk::contractimport!(
-        file = "../HouseAsset/target/wasm32-unknown-unknown/release/house_asset.wasm"
-    );
+use super::{ AppraisalCheck, CancelPolicy, CancelReason, ClosingCost, ClosingSchedule, CoSigner, Insurance, Loan, Notarization, Offer, RentToOwn, Ruling, HousePurchaseContract, HousePurchaseContractClient, InspectionStatus, Installment, PriceData, PurchaseStatus};
+use soroban_sdk::{contract, contractimpl, Env, testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke}, Address, BytesN, IntoVal, Map, String, Symbol, Vec, map, vec};
+use house_asset::{Asset, AssetClient, Metadata};
+use soroban_examples_testutils::{create_reentrant_token, create_token_contract, Scenario, TokenAdminClient, TokenClient};
+
+const CLOSING_DEADLINE: u64 = 30 * 86400;
+
+// Appraisal feed returning whatever value the test sets
+#[contract]
+pub struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    pub fn set_appraisal(e: Env, value: i128) {
+        e.storage().instance().set(&0, &value);
+    }
+
+    pub fn appraisal(e: Env, _asset: Address, _asset_id: String) -> i128 {
+        e.storage().instance().get(&0).unwrap_or(0)
+    }
+}
+
+// Fiat price feed returning whatever price the test sets
+#[contract]
+pub struct MockPriceFeed;
+
+#[contractimpl]
+impl MockPriceFeed {
+    pub fn set_price(e: Env, price: i128, timestamp: u64) {
+        e.storage().instance().set(&0, &PriceData { price, timestamp });
+    }
+
+    pub fn lastprice(e: Env, _token: Address) -> Option<PriceData> {
+        e.storage().instance().get(&0)
+    }
+}
+
+fn create_oracle(e: &Env, value: i128) -> Address {
+    let oracle = e.register(MockOracle, ());
+    MockOracleClient::new(e, &oracle).set_appraisal(&value);
+    oracle
 }
 
-use super::{ HousePurchaseContract, HousePurchaseContractClient};
-use soroban_sdk::{Env, testutils::Address as _, Address, token, String};
-use token::Client as TokenClient;
-use asset::Client as AssetClient;
-use token::StellarAssetClient as TokenAdminClient;
+// 5000 due in 10 days and the remaining 40000 in 20 days
+fn two_installments(e: &Env) -> Vec<Installment> {
+    vec![
+        e,
+        installment(e, "down payment", 5000, 10 * 86400),
+        installment(e, "completion", 40000, 20 * 86400),
+    ]
+}
 
-fn create_token_contract<'a>(e: &Env, admin: &Address) -> (TokenClient<'a>, TokenAdminClient<'a>) {
-    let sac = e.register_stellar_asset_contract_v2(admin.clone());
-    (
-        TokenClient::new(e, &sac.address()),
-        TokenAdminClient::new(e, &sac.address()),
-    )
+fn installment(e: &Env, label: &str, amount: i128, due_ts: u64) -> Installment {
+    Installment { label: String::from_str(e, label), amount, due_ts }
 }
 
 fn create_asset(e: &Env) -> AssetClient<'_> {
-    let asset = AssetClient::new(e, &e.register(asset::WASM, ()));
+    let asset = AssetClient::new(e, &e.register(Asset, (Address::generate(e),)));
     asset
 }
 
 struct TestData<'a> {
     buyer: Address,
+    inspector: Address,
+    asset_id: String,
     asset_contract: AssetClient<'a>,
     client:  HousePurchaseContractClient<'a>,
-    sac_token: TokenClient<'a>
+    sac_token: TokenClient<'a>,
+    sac_token_admin: TokenAdminClient<'a>
 }
 
 fn init_test_data(env: &Env) -> TestData<'_> {
@@ -41,10 +81,17 @@ fn init_test_data(env: &Env) -> TestData<'_> {
     );
 
     let buyer: Address = Address::generate(&env);
+    let inspector: Address = Address::generate(&env);
     let owner: Address = Address::generate(&env);
     let asset_contract = create_asset(&env);
+    let metadata = Metadata {
+        postal_address: String::from_str(&env, "Calle Mayor 1, 28013 Madrid"),
+        legal_id: String::from_str(&env, "9872023VH5797S0001WX"),
+        deed_hash: BytesN::from_array(&env, &[1; 32]),
+    };
     let asset_id = String::from_str(&env, "399fg7u6h69965h6");
-    asset_contract.initialize(&owner, &asset_id);
+    asset_contract.register_asset(&asset_id, &owner, &metadata);
+    asset_contract.approve_operator(&asset_id, &client.address);
     let token_admin = Address::generate(&env);
 
     let (sac_token, sac_token_admin) = create_token_contract(&env, &token_admin);
@@ -52,28 +99,37 @@ fn init_test_data(env: &Env) -> TestData<'_> {
 
     TestData {
         buyer,
+        inspector,
+        asset_id,
         asset_contract,
         client,
-        sac_token
+        sac_token,
+        sac_token_admin
     }
 }
 
+// The single buyer pays the whole current installment
+fn pay_installment(test_data: &TestData) -> u32 {
+    let installment = test_data.client.installments().get(test_data.client.paid_installments()).unwrap();
+    test_data.client.contribute(&test_data.buyer, &installment.amount)
+}
+
 #[test]
 fn test_initialize() {
     let env = Env::default();
     let test_data = init_test_data(&env);
 
-    assert_eq!(test_data.client.initialize(&test_data.asset_contract.address, &test_data.buyer, &test_data.sac_token.address, &5000_i128, &45000_i128), true);
+    assert_eq!(test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env)), true);
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #1)")]
+#[should_panic(expected = "HostError: Error(Contract, #601)")]
 fn test_already_initialized() {
     let env = Env::default();
     let test_data = init_test_data(&env);
 
-    test_data.client.initialize(&test_data.asset_contract.address, &test_data.buyer, &test_data.sac_token.address, &5000_i128, &45000_i128);
-    test_data.client.initialize(&test_data.asset_contract.address, &test_data.buyer, &test_data.sac_token.address, &5000_i128, &45000_i128);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
 }
 
 #[test]
@@ -81,40 +137,1474 @@ fn test_transfer() {
     let env = Env::default();
     let test_data = init_test_data(&env);
 
-    test_data.client.initialize(&test_data.asset_contract.address, &test_data.buyer, &test_data.sac_token.address, &5000_i128, &45000_i128);
-    test_data.client.transfer_first_payment();
-    assert_eq!(test_data.sac_token.balance(&test_data.asset_contract.owner()), 5000);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    assert_eq!(test_data.sac_token.balance(&test_data.client.address), 5000);
+    assert_eq!(test_data.sac_token.balance(&test_data.asset_contract.owner_of(&test_data.asset_id)), 0);
 
-    test_data.client.transfer_rest_of_payment();
-    assert_eq!(test_data.sac_token.balance(&test_data.asset_contract.owner()), 45000);
+    pay_installment(&test_data);
+    assert_eq!(test_data.sac_token.balance(&test_data.client.address), 45000);
+    assert_eq!(test_data.sac_token.balance(&test_data.asset_contract.owner_of(&test_data.asset_id)), 0);
 
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
     test_data.client.change_owner();
-    assert_eq!(test_data.asset_contract.owner(), test_data.buyer);
+    let signers: std::vec::Vec<Address> = env.auths().into_iter().map(|(addr, _)| addr).collect();
+    assert!(signers.contains(&test_data.buyer));
+    assert!(signers.contains(&seller));
+    assert_eq!(test_data.asset_contract.owner_of(&test_data.asset_id), test_data.buyer);
+    assert_eq!(test_data.sac_token.balance(&seller), 45000);
+    assert_eq!(test_data.sac_token.balance(&test_data.client.address), 0);
+}
+
+#[test]
+fn test_reentrant_token_cannot_double_book_payments() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let token = create_reentrant_token(&env);
+    token.mint(&test_data.buyer, &50000);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+
+    // The token calls `contribute` again while the first contribution pulls the tokens
+    token.arm(&test_data.client.address, &Symbol::new(&env, "contribute"), &(test_data.buyer.clone(), 5000_i128).into_val(&env));
+    test_data.client.contribute(&test_data.buyer, &5000);
+    assert_eq!(token.reentered(), 0);
+    assert_eq!(test_data.client.paid_installments(), 1);
+    assert_eq!(test_data.client.contribution(&test_data.buyer), 5000);
+    assert_eq!(token.balance(&test_data.client.address), 5000);
+
+    // And `cancel_purchase` while the refund is paid
+    env.ledger().set_timestamp(CLOSING_DEADLINE + 1);
+    token.arm(&test_data.client.address, &Symbol::new(&env, "cancel_purchase"), &(test_data.buyer.clone(),).into_val(&env));
+    test_data.client.cancel_purchase(&test_data.buyer);
+    assert_eq!(token.attempts(), 2);
+    assert_eq!(token.reentered(), 0);
+    assert_eq!(token.balance(&test_data.buyer), 50000);
+    assert_eq!(token.balance(&test_data.client.address), 0);
+    assert!(test_data.client.status() == PurchaseStatus::Cancelled);
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #2)")]
-fn test_first_payment_contract_not_initialized() {
+#[should_panic(expected = "HostError: Error(Contract, #602)")]
+fn test_pay_installment_contract_not_initialized() {
     let env = Env::default();
     let test_data = init_test_data(&env);
-    test_data.client.transfer_first_payment();
+    pay_installment(&test_data);
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #3)")]
-fn test_first_payment_not_transferred() {
+#[should_panic(expected = "HostError: Error(Contract, #603)")]
+fn test_initialize_installments_not_in_order() {
     let env = Env::default();
     let test_data = init_test_data(&env);
-    test_data.client.initialize(&test_data.asset_contract.address, &test_data.buyer, &test_data.sac_token.address, &5000_i128, &45000_i128);
-    test_data.client.transfer_rest_of_payment();
+    let installments = vec![
+        &env,
+        installment(&env, "down payment", 5000, 20 * 86400),
+        installment(&env, "completion", 40000, 10 * 86400),
+    ];
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &installments, &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #4)")]
+#[should_panic(expected = "HostError: Error(Contract, #604)")]
 fn test_change_owner_without_payment_transferred() {
     let env = Env::default();
     let test_data = init_test_data(&env);
-    test_data.client.initialize(&test_data.asset_contract.address, &test_data.buyer, &test_data.sac_token.address, &5000_i128, &45000_i128);
-    test_data.client.transfer_first_payment();
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.change_owner();
+}
+#[test]
+fn test_cancel_purchase_refunds_paid_installments() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+
+    env.ledger().set_timestamp(CLOSING_DEADLINE + 1);
+    let owner = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client.cancel_purchase(&test_data.buyer);
+
+    assert_eq!(test_data.sac_token.balance(&test_data.buyer), 50000);
+    assert_eq!(test_data.asset_contract.owner_of(&test_data.asset_id), owner);
+    assert!(test_data.client.status() == PurchaseStatus::Cancelled);
+}
+
+#[test]
+fn test_cancel_purchase_forfeits_paid_installments() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Forfeit, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+
+    env.ledger().set_timestamp(CLOSING_DEADLINE + 1);
+    let owner = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client.cancel_purchase(&owner);
+
+    assert_eq!(test_data.sac_token.balance(&owner), 5000);
+    assert_eq!(test_data.sac_token.balance(&test_data.buyer), 45000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #605)")]
+fn test_cancel_purchase_before_deadline() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.cancel_purchase(&test_data.buyer);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #606)")]
+fn test_pay_installment_after_deadline() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+
+    env.ledger().set_timestamp(CLOSING_DEADLINE + 1);
+    pay_installment(&test_data);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #608)")]
+fn test_cancel_purchase_by_third_party() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+
+    env.ledger().set_timestamp(CLOSING_DEADLINE + 1);
+    test_data.client.cancel_purchase(&Address::generate(&env));
+}
+
+#[test]
+fn test_cancel_purchase_refunds_whole_price_if_seller_never_closes() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Forfeit, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    pay_installment(&test_data);
+
+    env.ledger().set_timestamp(CLOSING_DEADLINE + 1);
+    test_data.client.cancel_purchase(&test_data.buyer);
+
+    assert_eq!(test_data.sac_token.balance(&test_data.buyer), 50000);
+    assert_eq!(test_data.sac_token.balance(&test_data.client.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #609)")]
+fn test_pay_installment_offer_not_accepted() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    pay_installment(&test_data);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #610)")]
+fn test_offer_already_accepted() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    test_data.client.accept_offer();
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_change_owner_signed_by_seller_only() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    pay_installment(&test_data);
+
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client
+        .mock_auths(&[MockAuth {
+            address: &seller,
+            invoke: &MockAuthInvoke {
+                contract: &test_data.client.address,
+                fn_name: "change_owner",
+                args: ().into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .change_owner();
+}
+
+#[test]
+fn test_pay_installments_in_order() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+
+    assert_eq!(pay_installment(&test_data), 1);
+    test_data.client.approve_inspection();
+    assert!(test_data.client.status() == PurchaseStatus::PaymentsInProgress);
+    assert_eq!(test_data.client.paid_installments(), 1);
+
+    env.ledger().set_timestamp(20 * 86400);
+    assert_eq!(pay_installment(&test_data), 2);
+    assert!(test_data.client.status() == PurchaseStatus::FullyPaid);
+    assert_eq!(test_data.sac_token.balance(&test_data.client.address), 45000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #611)")]
+fn test_pay_installment_overdue() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+
+    env.ledger().set_timestamp(20 * 86400 + 1);
+    pay_installment(&test_data);
+}
+
+#[test]
+fn test_cancel_purchase_with_overdue_installment() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Forfeit, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+
+    env.ledger().set_timestamp(20 * 86400 + 1);
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client.cancel_purchase(&seller);
+    assert_eq!(test_data.sac_token.balance(&seller), 5000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #612)")]
+fn test_pay_installment_before_inspection() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    pay_installment(&test_data);
+}
+
+#[test]
+fn test_waive_inspection() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.waive_inspection(&test_data.buyer);
+
+    assert!(test_data.client.inspection() == InspectionStatus::Waived);
+    assert_eq!(pay_installment(&test_data), 2);
+}
+
+#[test]
+fn test_failed_inspection_refunds_buyer() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Forfeit, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.fail_inspection();
+
+    test_data.client.cancel_purchase(&test_data.buyer);
+    assert_eq!(test_data.sac_token.balance(&test_data.buyer), 50000);
+    assert!(test_data.client.status() == PurchaseStatus::Cancelled);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #613)")]
+fn test_inspection_already_resolved() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.approve_inspection();
+    test_data.client.fail_inspection();
+}
+
+#[test]
+fn test_fractional_purchase() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let co_buyer = Address::generate(&env);
+    test_data.sac_token_admin.mint(&co_buyer, &50000);
+
+    let buyers = vec![&env, test_data.buyer.clone(), co_buyer.clone()];
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &buyers, &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+
+    assert_eq!(test_data.client.contribute(&test_data.buyer, &2000), 0);
+    assert_eq!(test_data.client.contribute(&co_buyer, &3000), 1);
+    test_data.client.approve_inspection();
+    assert_eq!(test_data.client.contribute(&test_data.buyer, &16000), 1);
+    assert_eq!(test_data.client.contribute(&co_buyer, &24000), 2);
+
+    assert_eq!(test_data.client.contribution(&test_data.buyer), 18000);
+    assert_eq!(test_data.client.share_of(&test_data.buyer), 4000);
+    assert_eq!(test_data.client.share_of(&co_buyer), 6000);
+
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client.change_owner();
+    assert_eq!(test_data.asset_contract.share_of(&test_data.asset_id, &test_data.buyer), 4000);
+    assert_eq!(test_data.asset_contract.share_of(&test_data.asset_id, &co_buyer), 6000);
+    assert_eq!(test_data.asset_contract.owner_of(&test_data.asset_id), co_buyer);
+    assert_eq!(test_data.sac_token.balance(&seller), 45000);
+}
+
+#[test]
+fn test_fractional_purchase_cancel_refunds_each_buyer() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let co_buyer = Address::generate(&env);
+    test_data.sac_token_admin.mint(&co_buyer, &50000);
+
+    let buyers = vec![&env, test_data.buyer.clone(), co_buyer.clone()];
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &buyers, &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    test_data.client.contribute(&test_data.buyer, &2000);
+    test_data.client.contribute(&co_buyer, &1000);
+
+    env.ledger().set_timestamp(CLOSING_DEADLINE + 1);
+    test_data.client.cancel_purchase(&co_buyer);
+    assert_eq!(test_data.sac_token.balance(&test_data.buyer), 50000);
+    assert_eq!(test_data.sac_token.balance(&co_buyer), 50000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #614)")]
+fn test_contribution_exceeds_installment() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    test_data.client.contribute(&test_data.buyer, &5001);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #608)")]
+fn test_contribution_from_non_buyer() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    test_data.client.contribute(&Address::generate(&env), &5000);
+}
+
+#[test]
+fn test_price_change() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+
+    // The inspection found repairs to be done, so the buyer asks for a lower rest of the price
+    let rest = vec![&env, installment(&env, "completion", 35000, 20 * 86400)];
+    test_data.client.propose_price_change(&test_data.buyer, &rest);
+    let installments = test_data.client.accept_price_change();
+
+    assert_eq!(installments, vec![&env, installment(&env, "down payment", 5000, 10 * 86400), installment(&env, "completion", 35000, 20 * 86400)]);
+    assert_eq!(test_data.client.price_proposal(), None);
+
+    pay_installment(&test_data);
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client.change_owner();
+    assert_eq!(test_data.sac_token.balance(&seller), 40000);
+    assert_eq!(test_data.sac_token.balance(&test_data.buyer), 10000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #617)")]
+fn test_price_change_outdated() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client.propose_price_change(&seller, &vec![&env, installment(&env, "completion", 50000, 20 * 86400)]);
+    pay_installment(&test_data);
+    test_data.client.accept_price_change();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #616)")]
+fn test_accept_price_change_without_proposal() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_price_change();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #506)")]
+fn test_change_owner_operator_not_approved() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    pay_installment(&test_data);
+
+    test_data.asset_contract.revoke_operator(&test_data.asset_id);
+    test_data.client.change_owner();
+}
+
+#[test]
+fn test_initialize_within_appraisal() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    // The price of 45000 is 10% below the 50000 appraisal
+    let appraisal_check = AppraisalCheck { oracle: create_oracle(&env, 50000), max_deviation_bps: 1000 };
+    assert_eq!(test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &Some(appraisal_check), &Vec::new(&env)), true);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #619)")]
+fn test_initialize_out_of_appraisal() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let appraisal_check = AppraisalCheck { oracle: create_oracle(&env, 60000), max_deviation_bps: 1000 };
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &Some(appraisal_check), &Vec::new(&env));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #619)")]
+fn test_price_change_out_of_appraisal() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let appraisal_check = AppraisalCheck { oracle: create_oracle(&env, 50000), max_deviation_bps: 1000 };
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &Some(appraisal_check), &Vec::new(&env));
+    test_data.client.accept_offer();
+
+    test_data.client.propose_price_change(&test_data.buyer, &vec![&env, installment(&env, "completion", 20000, 20 * 86400)]);
+    test_data.client.accept_price_change();
+}
+
+#[test]
+fn test_financed_purchase() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let lender = Address::generate(&env);
+    test_data.sac_token_admin.mint(&lender, &40000);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+
+    let loan = test_data.client.finance(&lender);
+    assert_eq!(loan, Loan { lender: lender.clone(), principal: 40000, repaid: 0 });
+    assert!(test_data.client.status() == PurchaseStatus::FullyPaid);
+
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client.change_owner();
+    assert_eq!(test_data.sac_token.balance(&seller), 45000);
+    assert_eq!(test_data.asset_contract.owner_of(&test_data.asset_id), test_data.buyer);
+    assert_eq!(test_data.asset_contract.lien(&test_data.asset_id), Some(lender.clone()));
+
+    assert_eq!(test_data.client.repay_lender(&test_data.buyer, &15000), 25000);
+    assert_eq!(test_data.client.repay_lender(&test_data.buyer, &25000), 0);
+    assert_eq!(test_data.sac_token.balance(&lender), 40000);
+
+    test_data.asset_contract.release_lien(&test_data.asset_id);
+    assert_eq!(test_data.asset_contract.lien(&test_data.asset_id), None);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #620)")]
+fn test_finance_without_down_payment() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    test_data.client.finance(&Address::generate(&env));
+}
+
+#[test]
+fn test_cancel_financed_purchase() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let lender = Address::generate(&env);
+    test_data.sac_token_admin.mint(&lender, &40000);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Forfeit, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    test_data.client.finance(&lender);
+
+    env.ledger().set_timestamp(CLOSING_DEADLINE + 1);
+    test_data.client.cancel_purchase(&test_data.buyer);
+    assert_eq!(test_data.sac_token.balance(&lender), 40000);
+    assert_eq!(test_data.sac_token.balance(&test_data.buyer), 50000);
+}
+
+#[test]
+fn test_rent_to_own() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    // 1000 of rent every month, half of it credited towards a price of 45000
+    test_data.client.initialize_rent_to_own(&test_data.asset_contract.address, &test_data.asset_id, &test_data.buyer, &test_data.sac_token.address, &45000, &1000, &5000, &0, &(12 * 30 * 86400));
+    test_data.client.accept_offer();
+
+    assert_eq!(test_data.client.pay_rent(&test_data.buyer), 500);
+    env.ledger().set_timestamp(30 * 86400);
+    assert_eq!(test_data.client.pay_rent(&test_data.buyer), 1000);
+    // The third rent was due at day 60, so paying it late adds no credit
+    env.ledger().set_timestamp(61 * 86400);
+    assert_eq!(test_data.client.pay_rent(&test_data.buyer), 1000);
+    assert_eq!(test_data.sac_token.balance(&seller), 3000);
+    assert_eq!(test_data.client.rent_to_own(), Some(RentToOwn { rent: 1000, credit_bps: 5000, start_ts: 0, paid_rents: 3, credit: 1000 }));
+
+    // Balloon payment
+    assert_eq!(pay_installment(&test_data), 1);
+    test_data.client.change_owner();
+    assert_eq!(test_data.sac_token.balance(&seller), 47000);
+    assert_eq!(test_data.sac_token.balance(&test_data.buyer), 3000);
+    assert_eq!(test_data.asset_contract.owner_of(&test_data.asset_id), test_data.buyer);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #624)")]
+fn test_pay_rent_not_rent_to_own() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    test_data.client.pay_rent(&test_data.buyer);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #625)")]
+fn test_initialize_rent_to_own_invalid_terms() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize_rent_to_own(&test_data.asset_contract.address, &test_data.asset_id, &test_data.buyer, &test_data.sac_token.address, &45000, &1000, &12000, &0, &(12 * 30 * 86400));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #626)")]
+fn test_change_owner_asset_frozen() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    pay_installment(&test_data);
+
+    test_data.asset_contract.freeze(&test_data.asset_id);
+    test_data.client.change_owner();
+}
+
+#[test]
+fn test_get_purchase_state() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    test_data.client.contribute(&test_data.buyer, &10000);
+
+    let info = test_data.client.get_purchase_state();
+    assert_eq!(info.asset_id, test_data.asset_id);
+    assert_eq!(info.buyers, vec![&env, test_data.buyer.clone()]);
+    assert_eq!(info.installments, two_installments(&env));
+    assert_eq!(info.paid_installments, 1);
+    assert_eq!(info.installment_contributed, 10000);
+    assert_eq!(info.escrowed, map![&env, (test_data.sac_token.address.clone(), 15000_i128)]);
+    assert_eq!(info.inspection, InspectionStatus::Approved);
+    assert_eq!(info.status, PurchaseStatus::PaymentsInProgress);
+}
+
+#[test]
+fn test_status_transitions() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    assert_eq!(test_data.client.status(), PurchaseStatus::Created);
+
+    test_data.client.accept_offer();
+    assert_eq!(test_data.client.status(), PurchaseStatus::Accepted);
+
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    assert_eq!(test_data.client.status(), PurchaseStatus::PaymentsInProgress);
+
+    pay_installment(&test_data);
+    assert_eq!(test_data.client.status(), PurchaseStatus::FullyPaid);
+
     test_data.client.change_owner();
-}
\ No newline at end of file
+    assert_eq!(test_data.client.status(), PurchaseStatus::Closed);
+}
+
+#[test]
+fn test_pay_milestones() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let milestones = vec![
+        &env,
+        installment(&env, "reservation fee", 1000, 5 * 86400),
+        installment(&env, "down payment", 9000, 15 * 86400),
+        installment(&env, "completion", 35000, 25 * 86400),
+    ];
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &milestones, &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+
+    assert_eq!(test_data.client.pay_milestone(&test_data.buyer, &0), 1);
+    test_data.client.approve_inspection();
+    test_data.client.contribute(&test_data.buyer, &4000);
+    assert_eq!(test_data.client.pay_milestone(&test_data.buyer, &1), 2);
+    assert_eq!(test_data.client.pay_milestone(&test_data.buyer, &2), 3);
+    assert_eq!(test_data.sac_token.balance(&test_data.buyer), 5000);
+    assert_eq!(test_data.client.status(), PurchaseStatus::FullyPaid);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #628)")]
+fn test_pay_milestone_out_of_order() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    test_data.client.pay_milestone(&test_data.buyer, &1);
+}
+
+#[test]
+fn test_pay_through_allowance() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let router = Address::generate(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    test_data.sac_token.approve(&test_data.buyer, &router, &50000, &(env.ledger().sequence() + 100));
+
+    assert_eq!(test_data.client.pay_milestone_from(&router, &test_data.buyer, &0), 1);
+    test_data.client.approve_inspection();
+    test_data.client.contribute_from(&router, &test_data.buyer, &10000);
+
+    // The payments are credited to the buyer, and only the router signed them
+    assert_eq!(test_data.client.contribution(&test_data.buyer), 15000);
+    assert_eq!(test_data.sac_token.balance(&test_data.client.address), 15000);
+    assert_eq!(test_data.sac_token.allowance(&test_data.buyer, &router), 35000);
+    let signers: std::vec::Vec<Address> = env.auths().into_iter().map(|(addr, _)| addr).collect();
+    assert_eq!(signers, std::vec![router]);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #660)")]
+fn test_pay_through_missing_allowance() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    test_data.client.pay_milestone_from(&Address::generate(&env), &test_data.buyer, &0);
+}
+
+#[test]
+fn test_pay_in_alternative_token() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let (alt_token, alt_token_admin) = create_token_contract(&env, &Address::generate(&env));
+    alt_token_admin.mint(&test_data.buyer, &200000);
+    // The 45000 price is quoted as 90000 in the alternative token
+    let alt_tokens = map![&env, (alt_token.address.clone(), 90000_i128)];
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &alt_tokens, &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    test_data.client.contribute_in(&test_data.buyer, &alt_token.address, &40000);
+    assert_eq!(test_data.client.milestone_token(&0), Some(test_data.sac_token.address.clone()));
+    assert_eq!(test_data.client.milestone_token(&1), Some(alt_token.address.clone()));
+    assert_eq!(alt_token.balance(&test_data.buyer), 120000);
+    assert_eq!(
+        test_data.client.get_purchase_state().escrowed,
+        map![&env, (test_data.sac_token.address.clone(), 5000_i128), (alt_token.address.clone(), 80000_i128)]
+    );
+
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client.change_owner();
+    assert_eq!(test_data.sac_token.balance(&seller), 5000);
+    assert_eq!(alt_token.balance(&seller), 80000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #629)")]
+fn test_pay_in_token_not_accepted() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    test_data.client.contribute_in(&test_data.buyer, &Address::generate(&env), &5000);
+}
+
+#[test]
+fn test_cancel_refunds_each_token() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let (alt_token, alt_token_admin) = create_token_contract(&env, &Address::generate(&env));
+    alt_token_admin.mint(&test_data.buyer, &200000);
+    let alt_tokens = map![&env, (alt_token.address.clone(), 90000_i128)];
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &alt_tokens, &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    test_data.client.contribute_in(&test_data.buyer, &alt_token.address, &10000);
+
+    env.ledger().set_timestamp(CLOSING_DEADLINE + 1);
+    test_data.client.cancel_purchase(&test_data.buyer);
+    assert_eq!(test_data.sac_token.balance(&test_data.buyer), 50000);
+    assert_eq!(alt_token.balance(&test_data.buyer), 200000);
+}
+
+#[test]
+fn test_closing_costs() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let notary = Address::generate(&env);
+    let tax_office = Address::generate(&env);
+    let closing_costs = vec![
+        &env,
+        ClosingCost { label: String::from_str(&env, "transfer tax"), recipient: tax_office.clone(), amount: 3000 },
+        ClosingCost { label: String::from_str(&env, "notary"), recipient: notary.clone(), amount: 500 },
+    ];
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &closing_costs);
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    pay_installment(&test_data);
+
+    let settlement = test_data.client.settlement();
+    assert_eq!(settlement.price, 45000);
+    assert_eq!(settlement.closing_costs, closing_costs);
+    assert_eq!(settlement.seller_proceeds, 41500);
+
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client.change_owner();
+    assert_eq!(test_data.sac_token.balance(&seller), 41500);
+    assert_eq!(test_data.sac_token.balance(&tax_office), 3000);
+    assert_eq!(test_data.sac_token.balance(&notary), 500);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #631)")]
+fn test_closing_costs_above_price() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let closing_costs = vec![&env, ClosingCost { label: String::from_str(&env, "agency"), recipient: Address::generate(&env), amount: 45000 }];
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &closing_costs);
+}
+
+#[test]
+fn test_cancel_by_buyer() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+
+    test_data.client.cancel_by_buyer(&test_data.buyer);
+    assert_eq!(test_data.client.status(), PurchaseStatus::Cancelled);
+    assert_eq!(test_data.client.paid_installments(), 0);
+    assert!(test_data.client.try_installments().is_err());
+}
+
+#[test]
+fn test_cancel_by_buyer_removes_cosigner() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.set_cosigner(&test_data.buyer, &Some(CoSigner { cosigner: Address::generate(&env), threshold: 1000 }));
+    test_data.client.accept_offer();
+
+    test_data.client.cancel_by_buyer(&test_data.buyer);
+    assert_eq!(test_data.client.cosigner(&test_data.buyer), None);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #656)")]
+fn test_cancel_by_buyer_during_dispute() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.appoint_arbiter(&Address::generate(&env));
+    test_data.client.accept_offer();
+
+    test_data.client.open_dispute(&test_data.buyer, &BytesN::from_array(&env, &[3; 32]));
+    test_data.client.cancel_by_buyer(&test_data.buyer);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #633)")]
+fn test_cancel_by_buyer_after_payment() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    test_data.client.contribute(&test_data.buyer, &1000);
+    test_data.client.cancel_by_buyer(&test_data.buyer);
+}
+
+#[test]
+fn test_release_retention() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    // 10% of the final 40000 is kept for 7 days after closing
+    test_data.client.set_retention(&1000, &(7 * 86400));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    pay_installment(&test_data);
+
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client.change_owner();
+    assert_eq!(test_data.sac_token.balance(&seller), 41000);
+    assert_eq!(test_data.client.get_purchase_state().escrowed, map![&env, (test_data.sac_token.address.clone(), 4000_i128)]);
+
+    env.ledger().set_timestamp(7 * 86400);
+    assert_eq!(test_data.client.release_retention(), 4000);
+    assert_eq!(test_data.sac_token.balance(&seller), 45000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #635)")]
+fn test_release_retention_within_dispute_window() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.set_retention(&1000, &(7 * 86400));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    pay_installment(&test_data);
+    test_data.client.change_owner();
+
+    env.ledger().set_timestamp(6 * 86400);
+    test_data.client.release_retention();
+}
+
+#[test]
+fn test_dispute_splits_the_retention() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.appoint_arbiter(&Address::generate(&env));
+    test_data.client.set_retention(&1000, &(7 * 86400));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    pay_installment(&test_data);
+
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client.change_owner();
+    env.ledger().set_timestamp(6 * 86400);
+    test_data.client.open_dispute(&test_data.buyer, &BytesN::from_array(&env, &[3; 32]));
+
+    // The retention is held past the dispute window until the ruling, which only splits it
+    env.ledger().set_timestamp(7 * 86400);
+    assert_eq!(test_data.client.try_release_retention(), Err(Ok(crate::Error::DisputeOpen)));
+    assert_eq!(test_data.client.try_resolve_dispute(&2500, &true), Err(Ok(crate::Error::InvalidRuling)));
+    test_data.client.resolve_dispute(&2500, &false);
+    assert_eq!(test_data.sac_token.balance(&seller), 42000);
+    assert_eq!(test_data.sac_token.balance(&test_data.buyer), 8000);
+    assert_eq!(test_data.client.retention().unwrap().retained, 0);
+    assert_eq!(test_data.client.status(), PurchaseStatus::Closed);
+    assert_eq!(test_data.asset_contract.owner_of(&test_data.asset_id), test_data.buyer);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #656)")]
+fn test_dispute_after_the_dispute_window() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.appoint_arbiter(&Address::generate(&env));
+    test_data.client.set_retention(&1000, &(7 * 86400));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    pay_installment(&test_data);
+    test_data.client.change_owner();
+
+    env.ledger().set_timestamp(7 * 86400);
+    test_data.client.open_dispute(&test_data.buyer, &BytesN::from_array(&env, &[3; 32]));
+}
+
+#[test]
+fn test_cosigner_required_above_threshold() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let guarantor = Address::generate(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.set_cosigner(&test_data.buyer, &Some(CoSigner { cosigner: guarantor.clone(), threshold: 10000 }));
+    test_data.client.accept_offer();
+
+    pay_installment(&test_data);
+    assert!(!env.auths().iter().any(|(address, _)| *address == guarantor));
+
+    test_data.client.approve_inspection();
+    pay_installment(&test_data);
+    assert!(env.auths().iter().any(|(address, _)| *address == guarantor));
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_cosigner_missing() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.set_cosigner(&test_data.buyer, &Some(CoSigner { cosigner: Address::generate(&env), threshold: 1000 }));
+    test_data.client.accept_offer();
+
+    test_data.client
+        .mock_auths(&[MockAuth {
+            address: &test_data.buyer,
+            invoke: &MockAuthInvoke {
+                contract: &test_data.client.address,
+                fn_name: "contribute",
+                args: (test_data.buyer.clone(), 5000_i128).into_val(&env),
+                sub_invokes: &[MockAuthInvoke {
+                    contract: &test_data.sac_token.address,
+                    fn_name: "transfer",
+                    args: (test_data.buyer.clone(), test_data.client.address.clone(), 5000_i128).into_val(&env),
+                    sub_invokes: &[],
+                }],
+            },
+        }])
+        .contribute(&test_data.buyer, &5000);
+}
+
+#[test]
+fn test_insurance_confirmed_before_closing() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let insurer = Address::generate(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.require_insurance(&insurer);
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    pay_installment(&test_data);
+
+    let policy_hash = BytesN::from_array(&env, &[7; 32]);
+    test_data.client.confirm_policy(&policy_hash);
+    assert_eq!(test_data.client.insurance(), Some(Insurance { insurer, policy_hash: Some(policy_hash), waived: false }));
+    test_data.client.change_owner();
+    assert_eq!(test_data.asset_contract.owner_of(&test_data.asset_id), test_data.buyer);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #642)")]
+fn test_change_owner_without_insurance() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.require_insurance(&Address::generate(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    pay_installment(&test_data);
+
+    test_data.client.change_owner();
+}
+
+#[test]
+fn test_waive_insurance() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.require_insurance(&Address::generate(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    pay_installment(&test_data);
+
+    test_data.client.waive_insurance(&test_data.buyer);
+    test_data.client.change_owner();
+    assert_eq!(test_data.asset_contract.owner_of(&test_data.asset_id), test_data.buyer);
+}
+
+#[test]
+fn test_notarized_purchase() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let notary = Address::generate(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.appoint_notary(&notary);
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    pay_installment(&test_data);
+
+    env.ledger().set_timestamp(15 * 86400);
+    let deed_hash = BytesN::from_array(&env, &[9; 32]);
+    test_data.client.notarize(&deed_hash);
+    test_data.client.change_owner();
+    assert_eq!(test_data.asset_contract.owner_of(&test_data.asset_id), test_data.buyer);
+    assert_eq!(test_data.client.notarization(), Some(Notarization { notary, document_hash: Some(deed_hash), ts: 15 * 86400 }));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #646)")]
+fn test_change_owner_not_notarized() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.appoint_notary(&Address::generate(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    pay_installment(&test_data);
+
+    test_data.client.change_owner();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #604)")]
+fn test_notarize_before_full_payment() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.appoint_notary(&Address::generate(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+
+    test_data.client.notarize(&BytesN::from_array(&env, &[9; 32]));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #648)")]
+fn test_change_owner_before_closing_date() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.schedule_closing(&(25 * 86400), &(2 * 86400), &1000);
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    pay_installment(&test_data);
+
+    env.ledger().set_timestamp(24 * 86400);
+    test_data.client.change_owner();
+}
+
+#[test]
+fn test_late_closing_penalty() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    assert_eq!(test_data.client.schedule_closing(&(20 * 86400), &(2 * 86400), &2000), ClosingSchedule { closing_ts: 20 * 86400, grace_period: 2 * 86400, penalty_bps: 2000 });
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    env.ledger().set_timestamp(23 * 86400);
+    test_data.client.cancel_purchase(&seller);
+    assert_eq!(test_data.sac_token.balance(&seller), 1000);
+    assert_eq!(test_data.sac_token.balance(&test_data.buyer), 49000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #647)")]
+fn test_closing_date_after_deadline() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.schedule_closing(&(CLOSING_DEADLINE + 1), &0, &0);
+}
+
+#[test]
+fn test_fiat_pricing() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let feed = MockPriceFeedClient::new(&env, &env.register(MockPriceFeed, ()));
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.set_fiat_pricing(&feed.address, &3600);
+    test_data.client.accept_offer();
+
+    feed.set_price(&2, &0);
+    pay_installment(&test_data);
+    assert_eq!(test_data.sac_token.balance(&test_data.client.address), 10000);
+
+    test_data.client.approve_inspection();
+    env.ledger().set_timestamp(86400);
+    feed.set_price(&1, &86400);
+    pay_installment(&test_data);
+
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client.change_owner();
+    assert_eq!(test_data.sac_token.balance(&seller), 50000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #650)")]
+fn test_fiat_pricing_stale_price() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let feed = MockPriceFeedClient::new(&env, &env.register(MockPriceFeed, ()));
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.set_fiat_pricing(&feed.address, &3600);
+    test_data.client.accept_offer();
+
+    feed.set_price(&1, &0);
+    env.ledger().set_timestamp(7200);
+    pay_installment(&test_data);
+}
+
+#[test]
+fn test_assign_buyer() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let spv = Address::generate(&env);
+    test_data.sac_token_admin.mint(&spv, &40000);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client.assign_buyer(&test_data.buyer, &spv);
+    assert!(env.auths().iter().any(|(address, _)| *address == seller));
+    assert_eq!(test_data.client.buyers(), vec![&env, spv.clone()]);
+    assert_eq!(test_data.client.contribution(&spv), 5000);
+    assert_eq!(test_data.client.contribution(&test_data.buyer), 0);
+
+    test_data.client.contribute(&spv, &40000);
+    test_data.client.change_owner();
+    assert_eq!(test_data.asset_contract.owner_of(&test_data.asset_id), spv);
+    assert_eq!(test_data.sac_token.balance(&seller), 45000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #608)")]
+fn test_assigned_buyer_cannot_contribute() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    test_data.client.assign_buyer(&test_data.buyer, &Address::generate(&env));
+
+    pay_installment(&test_data);
+}
+
+#[test]
+fn test_withdrawal_penalty() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Penalty(1000), &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client.cancel(&test_data.buyer, &CancelReason::Withdrawal);
+    assert_eq!(test_data.sac_token.balance(&seller), 500);
+    assert_eq!(test_data.sac_token.balance(&test_data.buyer), 49500);
+    assert_eq!(test_data.client.status(), PurchaseStatus::Cancelled);
+}
+
+#[test]
+fn test_withdrawal_after_inspection_waiver() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Penalty(1000), &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.waive_inspection(&test_data.buyer);
+
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client.cancel(&test_data.buyer, &CancelReason::Withdrawal);
+    assert_eq!(test_data.sac_token.balance(&seller), 5000);
+    assert_eq!(test_data.sac_token.balance(&test_data.buyer), 45000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #653)")]
+fn test_cancel_reason_not_met() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Penalty(1000), &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client.cancel(&seller, &CancelReason::InstallmentOverdue);
+}
+
+#[test]
+fn test_dispute_refunds_buyer() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let arbiter = Address::generate(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Forfeit, &None, &Vec::new(&env));
+    test_data.client.appoint_arbiter(&arbiter);
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client.open_dispute(&test_data.buyer, &BytesN::from_array(&env, &[3; 32]));
+    assert_eq!(test_data.client.resolve_dispute(&2000, &false), Ruling { seller_bps: 2000, transfer_ownership: false });
+    assert_eq!(test_data.sac_token.balance(&seller), 1000);
+    assert_eq!(test_data.sac_token.balance(&test_data.buyer), 49000);
+    assert_eq!(test_data.client.status(), PurchaseStatus::Cancelled);
+    assert_eq!(test_data.asset_contract.owner_of(&test_data.asset_id), seller);
+}
+
+#[test]
+fn test_dispute_transfers_ownership() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.appoint_arbiter(&Address::generate(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    pay_installment(&test_data);
+
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client.open_dispute(&seller, &BytesN::from_array(&env, &[3; 32]));
+    test_data.client.resolve_dispute(&10000, &true);
+    assert_eq!(test_data.asset_contract.owner_of(&test_data.asset_id), test_data.buyer);
+    assert_eq!(test_data.sac_token.balance(&seller), 45000);
+    assert_eq!(test_data.client.status(), PurchaseStatus::Closed);
+}
+
+#[test]
+fn test_dispute_transfers_financed_ownership() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let (arbiter, lender) = (Address::generate(&env), Address::generate(&env));
+    test_data.sac_token_admin.mint(&lender, &40000);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.appoint_arbiter(&arbiter);
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    test_data.client.finance(&lender);
+
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client.open_dispute(&seller, &BytesN::from_array(&env, &[3; 32]));
+
+    // Only the arbiter and the buyer, who pledges the house to the lender, sign the ruling
+    let args: Vec<soroban_sdk::Val> = (10000_u32, true).into_val(&env);
+    test_data.client
+        .mock_auths(&[
+            MockAuth {
+                address: &arbiter,
+                invoke: &MockAuthInvoke { contract: &test_data.client.address, fn_name: "resolve_dispute", args: args.clone(), sub_invokes: &[] },
+            },
+            MockAuth {
+                address: &test_data.buyer,
+                invoke: &MockAuthInvoke {
+                    contract: &test_data.client.address,
+                    fn_name: "resolve_dispute",
+                    args,
+                    sub_invokes: &[MockAuthInvoke {
+                        contract: &test_data.asset_contract.address,
+                        fn_name: "place_lien",
+                        args: (test_data.asset_id.clone(), lender.clone()).into_val(&env),
+                        sub_invokes: &[],
+                    }],
+                },
+            },
+        ])
+        .resolve_dispute(&10000, &true);
+
+    assert_eq!(test_data.asset_contract.owner_of(&test_data.asset_id), test_data.buyer);
+    assert_eq!(test_data.asset_contract.lien(&test_data.asset_id), Some(lender));
+    assert_eq!(test_data.sac_token.balance(&seller), 45000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #626)")]
+fn test_dispute_cannot_transfer_frozen_asset() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.appoint_arbiter(&Address::generate(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    pay_installment(&test_data);
+
+    test_data.client.open_dispute(&test_data.buyer, &BytesN::from_array(&env, &[3; 32]));
+    test_data.asset_contract.freeze(&test_data.asset_id);
+    test_data.client.resolve_dispute(&10000, &true);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #659)")]
+fn test_pay_installment_while_paused() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    test_data.client.pause();
+    pay_installment(&test_data);
+}
+
+#[test]
+fn test_pause_by_registry_admin() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.accept_offer();
+    test_data.client.pause();
+    assert_eq!(env.auths()[0].0, test_data.asset_contract.admin());
+    assert!(test_data.client.is_paused());
+
+    test_data.client.unpause();
+    assert!(!test_data.client.is_paused());
+    assert_eq!(pay_installment(&test_data), 1);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #656)")]
+fn test_change_owner_during_dispute() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.initialize(&test_data.asset_contract.address, &test_data.asset_id, &vec![&env, test_data.buyer.clone()], &test_data.inspector, &test_data.sac_token.address, &Map::new(&env), &two_installments(&env), &CLOSING_DEADLINE, &CancelPolicy::Refund, &None, &Vec::new(&env));
+    test_data.client.appoint_arbiter(&Address::generate(&env));
+    test_data.client.accept_offer();
+    pay_installment(&test_data);
+    test_data.client.approve_inspection();
+    pay_installment(&test_data);
+
+    test_data.client.open_dispute(&test_data.buyer, &BytesN::from_array(&env, &[3; 32]));
+    test_data.client.change_owner();
+}
+
+#[test]
+fn test_bidding() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let rival = Address::generate(&env);
+    test_data.sac_token_admin.mint(&rival, &50000);
+    test_data.client.open_bidding(&test_data.asset_contract.address, &test_data.asset_id, &test_data.inspector, &test_data.sac_token.address, &CLOSING_DEADLINE, &CancelPolicy::Refund);
+
+    let rival_installments = vec![&env, installment(&env, "deposit", 4000, 10 * 86400), installment(&env, "completion", 38000, 20 * 86400)];
+    test_data.client.submit_offer(&test_data.buyer, &two_installments(&env));
+    test_data.client.submit_offer(&rival, &rival_installments);
+    assert_eq!(test_data.client.offers().get(1).unwrap(), Offer { bidder: rival.clone(), installments: rival_installments });
+    assert_eq!(test_data.sac_token.balance(&test_data.client.address), 9000);
+
+    test_data.client.accept_bid(&test_data.buyer);
+    assert_eq!(test_data.client.status(), PurchaseStatus::PaymentsInProgress);
+    assert_eq!(test_data.client.buyers(), vec![&env, test_data.buyer.clone()]);
+    assert_eq!(test_data.client.paid_installments(), 1);
+    assert_eq!(test_data.client.offers().len(), 1);
+
+    assert_eq!(test_data.client.withdraw_offer(&rival), 4000);
+    assert_eq!(test_data.sac_token.balance(&rival), 50000);
+
+    test_data.client.approve_inspection();
+    pay_installment(&test_data);
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+    test_data.client.change_owner();
+    assert_eq!(test_data.asset_contract.owner_of(&test_data.asset_id), test_data.buyer);
+    assert_eq!(test_data.sac_token.balance(&seller), 45000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #636)")]
+fn test_offer_after_bid_accepted() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    test_data.client.open_bidding(&test_data.asset_contract.address, &test_data.asset_id, &test_data.inspector, &test_data.sac_token.address, &CLOSING_DEADLINE, &CancelPolicy::Refund);
+    test_data.client.submit_offer(&test_data.buyer, &two_installments(&env));
+    test_data.client.accept_bid(&test_data.buyer);
+
+    test_data.client.submit_offer(&Address::generate(&env), &two_installments(&env));
+}
+
+// Bidding up to an approved inspection: the buyer's offer escrows the down payment, is accepted two
+// days later and the house passes inspection on day 5
+fn offer_and_inspection<'a>(scenario: Scenario<'a, TestData<'a>>) -> Scenario<'a, TestData<'a>> {
+    scenario
+        .step("open bidding", |_, data| {
+            data.client.open_bidding(&data.asset_contract.address, &data.asset_id, &data.inspector, &data.sac_token.address, &CLOSING_DEADLINE, &CancelPolicy::Refund);
+        })
+        .step("submit offer", |e, data| {
+            data.client.submit_offer(&data.buyer, &two_installments(e));
+        })
+        .wait_days(2)
+        .step("accept bid", |_, data| {
+            data.client.accept_bid(&data.buyer);
+        })
+        .wait_days(3)
+        .step("approve inspection", |_, data| {
+            data.client.approve_inspection();
+        })
+}
+
+#[test]
+fn test_scenario_offer_inspection_closing() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+
+    let test_data = Scenario::new(&env, test_data)
+        .with(offer_and_inspection)
+        .wait_days(7)
+        .step("pay completion", |_, data| {
+            assert_eq!(pay_installment(data), 2);
+        })
+        .step("close", |_, data| {
+            data.client.change_owner();
+        })
+        .run();
+
+    assert_eq!(test_data.client.status(), PurchaseStatus::Closed);
+    assert_eq!(test_data.asset_contract.owner_of(&test_data.asset_id), test_data.buyer);
+    assert_eq!(test_data.sac_token.balance(&seller), 45000);
+}
+
+#[test]
+fn test_scenario_overdue_installment_refund() {
+    let env = Env::default();
+    let test_data = init_test_data(&env);
+    let seller = test_data.asset_contract.owner_of(&test_data.asset_id);
+
+    // The completion is due on day 20 and never paid
+    let test_data = Scenario::new(&env, test_data)
+        .with(offer_and_inspection)
+        .wait_days(16)
+        .step("cancel", |_, data| {
+            data.client.cancel_purchase(&data.buyer);
+        })
+        .run();
+
+    assert_eq!(test_data.client.status(), PurchaseStatus::Cancelled);
+    assert_eq!(test_data.asset_contract.owner_of(&test_data.asset_id), seller);
+    assert_eq!(test_data.sac_token.balance(&test_data.buyer), 50000);
+}
+
+#[test]
+fn error_codes_in_registry_range() {
+    use super::Error;
+
+    assert!(soroban_examples_errors::HOUSE_PURCHASE.contains(Error::ContractAlreadyInitialized as u32));
+    assert!(soroban_examples_errors::HOUSE_PURCHASE.contains(Error::TokenTransferFailed as u32));
+}
@@ -0,0 +1,54 @@
+//! Scenarios that run several of the example contracts together, deployed natively in one test
+//! environment. Each scenario lives in its own file under `tests/`; this crate only deploys the contracts.
+
+use ballot::{Ballot, BallotClient};
+use crypto_deposit::{CryptoDeposit, CryptoDepositClient};
+use investment::contract::{InvestmentContract, InvestmentContractClient};
+use soroban_sdk::{Address, Env};
+use timelock_controller::{TimelockController, TimelockControllerClient};
+
+/// Deploys a Ballot open to votes between `from` and `to`, registered by `admin`.
+pub fn deploy_ballot<'a>(e: &Env, admin: &Address, from: u64, to: u64) -> BallotClient<'a> {
+    let ballot = BallotClient::new(e, &e.register(Ballot, (admin.clone(),)));
+    ballot.configure(&from, &to);
+    ballot
+}
+
+/// Deploys a CryptoDeposit vault over `token`, without withdrawal fee, emergency delay or minimum deposit.
+pub fn deploy_crypto_deposit<'a>(e: &Env, admin: &Address, token: &Address) -> CryptoDepositClient<'a> {
+    CryptoDepositClient::new(e, &e.register(CryptoDeposit, (admin.clone(), token.clone(), 0_u32, 0_u64, 0_i128)))
+}
+
+/// Deploys an Investment project paying back `interest_rate` (in basis points) as a reverse loan in `return_months`
+/// monthly installments, claimable a week after each investment.
+pub fn deploy_investment<'a>(
+    e: &Env,
+    admin: &Address,
+    project_address: &Address,
+    token: &Address,
+    interest_rate: u32,
+    return_months: u32,
+) -> InvestmentContractClient<'a> {
+    InvestmentContractClient::new(
+        e,
+        &e.register(
+            InvestmentContract {},
+            (
+                admin.clone(),
+                project_address.clone(),
+                token.clone(),
+                interest_rate,
+                7_u64,
+                1_000_000_000_000_i128,
+                1_u32,
+                return_months,
+                1_i128,
+            ),
+        ),
+    )
+}
+
+/// Deploys a TimelockController whose calls, queued by `admin`, wait at least `min_delay` seconds.
+pub fn deploy_timelock<'a>(e: &Env, admin: &Address, min_delay: u64) -> TimelockControllerClient<'a> {
+    TimelockControllerClient::new(e, &e.register(TimelockController, (admin.clone(), min_delay)))
+}
@@ -0,0 +1,175 @@
+// Large withdrawals of an Investment project are put to a vote: the project can only take them out once
+// a Ballot has closed with the approving option as winner.
+
+use investment::Error;
+use soroban_examples_integration_tests::{deploy_ballot, deploy_investment};
+use soroban_examples_testutils::{create_env, create_token_contract, mint_to};
+use soroban_sdk::{symbol_short, testutils::{Address as _, Ledger}, Address, Env, Symbol};
+
+const THRESHOLD: i128 = 10_000;
+const YES: Symbol = symbol_short!("yes");
+const NO: Symbol = symbol_short!("no");
+
+struct Setup<'a> {
+    e: Env,
+    investment: investment::contract::InvestmentContractClient<'a>,
+    project: Address,
+    token: soroban_examples_testutils::TokenClient<'a>,
+}
+
+fn setup<'a>() -> Setup<'a> {
+    let e = create_env();
+    let (company, project, investor) = (Address::generate(&e), Address::generate(&e), Address::generate(&e));
+    let (token, token_admin) = create_token_contract(&e, &Address::generate(&e));
+
+    let investment = deploy_investment(&e, &company, &project, &token.address, 500, 4);
+    mint_to(&token_admin, &[&investor], 100_000);
+    investment.invest(&investor, &100_000);
+
+    Setup { e, investment, project, token }
+}
+
+fn run_ballot(s: &Setup, votes: &[(Symbol, Symbol)]) -> Address {
+    let ballot = deploy_ballot(&s.e, &Address::generate(&s.e), 1000, 2000);
+    s.e.ledger().set_timestamp(1500);
+    for (voter, option) in votes {
+        ballot.vote(voter, option);
+    }
+
+    s.e.ledger().set_timestamp(2001);
+    ballot.address
+}
+
+#[test]
+fn small_withdrawals_need_no_ballot() {
+    let s = setup();
+    let ballot = run_ballot(&s, &[(symbol_short!("v1"), NO)]);
+    s.investment.set_withdrawal_governance(&ballot, &THRESHOLD, &YES);
+
+    s.investment.single_withdrawn(&(THRESHOLD - 1));
+    assert_eq!(s.token.balance(&s.project), THRESHOLD - 1);
+}
+
+#[test]
+fn split_withdrawals_need_the_ballot() {
+    let s = setup();
+    let ballot = run_ballot(&s, &[(symbol_short!("v1"), NO)]);
+    s.investment.set_withdrawal_governance(&ballot, &THRESHOLD, &YES);
+
+    // Withdrawals add up: the one taking the total to the threshold needs the approval
+    s.investment.single_withdrawn(&(THRESHOLD / 2));
+    assert_eq!(s.investment.get_withdrawal_governance().unwrap().withdrawn, THRESHOLD / 2);
+    assert_eq!(
+        s.investment.try_single_withdrawn(&(THRESHOLD / 2)),
+        Err(Ok(Error::WithdrawalNotApprovedByBallot))
+    );
+    assert_eq!(s.token.balance(&s.project), THRESHOLD / 2);
+}
+
+#[test]
+fn passed_ballot_approves_one_large_withdrawal() {
+    let s = setup();
+    let ballot = run_ballot(&s, &[(symbol_short!("v1"), YES), (symbol_short!("v2"), YES), (symbol_short!("v3"), NO)]);
+    s.investment.set_withdrawal_governance(&ballot, &THRESHOLD, &YES);
+
+    s.investment.single_withdrawn(&THRESHOLD);
+    assert_eq!(s.token.balance(&s.project), THRESHOLD);
+    assert!(s.investment.get_withdrawal_governance().unwrap().used);
+    assert_eq!(s.investment.get_withdrawal_governance().unwrap().withdrawn, 0);
+
+    // The approval is spent: the next large withdrawal needs a new ballot
+    assert_eq!(
+        s.investment.try_single_withdrawn(&THRESHOLD),
+        Err(Ok(Error::WithdrawalNotApprovedByBallot))
+    );
+}
+
+#[test]
+fn rejected_ballot_blocks_large_withdrawals() {
+    let s = setup();
+    let ballot = run_ballot(&s, &[(symbol_short!("v1"), YES), (symbol_short!("v2"), NO), (symbol_short!("v3"), NO)]);
+    s.investment.set_withdrawal_governance(&ballot, &THRESHOLD, &YES);
+
+    assert_eq!(
+        s.investment.try_single_withdrawn(&THRESHOLD),
+        Err(Ok(Error::WithdrawalNotApprovedByBallot))
+    );
+}
+
+#[test]
+fn open_ballot_blocks_large_withdrawals() {
+    let s = setup();
+    let ballot = deploy_ballot(&s.e, &Address::generate(&s.e), 1000, 2000);
+    s.e.ledger().set_timestamp(1500);
+    ballot.vote(&symbol_short!("v1"), &YES);
+    s.investment.set_withdrawal_governance(&ballot.address, &THRESHOLD, &YES);
+
+    assert_eq!(
+        s.investment.try_single_withdrawn(&THRESHOLD),
+        Err(Ok(Error::WithdrawalNotApprovedByBallot))
+    );
+}
+
+#[test]
+fn relinking_a_passed_ballot_does_not_approve_again() {
+    let s = setup();
+    let ballot = run_ballot(&s, &[(symbol_short!("v1"), YES)]);
+    s.investment.set_withdrawal_governance(&ballot, &THRESHOLD, &YES);
+    s.investment.single_withdrawn(&THRESHOLD);
+
+    // Linking the same ballot again keeps its approval spent
+    let governance = s.investment.set_withdrawal_governance(&ballot, &THRESHOLD, &YES);
+    assert!(governance.used);
+    assert_eq!(
+        s.investment.try_single_withdrawn(&THRESHOLD),
+        Err(Ok(Error::WithdrawalNotApprovedByBallot))
+    );
+}
+
+#[test]
+fn a_ballot_is_linked_only_once() {
+    let s = setup();
+    let first = run_ballot(&s, &[(symbol_short!("v1"), YES)]);
+    s.investment.set_withdrawal_governance(&first, &THRESHOLD, &YES);
+    s.investment.single_withdrawn(&THRESHOLD);
+
+    let second = run_ballot(&s, &[(symbol_short!("v1"), YES)]);
+    s.investment.set_withdrawal_governance(&second, &THRESHOLD, &YES);
+    s.investment.single_withdrawn(&THRESHOLD);
+
+    assert_eq!(
+        s.investment.try_set_withdrawal_governance(&first, &THRESHOLD, &YES),
+        Err(Ok(Error::BallotAlreadyLinked))
+    );
+    assert_eq!(
+        s.investment.try_set_withdrawal_governance(&second, &THRESHOLD, &NO),
+        Err(Ok(Error::BallotAlreadyLinked))
+    );
+}
+
+#[test]
+fn governance_only_gets_stricter() {
+    let s = setup();
+    let ballot = run_ballot(&s, &[(symbol_short!("v1"), NO)]);
+    s.investment.set_withdrawal_governance(&ballot, &THRESHOLD, &YES);
+    s.investment.single_withdrawn(&(THRESHOLD / 2));
+
+    // The current approval is still pending, so the ballot cannot be swapped for another one
+    let other = run_ballot(&s, &[(symbol_short!("v1"), YES)]);
+    assert_eq!(
+        s.investment.try_set_withdrawal_governance(&other, &THRESHOLD, &YES),
+        Err(Ok(Error::BallotApprovalPending))
+    );
+    assert_eq!(
+        s.investment.try_set_withdrawal_governance(&ballot, &(THRESHOLD + 1), &YES),
+        Err(Ok(Error::InvalidGovernanceThreshold))
+    );
+
+    // Lowering the threshold keeps what was withdrawn so far
+    let governance = s.investment.set_withdrawal_governance(&ballot, &(THRESHOLD / 2 + 1), &YES);
+    assert_eq!(governance.withdrawn, THRESHOLD / 2);
+    assert_eq!(
+        s.investment.try_single_withdrawn(&1),
+        Err(Ok(Error::WithdrawalNotApprovedByBallot))
+    );
+}
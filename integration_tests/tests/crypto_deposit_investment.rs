@@ -0,0 +1,89 @@
+// CryptoDeposit invests its surplus in an Investment project while its users' deposits stay liquid;
+// the project pays the returns back to the vault, which credits the yield to the depositors.
+
+use crypto_deposit::{TOPIC_STRATEGY_DEPLOYED, TOPIC_STRATEGY_SYNCED};
+use soroban_examples_integration_tests::{deploy_crypto_deposit, deploy_investment};
+use soroban_examples_testutils::{advance_days, create_env, create_token_contract, mint_to};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Events, MockAuth, MockAuthInvoke},
+    Address, Env, IntoVal, Symbol, TryFromVal,
+};
+
+const RETURN_MONTHS: u32 = 4;
+
+fn has_event(e: &Env, contract: &Address, topic: Symbol) -> bool {
+    e.events().all().iter().any(|(address, topics, _)| {
+        address == *contract && topics.iter().any(|t| Symbol::try_from_val(e, &t).map(|s| s == topic).unwrap_or(false))
+    })
+}
+
+#[test]
+fn pooled_deposits_earn_the_project_returns() {
+    let e = create_env();
+    let (vault_admin, company, project) = (Address::generate(&e), Address::generate(&e), Address::generate(&e));
+    let (alice, bob) = (Address::generate(&e), Address::generate(&e));
+    let (token, token_admin) = create_token_contract(&e, &Address::generate(&e));
+
+    let vault = deploy_crypto_deposit(&e, &vault_admin, &token.address);
+    let investment = deploy_investment(&e, &company, &project, &token.address, 500, RETURN_MONTHS);
+
+    // Users deposit into the vault
+    mint_to(&token_admin, &[&alice], 60_000);
+    mint_to(&token_admin, &[&bob], 40_000);
+    vault.deposit(&alice, &60_000, &None, &None);
+    vault.deposit(&bob, &40_000, &None, &None);
+    assert_eq!(token.balance(&vault.address), 100_000);
+
+    // The vault admin funds the surplus the strategy invests
+    mint_to(&token_admin, &[&vault.address], 100_000);
+
+    // Only the vault admin signs: the vault authorizes the token transfer the Investment contract makes
+    // on its behalf, one call deeper, by itself
+    vault.set_strategy(&investment.address);
+    e.mock_auths(&[MockAuth {
+        address: &vault_admin,
+        invoke: &MockAuthInvoke {
+            contract: &vault.address,
+            fn_name: "deploy_to_strategy",
+            args: (100_000_i128,).into_val(&e),
+            sub_invokes: &[],
+        },
+    }]);
+    let position = vault.deploy_to_strategy(&100_000);
+    e.mock_all_auths();
+
+    assert!(has_event(&e, &vault.address, TOPIC_STRATEGY_DEPLOYED));
+    assert!(has_event(&e, &investment.address, symbol_short!("CBUPDATED")));
+    assert_eq!(token.balance(&vault.address), 100_000);
+    assert_eq!(position.positions.len(), 1);
+
+    // The company funds the reserve and pays the vault every month until the loan is returned
+    let claimable_ts = position.positions.get(0).unwrap();
+    let regular_payment = investment.get_investment(&vault.address, &claimable_ts).regular_payment;
+    advance_days(&e, 7);
+    for _ in 0..RETURN_MONTHS {
+        mint_to(&token_admin, &[&company], regular_payment);
+        investment.add_company_transfer(&regular_payment);
+        investment.process_investor_payment(&vault.address, &claimable_ts);
+        advance_days(&e, 30);
+    }
+
+    let position = vault.sync_strategy();
+    assert!(has_event(&e, &vault.address, TOPIC_STRATEGY_SYNCED));
+    assert_eq!(position.returned, regular_payment * RETURN_MONTHS as i128);
+    assert_eq!(token.balance(&vault.address), 100_000 + position.returned);
+
+    // The yield is credited to the depositors pro rata, the principal goes back to the surplus
+    let yield_amount = position.returned - position.deployed;
+    assert!(yield_amount > 0);
+    assert!(position.credited > 0 && position.credited <= yield_amount);
+    assert!((vault.balance(&alice) - (60_000 + position.credited * 60 / 100)).abs() <= 1);
+    assert!((vault.balance(&bob) - (40_000 + position.credited * 40 / 100)).abs() <= 1);
+
+    // Depositors leave with more than they brought
+    vault.withdraw(&alice, &vault.balance(&alice));
+    vault.withdraw(&bob, &vault.balance(&bob));
+    assert!(token.balance(&alice) > 60_000);
+    assert!(token.balance(&bob) > 40_000);
+}
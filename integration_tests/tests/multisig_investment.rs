@@ -0,0 +1,74 @@
+// Withdrawals of an Investment project approved by a signer set follow the same rules as the admin's: the
+// admin cannot name itself the only signer to take a large withdrawal without a vote or notice.
+
+use investment::Error;
+use soroban_examples_integration_tests::{deploy_ballot, deploy_investment};
+use soroban_examples_testutils::{advance_days, create_env, create_token_contract, mint_to, SECONDS_IN_DAY};
+use soroban_sdk::{symbol_short, testutils::{Address as _, Ledger}, vec, Address, Env, Symbol};
+
+const THRESHOLD: i128 = 10_000;
+const YES: Symbol = symbol_short!("yes");
+
+struct Setup<'a> {
+    e: Env,
+    company: Address,
+    investment: investment::contract::InvestmentContractClient<'a>,
+    project: Address,
+    token: soroban_examples_testutils::TokenClient<'a>,
+}
+
+// The company, admin of the project, makes itself the only withdrawal signer
+fn setup<'a>() -> Setup<'a> {
+    let e = create_env();
+    let (company, project, investor) = (Address::generate(&e), Address::generate(&e), Address::generate(&e));
+    let (token, token_admin) = create_token_contract(&e, &Address::generate(&e));
+
+    let investment = deploy_investment(&e, &company, &project, &token.address, 500, 4);
+    mint_to(&token_admin, &[&investor], 100_000);
+    investment.invest(&investor, &100_000);
+    investment.set_withdrawal_signers(&vec![&e, company.clone()], &1);
+
+    Setup { e, company, investment, project, token }
+}
+
+fn multisig_withdraw(s: &Setup, amount: i128) -> Result<bool, Error> {
+    s.investment.propose_withdrawal(&s.company, &amount, &(s.e.ledger().timestamp() + 3600));
+    match s.investment.try_multisig_withdrawn() {
+        Ok(result) => Ok(result.unwrap()),
+        Err(err) => Err(err.unwrap()),
+    }
+}
+
+#[test]
+fn multisig_withdrawals_need_the_ballot() {
+    let s = setup();
+    let ballot = deploy_ballot(&s.e, &Address::generate(&s.e), 1000, 2000);
+    s.e.ledger().set_timestamp(2001);
+    s.investment.set_withdrawal_governance(&ballot.address, &THRESHOLD, &YES);
+
+    assert_eq!(multisig_withdraw(&s, THRESHOLD), Err(Error::WithdrawalNotApprovedByBallot));
+
+    // Splitting it does not help either
+    s.investment.cancel_withdrawal(&s.company);
+    assert_eq!(multisig_withdraw(&s, THRESHOLD / 2), Ok(true));
+    assert_eq!(multisig_withdraw(&s, THRESHOLD / 2), Err(Error::WithdrawalNotApprovedByBallot));
+    assert_eq!(s.token.balance(&s.project), THRESHOLD / 2);
+}
+
+#[test]
+fn multisig_withdrawals_need_the_timelock_notice() {
+    let s = setup();
+    s.investment.set_withdrawal_timelock(&THRESHOLD, &(2 * SECONDS_IN_DAY));
+
+    assert_eq!(multisig_withdraw(&s, THRESHOLD), Err(Error::WithdrawalRequiresTimelock));
+    s.investment.cancel_withdrawal(&s.company);
+
+    // Once queued, the withdrawal only goes out after the delay
+    s.investment.queue_withdrawal(&THRESHOLD, &(2 * SECONDS_IN_DAY));
+    assert_eq!(multisig_withdraw(&s, THRESHOLD), Err(Error::OperationNotReady));
+    s.investment.cancel_withdrawal(&s.company);
+
+    advance_days(&s.e, 2);
+    assert_eq!(multisig_withdraw(&s, THRESHOLD), Ok(true));
+    assert_eq!(s.token.balance(&s.project), THRESHOLD);
+}
@@ -0,0 +1,91 @@
+// An Investment project administered through a TimelockController: the company hands the admin role
+// over to the timelock, and from then on every admin operation is queued and announced before it runs.
+
+use investment::Error;
+use soroban_examples_integration_tests::{deploy_investment, deploy_timelock};
+use soroban_examples_testutils::{advance_days, create_env, create_token_contract, mint_to, SECONDS_IN_DAY};
+use soroban_sdk::{testutils::{Address as _, MockAuth, MockAuthInvoke}, vec, Address, Env, IntoVal, Symbol};
+use timelock_controller::{Call, TimelockControllerClient};
+
+const MIN_DELAY: u64 = 2 * SECONDS_IN_DAY;
+
+struct Setup<'a> {
+    e: Env,
+    company: Address,
+    investor: Address,
+    investment: investment::contract::InvestmentContractClient<'a>,
+    timelock: TimelockControllerClient<'a>,
+}
+
+fn investment_call(s: &Setup, function: Symbol) -> Call {
+    Call { contract: s.investment.address.clone(), function, args: vec![&s.e], salt: 0 }
+}
+
+// The admin transfer itself goes through the timelock: the timelock accepts it in a queued call
+fn setup<'a>() -> Setup<'a> {
+    let e = create_env();
+    let (company, project, investor) = (Address::generate(&e), Address::generate(&e), Address::generate(&e));
+    let (token, token_admin) = create_token_contract(&e, &Address::generate(&e));
+    mint_to(&token_admin, &[&investor], 100_000);
+
+    let investment = deploy_investment(&e, &company, &project, &token.address, 500, 4);
+    let timelock = deploy_timelock(&e, &company, MIN_DELAY);
+    let s = Setup { e, company, investor, investment, timelock };
+
+    s.investment.propose_admin(&s.timelock.address);
+    let accept = investment_call(&s, Symbol::new(&s.e, "accept_admin"));
+    s.timelock.schedule(&accept, &MIN_DELAY);
+    advance_days(&s.e, 2);
+    s.timelock.execute(&accept);
+    assert_eq!(s.investment.admin(), s.timelock.address);
+
+    s
+}
+
+#[test]
+fn admin_operation_waits_for_the_delay() {
+    let s = setup();
+    s.investment.invest(&s.investor, &10_000);
+
+    let stop = investment_call(&s, Symbol::new(&s.e, "stop_investments"));
+    s.timelock.schedule(&stop, &MIN_DELAY);
+    advance_days(&s.e, 1);
+    assert!(s.timelock.try_execute(&stop).is_err());
+
+    // Investors still have a day of notice
+    s.investment.invest(&s.investor, &10_000);
+
+    advance_days(&s.e, 1);
+    s.timelock.execute(&stop);
+    assert_eq!(s.investment.try_invest(&s.investor, &10_000), Err(Ok(Error::ContractMustBeActiveToInvest)));
+}
+
+#[test]
+fn cancelled_operation_never_runs() {
+    let s = setup();
+
+    let stop = investment_call(&s, Symbol::new(&s.e, "stop_investments"));
+    let hash = s.timelock.schedule(&stop, &MIN_DELAY);
+    s.timelock.cancel(&hash);
+
+    advance_days(&s.e, 3);
+    assert!(s.timelock.try_execute(&stop).is_err());
+    s.investment.invest(&s.investor, &10_000);
+}
+
+#[test]
+fn company_can_no_longer_act_directly() {
+    let s = setup();
+
+    // The company signs the call, but the admin of the project is now the timelock
+    s.e.mock_auths(&[MockAuth {
+        address: &s.company,
+        invoke: &MockAuthInvoke {
+            contract: &s.investment.address,
+            fn_name: "stop_investments",
+            args: ().into_val(&s.e),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(s.investment.try_stop_investments().is_err());
+}
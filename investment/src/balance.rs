@@ -1,9 +1,11 @@
+use soroban_examples_fixed::{self as fixed, MathError, Rounding, BPS_DENOMINATOR};
 use soroban_sdk::contracttype;
 
 pub(self) const LOWER_AMOUNT_FOR_COMMISSION_REDUCTION: i128 = 100;
 pub(self) const LOWER_DIVISOR: u32 = 10;
 pub(self) const UPPER_DIVISOR: u32 = 60;
 pub(self) const AMOUNT_PER_COMMISSION_REDUCTION: i128 = 400;
+pub(self) const RESERVE_FUND_BPS: u32 = 500;
 
 pub fn calculate_rate_denominator(amount: &i128, decimals: u32) -> u32 {
     let scale_factor = 10_i128.pow(decimals);
@@ -59,23 +61,25 @@ pub struct Amount {
 }
 
 pub trait CalculateAmounts {
-    fn from_investment(amount: &i128, i_rate: &u32, decimals: u32) -> Amount;
+    fn from_investment(amount: &i128, i_rate: &u32, decimals: u32) -> Result<Amount, MathError>;
 }
 
 impl CalculateAmounts for Amount {
-    fn from_investment(amount: &i128, i_rate: &u32, decimals: u32) -> Amount {
+    fn from_investment(amount: &i128, i_rate: &u32, decimals: u32) -> Result<Amount, MathError> {
 
         let rate_denominator: u32 = calculate_rate_denominator(&amount, decimals);
 
-        let amount_to_commission = amount * (*i_rate as i128) / (rate_denominator as i128) / 100 / 100;
-        let amount_to_reserve_fund = amount * 5 / 100;
-        let amount_to_invest = amount - amount_to_commission - amount_to_reserve_fund; 
+        // Both shares round down so the rounding dust always stays in the amount to invest
+        let commission_denominator = fixed::mul(rate_denominator as i128, BPS_DENOMINATOR)?;
+        let amount_to_commission = fixed::mul_div(*amount, *i_rate as i128, commission_denominator, Rounding::Down)?;
+        let amount_to_reserve_fund = fixed::bps_of(*amount, RESERVE_FUND_BPS, Rounding::Down)?;
+        let amount_to_invest = fixed::sub(fixed::sub(*amount, amount_to_commission)?, amount_to_reserve_fund)?;
 
-        Amount {
+        Ok(Amount {
             amount_to_invest,
             amount_to_reserve_fund,
             amount_to_commission,
-        }
+        })
     }
 }
 
@@ -1,3 +1,6 @@
+// Version of the contract data layout, bumped whenever a release needs a migration
+pub const CONTRACT_VERSION: u32 = 1;
+
 // Time constants in seconds
 pub const SECONDS_IN_DAY: u64 = 86400;
 pub const SECONDS_IN_WEEK: u64 = 7 * SECONDS_IN_DAY;
@@ -1,7 +1,15 @@
+use soroban_examples_access as access;
+use soroban_examples_pausable as pausable;
+use soroban_examples_events as events;
+use soroban_examples_upgrade as upgrade;
+use soroban_examples_multisig::{self as multisig, MultisigRequest, MultisigStatus};
+use soroban_examples_ownership::{self as ownership, PendingAdmin};
+use soroban_examples_timelock::{self as timelock, OperationState, TimelockOperation};
+use soroban_examples_transfer as transfer;
 use soroban_sdk::token::TokenClient;
-use soroban_sdk::{contract, contractimpl, token, Address, Env, Map};
+use soroban_sdk::{contract, contractimpl, token, Address, BytesN, Env, Map, Symbol, Vec};
 
-use crate::constants::{SECONDS_IN_MONTH};
+use crate::constants::{CONTRACT_VERSION, SECONDS_IN_MONTH};
 use crate::balance::{
     decrement_project_balance_from_company_withdrawal,
     decrement_project_balance_from_payment_to_investor,
@@ -10,17 +18,19 @@ use crate::balance::{
     Amount, CalculateAmounts, ContractBalances,
 };
 use crate::claim::{calculate_next_claim, Claim};
+use crate::governance::{DirectWithdrawals, WithdrawalGovernance};
 use crate::data::{
-    ContractData, Error, FromNumber, State, TOPIC_CONTRACT_BALANCE_UPDATED, TOPIC_CONTRACT_STATUS_UPDATED,
+    ContractData, Error, FromNumber, State, CONTRACT_TAG, OP_WITHDRAW, TOPIC_CONTRACT_BALANCE_UPDATED, TOPIC_CONTRACT_STATUS_UPDATED,
 };
 use crate::investment::{
     build_investment, process_investment_payment, Investment, InvestmentReturnType,
     InvestmentStatus,
 };
 use crate::storage::{
-    get_balances_or_new, get_claims_map_or_new, get_contract_data, get_investment,
-    set_investment, update_claims_map, update_contract_balances,
-    update_contract_data,
+    get_balances_or_new, get_claims_map_or_new, get_contract_data, get_direct_withdrawals, get_investment,
+    get_withdrawal_governance, get_withdrawal_timelock, is_ballot_linked, set_ballot_linked, set_investment,
+    update_claims_map, update_contract_balances, update_contract_data, update_direct_withdrawals,
+    update_withdrawal_governance, update_withdrawal_timelock,
 };
 
 macro_rules! require {
@@ -42,10 +52,116 @@ fn get_token<'a>(env: &'a Env, contract_data: &ContractData) -> TokenClient<'a>
     token::Client::new(env, &contract_data.token)
 }
 
-fn require_admin(env: &Env) -> ContractData {
-    let contract_data = get_contract_data(env);
-    contract_data.admin.require_auth();
-    contract_data
+// The admin is set on construction, so it can only be missing once the instance has expired
+fn require_admin(env: &Env) -> (Address, ContractData) {
+    let admin = access::require_admin(env).unwrap_or_else(|_| panic!("Contract data has expired"));
+    (admin, get_contract_data(env))
+}
+
+// Emergency stop: no funds move in or out of the contract while it is paused
+fn when_not_paused(env: &Env) -> Result<(), Error> {
+    pausable::when_not_paused(env).map_err(|_| Error::ContractPaused)
+}
+
+// Moves `amount` of the contract tokens, failing with the contract errors instead of the token ones
+fn transfer_tokens(tk: &TokenClient, from: &Address, to: &Address, amount: i128) -> Result<(), Error> {
+    tk.try_transfer(from, to, &amount)
+        .map_err(|_| Error::RecipientCannotReceivePayment)?
+        .map_err(|_| Error::InvalidPaymentData)?;
+    Ok(())
+}
+
+// Books a new investment of `addr`, funded by `addr` itself or, when a spender is given, through
+// the allowance `addr` granted to it
+fn make_investment(env: &Env, addr: &Address, amount: i128, spender: Option<&Address>) -> Result<Investment, Error> {
+    when_not_paused(env)?;
+    let mut contract_data: ContractData = get_contract_data(env);
+    let tk = get_token(env, &contract_data);
+
+    require!(
+        amount >= contract_data.min_per_investment, Error::AmountLessThanMinimum,
+        contract_data.state == State::Actve, Error::ContractMustBeActiveToInvest,
+        tk.balance(addr) >= amount,Error::AddressInsufficientBalance
+    );
+
+
+    let token_decimals = tk.decimals();
+    let amounts: Amount = Amount::from_investment(&amount, &contract_data.interest_rate, token_decimals)?;
+    
+    // Validate goal before transfer
+    let mut contract_balances = get_balances_or_new(env);
+    let invested_amount = amounts.amount_to_invest + amounts.amount_to_reserve_fund;
+    require!(
+        contract_balances.received_so_far + invested_amount <= contract_data.goal,
+        Error::WouldExceedGoal
+    );
+
+    recalculate_contract_balances_from_investment(&mut contract_balances, &amounts);
+    update_contract_balances(env, &contract_balances);
+
+    let addr_investment: Investment = build_investment(env, &contract_data, &amount, token_decimals)?;
+    update_investment(env, addr, &addr_investment);
+
+    if contract_balances.received_so_far >= contract_data.goal {
+        contract_data.state = State::FundsReached;
+        update_contract_data(env, &contract_data);
+        events::publish(env, CONTRACT_TAG, TOPIC_CONTRACT_STATUS_UPDATED, contract_data.state);
+    }
+
+    match spender {
+        Some(spender) => transfer::pull_from(env, &contract_data.token, spender, addr, amount)?,
+        None => transfer_tokens(&tk, addr, &env.current_contract_address(), amount)?,
+    }
+
+    events::publish(env, CONTRACT_TAG, TOPIC_CONTRACT_BALANCE_UPDATED, contract_balances);
+
+    Ok(addr_investment)
+}
+
+// Transfers `amount` from the project balance to the project address
+fn withdraw_to_project(env: &Env, contract_data: &ContractData, amount: i128) -> Result<(), Error> {
+    let mut contract_balances: ContractBalances = get_balances_or_new(env);
+    require!(contract_balances.project >= amount, Error::ContractInsufficientBalance);
+
+    // Checks-effects-interactions: the balance is debited before the token is called
+    decrement_project_balance_from_company_withdrawal(&mut contract_balances, &amount);
+    update_contract_balances(env, &contract_balances);
+
+    let tk = get_token(env, contract_data);
+    transfer_tokens(&tk, &env.current_contract_address(), &contract_data.project_address, amount)?;
+    events::publish(env, CONTRACT_TAG, TOPIC_CONTRACT_BALANCE_UPDATED, contract_balances);
+    Ok(())
+}
+
+// Withdrawals add up towards the governance threshold: the one reaching it spends the approval of the
+// linked ballot and the count starts over, so splitting a large withdrawal does not get around the vote
+fn spend_ballot_approval(env: &Env, amount: i128) -> Result<(), Error> {
+    if let Some(mut governance) = get_withdrawal_governance(env) {
+        if governance.withdrawn + amount >= governance.threshold {
+            require!(governance.is_approved(env), Error::WithdrawalNotApprovedByBallot);
+            governance.used = true;
+            governance.withdrawn = 0;
+        } else {
+            governance.withdrawn += amount;
+        }
+        update_withdrawal_governance(env, &governance);
+    }
+
+    Ok(())
+}
+
+// Withdrawals that skip the timelock add up over each timelock delay and must stay below the threshold
+// together, so splitting a large withdrawal does not take it out without notice
+fn count_direct_withdrawal(env: &Env, threshold: i128, amount: i128) -> Result<(), Error> {
+    let now = env.ledger().timestamp();
+    let mut withdrawals = get_direct_withdrawals(env)
+        .filter(|withdrawals| now < withdrawals.start + timelock::min_delay(env))
+        .unwrap_or(DirectWithdrawals { start: now, withdrawn: 0 });
+
+    require!(withdrawals.withdrawn + amount < threshold, Error::WithdrawalRequiresTimelock);
+    withdrawals.withdrawn += amount;
+    update_direct_withdrawals(env, &withdrawals);
+    Ok(())
 }
 
 fn update_investment(e: &Env, addr: &Address, investment: &Investment) {
@@ -114,7 +230,6 @@ impl InvestmentContract {
             claim_block_days,
             token: token_addr,
             project_address,
-            admin: admin_addr,
             state: State::Actve,
             return_type: ret_type,
             return_months,
@@ -122,6 +237,8 @@ impl InvestmentContract {
             goal,
         };
 
+        access::set_admin(&env, &admin_addr);
+        upgrade::set_version(&env, CONTRACT_VERSION);
         update_contract_data(&env, &contract_data);
         Ok(())
     }
@@ -150,8 +267,10 @@ impl InvestmentContract {
     /// * `AddressInvestmentNextTransferNotClaimableYet` if less than a month has passed since last payment.
     /// * `ContractInsufficientBalance` if reserve balance is insufficient.
     /// * `RecipientCannotReceivePayment` or `InvalidPaymentData` if token transfer fails.
+    /// * `ContractPaused` if the contract is paused.
     pub fn process_investor_payment(env: Env, addr: Address, ts: u64) -> Result<Investment, Error> {
-        let contract_data = require_admin(&env);
+        let (_, contract_data) = require_admin(&env);
+        when_not_paused(&env)?;
 
         let mut investment = get_investment(&env, &addr, ts).ok_or(Error::AddressHasNotInvested)?;
 
@@ -166,16 +285,13 @@ impl InvestmentContract {
         let amount_to_transfer: i128 = process_investment_payment(&env, &mut investment, &contract_data);
 
         require!(amount_to_transfer <= contract_balances.reserve, Error::ContractInsufficientBalance);
-        tk.try_transfer(&env.current_contract_address(), &addr, &amount_to_transfer)
-            .map_err(|_| Error::RecipientCannotReceivePayment)?
-            .map_err(|_| Error::InvalidPaymentData)?
-        ;
 
         update_investment(&env, &addr, &investment);
         decrement_project_balance_from_payment_to_investor(&mut contract_balances, &amount_to_transfer);
         update_contract_balances(&env, &contract_balances);
+        transfer_tokens(&tk, &env.current_contract_address(), &addr, amount_to_transfer)?;
 
-        env.events().publish((TOPIC_CONTRACT_BALANCE_UPDATED,), contract_balances);
+        events::publish(&env, CONTRACT_TAG, TOPIC_CONTRACT_BALANCE_UPDATED, contract_balances);
         Ok(investment)
     }
 
@@ -202,48 +318,45 @@ impl InvestmentContract {
     /// * `ContractMustBeActiveToInvest` if contract is paused or funding is reached.
     /// * `AddressInsufficientBalance` if investor doesn't have enough tokens.
     /// * `WouldExceedGoal` if this investment would exceed the funding goal.
+    /// * `ContractPaused` if the contract is paused.
     pub fn invest(env: Env, addr: Address, amount: i128) -> Result<Investment, Error> {
         addr.require_auth();
-        let mut contract_data: ContractData = get_contract_data(&env);
-        let tk = get_token(&env, &contract_data);
-
-        require!(
-            amount >= contract_data.min_per_investment, Error::AmountLessThanMinimum,
-            contract_data.state == State::Actve, Error::ContractMustBeActiveToInvest,
-            tk.balance(&addr) >= amount,Error::AddressInsufficientBalance
-        );
-
-
-        let token_decimals = tk.decimals();
-        let amounts: Amount = Amount::from_investment(&amount, &contract_data.interest_rate, token_decimals);
-        
-        // Validate goal before transfer
-        let mut contract_balances = get_balances_or_new(&env);
-        let invested_amount = amounts.amount_to_invest + amounts.amount_to_reserve_fund;
-        require!(
-            contract_balances.received_so_far + invested_amount <= contract_data.goal,
-            Error::WouldExceedGoal
-        );
-
-        tk.try_transfer(&addr, &env.current_contract_address(), &amount)
-            .map_err(|_| Error::RecipientCannotReceivePayment)?
-            .map_err(|_| Error::InvalidPaymentData)?;
-
-        recalculate_contract_balances_from_investment(&mut contract_balances, &amounts);
-        update_contract_balances(&env, &contract_balances);
-
-        let addr_investment: Investment = build_investment(&env, &contract_data, &amount, token_decimals);
-        update_investment(&env, &addr, &addr_investment);
-
-        if contract_balances.received_so_far >= contract_data.goal {
-            contract_data.state = State::FundsReached;
-            update_contract_data(&env, &contract_data);
-            env.events().publish((TOPIC_CONTRACT_STATUS_UPDATED,), contract_data.state);
-        }
+        make_investment(&env, &addr, amount, None)
+    }
 
-        env.events().publish((TOPIC_CONTRACT_BALANCE_UPDATED,), contract_balances);
+    /// Invests `amount` on behalf of `addr`, pulled through the token allowance `addr` granted to `spender`.
+    ///
+    /// Lets smart wallets and routers invest for their users: only `spender` authorizes the call.
+    /// The investment is recorded under `addr`, who receives the returns.
+    ///
+    /// # Errors
+    ///
+    /// Same as `invest`, plus `InsufficientAllowance` if `addr` has not approved `spender` for `amount`.
+    pub fn invest_from(env: Env, spender: Address, addr: Address, amount: i128) -> Result<Investment, Error> {
+        spender.require_auth();
+        make_investment(&env, &addr, amount, Some(&spender))
+    }
 
-        Ok(addr_investment)
+    /// Retrieves an investment made by an address.
+    ///
+    /// Lets investors (including other contracts) follow the state of their position, such as the
+    /// amount already paid back to them.
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - The execution environment.
+    /// * `addr` - The investor's address.
+    /// * `ts` - The claimable timestamp identifying the specific investment.
+    ///
+    /// # Returns
+    ///
+    /// * The `Investment` object.
+    ///
+    /// # Errors
+    ///
+    /// * `AddressHasNotInvested` if no investment exists for this address and timestamp.
+    pub fn get_investment(env: Env, addr: Address, ts: u64) -> Result<Investment, Error> {
+        get_investment(&env, &addr, ts).ok_or(Error::AddressHasNotInvested)
     }
 
     /// Retrieves the current contract balances (admin only).
@@ -283,14 +396,55 @@ impl InvestmentContract {
     ///
     /// * `ContractMustBeActiveToBePaused` if the contract is not in 'Active' state.
     pub fn stop_investments(env: Env) -> Result<bool, Error> {
-        let mut contract_data = require_admin(&env);
+        let (_, mut contract_data) = require_admin(&env);
         require!(contract_data.state == State::Actve, Error::ContractMustBeActiveToBePaused);
         contract_data.state = State::Paused;
         update_contract_data(&env, &contract_data);
+        events::publish(&env, CONTRACT_TAG, TOPIC_CONTRACT_STATUS_UPDATED, contract_data.state);
 
         Ok(true)
     }
 
+    /// Emergency stop (admin only).
+    ///
+    /// Unlike `stop_investments`, which only closes the contract to new investors, pausing blocks every
+    /// movement of funds: investments, investor payments and project withdrawals.
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - The execution environment.
+    ///
+    /// # Returns
+    ///
+    /// * `true` on success.
+    pub fn pause(env: Env) -> bool {
+        let (admin, _) = require_admin(&env);
+        pausable::pause(&env, &admin);
+
+        true
+    }
+
+    /// Lifts the emergency stop (admin only).
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - The execution environment.
+    ///
+    /// # Returns
+    ///
+    /// * `true` on success.
+    pub fn unpause(env: Env) -> bool {
+        let (admin, _) = require_admin(&env);
+        pausable::unpause(&env, &admin);
+
+        true
+    }
+
+    /// Returns whether the emergency stop is active.
+    pub fn is_paused(env: Env) -> bool {
+        pausable::is_paused(&env)
+    }
+
     /// Resumes accepting new investments.
     ///
     /// Allows the admin to change the contract state back to 'Active', which allows new investments again.
@@ -303,10 +457,11 @@ impl InvestmentContract {
     ///
     /// * Returns `true` on success, or an error if something goes wrong.
     pub fn restart_investments(env: Env) -> Result<bool, Error> {
-        let mut contract_data = require_admin(&env);
+        let (_, mut contract_data) = require_admin(&env);
         require!(contract_data.state == State::Paused, Error::ContractMustBePausedToRestartAgain);
         contract_data.state = State::Actve;
         update_contract_data(&env, &contract_data);
+        events::publish(&env, CONTRACT_TAG, TOPIC_CONTRACT_STATUS_UPDATED, contract_data.state);
 
         Ok(true)
     }
@@ -327,32 +482,327 @@ impl InvestmentContract {
     ///
     /// # Errors
     ///
+    /// * `WithdrawalRequiresMultisig` if withdrawal signers are configured.
+    /// * `WithdrawalRequiresTimelock` if the amount, added to the other withdrawals made without the timelock within
+    ///   its delay, reaches the timelock threshold.
+    /// * `WithdrawalNotApprovedByBallot` if the amount, added to the withdrawals made since the last approval was spent,
+    ///   reaches the governance threshold and the ballot has not approved it.
     /// * `ContractInsufficientBalance` if project balance is less than the requested amount.
     /// * `RecipientCannotReceivePayment` or `InvalidPaymentData` if the transfer fails.
+    /// * `ContractPaused` if the contract is paused.
     pub fn single_withdrawn(env: Env, amount: i128) -> Result<bool, Error> {
-        let contract_data = require_admin(&env);
+        let (_, contract_data) = require_admin(&env);
+        when_not_paused(&env)?;
+        require!(!multisig::is_configured(&env), Error::WithdrawalRequiresMultisig);
 
-        let mut contract_balances: ContractBalances = get_balances_or_new(&env);
-        require!(contract_balances.project >= amount, Error::ContractInsufficientBalance);
+        if let Some(threshold) = get_withdrawal_timelock(&env) {
+            count_direct_withdrawal(&env, threshold, amount)?;
+        }
 
-        let tk = get_token(&env, &contract_data);
+        spend_ballot_approval(&env, amount)?;
+        withdraw_to_project(&env, &contract_data, amount)?;
+        Ok(true)
+    }
 
-        // Verify the transfer can be completed
-        tk.try_transfer(
-            &env.current_contract_address(),
-            &contract_data.project_address,
-            &amount,
-        )
-        .map_err(|_| Error::RecipientCannotReceivePayment)?
-        .map_err(|_| Error::InvalidPaymentData)?;
-        
-        decrement_project_balance_from_company_withdrawal(&mut contract_balances, &amount);
-        update_contract_balances(&env, &contract_balances);
-        env.events().publish((TOPIC_CONTRACT_BALANCE_UPDATED,), contract_balances);
+    /// Puts large withdrawals to a vote (admin only).
+    ///
+    /// From now on, withdrawals adding up to `threshold` or more need `ballot` (a deployed Ballot
+    /// contract) to have closed with `approving_candidate` as winner. Each approval pays for the
+    /// withdrawal reaching the threshold, after which the count starts over and reaching it again
+    /// needs a new ballot set through this function.
+    ///
+    /// The governance can only get stricter. Calling this again with the current ballot can only lower
+    /// the threshold, and keeps the approval state and the amount withdrawn so far. A new ballot can
+    /// only be linked once the current approval has been spent, and a ballot that was ever linked can
+    /// never be linked again, so a passed ballot cannot approve more than one large withdrawal. The
+    /// contract cannot tell a genuine Ballot from another contract answering `winner`: investors
+    /// should check every ballot the admin links.
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - The execution environment.
+    /// * `ballot` - The Ballot contract deciding the withdrawal.
+    /// * `threshold` - The amount from which withdrawals need the ballot approval.
+    /// * `approving_candidate` - The ballot option that approves the withdrawal.
+    ///
+    /// # Returns
+    ///
+    /// * The stored governance settings.
+    ///
+    /// # Errors
+    ///
+    /// * `InvalidGovernanceThreshold` if the threshold is not positive, or higher than the current one.
+    /// * `BallotAlreadyLinked` if the ballot was linked before, or is the current one with another approving candidate.
+    /// * `BallotApprovalPending` if the current ballot has not approved a withdrawal yet.
+    pub fn set_withdrawal_governance(env: Env, ballot: Address, threshold: i128, approving_candidate: Symbol) -> Result<WithdrawalGovernance, Error> {
+        require_admin(&env);
+        let current = get_withdrawal_governance(&env);
+        let current_threshold = current.as_ref().map(|governance| governance.threshold).unwrap_or(i128::MAX);
+        require!(threshold > 0 && threshold <= current_threshold, Error::InvalidGovernanceThreshold);
+
+        let governance = match current {
+            Some(mut governance) if governance.ballot == ballot => {
+                require!(governance.approving_candidate == approving_candidate, Error::BallotAlreadyLinked);
+                governance.threshold = threshold;
+                governance
+            }
+            current => {
+                require!(!is_ballot_linked(&env, &ballot), Error::BallotAlreadyLinked);
+                require!(current.as_ref().map(|governance| governance.used).unwrap_or(true), Error::BallotApprovalPending);
+                set_ballot_linked(&env, &ballot);
+
+                // The withdrawals made since the last approval keep counting towards the threshold
+                let withdrawn = current.map(|governance| governance.withdrawn).unwrap_or(0);
+                WithdrawalGovernance { ballot, threshold, approving_candidate, used: false, withdrawn }
+            }
+        };
+
+        update_withdrawal_governance(&env, &governance);
+        Ok(governance)
+    }
+
+    /// Returns the ballot governing large withdrawals, if any.
+    pub fn get_withdrawal_governance(env: Env) -> Option<WithdrawalGovernance> {
+        get_withdrawal_governance(&env)
+    }
+
+    /// Puts large withdrawals behind a timelock (admin only).
+    ///
+    /// From now on, `single_withdrawn` rejects withdrawals adding up to `threshold` or more within
+    /// `min_delay` seconds: the admin queues them with `queue_withdrawal` and executes them with
+    /// `execute_withdrawal` once the delay has passed, which gives investors at least `min_delay`
+    /// seconds of notice through the `TLSCHED` event.
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - The execution environment.
+    /// * `threshold` - The amount from which withdrawals need to be queued.
+    /// * `min_delay` - The minimum time, in seconds, between queuing and executing a withdrawal.
+    ///
+    /// # Errors
+    ///
+    /// * `InvalidGovernanceThreshold` if the threshold is not positive, or higher than the current one.
+    /// * `TimelockDelayTooShort` if the delay is shorter than the current one.
+    pub fn set_withdrawal_timelock(env: Env, threshold: i128, min_delay: u64) -> Result<bool, Error> {
+        require_admin(&env);
+
+        // Once configured, the timelock can only get stricter: otherwise the admin could lift it
+        // right before a withdrawal
+        let current = get_withdrawal_timelock(&env).unwrap_or(i128::MAX);
+        require!(
+            threshold > 0 && threshold <= current, Error::InvalidGovernanceThreshold,
+            min_delay >= timelock::min_delay(&env), Error::TimelockDelayTooShort
+        );
+
+        update_withdrawal_timelock(&env, &threshold);
+        timelock::set_min_delay(&env, min_delay);
+        Ok(true)
+    }
+
+    /// Returns the amount from which withdrawals need to be queued, if the timelock is configured.
+    pub fn get_withdrawal_timelock(env: Env) -> Option<i128> {
+        get_withdrawal_timelock(&env)
+    }
+
+    /// Queues the withdrawal of `amount` to the project address, executable after `delay` seconds (admin only).
+    ///
+    /// Returns the hash identifying the queued withdrawal.
+    ///
+    /// # Errors
+    ///
+    /// * `TimelockNotConfigured` if `set_withdrawal_timelock` has not been called.
+    /// * `TimelockDelayTooShort` if `delay` is shorter than the minimum delay.
+    /// * `OperationAlreadyQueued` if a withdrawal of the same amount is already queued.
+    pub fn queue_withdrawal(env: Env, amount: i128, delay: u64) -> Result<BytesN<32>, Error> {
+        require_admin(&env);
+        get_withdrawal_timelock(&env).ok_or(Error::TimelockNotConfigured)?;
+        Ok(timelock::schedule(&env, &OP_WITHDRAW, &amount, delay)?)
+    }
 
+    /// Executes a queued withdrawal of `amount` once its delay has passed (admin only).
+    ///
+    /// A withdrawal reaching the governance threshold still needs the ballot approval.
+    ///
+    /// # Errors
+    ///
+    /// * `OperationNotQueued` if no withdrawal of `amount` is queued.
+    /// * `OperationNotReady` if its delay has not passed yet.
+    /// * Same as `single_withdrawn` otherwise.
+    pub fn execute_withdrawal(env: Env, amount: i128) -> Result<bool, Error> {
+        let (_, contract_data) = require_admin(&env);
+        when_not_paused(&env)?;
+        require!(!multisig::is_configured(&env), Error::WithdrawalRequiresMultisig);
+
+        timelock::execute(&env, &OP_WITHDRAW, &amount)?;
+        spend_ballot_approval(&env, amount)?;
+        withdraw_to_project(&env, &contract_data, amount)?;
         Ok(true)
     }
 
+    /// Drops the queued withdrawal `hash` (admin only).
+    pub fn cancel_queued_withdrawal(env: Env, hash: BytesN<32>) -> Result<bool, Error> {
+        require_admin(&env);
+        timelock::cancel(&env, &hash)?;
+        Ok(true)
+    }
+
+    /// Returns the queued withdrawal `hash`, if any.
+    pub fn get_queued_withdrawal(env: Env, hash: BytesN<32>) -> Option<TimelockOperation> {
+        timelock::get_operation(&env, &hash)
+    }
+
+    /// Sets the signers that approve project withdrawals (admin only).
+    ///
+    /// Once a signer set is configured, `single_withdrawn` is disabled and every withdrawal must be
+    /// proposed by a signer and approved by `threshold` of them. Calling it again replaces the set.
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - The execution environment.
+    /// * `signers` - The addresses allowed to propose and approve withdrawals.
+    /// * `threshold` - The number of approvals a withdrawal needs.
+    ///
+    /// # Returns
+    ///
+    /// * `true` on success.
+    ///
+    /// # Errors
+    ///
+    /// * `InvalidSignerSet` if the threshold is 0 or greater than the number of signers, or a signer repeats.
+    pub fn set_withdrawal_signers(env: Env, signers: Vec<Address>, threshold: u32) -> Result<bool, Error> {
+        require_admin(&env);
+        multisig::set_signers(&env, &signers, threshold)?;
+
+        Ok(true)
+    }
+
+    /// Proposes a withdrawal from the project balance to the project address (signers only).
+    ///
+    /// The proposal counts as the first approval and must gather the rest before `expires_at`.
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - The execution environment.
+    /// * `signer` - The proposing signer (requires authentication).
+    /// * `amount` - The amount to withdraw.
+    /// * `expires_at` - The timestamp from which the request can no longer be approved or executed.
+    ///
+    /// # Returns
+    ///
+    /// * The created `MultisigRequest`.
+    ///
+    /// # Errors
+    ///
+    /// * `ContractInsufficientBalance` if project balance is less than the requested amount.
+    /// * `AddressIsNotSigner` if the signer is not part of the signer set.
+    /// * `MultisigRequestPending` if another withdrawal request is still open.
+    /// * `MultisigRequestExpired` if `expires_at` has already passed.
+    pub fn propose_withdrawal(env: Env, signer: Address, amount: i128, expires_at: u64) -> Result<MultisigRequest, Error> {
+        require!(get_balances_or_new(&env).project >= amount, Error::ContractInsufficientBalance);
+
+        let request = multisig::propose(&env, &OP_WITHDRAW, &signer, &amount, expires_at)?;
+        Ok(request)
+    }
+
+    /// Approves the pending withdrawal request (signers only).
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - The execution environment.
+    /// * `signer` - The approving signer (requires authentication).
+    ///
+    /// # Returns
+    ///
+    /// * The request status after the approval (`Approved` once the threshold is reached).
+    ///
+    /// # Errors
+    ///
+    /// * `AddressIsNotSigner` if the signer is not part of the signer set.
+    /// * `MultisigRequestNotFound` if there is no withdrawal request.
+    /// * `MultisigRequestExpired` if the request has expired.
+    /// * `AddressAlreadyApproved` if the signer already approved the request.
+    pub fn approve_withdrawal(env: Env, signer: Address) -> Result<MultisigStatus, Error> {
+        let status = multisig::approve(&env, &OP_WITHDRAW, &signer)?;
+        Ok(status)
+    }
+
+    /// Drops the pending withdrawal request (signers only).
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - The execution environment.
+    /// * `signer` - The cancelling signer (requires authentication).
+    ///
+    /// # Returns
+    ///
+    /// * `true` on success.
+    ///
+    /// # Errors
+    ///
+    /// * `AddressIsNotSigner` if the signer is not part of the signer set.
+    /// * `MultisigRequestNotFound` if there is no withdrawal request.
+    pub fn cancel_withdrawal(env: Env, signer: Address) -> Result<bool, Error> {
+        multisig::cancel(&env, &OP_WITHDRAW, &signer)?;
+        Ok(true)
+    }
+
+    /// Executes an approved withdrawal request, transferring the proposed amount to the project address.
+    ///
+    /// Anyone can trigger it once the request has gathered enough approvals.
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - The execution environment.
+    ///
+    /// # Returns
+    ///
+    /// * `true` on success.
+    ///
+    /// # Errors
+    ///
+    /// * `MultisigNotConfigured` if no signer set is configured.
+    /// * `MultisigRequestNotFound` if there is no withdrawal request.
+    /// * `NotEnoughApprovals` if the threshold has not been reached.
+    /// * `MultisigRequestExpired` if the request has expired.
+    /// * `ContractInsufficientBalance` if project balance is less than the approved amount.
+    /// * `ContractPaused` if the contract is paused.
+    /// * `WithdrawalRequiresTimelock` if the amount reaches the timelock threshold and was not queued with `queue_withdrawal`.
+    /// * `OperationNotReady` if the amount was queued and its delay has not passed yet.
+    /// * `WithdrawalNotApprovedByBallot` if the amount reaches the governance threshold and the ballot has not approved it.
+    pub fn multisig_withdrawn(env: Env) -> Result<bool, Error> {
+        when_not_paused(&env)?;
+        let contract_data = get_contract_data(&env);
+        let amount: i128 = multisig::execute(&env, &OP_WITHDRAW)?;
+
+        // The signers pick who approves a withdrawal, not the rules it follows: a large one still needs
+        // the timelock notice, given by queuing it with `queue_withdrawal`, and the ballot approval
+        if let Some(threshold) = get_withdrawal_timelock(&env) {
+            let hash = timelock::hash_operation(&env, &OP_WITHDRAW, &amount);
+            if timelock::state(&env, &hash) == OperationState::Unset {
+                count_direct_withdrawal(&env, threshold, amount)?;
+            } else {
+                timelock::execute(&env, &OP_WITHDRAW, &amount)?;
+            }
+        }
+
+        spend_ballot_approval(&env, amount)?;
+        withdraw_to_project(&env, &contract_data, amount)?;
+        Ok(true)
+    }
+
+    /// Retrieves the pending withdrawal request, if any.
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - The execution environment.
+    ///
+    /// # Returns
+    ///
+    /// * The `MultisigRequest`, or `None` if there is no request.
+    pub fn get_withdrawal_request(env: Env) -> Option<MultisigRequest> {
+        multisig::get_request(&env, &OP_WITHDRAW)
+    }
+
     /// Calculates additional funds needed in reserve balance (admin only).
     ///
     /// Analyzes upcoming payment claims (within the next week) and compares them against
@@ -407,18 +857,16 @@ impl InvestmentContract {
     ///
     /// * `AddressInsufficientBalance` if admin doesn't have enough tokens.
     pub fn add_company_transfer(env: Env, amount: i128) -> Result<bool, Error> {
-        let contract_data = require_admin(&env);
+        let (admin, contract_data) = require_admin(&env);
 
         let tk = get_token(&env, &contract_data);
-        require!(tk.balance(&contract_data.admin) >= amount, Error::AddressInsufficientBalance);
-        tk.try_transfer(&contract_data.admin, &env.current_contract_address(), &amount)
-            .map_err(|_| Error::RecipientCannotReceivePayment)?
-            .map_err(|_| Error::InvalidPaymentData)?;
+        require!(tk.balance(&admin) >= amount, Error::AddressInsufficientBalance);
 
         let mut contract_balances = get_balances_or_new(&env);
         increment_reserve_balance_from_company_contribution(&mut contract_balances, &amount);
         update_contract_balances(&env, &contract_balances);
-        env.events().publish((TOPIC_CONTRACT_BALANCE_UPDATED,), contract_balances);
+        transfer_tokens(&tk, &admin, &env.current_contract_address(), amount)?;
+        events::publish(&env, CONTRACT_TAG, TOPIC_CONTRACT_BALANCE_UPDATED, contract_balances);
 
         Ok(true)
     }
@@ -451,8 +899,118 @@ impl InvestmentContract {
 
         move_from_project_balance_to_reserve_balance(&mut contract_balances, &amount);
         update_contract_balances(&env, &contract_balances);
-        env.events().publish((TOPIC_CONTRACT_BALANCE_UPDATED,), contract_balances);
+        events::publish(&env, CONTRACT_TAG, TOPIC_CONTRACT_BALANCE_UPDATED, contract_balances);
 
         Ok(true)
     }
+
+    /// Replaces the contract code with the already uploaded wasm `new_wasm_hash` (admin only).
+    ///
+    /// Storage is kept as is and the constructor is not run again; the admin must call `migrate`
+    /// on the new code right after.
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - The execution environment.
+    /// * `new_wasm_hash` - The hash of the uploaded wasm.
+    ///
+    /// # Returns
+    ///
+    /// * `true` on success.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> bool {
+        require_admin(&env);
+        upgrade::upgrade(&env, &new_wasm_hash);
+
+        true
+    }
+
+    /// Brings the stored data up to the version of the running code (admin only).
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - The execution environment.
+    ///
+    /// # Returns
+    ///
+    /// * The version the data was migrated from.
+    ///
+    /// # Errors
+    ///
+    /// * `AlreadyMigrated` if the data is already at the version of the running code.
+    pub fn migrate(env: Env) -> Result<u32, Error> {
+        require_admin(&env);
+
+        // Version 1 is the first versioned layout, so there is nothing to convert yet
+        upgrade::migrate(&env, CONTRACT_VERSION, |_env, _from| {}).map_err(|_| Error::AlreadyMigrated)
+    }
+
+    /// Returns the version of the stored data.
+    pub fn version(env: Env) -> u32 {
+        upgrade::version(&env)
+    }
+
+    /// Returns the current admin.
+    pub fn admin(env: Env) -> Address {
+        access::get_admin(&env).unwrap_or_else(|| panic!("Contract data has expired"))
+    }
+
+    /// Proposes `new_admin` as the next admin (admin only).
+    ///
+    /// The change only takes effect once `new_admin` calls `accept_admin`. A new proposal replaces
+    /// the pending one.
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - The execution environment.
+    /// * `new_admin` - The proposed admin.
+    ///
+    /// # Returns
+    ///
+    /// * The pending proposal, which expires after a week.
+    pub fn propose_admin(env: Env, new_admin: Address) -> Result<PendingAdmin, Error> {
+        Ok(ownership::propose_admin(&env, &new_admin)?)
+    }
+
+    /// Makes the proposed address the admin. Requires its signature.
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - The execution environment.
+    ///
+    /// # Returns
+    ///
+    /// * `true` on success.
+    ///
+    /// # Errors
+    ///
+    /// * `NoPendingAdmin` if there is no proposal.
+    /// * `AdminProposalExpired` if the proposal has expired.
+    /// * `StaleAdminProposal` if the admin who made the proposal is no longer the admin.
+    pub fn accept_admin(env: Env) -> Result<bool, Error> {
+        ownership::accept_admin(&env)?;
+        Ok(true)
+    }
+
+    /// Withdraws the pending admin proposal (admin only).
+    ///
+    /// # Parameters
+    ///
+    /// * `env` - The execution environment.
+    ///
+    /// # Returns
+    ///
+    /// * `true` on success.
+    ///
+    /// # Errors
+    ///
+    /// * `NoPendingAdmin` if there is no proposal.
+    pub fn cancel_admin_transfer(env: Env) -> Result<bool, Error> {
+        ownership::cancel_admin_transfer(&env)?;
+        Ok(true)
+    }
+
+    /// Returns the pending admin proposal, if any.
+    pub fn pending_admin(env: Env) -> Option<PendingAdmin> {
+        ownership::pending_admin(&env)
+    }
 }
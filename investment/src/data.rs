@@ -1,3 +1,8 @@
+use soroban_examples_fixed::MathError;
+use soroban_examples_multisig::MultisigError;
+use soroban_examples_ownership::OwnershipError;
+use soroban_examples_timelock::TimelockError;
+use soroban_examples_transfer::TransferError;
 use soroban_sdk::{contracterror, contracttype, symbol_short, Address, Symbol};
 use crate::investment::InvestmentReturnType;
 
@@ -8,9 +13,14 @@ pub trait FromNumber {
         N: Into<u32>;
 }
 
+// Topics follow the workspace event schema: (CONTRACT_TAG, action)
+pub const CONTRACT_TAG: Symbol = symbol_short!("INVEST");
 pub const TOPIC_CONTRACT_BALANCE_UPDATED: Symbol = symbol_short!("CBUPDATED");
 pub const TOPIC_CONTRACT_STATUS_UPDATED: Symbol = symbol_short!("STUPDATED");
 
+// Multisig operation under which project withdrawals are approved
+pub const OP_WITHDRAW: Symbol = symbol_short!("withdraw");
+
 
 #[contracttype]
 pub struct ContractData {
@@ -18,7 +28,6 @@ pub struct ContractData {
     pub claim_block_days: u64,
     pub token: Address,
     pub project_address: Address,
-    pub admin: Address,
     pub state: State,
     pub return_type: InvestmentReturnType,
     pub return_months: u32,
@@ -30,25 +39,104 @@ pub struct ContractData {
 #[repr(u32)]
 #[contracterror]
 pub enum Error {
-    AddressInsufficientBalance = 1,
-    ContractInsufficientBalance = 2,
-    AmountLessThanMinimum = 5,
-    InterestRateMustBeGreaterThanZero = 6,
-    GoalMustBeGreaterThanZero = 7,
-    UnsupportedReturnType = 8,
-    ReturnMonthsMustBeGreaterThanZero = 9,
-    MinPerInvestmentMustBeGreaterThanZero = 10,
-    AddressHasNotInvested = 14,
-    AddressInvestmentIsNotClaimableYet = 15,
-    AddressInvestmentIsFinished = 16,
-    AddressInvestmentNextTransferNotClaimableYet = 17,
-    ProjectBalanceInsufficientAmount = 24,
-    ContractMustBePausedToRestartAgain = 25,
-    ContractMustBeActiveToBePaused = 26,
-    ContractMustBeActiveToInvest = 27,
-    RecipientCannotReceivePayment = 28,
-    InvalidPaymentData = 29,
-    WouldExceedGoal = 30
+    AddressInsufficientBalance = 401,
+    ContractInsufficientBalance = 402,
+    AmountLessThanMinimum = 405,
+    InterestRateMustBeGreaterThanZero = 406,
+    GoalMustBeGreaterThanZero = 407,
+    UnsupportedReturnType = 408,
+    ReturnMonthsMustBeGreaterThanZero = 409,
+    MinPerInvestmentMustBeGreaterThanZero = 410,
+    AddressHasNotInvested = 414,
+    AddressInvestmentIsNotClaimableYet = 415,
+    AddressInvestmentIsFinished = 416,
+    AddressInvestmentNextTransferNotClaimableYet = 417,
+    ProjectBalanceInsufficientAmount = 424,
+    ContractMustBePausedToRestartAgain = 425,
+    ContractMustBeActiveToBePaused = 426,
+    ContractMustBeActiveToInvest = 427,
+    RecipientCannotReceivePayment = 428,
+    InvalidPaymentData = 429,
+    WouldExceedGoal = 430,
+    InvalidSignerSet = 431,
+    MultisigNotConfigured = 432,
+    AddressIsNotSigner = 433,
+    MultisigRequestPending = 434,
+    MultisigRequestNotFound = 435,
+    MultisigRequestExpired = 436,
+    AddressAlreadyApproved = 437,
+    NotEnoughApprovals = 438,
+    WithdrawalRequiresMultisig = 439,
+    ContractPaused = 440,
+    AlreadyMigrated = 441,
+    NoPendingAdmin = 442,
+    AdminProposalExpired = 443,
+    StaleAdminProposal = 444,
+    WithdrawalNotApprovedByBallot = 445,
+    InvalidGovernanceThreshold = 446,
+    ArithmeticError = 447,
+    InsufficientAllowance = 448,
+    WithdrawalRequiresTimelock = 449,
+    TimelockNotConfigured = 450,
+    TimelockDelayTooShort = 451,
+    OperationAlreadyQueued = 452,
+    OperationNotQueued = 453,
+    OperationNotReady = 454,
+    BallotApprovalPending = 455,
+    BallotAlreadyLinked = 456
+}
+
+impl From<OwnershipError> for Error {
+    fn from(err: OwnershipError) -> Self {
+        match err {
+            OwnershipError::AdminNotSet => panic!("Contract data has expired"),
+            OwnershipError::NoPendingAdmin => Error::NoPendingAdmin,
+            OwnershipError::ProposalExpired => Error::AdminProposalExpired,
+            OwnershipError::StaleProposal => Error::StaleAdminProposal,
+        }
+    }
+}
+
+impl From<MathError> for Error {
+    fn from(_err: MathError) -> Self {
+        Error::ArithmeticError
+    }
+}
+
+impl From<TimelockError> for Error {
+    fn from(err: TimelockError) -> Self {
+        match err {
+            TimelockError::DelayTooShort => Error::TimelockDelayTooShort,
+            TimelockError::AlreadyQueued => Error::OperationAlreadyQueued,
+            TimelockError::NotQueued => Error::OperationNotQueued,
+            TimelockError::NotReady => Error::OperationNotReady,
+        }
+    }
+}
+
+impl From<TransferError> for Error {
+    fn from(err: TransferError) -> Self {
+        match err {
+            TransferError::InsufficientAllowance => Error::InsufficientAllowance,
+            TransferError::InsufficientBalance => Error::AddressInsufficientBalance,
+            TransferError::TransferFailed => Error::InvalidPaymentData,
+        }
+    }
+}
+
+impl From<MultisigError> for Error {
+    fn from(err: MultisigError) -> Self {
+        match err {
+            MultisigError::InvalidSignerSet => Error::InvalidSignerSet,
+            MultisigError::NotConfigured => Error::MultisigNotConfigured,
+            MultisigError::NotASigner => Error::AddressIsNotSigner,
+            MultisigError::RequestPending => Error::MultisigRequestPending,
+            MultisigError::NoRequest => Error::MultisigRequestNotFound,
+            MultisigError::RequestExpired => Error::MultisigRequestExpired,
+            MultisigError::AlreadyApproved => Error::AddressAlreadyApproved,
+            MultisigError::NotEnoughApprovals => Error::NotEnoughApprovals,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -66,6 +154,9 @@ pub enum DataKey {
     ContractData,
     Investment(Address),
     ClaimsMap,
-    MultisigRequest,
-    ContractBalances
+    ContractBalances,
+    WithdrawalGovernance,
+    WithdrawalTimelock,
+    DirectWithdrawals,
+    LinkedBallot(Address)
 }
@@ -0,0 +1,36 @@
+use soroban_sdk::{contractclient, contracttype, Address, Env, Symbol};
+
+// Result getter of the workspace's Ballot contract (`ballot` crate), so withdrawals can be put to a vote
+// without importing its wasm.
+#[contractclient(name = "BallotClient")]
+pub trait BallotInterface {
+    fn winner(env: Env) -> Option<Symbol>;
+}
+
+/// Ballot that has to approve the next large withdrawal. `approving_candidate` is the option that
+/// approves it (e.g. `yes`), and the approval is spent once the withdrawal is made. `withdrawn` adds up
+/// the withdrawals made since, so that several smaller ones reaching the threshold need an approval too.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithdrawalGovernance {
+    pub ballot: Address,
+    pub threshold: i128,
+    pub approving_candidate: Symbol,
+    pub used: bool,
+    pub withdrawn: i128,
+}
+
+impl WithdrawalGovernance {
+    pub fn is_approved(&self, env: &Env) -> bool {
+        !self.used && BallotClient::new(env, &self.ballot).winner() == Some(self.approving_candidate.clone())
+    }
+}
+
+/// Withdrawals made without going through the timelock since `start`. They add up until the timelock
+/// delay has passed, so splitting a large withdrawal does not take it out without notice.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DirectWithdrawals {
+    pub start: u64,
+    pub withdrawn: i128,
+}
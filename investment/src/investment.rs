@@ -1,4 +1,5 @@
 
+use soroban_examples_fixed::{self as fixed, MathError, Rounding};
 use soroban_sdk::{contracttype, Env};
 use crate::{balance::{Amount, CalculateAmounts}, constants::SECONDS_IN_DAY, data::{ContractData, FromNumber}};
 
@@ -48,21 +49,22 @@ impl FromNumber for InvestmentReturnType {
 }
 
 
-pub fn build_investment(env: &Env, cd: &ContractData, amount: &i128, decimals: u32) -> Investment{
-    let amounts: Amount = Amount::from_investment(amount, &cd.interest_rate, decimals);
-    let real_amount = amounts.amount_to_invest + amounts.amount_to_reserve_fund;
-    let current_interest = (real_amount * cd.interest_rate as i128) / 100 / 100;
+pub fn build_investment(env: &Env, cd: &ContractData, amount: &i128, decimals: u32) -> Result<Investment, MathError> {
+    let amounts: Amount = Amount::from_investment(amount, &cd.interest_rate, decimals)?;
+    let real_amount = fixed::add(amounts.amount_to_invest, amounts.amount_to_reserve_fund)?;
+    let current_interest = fixed::bps_of(real_amount, cd.interest_rate, Rounding::Down)?;
     let status: InvestmentStatus = match cd.claim_block_days {
         cbd if cbd > 0 => InvestmentStatus::Blocked,
         _ => InvestmentStatus::Claimable
     };
 
-    let total = real_amount + current_interest;
+    let total = fixed::add(real_amount, current_interest)?;
     let claimable_ts = env.ledger().timestamp() + (cd.claim_block_days * SECONDS_IN_DAY);
 
+    // Rounding down keeps the sum of the payments within what the investment owes
     let regular_payment = match cd.return_type {
-        InvestmentReturnType::Coupon => current_interest / cd.return_months as i128,
-        InvestmentReturnType::ReverseLoan => total / cd.return_months as i128
+        InvestmentReturnType::Coupon => fixed::div(current_interest, cd.return_months as i128, Rounding::Down)?,
+        InvestmentReturnType::ReverseLoan => fixed::div(total, cd.return_months as i128, Rounding::Down)?
     };
 
     let investment = Investment {
@@ -78,7 +80,7 @@ pub fn build_investment(env: &Env, cd: &ContractData, amount: &i128, decimals: u
         payments_transferred: 0_u32
     };
 
-    investment
+    Ok(investment)
 }
 
 pub fn process_investment_payment(env: &Env, investment: &mut Investment, contract_data: &ContractData) -> i128 {
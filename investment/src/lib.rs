@@ -6,4 +6,10 @@ pub mod contract;
 pub mod investment;
 mod storage;
 mod claim;
-pub mod balance;
\ No newline at end of file
+pub mod governance;
+pub mod balance;
+
+pub use data::Error;
+
+#[cfg(all(test, feature = "fuzz"))]
+mod prop_test;
@@ -0,0 +1,71 @@
+extern crate std;
+
+use proptest::prelude::*;
+use soroban_sdk::{testutils::Address as _, Address, Env};
+
+use crate::balance::{calculate_rate_denominator, Amount, CalculateAmounts};
+use crate::data::{ContractData, State};
+use crate::investment::{build_investment, process_investment_payment, InvestmentReturnType, InvestmentStatus};
+
+const DECIMALS: u32 = 7;
+
+fn contract_data(e: &Env, interest_rate: u32, return_type: InvestmentReturnType, return_months: u32) -> ContractData {
+    ContractData {
+        interest_rate,
+        claim_block_days: 0,
+        token: Address::generate(e),
+        project_address: Address::generate(e),
+        state: State::Actve,
+        return_type,
+        return_months,
+        min_per_investment: 1,
+        goal: i128::MAX,
+    }
+}
+
+proptest! {
+    #[test]
+    fn rate_denominator_stays_in_range(amount in 0_i128..10_i128.pow(24)) {
+        let denominator = calculate_rate_denominator(&amount, DECIMALS);
+        prop_assert!((10..=70).contains(&denominator));
+    }
+
+    #[test]
+    fn investment_splits_sum_up_to_the_amount(amount in 0_i128..10_i128.pow(24), i_rate in 1_u32..=10_000) {
+        let amounts = Amount::from_investment(&amount, &i_rate, DECIMALS).unwrap();
+
+        prop_assert!(amounts.amount_to_commission >= 0);
+        prop_assert!(amounts.amount_to_reserve_fund >= 0);
+        prop_assert!(amounts.amount_to_invest >= 0);
+        prop_assert_eq!(
+            amounts.amount_to_invest + amounts.amount_to_reserve_fund + amounts.amount_to_commission,
+            amount
+        );
+    }
+
+    #[test]
+    fn investor_is_paid_the_contractual_total(
+        amount in 1_i128..10_i128.pow(24),
+        i_rate in 1_u32..=10_000,
+        coupon in any::<bool>(),
+        return_months in 1_u32..=120,
+    ) {
+        let e = Env::default();
+        let return_type = if coupon { InvestmentReturnType::Coupon } else { InvestmentReturnType::ReverseLoan };
+        let cd = contract_data(&e, i_rate, return_type, return_months);
+        let mut investment = build_investment(&e, &cd, &amount, DECIMALS).unwrap();
+
+        let mut transferred = 0_i128;
+        for _ in 0..return_months {
+            prop_assert!(investment.status != InvestmentStatus::Finished);
+            transferred += process_investment_payment(&e, &mut investment, &cd);
+        }
+
+        prop_assert!(investment.status == InvestmentStatus::Finished);
+        prop_assert_eq!(investment.paid, transferred);
+
+        // Regular payments are rounded down, so at most one unit per month is left unpaid
+        let dust = investment.total - investment.paid;
+        prop_assert!(dust >= 0 && dust < return_months as i128);
+    }
+}
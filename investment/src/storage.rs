@@ -1,90 +1,82 @@
-use crate::{balance::ContractBalances, claim::Claim, data::{ContractData, DataKey}, investment::Investment};
+use crate::{balance::ContractBalances, claim::Claim, data::{ContractData, DataKey}, governance::{DirectWithdrawals, WithdrawalGovernance}, investment::Investment};
+use soroban_examples_storage::{StorageCell, StorageMap};
 use soroban_sdk::{Address, Env, Map};
 
-pub(self) const DAY_IN_LEDGERS: u32 = 17280;
-
-// Instance storage: accessed frequently, moderate TTL
-pub(self) const INSTANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;      // ~30 days
-pub(self) const INSTANCE_LIFETIME_THRESHOLD: u32 = 15 * DAY_IN_LEDGERS; // ~15 days
-
-// Persistent storage: critical user data, long TTL for safety
-pub(self) const PERSISTENT_BUMP_AMOUNT: u32 = 180 * DAY_IN_LEDGERS;    // ~6 months
-pub(self) const PERSISTENT_LIFETIME_THRESHOLD: u32 = 90 * DAY_IN_LEDGERS; // ~3 months
-
+// Every access goes through a cell, which extends the TTL of the storage it reads or writes
+const CONTRACT_DATA: StorageCell<DataKey, ContractData> = StorageCell::instance(DataKey::ContractData);
+const CLAIMS_MAP: StorageCell<DataKey, Map<Address, Claim>> = StorageCell::instance(DataKey::ClaimsMap);
+const CONTRACT_BALANCES: StorageCell<DataKey, ContractBalances> = StorageCell::instance(DataKey::ContractBalances);
+const WITHDRAWAL_GOVERNANCE: StorageCell<DataKey, WithdrawalGovernance> = StorageCell::instance(DataKey::WithdrawalGovernance);
+const WITHDRAWAL_TIMELOCK: StorageCell<DataKey, i128> = StorageCell::instance(DataKey::WithdrawalTimelock);
+const DIRECT_WITHDRAWALS: StorageCell<DataKey, DirectWithdrawals> = StorageCell::instance(DataKey::DirectWithdrawals);
+// A ballot approves withdrawals only once: linking it again would re-arm an approval already spent
+const LINKED_BALLOTS: StorageMap<Address, bool, DataKey> = StorageMap::persistent(|ballot| DataKey::LinkedBallot(ballot.clone()));
+const INVESTMENTS: StorageMap<Address, Map<u64, Investment>, DataKey> = StorageMap::persistent(|addr| DataKey::Investment(addr.clone()));
 
 pub fn get_contract_data(e: &Env) -> ContractData {
-    let contract_data = e.storage()
-        .instance()
-        .get(&DataKey::ContractData)
-        .unwrap_or_else(|| panic!("Contract data has expired"));
-    
-    bump_instance_ttl(e);
-    contract_data
+    CONTRACT_DATA.get_or_else(e, || panic!("Contract data has expired"))
 }
 
 pub fn update_contract_data(e: &Env, contract_data: &ContractData) {
-    e.storage().instance().set(&DataKey::ContractData, contract_data);
+    CONTRACT_DATA.set(e, contract_data);
 }
 
 pub fn get_investment(e: &Env, addr: &Address, ts: u64) -> Option<Investment> {
-    let key = DataKey::Investment(addr.clone());
-    let addr_investments: Option<Map<u64, Investment>> = e.storage().persistent().get(&key);
-    
-    if let Some(investments) = addr_investments {
-        bump_persistent_ttl(e, &key);
-        investments.get(ts)
-    } else {
-        None
-    }
+    INVESTMENTS.get(e, addr).and_then(|investments| investments.get(ts))
 }
 
 pub fn set_investment(e: &Env, addr: &Address, investment: &Investment) {
-    let key = DataKey::Investment(addr.clone());
-
-    let mut addr_investments = e.storage().persistent().get(&key).unwrap_or(Map::<u64, Investment>::new(&e));
-    addr_investments.set(investment.claimable_ts, *investment);
-
-    e.storage().persistent().set(&key, &addr_investments);
+    INVESTMENTS.update(e, addr, |investments| {
+        let mut addr_investments = investments.unwrap_or(Map::new(e));
+        addr_investments.set(investment.claimable_ts, *investment);
+        addr_investments
+    });
 }
 
 pub fn update_claims_map(e: &Env, claims_map: Map<Address, Claim>) {
-    e.storage().instance().set(&DataKey::ClaimsMap, &claims_map);
+    CLAIMS_MAP.set(e, &claims_map);
 }
 
 pub fn get_claims_map_or_new(e: &Env) -> Map<Address, Claim> {
-    let key = DataKey::ClaimsMap;
-    let claims_map = e.storage().instance()
-        .get(&key) 
-        .unwrap_or(Map::<Address, Claim>::new(&e))
-    ;
-
-    bump_instance_ttl(e);
-    claims_map        
+    CLAIMS_MAP.get_or_else(e, || Map::new(e))
 }
 
 pub fn update_contract_balances(e: &Env, contract_balances: &ContractBalances) {
-    e.storage().instance().set(&DataKey::ContractBalances, contract_balances);
+    CONTRACT_BALANCES.set(e, contract_balances);
 }
 
 pub fn get_balances_or_new(e: &Env) -> ContractBalances {
+    CONTRACT_BALANCES.get_or_else(e, ContractBalances::new)
+}
 
-    let key = DataKey::ContractBalances;
-    let contract_balances = e.storage().instance()
-        .get(&key) 
-        .unwrap_or(ContractBalances::new())
-    ;
+pub fn get_withdrawal_governance(e: &Env) -> Option<WithdrawalGovernance> {
+    WITHDRAWAL_GOVERNANCE.get(e)
+}
+
+pub fn update_withdrawal_governance(e: &Env, governance: &WithdrawalGovernance) {
+    WITHDRAWAL_GOVERNANCE.set(e, governance);
+}
 
-    bump_instance_ttl(e);
-    contract_balances
+pub fn get_withdrawal_timelock(e: &Env) -> Option<i128> {
+    WITHDRAWAL_TIMELOCK.get(e)
 }
 
-fn bump_instance_ttl(e: &Env) {
-    e.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+pub fn update_withdrawal_timelock(e: &Env, threshold: &i128) {
+    WITHDRAWAL_TIMELOCK.set(e, threshold);
 }
 
-fn bump_persistent_ttl(e: &Env, key: &DataKey) {
-    e.storage().persistent().extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+pub fn get_direct_withdrawals(e: &Env) -> Option<DirectWithdrawals> {
+    DIRECT_WITHDRAWALS.get(e)
 }
 
+pub fn update_direct_withdrawals(e: &Env, withdrawals: &DirectWithdrawals) {
+    DIRECT_WITHDRAWALS.set(e, withdrawals);
+}
 
+pub fn is_ballot_linked(e: &Env, ballot: &Address) -> bool {
+    LINKED_BALLOTS.has(e, ballot)
+}
 
+pub fn set_ballot_linked(e: &Env, ballot: &Address) {
+    LINKED_BALLOTS.set(e, ballot, &true);
+}
@@ -3,20 +3,11 @@ use investment::{
     contract::{InvestmentContract, InvestmentContractClient},
     investment::{Investment, InvestmentStatus}
 };
+use soroban_examples_testutils::{create_token_contract, mint_to, TokenAdminClient, TokenClient};
 use soroban_sdk::{
     testutils::{Address as _, Ledger},
-    token, Address, Env,
+    Address, Env,
 };
-use token::Client as TokenClient;
-use token::StellarAssetClient as TokenAdminClient;
-
-pub fn create_token_contract<'a>(e: &Env, admin: &Address) -> (TokenClient<'a>, TokenAdminClient<'a>) {
-    let sac = e.register_stellar_asset_contract_v2(admin.clone());
-    (
-        TokenClient::new(e, &sac.address()),
-        TokenAdminClient::new(e, &sac.address()),
-    )
-}
 
 pub struct TestData<'a> {
     pub user: Address,
@@ -72,8 +63,7 @@ pub fn create_investment_contract(
 
 pub fn do_mint_and_invest(e: &Env, test_data: &TestData) {
     let another_user: Address = Address::generate(e);
-    test_data.token_admin.mint(&test_data.user, &1000000);
-    test_data.token_admin.mint(&another_user, &1000000);
+    mint_to(&test_data.token_admin, &[&test_data.user, &another_user], 1000000);
 
     test_data.client.invest(&test_data.user, &100000);
     test_data.client.invest(&another_user, &50000);
@@ -1,7 +1,8 @@
 mod common;
 
 use common::{create_investment_contract, do_mint_and_invest};
-use soroban_sdk::Env;
+use soroban_examples_testutils::{advance_days, advance_time};
+use soroban_sdk::{testutils::{Address as _, Ledger}, vec, Address, Env};
 
 // ==================== Constructor Error Tests ====================
 
@@ -43,7 +44,7 @@ fn test_constructor_min_investment_zero() {
 // ==================== Investment Error Tests ====================
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #30)")]
+#[should_panic(expected = "HostError: Error(Contract, #430)")]
 fn test_goal_reached() {
     let e = Env::default();
     let test_data = create_investment_contract(&e, 500_u32, 7_u64, 90000_i128, 2_u32, 4_u32, 100_i128);
@@ -54,7 +55,7 @@ fn test_goal_reached() {
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #1)")]
+#[should_panic(expected = "HostError: Error(Contract, #401)")]
 fn test_invest_insufficient_balance() {
     let e = Env::default();
     let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
@@ -65,7 +66,7 @@ fn test_invest_insufficient_balance() {
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #5)")]
+#[should_panic(expected = "HostError: Error(Contract, #405)")]
 fn test_invest_amount_less_than_minimum() {
     let e = Env::default();
     let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
@@ -76,7 +77,7 @@ fn test_invest_amount_less_than_minimum() {
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #27)")]
+#[should_panic(expected = "HostError: Error(Contract, #427)")]
 fn test_invest_contract_paused() {
     let e = Env::default();
     let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
@@ -92,7 +93,7 @@ fn test_invest_contract_paused() {
 // ==================== Payment Processing Error Tests ====================
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #14)")]
+#[should_panic(expected = "HostError: Error(Contract, #414)")]
 fn test_process_payment_address_not_invested() {
     let e = Env::default();
     let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
@@ -103,7 +104,7 @@ fn test_process_payment_address_not_invested() {
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #15)")]
+#[should_panic(expected = "HostError: Error(Contract, #415)")]
 fn test_process_payment_not_claimable_yet() {
     let e = Env::default();
     let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
@@ -116,7 +117,7 @@ fn test_process_payment_not_claimable_yet() {
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #16)")]
+#[should_panic(expected = "HostError: Error(Contract, #416)")]
 fn test_process_payment_investment_finished() {
     use investment::investment::InvestmentStatus;
     use soroban_sdk::testutils::Ledger;
@@ -136,8 +137,7 @@ fn test_process_payment_investment_finished() {
     let mut count = 0;
     let mut inv = test_data.client.process_investor_payment(&test_data.user, &investment.claimable_ts);
     while inv.status != InvestmentStatus::Finished && count < 4 {
-        let current_ts = e.ledger().timestamp();
-        e.ledger().set_timestamp(current_ts + (31 * 24 * 60 * 60)); // +1 month
+        advance_days(&e, 31); // +1 month
         inv = test_data.client.process_investor_payment(&test_data.user, &investment.claimable_ts);
         count += 1;
     }
@@ -147,7 +147,7 @@ fn test_process_payment_investment_finished() {
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #17)")]
+#[should_panic(expected = "HostError: Error(Contract, #417)")]
 fn test_process_payment_next_transfer_not_ready() {
     use soroban_sdk::testutils::Ledger;
 
@@ -164,8 +164,7 @@ fn test_process_payment_next_transfer_not_ready() {
     test_data.client.process_investor_payment(&test_data.user, &investment.claimable_ts);
 
     // Advance only 15 days (less than a month)
-    let current_ts = e.ledger().timestamp();
-    e.ledger().set_timestamp(current_ts + (15 * 24 * 60 * 60));
+    advance_days(&e, 15);
 
     // Try to process payment before a month has passed since the last transfer
     test_data.client.process_investor_payment(&test_data.user, &investment.claimable_ts);
@@ -174,7 +173,7 @@ fn test_process_payment_next_transfer_not_ready() {
 // ==================== Withdrawal Error Tests ====================
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #2)")]
+#[should_panic(expected = "HostError: Error(Contract, #402)")]
 fn test_single_withdrawn_insufficient_balance() {
     let e = Env::default();
     let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
@@ -184,7 +183,87 @@ fn test_single_withdrawn_insufficient_balance() {
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #24)")]
+#[should_panic(expected = "HostError: Error(Contract, #439)")]
+fn test_single_withdrawn_with_signers() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+
+    do_mint_and_invest(&e, &test_data);
+    test_data.client.set_withdrawal_signers(&vec![&e, Address::generate(&e)], &1);
+    test_data.client.single_withdrawn(&40000_i128);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #431)")]
+fn test_set_withdrawal_signers_invalid_threshold() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+
+    test_data.client.set_withdrawal_signers(&vec![&e, Address::generate(&e)], &2);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #438)")]
+fn test_multisig_withdrawn_not_enough_approvals() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+    let (signer_1, signer_2) = (Address::generate(&e), Address::generate(&e));
+
+    do_mint_and_invest(&e, &test_data);
+    test_data.client.set_withdrawal_signers(&vec![&e, signer_1.clone(), signer_2], &2);
+    test_data.client.propose_withdrawal(&signer_1, &40000_i128, &(e.ledger().timestamp() + 3600));
+    test_data.client.multisig_withdrawn();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #436)")]
+fn test_approve_withdrawal_expired() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+    let (signer_1, signer_2) = (Address::generate(&e), Address::generate(&e));
+
+    do_mint_and_invest(&e, &test_data);
+    test_data.client.set_withdrawal_signers(&vec![&e, signer_1.clone(), signer_2.clone()], &2);
+    test_data.client.propose_withdrawal(&signer_1, &40000_i128, &(e.ledger().timestamp() + 3600));
+
+    advance_time(&e, 3600);
+    test_data.client.approve_withdrawal(&signer_2);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #433)")]
+fn test_propose_withdrawal_not_signer() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+
+    do_mint_and_invest(&e, &test_data);
+    test_data.client.set_withdrawal_signers(&vec![&e, Address::generate(&e)], &1);
+    test_data.client.propose_withdrawal(&test_data.user, &40000_i128, &(e.ledger().timestamp() + 3600));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #440)")]
+fn test_invest_when_paused() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+
+    test_data.client.pause();
+    do_mint_and_invest(&e, &test_data);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #440)")]
+fn test_single_withdrawn_when_paused() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+
+    do_mint_and_invest(&e, &test_data);
+    test_data.client.pause();
+    test_data.client.single_withdrawn(&40000_i128);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #424)")]
 fn test_move_funds_insufficient_project_balance() {
     let e = Env::default();
     let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
@@ -198,7 +277,7 @@ fn test_move_funds_insufficient_project_balance() {
 // ==================== Transfer Error Tests ====================
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #1)")]
+#[should_panic(expected = "HostError: Error(Contract, #401)")]
 fn test_add_company_transfer_insufficient_balance() {
     let e = Env::default();
     let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
@@ -208,7 +287,7 @@ fn test_add_company_transfer_insufficient_balance() {
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #2)")]
+#[should_panic(expected = "HostError: Error(Contract, #402)")]
 fn test_process_payment_insufficient_reserve() {
     use soroban_sdk::testutils::Ledger;
 
@@ -229,7 +308,7 @@ fn test_process_payment_insufficient_reserve() {
 // ==================== Lifecycle Error Tests ====================
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #26)")]
+#[should_panic(expected = "HostError: Error(Contract, #426)")]
 fn test_stop_investments_already_paused() {
     let e = Env::default();
     let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
@@ -239,7 +318,7 @@ fn test_stop_investments_already_paused() {
 }
 
 #[test]
-#[should_panic(expected = "HostError: Error(Contract, #25)")]
+#[should_panic(expected = "HostError: Error(Contract, #425)")]
 fn test_restart_investments_already_active() {
     let e = Env::default();
     let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
@@ -247,3 +326,113 @@ fn test_restart_investments_already_active() {
     test_data.client.restart_investments();
 }
 
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #443)")]
+fn test_accept_admin_after_proposal_expired() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+
+    test_data.client.propose_admin(&Address::generate(&e));
+    advance_days(&e, 7);
+    test_data.client.accept_admin();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #442)")]
+fn test_accept_admin_after_cancel() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+
+    test_data.client.propose_admin(&Address::generate(&e));
+    test_data.client.cancel_admin_transfer();
+    test_data.client.accept_admin();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #446)")]
+fn test_set_withdrawal_governance_invalid_threshold() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+    test_data.client.set_withdrawal_governance(&Address::generate(&e), &0, &soroban_sdk::symbol_short!("yes"));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #448)")]
+fn test_invest_from_without_allowance() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+    let router = Address::generate(&e);
+
+    test_data.token_admin.mint(&test_data.user, &1000000);
+    test_data.token.approve(&test_data.user, &router, &50000, &(e.ledger().sequence() + 100));
+    test_data.client.invest_from(&router, &test_data.user, &100000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #449)")]
+fn test_single_withdrawn_above_timelock_threshold() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+    do_mint_and_invest(&e, &test_data);
+    test_data.client.set_withdrawal_timelock(&30000_i128, &86400);
+
+    test_data.client.single_withdrawn(&30000_i128);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #449)")]
+fn test_split_withdrawals_above_timelock_threshold() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+    do_mint_and_invest(&e, &test_data);
+    test_data.client.set_withdrawal_timelock(&30000_i128, &86400);
+
+    test_data.client.single_withdrawn(&20000_i128);
+    advance_time(&e, 86399);
+    test_data.client.single_withdrawn(&10000_i128);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #454)")]
+fn test_execute_withdrawal_before_delay() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+    do_mint_and_invest(&e, &test_data);
+    test_data.client.set_withdrawal_timelock(&30000_i128, &86400);
+    test_data.client.queue_withdrawal(&40000_i128, &86400);
+
+    advance_time(&e, 86399);
+    test_data.client.execute_withdrawal(&40000_i128);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #453)")]
+fn test_execute_cancelled_withdrawal() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+    do_mint_and_invest(&e, &test_data);
+    test_data.client.set_withdrawal_timelock(&30000_i128, &86400);
+    let hash = test_data.client.queue_withdrawal(&40000_i128, &86400);
+    test_data.client.cancel_queued_withdrawal(&hash);
+
+    advance_days(&e, 1);
+    test_data.client.execute_withdrawal(&40000_i128);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #451)")]
+fn test_withdrawal_timelock_cannot_be_relaxed() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+    test_data.client.set_withdrawal_timelock(&30000_i128, &86400);
+
+    test_data.client.set_withdrawal_timelock(&30000_i128, &3600);
+}
+
+#[test]
+fn error_codes_in_registry_range() {
+    use investment::Error;
+
+    assert!(soroban_examples_errors::INVESTMENT.contains(Error::AddressInsufficientBalance as u32));
+    assert!(soroban_examples_errors::INVESTMENT.contains(Error::OperationNotReady as u32));
+}
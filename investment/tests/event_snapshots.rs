@@ -0,0 +1,24 @@
+mod common;
+
+use common::create_investment_contract;
+use soroban_examples_testutils::EventSnapshot;
+use soroban_sdk::Env;
+
+// Indexers decode the `CBUPDATED` and `STUPDATED` payloads; any change to them has to show up here
+#[test]
+fn invest_fund_and_stop() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+    test_data.token_admin.mint(&test_data.user, &1000000);
+    test_data.token_admin.mint(&test_data.admin, &1000000);
+
+    let mut snapshot = EventSnapshot::new(&e, &test_data.client.address);
+    test_data.client.invest(&test_data.user, &100000);
+    snapshot.capture("invest");
+    test_data.client.add_company_transfer(&100000);
+    snapshot.capture("add_company_transfer");
+    test_data.client.stop_investments();
+    snapshot.capture("stop_investments");
+
+    snapshot.assert_matches("snapshots/events/invest_fund_and_stop.events");
+}
@@ -0,0 +1,113 @@
+use investment::contract::{InvestmentContract, InvestmentContractClient};
+use soroban_examples_testutils::{advance_days, create_env, create_reentrant_token, ReentrantTokenClient};
+use soroban_sdk::{testutils::Address as _, Address, Env, IntoVal, Symbol};
+
+// Every token movement goes through a token that tries to call back into the contract, and the
+// contract books each operation exactly once
+struct TestData<'a> {
+    user: Address,
+    project_address: Address,
+    admin: Address,
+    client: InvestmentContractClient<'a>,
+    token: ReentrantTokenClient<'a>,
+}
+
+fn create_contract(e: &Env) -> TestData<'_> {
+    let admin = Address::generate(e);
+    let user = Address::generate(e);
+    let project_address = Address::generate(e);
+    let token = create_reentrant_token(e);
+
+    let client = InvestmentContractClient::new(
+        e,
+        &e.register(
+            InvestmentContract {},
+            (admin.clone(), project_address.clone(), token.address.clone(), 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128),
+        ),
+    );
+
+    token.mint(&user, &1000000);
+    token.mint(&admin, &1000000);
+    TestData { user, project_address, admin, client, token }
+}
+
+#[test]
+fn invest_is_booked_once() {
+    let e = create_env();
+    let test_data = create_contract(&e);
+
+    test_data.token.arm(
+        &test_data.client.address,
+        &Symbol::new(&e, "invest"),
+        &(test_data.user.clone(), 100000_i128).into_val(&e),
+    );
+    test_data.client.invest(&test_data.user, &100000);
+
+    assert_eq!(test_data.token.attempts(), 1);
+    assert_eq!(test_data.token.reentered(), 0);
+
+    let balances = test_data.client.get_contract_balance();
+    assert_eq!(balances.sum(), 100000);
+    assert_eq!(test_data.token.balance(&test_data.client.address), 100000);
+}
+
+#[test]
+fn investor_payment_is_made_once() {
+    let e = create_env();
+    let test_data = create_contract(&e);
+    let investment = test_data.client.invest(&test_data.user, &100000);
+    test_data.client.add_company_transfer(&100000);
+    advance_days(&e, 8);
+
+    test_data.token.arm(
+        &test_data.client.address,
+        &Symbol::new(&e, "process_investor_payment"),
+        &(test_data.user.clone(), investment.claimable_ts).into_val(&e),
+    );
+    let user_balance = test_data.token.balance(&test_data.user);
+    let paid = test_data.client.process_investor_payment(&test_data.user, &investment.claimable_ts);
+
+    assert_eq!(test_data.token.attempts(), 1);
+    assert_eq!(test_data.token.reentered(), 0);
+    assert_eq!(paid.payments_transferred, 1);
+    assert_eq!(test_data.token.balance(&test_data.user), user_balance + paid.regular_payment);
+    assert_eq!(test_data.client.get_contract_balance().payments, paid.regular_payment);
+}
+
+#[test]
+fn project_withdrawal_is_debited_once() {
+    let e = create_env();
+    let test_data = create_contract(&e);
+    test_data.client.invest(&test_data.user, &100000);
+    let project_balance = test_data.client.get_contract_balance().project;
+
+    test_data.token.arm(
+        &test_data.client.address,
+        &Symbol::new(&e, "single_withdrawn"),
+        &(40000_i128,).into_val(&e),
+    );
+    test_data.client.single_withdrawn(&40000);
+
+    assert_eq!(test_data.token.attempts(), 1);
+    assert_eq!(test_data.token.reentered(), 0);
+    assert_eq!(test_data.client.get_contract_balance().project, project_balance - 40000);
+    assert_eq!(test_data.token.balance(&test_data.project_address), 40000);
+}
+
+#[test]
+fn company_transfer_is_credited_once() {
+    let e = create_env();
+    let test_data = create_contract(&e);
+
+    test_data.token.arm(
+        &test_data.client.address,
+        &Symbol::new(&e, "add_company_transfer"),
+        &(50000_i128,).into_val(&e),
+    );
+    test_data.client.add_company_transfer(&50000);
+
+    assert_eq!(test_data.token.attempts(), 1);
+    assert_eq!(test_data.token.reentered(), 0);
+    assert_eq!(test_data.client.get_contract_balance().reserve_contributions, 50000);
+    assert_eq!(test_data.token.balance(&test_data.admin), 950000);
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use common::{create_investment_contract, TestData};
+use investment::investment::{Investment, InvestmentStatus};
+use soroban_examples_testutils::{Scenario, SECONDS_IN_DAY};
+use soroban_sdk::Env;
+
+const RETURN_MONTHS: u32 = 4;
+
+struct Flow<'a> {
+    data: TestData<'a>,
+    investment: Option<Investment>,
+}
+
+fn investment_flow(e: &Env) -> Scenario<'_, Flow<'_>> {
+    let data = create_investment_contract(e, 500_u32, 7_u64, 1000000_i128, 1_u32, RETURN_MONTHS, 100_i128);
+    data.token_admin.mint(&data.user, &1000000);
+    data.token_admin.mint(&data.admin, &1000000);
+
+    Scenario::new(e, Flow { data, investment: None })
+        .step("invest", |_, flow| flow.investment = Some(flow.data.client.invest(&flow.data.user, &100000)))
+        .step("fund the reserve", |_, flow| {
+            flow.data.client.add_company_transfer(&150000);
+        })
+        .wait_days(8)
+}
+
+// One investor payment a month; every payment but the last leaves the investment cash flowing
+fn monthly_payments<'a>(scenario: Scenario<'a, Flow<'a>>) -> Scenario<'a, Flow<'a>> {
+    scenario.repeat("investor payment", RETURN_MONTHS, 31 * SECONDS_IN_DAY, |_, flow, month| {
+        let claimable_ts = flow.investment.as_ref().unwrap().claimable_ts;
+        let investment = flow.data.client.process_investor_payment(&flow.data.user, &claimable_ts);
+
+        assert_eq!(investment.payments_transferred, month + 1);
+        assert_eq!(investment.paid, investment.regular_payment * (month + 1) as i128);
+        let expected = if month + 1 < RETURN_MONTHS { InvestmentStatus::CashFlowing } else { InvestmentStatus::Finished };
+        assert_eq!(investment.status, expected);
+        flow.investment = Some(investment);
+    })
+}
+
+#[test]
+fn invest_and_collect_every_payment() {
+    let e = Env::default();
+    let flow = investment_flow(&e).with(monthly_payments).run();
+
+    let investment = flow.investment.unwrap();
+    assert_eq!(flow.data.token.balance(&flow.data.user), 1000000 - 100000 + investment.paid);
+    assert_eq!(flow.data.client.get_contract_balance().payments, investment.paid);
+}
+
+#[test]
+fn payments_go_on_after_investments_stop() {
+    let e = Env::default();
+    let flow = investment_flow(&e)
+        .step("stop investments", |_, flow| {
+            flow.data.client.stop_investments();
+        })
+        .with(monthly_payments)
+        .run();
+
+    assert_eq!(flow.investment.unwrap().status, InvestmentStatus::Finished);
+}
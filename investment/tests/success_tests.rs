@@ -3,7 +3,11 @@ mod common;
 use common::{create_investment_contract, do_mint_and_invest, do_test_investment};
 use investment::balance::{calculate_rate_denominator, ContractBalances};
 use investment::investment::Investment;
-use soroban_sdk::{testutils::Ledger, Env};
+use investment::Error;
+use soroban_examples_multisig::MultisigStatus;
+use soroban_examples_events::EVENT_VERSION;
+use soroban_examples_testutils::{advance_days, advance_time};
+use soroban_sdk::{symbol_short, testutils::{Address as _, Events, Ledger}, vec, Address, Env, IntoVal, TryFromVal};
 
 #[test]
 fn test_commision_calculator() {
@@ -26,8 +30,7 @@ fn test_investment_reverse_loan() {
 
     let investment_user: Investment = test_data.client.invest(&test_data.user, &100000);
 
-    let current_ts = e.ledger().timestamp();
-    e.ledger().set_timestamp(current_ts + 604888);
+    advance_time(&e, 604888);
 
     test_data.client.add_company_transfer(&100000_i128);
 
@@ -46,8 +49,7 @@ fn test_investment_coupon() {
 
     let investment_user: Investment = test_data.client.invest(&test_data.user, &100000);
 
-    let current_ts = e.ledger().timestamp();
-    e.ledger().set_timestamp(current_ts + 604888);
+    advance_time(&e, 604888);
 
     test_data.client.add_company_transfer(&100000_i128);
 
@@ -71,6 +73,36 @@ fn test_check_contract_balance() {
     assert!(contract_balances.project > contract_balances.reserve);
 }
 
+#[test]
+fn test_get_investment() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+
+    test_data.token_admin.mint(&test_data.user, &1000000);
+    let investment_user: Investment = test_data.client.invest(&test_data.user, &100000);
+    let stored: Investment = test_data.client.get_investment(&test_data.user, &investment_user.claimable_ts);
+
+    assert_eq!(stored.deposited, investment_user.deposited);
+    assert_eq!(stored.total, investment_user.total);
+    assert_eq!(stored.paid, 0_i128);
+}
+
+#[test]
+fn test_invest_from_allowance() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+    let router = Address::generate(&e);
+
+    test_data.token_admin.mint(&test_data.user, &1000000);
+    test_data.token.approve(&test_data.user, &router, &150000, &(e.ledger().sequence() + 100));
+    let investment_user: Investment = test_data.client.invest_from(&router, &test_data.user, &100000);
+
+    // The investment belongs to the owner of the funds, not to the spender
+    assert_eq!(test_data.client.get_investment(&test_data.user, &investment_user.claimable_ts).deposited, 100000);
+    assert_eq!(test_data.token.balance(&test_data.client.address), 100000);
+    assert_eq!(test_data.token.allowance(&test_data.user, &router), 50000);
+}
+
 #[test]
 fn test_single_withdrawn() {
     let e = Env::default();
@@ -84,6 +116,93 @@ fn test_single_withdrawn() {
     );
 }
 
+#[test]
+fn test_timelocked_withdrawn() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+    do_mint_and_invest(&e, &test_data);
+    test_data.client.set_withdrawal_timelock(&30000_i128, &(2 * 86400));
+
+    // Small withdrawals are not delayed
+    test_data.client.single_withdrawn(&10000_i128);
+
+    let hash = test_data.client.queue_withdrawal(&40000_i128, &(2 * 86400));
+    assert_eq!(test_data.client.get_queued_withdrawal(&hash).unwrap().ready_at, e.ledger().timestamp() + 2 * 86400);
+
+    advance_days(&e, 2);
+    test_data.client.execute_withdrawal(&40000_i128);
+    assert_eq!(test_data.token.balance(&test_data.project_address), 50000_i128);
+    assert_eq!(test_data.client.get_queued_withdrawal(&hash), None);
+}
+
+#[test]
+fn test_direct_withdrawals_add_up_within_timelock_delay() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+    do_mint_and_invest(&e, &test_data);
+    test_data.client.set_withdrawal_timelock(&30000_i128, &86400);
+
+    test_data.client.single_withdrawn(&20000_i128);
+    assert_eq!(test_data.client.try_single_withdrawn(&10000_i128), Err(Ok(Error::WithdrawalRequiresTimelock)));
+    test_data.client.single_withdrawn(&9999_i128);
+
+    // Once the delay has passed the count starts over
+    advance_days(&e, 1);
+    test_data.client.single_withdrawn(&20000_i128);
+    assert_eq!(test_data.token.balance(&test_data.project_address), 49999_i128);
+}
+
+#[test]
+fn test_multisig_withdrawn() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+    do_mint_and_invest(&e, &test_data);
+
+    let signers = [Address::generate(&e), Address::generate(&e), Address::generate(&e)];
+    test_data.client.set_withdrawal_signers(&vec![&e, signers[0].clone(), signers[1].clone(), signers[2].clone()], &2);
+
+    let request = test_data.client.propose_withdrawal(&signers[0], &40000_i128, &(e.ledger().timestamp() + 3600));
+    assert_eq!(request.approvals.len(), 1);
+    assert_eq!(test_data.client.approve_withdrawal(&signers[2]), MultisigStatus::Approved);
+
+    test_data.client.multisig_withdrawn();
+    assert_eq!(test_data.token.balance(&test_data.project_address), 40000_i128);
+    assert_eq!(test_data.client.get_withdrawal_request(), None);
+}
+
+#[test]
+fn test_pause_and_unpause() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+
+    test_data.client.pause();
+    assert!(test_data.client.is_paused());
+
+    test_data.client.unpause();
+    assert!(!test_data.client.is_paused());
+    do_mint_and_invest(&e, &test_data);
+}
+
+#[test]
+fn test_events_follow_schema() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+    test_data.token_admin.mint(&test_data.user, &1000000);
+    test_data.client.invest(&test_data.user, &100000);
+
+    let (contract, topics, data) = e.events().all().last().unwrap();
+    assert_eq!(contract, test_data.client.address);
+    assert_eq!(topics, (symbol_short!("INVEST"), symbol_short!("CBUPDATED")).into_val(&e));
+
+    let (version, balances) = <(u32, ContractBalances)>::try_from_val(&e, &data).unwrap();
+    assert_eq!(version, EVENT_VERSION);
+    assert_eq!(balances.received_so_far, test_data.client.get_contract_balance().received_so_far);
+
+    test_data.client.stop_investments();
+    let (_, topics, _) = e.events().all().last().unwrap();
+    assert_eq!(topics, (symbol_short!("INVEST"), symbol_short!("STUPDATED")).into_val(&e));
+}
+
 #[test]
 fn test_add_company_transfer() {
     let e = Env::default();
@@ -153,8 +272,7 @@ fn test_multiple_investments_same_user() {
     let claimable_ts_1 = investment_1.claimable_ts;
     let deposited_1 = investment_1.deposited;
     
-    let current_ts = e.ledger().timestamp();
-    e.ledger().set_timestamp(current_ts + (8 * 24 * 60 * 60));
+    advance_days(&e, 8);
     
     let investment_2 = test_data.client.invest(&test_data.user, &50000);
     let claimable_ts_2 = investment_2.claimable_ts;
@@ -338,3 +456,18 @@ fn test_check_reserve_balance_multiple_claims_in_next_week() {
     assert_eq!(needed, expected_diff, "Should sum both claims and subtract reserve");
     assert!(needed > 0, "Should need additional funds for multiple claims");
 }
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+    let new_admin = Address::generate(&e);
+
+    let pending = test_data.client.propose_admin(&new_admin);
+    assert_eq!(pending.proposed_by, test_data.admin);
+    assert_eq!(test_data.client.admin(), test_data.admin);
+
+    test_data.client.accept_admin();
+    assert_eq!(test_data.client.admin(), new_admin);
+    assert!(test_data.client.pending_admin().is_none());
+}
@@ -0,0 +1,38 @@
+// Installs the release wasm: `cargo build --target wasm32-unknown-unknown --release`, then
+// `cargo test --features upgrade-tests`
+#![cfg(feature = "upgrade-tests")]
+
+mod common;
+
+mod investment_wasm {
+    soroban_sdk::contractimport!(
+        file = "target/wasm32-unknown-unknown/release/investment.wasm"
+    );
+}
+
+use common::{create_investment_contract, do_mint_and_invest};
+use soroban_examples_upgrade::set_version;
+use soroban_sdk::Env;
+
+#[test]
+fn test_upgrade_and_migrate() {
+    let e = Env::default();
+    let test_data = create_investment_contract(&e, 500_u32, 7_u64, 1000000_i128, 1_u32, 4_u32, 100_i128);
+    do_mint_and_invest(&e, &test_data);
+    assert_eq!(test_data.client.version(), 1);
+
+    // Simulate data written by a build that predates versioning
+    e.as_contract(&test_data.client.address, || set_version(&e, 0));
+
+    let new_wasm_hash = e.deployer().upload_contract_wasm(investment_wasm::WASM);
+    assert_eq!(test_data.client.upgrade(&new_wasm_hash), true);
+
+    let upgraded = investment_wasm::Client::new(&e, &test_data.client.address);
+    assert_eq!(upgraded.version(), 0);
+    assert_eq!(upgraded.migrate(), 0);
+    assert_eq!(upgraded.version(), 1);
+    assert_eq!(upgraded.get_contract_balance().received_so_far, test_data.client.get_contract_balance().received_so_far);
+
+    // The migration only runs once per version
+    assert!(upgraded.try_migrate().is_err());
+}
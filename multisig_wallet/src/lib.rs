@@ -0,0 +1,156 @@
+#![no_std]
+
+//! N-of-M multisig wallet: the same signer set and approval threshold `Investment` uses for project
+//! withdrawals, turned into a standalone account. The wallet holds tokens like any other address and
+//! acts through proposals: an owner proposes an action with an expiry, the other owners approve it,
+//! and once the threshold is reached anyone can execute it. An action is either an arbitrary call to
+//! another contract, made with the wallet as invoker (so a token `transfer` from the wallet needs no
+//! other signature), or a change of the owners and threshold of the wallet itself.
+//!
+//! Each proposal is its own request of `soroban-examples-multisig`, so any number of them can be
+//! pending at the same time.
+
+use soroban_examples_events as events;
+use soroban_examples_multisig::{self as multisig, MultisigError, MultisigRequest, MultisigStatus};
+use soroban_sdk::{contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, IntoVal, Symbol, Val, Vec};
+
+mod storage;
+
+// Topics follow the workspace event schema: (CONTRACT_TAG, action)
+pub const CONTRACT_TAG: Symbol = symbol_short!("MULTISIG");
+pub const TOPIC_PROPOSED: Symbol = symbol_short!("PROPOSED");
+pub const TOPIC_APPROVED: Symbol = symbol_short!("APPROVED");
+pub const TOPIC_EXECUTED: Symbol = symbol_short!("EXECUTED");
+pub const TOPIC_CANCELLED: Symbol = symbol_short!("CANCELLED");
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    InvalidOwnerSet = 1401,
+    NotAnOwner = 1402,
+    ProposalNotFound = 1403,
+    ProposalExpired = 1404,
+    AlreadyApproved = 1405,
+    NotEnoughApprovals = 1406,
+}
+
+impl From<MultisigError> for Error {
+    fn from(err: MultisigError) -> Self {
+        match err {
+            MultisigError::InvalidSignerSet | MultisigError::NotConfigured => Error::InvalidOwnerSet,
+            MultisigError::NotASigner => Error::NotAnOwner,
+            // Proposal ids are never reused, so no request is ever pending under a new one
+            MultisigError::NoRequest | MultisigError::RequestPending => Error::ProposalNotFound,
+            MultisigError::RequestExpired => Error::ProposalExpired,
+            MultisigError::AlreadyApproved => Error::AlreadyApproved,
+            MultisigError::NotEnoughApprovals => Error::NotEnoughApprovals,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Call {
+    pub contract: Address,
+    pub function: Symbol,
+    pub args: Vec<Val>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Action {
+    /// Invokes a function of another contract on behalf of the wallet.
+    Call(Call),
+    /// Replaces the owners and the threshold of the wallet.
+    SetOwners(Vec<Address>, u32),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub action: Action,
+    pub request: MultisigRequest,
+    pub status: MultisigStatus,
+}
+
+// Multisig requests are keyed by a symbol: `proposal_` followed by the zero-padded id
+fn proposal_op(e: &Env, proposal_id: u32) -> Symbol {
+    let mut op = *b"proposal_0000000000";
+    let mut id = proposal_id;
+    for digit in op.iter_mut().rev().take(10) {
+        *digit = b'0' + (id % 10) as u8;
+        id /= 10;
+    }
+    Symbol::new(e, core::str::from_utf8(&op).unwrap())
+}
+
+#[contract]
+pub struct MultisigWallet;
+
+#[contractimpl]
+impl MultisigWallet {
+    /// Creates the wallet, whose proposals need `threshold` of the `owners` to approve them.
+    pub fn __constructor(e: Env, owners: Vec<Address>, threshold: u32) -> Result<(), Error> {
+        multisig::set_signers(&e, &owners, threshold)?;
+        Ok(())
+    }
+
+    /// `owner` proposes `action`, which has to gather the approvals before `expires_at`. The proposal
+    /// counts as the approval of `owner`. Returns its id.
+    pub fn propose(e: Env, owner: Address, action: Action, expires_at: u64) -> Result<u32, Error> {
+        let proposal_id = storage::next_proposal_id(&e);
+        multisig::propose(&e, &proposal_op(&e, proposal_id), &owner, &action, expires_at)?;
+        events::publish(&e, CONTRACT_TAG, TOPIC_PROPOSED, (proposal_id, owner));
+        Ok(proposal_id)
+    }
+
+    /// Adds the approval of `owner`. Returns the status of the proposal, `Approved` once it can be executed.
+    pub fn approve(e: Env, owner: Address, proposal_id: u32) -> Result<MultisigStatus, Error> {
+        let status = multisig::approve(&e, &proposal_op(&e, proposal_id), &owner)?;
+        events::publish(&e, CONTRACT_TAG, TOPIC_APPROVED, (proposal_id, owner));
+        Ok(status)
+    }
+
+    /// Drops a proposal. Any owner can do it.
+    pub fn cancel(e: Env, owner: Address, proposal_id: u32) -> Result<bool, Error> {
+        multisig::cancel(&e, &proposal_op(&e, proposal_id), &owner)?;
+        events::publish(&e, CONTRACT_TAG, TOPIC_CANCELLED, (proposal_id, owner));
+        Ok(true)
+    }
+
+    /// Executes an approved proposal; anyone can trigger it. Returns what the called function
+    /// returned, or nothing for a change of owners.
+    pub fn execute(e: Env, proposal_id: u32) -> Result<Val, Error> {
+        let action: Action = multisig::execute(&e, &proposal_op(&e, proposal_id))?;
+        let result = match action {
+            Action::Call(call) => e.invoke_contract::<Val>(&call.contract, &call.function, call.args),
+            Action::SetOwners(owners, threshold) => {
+                multisig::set_signers(&e, &owners, threshold)?;
+                ().into_val(&e)
+            }
+        };
+
+        events::publish(&e, CONTRACT_TAG, TOPIC_EXECUTED, proposal_id);
+        Ok(result)
+    }
+
+    pub fn owners(e: Env) -> Vec<Address> {
+        multisig::get_config(&e).map(|config| config.signers).unwrap_or(Vec::new(&e))
+    }
+
+    pub fn threshold(e: Env) -> u32 {
+        multisig::get_config(&e).map(|config| config.threshold).unwrap_or(0)
+    }
+
+    /// A proposal still pending execution.
+    pub fn proposal(e: Env, proposal_id: u32) -> Option<Proposal> {
+        let op = proposal_op(&e, proposal_id);
+        let action = multisig::get_payload(&e, &op)?;
+        let request = multisig::get_request(&e, &op)?;
+        Some(Proposal { action, request, status: multisig::status(&e, &op) })
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,15 @@
+use soroban_examples_storage::StorageCell;
+use soroban_sdk::{contracttype, Env};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    ProposalCount,
+}
+
+// Proposals themselves are multisig requests, kept by `soroban-examples-multisig`
+const PROPOSAL_COUNT: StorageCell<DataKey, u32> = StorageCell::instance(DataKey::ProposalCount);
+
+pub fn next_proposal_id(e: &Env) -> u32 {
+    PROPOSAL_COUNT.update(e, |count| count.unwrap_or(0) + 1)
+}
@@ -0,0 +1,188 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::{Action, Call, MultisigWallet, MultisigWalletClient, CONTRACT_TAG, TOPIC_EXECUTED};
+use soroban_examples_events::EVENT_VERSION;
+use soroban_examples_multisig::MultisigStatus;
+use soroban_examples_testutils::{advance_time, create_env, create_token_contract, mint_to, TokenClient};
+use soroban_sdk::{symbol_short, testutils::{Address as _, Events, Ledger}, vec, Address, Env, IntoVal, TryFromVal, Val};
+
+const START: u64 = 1_000;
+const EXPIRES_AT: u64 = START + 3_600;
+
+struct TestData<'a> {
+    owners: [Address; 3],
+    token: TokenClient<'a>,
+    client: MultisigWalletClient<'a>,
+}
+
+// A 2-of-3 wallet holding 1000 tokens
+fn create_wallet(e: &Env) -> TestData<'_> {
+    let owners = [Address::generate(e), Address::generate(e), Address::generate(e)];
+    let client = MultisigWalletClient::new(
+        e,
+        &e.register(MultisigWallet, (vec![e, owners[0].clone(), owners[1].clone(), owners[2].clone()], 2_u32)),
+    );
+    let (token, token_admin) = create_token_contract(e, &Address::generate(e));
+    mint_to(&token_admin, &[&client.address], 1_000);
+    e.ledger().set_timestamp(START);
+
+    TestData { owners, token, client }
+}
+
+fn transfer_call(e: &Env, test_data: &TestData, to: &Address, amount: i128) -> Action {
+    Action::Call(Call {
+        contract: test_data.token.address.clone(),
+        function: symbol_short!("transfer"),
+        args: vec![e, test_data.client.address.into_val(e), to.into_val(e), amount.into_val(e)],
+    })
+}
+
+#[test]
+fn test_approved_call_moves_the_wallet_funds() {
+    let e = create_env();
+    let test_data = create_wallet(&e);
+    let recipient = Address::generate(&e);
+
+    let proposal_id = test_data.client.propose(&test_data.owners[0], &transfer_call(&e, &test_data, &recipient, 400), &EXPIRES_AT);
+    assert_eq!(test_data.client.proposal(&proposal_id).unwrap().status, MultisigStatus::Pending);
+    assert_eq!(test_data.client.approve(&test_data.owners[2], &proposal_id), MultisigStatus::Approved);
+
+    test_data.client.execute(&proposal_id);
+    assert_eq!(
+        e.events().all().last().unwrap(),
+        (test_data.client.address.clone(), (CONTRACT_TAG, TOPIC_EXECUTED).into_val(&e), (EVENT_VERSION, proposal_id).into_val(&e))
+    );
+    assert_eq!(test_data.token.balance(&recipient), 400);
+    assert_eq!(test_data.token.balance(&test_data.client.address), 600);
+    assert_eq!(test_data.client.proposal(&proposal_id), None);
+}
+
+#[test]
+fn test_execute_returns_the_call_result() {
+    let e = create_env();
+    let test_data = create_wallet(&e);
+
+    let balance = Action::Call(Call {
+        contract: test_data.token.address.clone(),
+        function: symbol_short!("balance"),
+        args: vec![&e, test_data.client.address.into_val(&e)],
+    });
+    let proposal_id = test_data.client.propose(&test_data.owners[1], &balance, &EXPIRES_AT);
+    test_data.client.approve(&test_data.owners[0], &proposal_id);
+
+    let result: Val = test_data.client.execute(&proposal_id);
+    assert_eq!(i128::try_from_val(&e, &result).unwrap(), 1_000);
+}
+
+#[test]
+fn test_proposals_are_independent() {
+    let e = create_env();
+    let test_data = create_wallet(&e);
+    let (alice, bob) = (Address::generate(&e), Address::generate(&e));
+
+    let to_alice = test_data.client.propose(&test_data.owners[0], &transfer_call(&e, &test_data, &alice, 100), &EXPIRES_AT);
+    let to_bob = test_data.client.propose(&test_data.owners[1], &transfer_call(&e, &test_data, &bob, 200), &EXPIRES_AT);
+    test_data.client.approve(&test_data.owners[2], &to_bob);
+    test_data.client.execute(&to_bob);
+
+    assert_eq!(test_data.client.proposal(&to_alice).unwrap().status, MultisigStatus::Pending);
+    assert_eq!(test_data.token.balance(&bob), 200);
+}
+
+#[test]
+fn test_owners_change_through_a_proposal() {
+    let e = create_env();
+    let test_data = create_wallet(&e);
+    let new_owner = Address::generate(&e);
+
+    let new_owners = vec![&e, test_data.owners[0].clone(), new_owner.clone()];
+    let proposal_id = test_data.client.propose(&test_data.owners[0], &Action::SetOwners(new_owners.clone(), 2), &EXPIRES_AT);
+    test_data.client.approve(&test_data.owners[1], &proposal_id);
+    test_data.client.execute(&proposal_id);
+    assert_eq!(test_data.client.owners(), new_owners);
+
+    // The removed owners cannot propose any more, and the new one approves
+    assert!(test_data.client.try_propose(&test_data.owners[1], &transfer_call(&e, &test_data, &new_owner, 1), &EXPIRES_AT).is_err());
+    let proposal_id = test_data.client.propose(&new_owner, &transfer_call(&e, &test_data, &new_owner, 1), &EXPIRES_AT);
+    assert_eq!(test_data.client.approve(&test_data.owners[0], &proposal_id), MultisigStatus::Approved);
+}
+
+#[test]
+fn test_cancelled_proposal_is_dropped() {
+    let e = create_env();
+    let test_data = create_wallet(&e);
+
+    let proposal_id = test_data.client.propose(&test_data.owners[0], &transfer_call(&e, &test_data, &test_data.owners[0], 1), &EXPIRES_AT);
+    assert!(test_data.client.cancel(&test_data.owners[1], &proposal_id));
+    assert_eq!(test_data.client.proposal(&proposal_id), None);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1402)")]
+fn test_outsider_cannot_propose() {
+    let e = create_env();
+    let test_data = create_wallet(&e);
+    let outsider = Address::generate(&e);
+
+    test_data.client.propose(&outsider, &transfer_call(&e, &test_data, &outsider, 1000), &EXPIRES_AT);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1403)")]
+fn test_execute_twice() {
+    let e = create_env();
+    let test_data = create_wallet(&e);
+
+    let proposal_id = test_data.client.propose(&test_data.owners[0], &transfer_call(&e, &test_data, &test_data.owners[0], 1), &EXPIRES_AT);
+    test_data.client.approve(&test_data.owners[1], &proposal_id);
+    test_data.client.execute(&proposal_id);
+    test_data.client.execute(&proposal_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1404)")]
+fn test_expired_proposal() {
+    let e = create_env();
+    let test_data = create_wallet(&e);
+
+    let proposal_id = test_data.client.propose(&test_data.owners[0], &transfer_call(&e, &test_data, &test_data.owners[0], 1), &EXPIRES_AT);
+    test_data.client.approve(&test_data.owners[1], &proposal_id);
+    advance_time(&e, 3_600);
+    test_data.client.execute(&proposal_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1405)")]
+fn test_proposer_cannot_approve_again() {
+    let e = create_env();
+    let test_data = create_wallet(&e);
+
+    let proposal_id = test_data.client.propose(&test_data.owners[0], &transfer_call(&e, &test_data, &test_data.owners[0], 1), &EXPIRES_AT);
+    test_data.client.approve(&test_data.owners[0], &proposal_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1406)")]
+fn test_execute_without_enough_approvals() {
+    let e = create_env();
+    let test_data = create_wallet(&e);
+
+    let proposal_id = test_data.client.propose(&test_data.owners[0], &transfer_call(&e, &test_data, &test_data.owners[0], 1), &EXPIRES_AT);
+    test_data.client.execute(&proposal_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Context, InvalidAction)")]
+fn test_threshold_above_the_owners() {
+    let e = create_env();
+    e.register(MultisigWallet, (vec![&e, Address::generate(&e)], 2_u32));
+}
+
+#[test]
+fn error_codes_in_registry_range() {
+    use super::Error;
+
+    assert!(soroban_examples_errors::MULTISIG_WALLET.contains(Error::InvalidOwnerSet as u32));
+    assert!(soroban_examples_errors::MULTISIG_WALLET.contains(Error::NotEnoughApprovals as u32));
+}
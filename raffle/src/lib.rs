@@ -0,0 +1,209 @@
+#![no_std]
+
+//! Raffle paid in a token. Anyone buys tickets until the sales end; then the organizer calls `draw`,
+//! which picks the winning ticket with the contract PRNG and pays the pot to its owner, minus the
+//! organizer's fee. If fewer tickets than the minimum were sold, or the organizer does not draw within
+//! `DRAW_WINDOW` of the sales end, there is no draw and every buyer takes its money back with `refund`.
+//!
+//! `env.prng()` is seeded by the network for each invocation, so nobody can predict the draw before
+//! the ledger closes, but validators have some influence over it: fine for an example or a small
+//! raffle, not for one worth attacking the network over.
+//!
+//! The draw is not open to anyone because a caller sees the outcome before its transaction commits: a
+//! contract that calls `draw`, reads `winner()` and panics when it did not win reverts the whole draw,
+//! and can try again on the next ledger until one of its tickets comes out. Only the organizer can
+//! draw, so a buyer cannot do that. The organizer still could, so buyers trust it not to discard draws
+//! it does not like, as with any raffle run by someone.
+
+use soroban_examples_events as events;
+use soroban_examples_fixed::{self as fixed, MathError, Rounding};
+use soroban_sdk::{contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Env, Symbol};
+
+mod storage;
+
+pub const MAX_FEE_BPS: u32 = 10_000;
+/// Time the organizer has to draw once the sales are over; after that the raffle is cancelled.
+pub const DRAW_WINDOW: u64 = 7 * 24 * 60 * 60;
+
+// Topics follow the workspace event schema: (CONTRACT_TAG, action)
+pub const CONTRACT_TAG: Symbol = symbol_short!("RAFFLE");
+pub const TOPIC_SOLD: Symbol = symbol_short!("SOLD");
+pub const TOPIC_DRAWN: Symbol = symbol_short!("DRAWN");
+pub const TOPIC_REFUNDED: Symbol = symbol_short!("REFUNDED");
+
+macro_rules! require {
+    ($cond:expr, $err:expr) => {
+        if !$cond {
+            return Err($err);
+        }
+    };
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    SalesClosed = 1801,
+    SalesNotEnded = 1802,
+    AlreadyDrawn = 1803,
+    MinimumNotReached = 1804,
+    MinimumReached = 1805,
+    NothingToRefund = 1806,
+    InvalidAmount = 1807,
+    InvalidTerms = 1808,
+    ArithmeticError = 1809,
+    DrawWindowExpired = 1810,
+}
+
+impl From<MathError> for Error {
+    fn from(_err: MathError) -> Self {
+        Error::ArithmeticError
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RaffleConfig {
+    pub organizer: Address,
+    pub token: Address,
+    pub ticket_price: i128,
+    /// Tickets that must be sold for the draw to take place.
+    pub min_tickets: u32,
+    /// Share of the pot kept by the organizer, in basis points.
+    pub fee_bps: u32,
+    pub sales_end: u64,
+}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum RaffleStatus {
+    Open = 1,
+    /// Sales are over and the minimum was reached; waiting for the draw.
+    Closed = 2,
+    Drawn = 3,
+    /// Sales are over without reaching the minimum, or the organizer did not draw in time; buyers can
+    /// get refunded.
+    Cancelled = 4,
+}
+
+fn current_status(e: &Env, config: &RaffleConfig) -> RaffleStatus {
+    if e.ledger().timestamp() < config.sales_end {
+        RaffleStatus::Open
+    } else if storage::get_ticket_count(e) < config.min_tickets {
+        RaffleStatus::Cancelled
+    } else if storage::get_winner(e).is_some() {
+        RaffleStatus::Drawn
+    } else if e.ledger().timestamp() >= config.sales_end.saturating_add(DRAW_WINDOW) {
+        RaffleStatus::Cancelled
+    } else {
+        RaffleStatus::Closed
+    }
+}
+
+#[contract]
+pub struct Raffle;
+
+#[contractimpl]
+impl Raffle {
+    /// Sells tickets at `ticket_price` of `token` until `sales_end`. The draw needs `min_tickets` sold,
+    /// and `organizer` keeps `fee_bps` of the pot.
+    pub fn __constructor(e: Env, organizer: Address, token: Address, ticket_price: i128, min_tickets: u32, fee_bps: u32, sales_end: u64) -> Result<(), Error> {
+        organizer.require_auth();
+        require!(ticket_price > 0 && min_tickets > 0 && fee_bps <= MAX_FEE_BPS, Error::InvalidTerms);
+        require!(sales_end > e.ledger().timestamp(), Error::InvalidTerms);
+
+        storage::set_config(&e, &RaffleConfig { organizer, token, ticket_price, min_tickets, fee_bps, sales_end });
+        Ok(())
+    }
+
+    /// `buyer` pays for `count` tickets. Returns how many tickets it holds.
+    pub fn buy_tickets(e: Env, buyer: Address, count: u32) -> Result<u32, Error> {
+        buyer.require_auth();
+        require!(count > 0, Error::InvalidAmount);
+        let config = storage::get_config(&e);
+        require!(current_status(&e, &config) == RaffleStatus::Open, Error::SalesClosed);
+
+        let price = fixed::mul(config.ticket_price, count as i128)?;
+        let tickets = storage::add_purchase(&e, &buyer, count);
+        token::Client::new(&e, &config.token).transfer(&buyer, &e.current_contract_address(), &price);
+        events::publish(&e, CONTRACT_TAG, TOPIC_SOLD, (buyer, count));
+        Ok(tickets)
+    }
+
+    /// Draws the winning ticket once the sales are over, paying the prize to its owner and the fee to
+    /// the organizer. Only the organizer can call it, within `DRAW_WINDOW` of the sales end (see the
+    /// module docs for why). Returns the winner.
+    pub fn draw(e: Env) -> Result<Address, Error> {
+        let config = storage::get_config(&e);
+        config.organizer.require_auth();
+        match current_status(&e, &config) {
+            RaffleStatus::Closed => {}
+            RaffleStatus::Open => return Err(Error::SalesNotEnded),
+            RaffleStatus::Drawn => return Err(Error::AlreadyDrawn),
+            RaffleStatus::Cancelled if storage::get_ticket_count(&e) < config.min_tickets => return Err(Error::MinimumNotReached),
+            RaffleStatus::Cancelled => return Err(Error::DrawWindowExpired),
+        }
+
+        let ticket_count = storage::get_ticket_count(&e);
+        let ticket = e.prng().gen_range::<u64>(0..ticket_count as u64) as u32;
+        let winner = storage::buyer_of(&e, ticket);
+
+        let pot = fixed::mul(config.ticket_price, ticket_count as i128)?;
+        let fee = fixed::bps_of(pot, config.fee_bps, Rounding::Down)?;
+        let prize = fixed::sub(pot, fee)?;
+        storage::set_winner(&e, &winner);
+
+        let token = token::Client::new(&e, &config.token);
+        token.transfer(&e.current_contract_address(), &winner, &prize);
+        if fee > 0 {
+            token.transfer(&e.current_contract_address(), &config.organizer, &fee);
+        }
+        events::publish(&e, CONTRACT_TAG, TOPIC_DRAWN, (winner.clone(), ticket, prize));
+        Ok(winner)
+    }
+
+    /// Pays `buyer` its tickets back once the raffle has been cancelled. Returns the amount.
+    pub fn refund(e: Env, buyer: Address) -> Result<i128, Error> {
+        buyer.require_auth();
+        let config = storage::get_config(&e);
+        match current_status(&e, &config) {
+            RaffleStatus::Cancelled => {}
+            RaffleStatus::Open => return Err(Error::SalesNotEnded),
+            _ => return Err(Error::MinimumReached),
+        }
+
+        let tickets = storage::get_tickets(&e, &buyer);
+        require!(tickets > 0, Error::NothingToRefund);
+
+        // The ticket count is left as it was, so the raffle stays cancelled
+        let amount = fixed::mul(config.ticket_price, tickets as i128)?;
+        storage::remove_tickets(&e, &buyer);
+        token::Client::new(&e, &config.token).transfer(&e.current_contract_address(), &buyer, &amount);
+        events::publish(&e, CONTRACT_TAG, TOPIC_REFUNDED, (buyer, amount));
+        Ok(amount)
+    }
+
+    pub fn config(e: Env) -> RaffleConfig {
+        storage::get_config(&e)
+    }
+
+    pub fn status(e: Env) -> RaffleStatus {
+        current_status(&e, &storage::get_config(&e))
+    }
+
+    pub fn ticket_count(e: Env) -> u32 {
+        storage::get_ticket_count(&e)
+    }
+
+    pub fn tickets_of(e: Env, buyer: Address) -> u32 {
+        storage::get_tickets(&e, &buyer)
+    }
+
+    pub fn winner(e: Env) -> Option<Address> {
+        storage::get_winner(&e)
+    }
+}
+
+#[cfg(test)]
+mod test;
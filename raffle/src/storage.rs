@@ -0,0 +1,89 @@
+use crate::RaffleConfig;
+use soroban_examples_storage::{StorageCell, StorageMap};
+use soroban_sdk::{contracttype, Address, Env};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Config,
+    TicketCount,
+    PurchaseCount,
+    Winner,
+    Purchase(u32),
+    Tickets(Address),
+}
+
+/// A batch of consecutive tickets, from `first_ticket` on, bought in a single call.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Purchase {
+    pub buyer: Address,
+    pub first_ticket: u32,
+}
+
+// Tickets are stored per purchase rather than one entry each, so buying many at once costs a single
+// entry and the draw finds the owner of a ticket with a binary search over the purchases
+const CONFIG: StorageCell<DataKey, RaffleConfig> = StorageCell::instance(DataKey::Config);
+const TICKET_COUNT: StorageCell<DataKey, u32> = StorageCell::instance(DataKey::TicketCount);
+const PURCHASE_COUNT: StorageCell<DataKey, u32> = StorageCell::instance(DataKey::PurchaseCount);
+const WINNER: StorageCell<DataKey, Address> = StorageCell::instance(DataKey::Winner);
+const PURCHASES: StorageMap<u32, Purchase, DataKey> = StorageMap::persistent(|index| DataKey::Purchase(*index));
+const TICKETS: StorageMap<Address, u32, DataKey> = StorageMap::persistent(|buyer| DataKey::Tickets(buyer.clone()));
+
+pub fn get_config(e: &Env) -> RaffleConfig {
+    CONFIG.get_or_else(e, || panic!("Raffle config has expired"))
+}
+
+pub fn set_config(e: &Env, config: &RaffleConfig) {
+    CONFIG.set(e, config);
+}
+
+pub fn get_ticket_count(e: &Env) -> u32 {
+    TICKET_COUNT.get_or_else(e, || 0)
+}
+
+/// Records `count` tickets for `buyer` and returns how many it holds.
+pub fn add_purchase(e: &Env, buyer: &Address, count: u32) -> u32 {
+    let first_ticket = get_ticket_count(e);
+    let index = PURCHASE_COUNT.get_or_else(e, || 0);
+    PURCHASES.set(e, &index, &Purchase { buyer: buyer.clone(), first_ticket });
+    PURCHASE_COUNT.set(e, &(index + 1));
+    TICKET_COUNT.set(e, &(first_ticket + count));
+    TICKETS.update(e, buyer, |tickets| tickets.unwrap_or(0) + count)
+}
+
+/// Owner of `ticket`, which must be below the ticket count.
+pub fn buyer_of(e: &Env, ticket: u32) -> Address {
+    // Last purchase whose first ticket is not after `ticket`
+    let (mut low, mut high) = (0, PURCHASE_COUNT.get_or_else(e, || 0));
+    while high - low > 1 {
+        let middle = low + (high - low) / 2;
+        if get_purchase(e, middle).first_ticket <= ticket {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+
+    get_purchase(e, low).buyer
+}
+
+fn get_purchase(e: &Env, index: u32) -> Purchase {
+    PURCHASES.get_or_else(e, &index, || panic!("Raffle purchase has expired"))
+}
+
+pub fn get_tickets(e: &Env, buyer: &Address) -> u32 {
+    TICKETS.get_or_else(e, buyer, || 0)
+}
+
+pub fn remove_tickets(e: &Env, buyer: &Address) {
+    TICKETS.remove(e, buyer);
+}
+
+pub fn get_winner(e: &Env) -> Option<Address> {
+    WINNER.get(e)
+}
+
+pub fn set_winner(e: &Env, winner: &Address) {
+    WINNER.set(e, winner);
+}
@@ -0,0 +1,235 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::{storage, Raffle, RaffleClient, RaffleStatus, CONTRACT_TAG, DRAW_WINDOW, TOPIC_REFUNDED};
+use soroban_examples_events::EVENT_VERSION;
+use soroban_examples_testutils::{advance_days, create_env, create_token_contract, mint_to, TokenClient, SECONDS_IN_DAY};
+use soroban_sdk::{testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke}, Address, Env, IntoVal};
+
+const START: u64 = 1_000;
+const SALES_END: u64 = START + 7 * SECONDS_IN_DAY;
+const TICKET_PRICE: i128 = 10;
+const MIN_TICKETS: u32 = 5;
+// 5% of the pot
+const FEE_BPS: u32 = 500;
+
+struct TestData<'a> {
+    organizer: Address,
+    alice: Address,
+    bob: Address,
+    token: TokenClient<'a>,
+    client: RaffleClient<'a>,
+}
+
+fn create_raffle(e: &Env) -> TestData<'_> {
+    let (organizer, alice, bob) = (Address::generate(e), Address::generate(e), Address::generate(e));
+    let (token, token_admin) = create_token_contract(e, &Address::generate(e));
+    mint_to(&token_admin, &[&alice, &bob], 1_000);
+    e.ledger().set_timestamp(START);
+
+    let client = RaffleClient::new(e, &e.register(Raffle, (organizer.clone(), token.address.clone(), TICKET_PRICE, MIN_TICKETS, FEE_BPS, SALES_END)));
+
+    TestData { organizer, alice, bob, token, client }
+}
+
+fn end_sales(e: &Env) {
+    e.ledger().set_timestamp(SALES_END);
+}
+
+#[test]
+fn test_draw_pays_the_winner_and_the_organizer() {
+    let e = create_env();
+    let test_data = create_raffle(&e);
+
+    test_data.client.buy_tickets(&test_data.alice, &4);
+    advance_days(&e, 2);
+    test_data.client.buy_tickets(&test_data.bob, &10);
+    assert_eq!(test_data.client.buy_tickets(&test_data.alice, &6), 10);
+    assert_eq!(test_data.client.ticket_count(), 20);
+    assert_eq!(test_data.token.balance(&test_data.client.address), 200);
+
+    end_sales(&e);
+    assert_eq!(test_data.client.status(), RaffleStatus::Closed);
+    let winner = test_data.client.draw();
+    assert!(winner == test_data.alice || winner == test_data.bob);
+    assert_eq!(test_data.client.winner(), Some(winner.clone()));
+    assert_eq!(test_data.client.status(), RaffleStatus::Drawn);
+
+    // Each of them paid 100 for 10 tickets; the winner takes the pot of 200 minus the 5% fee
+    assert_eq!(test_data.token.balance(&winner), 900 + 190);
+    assert_eq!(test_data.token.balance(&test_data.organizer), 10);
+    assert_eq!(test_data.token.balance(&test_data.client.address), 0);
+}
+
+#[test]
+fn test_tickets_are_drawn_from_the_right_purchase() {
+    let e = create_env();
+    let test_data = create_raffle(&e);
+
+    e.as_contract(&test_data.client.address, || {
+        storage::add_purchase(&e, &test_data.alice, 3);
+        storage::add_purchase(&e, &test_data.bob, 2);
+        storage::add_purchase(&e, &test_data.alice, 1);
+
+        let owners = [&test_data.alice, &test_data.alice, &test_data.alice, &test_data.bob, &test_data.bob, &test_data.alice];
+        for (ticket, owner) in owners.iter().enumerate() {
+            assert_eq!(&storage::buyer_of(&e, ticket as u32), *owner);
+        }
+        assert_eq!(storage::get_tickets(&e, &test_data.alice), 4);
+    });
+}
+
+#[test]
+fn test_cancelled_raffle_refunds_every_buyer() {
+    let e = create_env();
+    let test_data = create_raffle(&e);
+
+    test_data.client.buy_tickets(&test_data.alice, &3);
+    test_data.client.buy_tickets(&test_data.bob, &1);
+
+    end_sales(&e);
+    assert_eq!(test_data.client.status(), RaffleStatus::Cancelled);
+    assert_eq!(test_data.client.refund(&test_data.alice), 30);
+    assert_eq!(
+        e.events().all().last().unwrap(),
+        (test_data.client.address.clone(), (CONTRACT_TAG, TOPIC_REFUNDED).into_val(&e), (EVENT_VERSION, (test_data.alice.clone(), 30_i128)).into_val(&e))
+    );
+
+    // Refunds do not change the outcome of the raffle
+    assert_eq!(test_data.client.status(), RaffleStatus::Cancelled);
+    assert_eq!(test_data.client.refund(&test_data.bob), 10);
+    assert_eq!(test_data.token.balance(&test_data.alice), 1_000);
+    assert_eq!(test_data.token.balance(&test_data.bob), 1_000);
+    assert_eq!(test_data.client.tickets_of(&test_data.alice), 0);
+}
+
+#[test]
+fn test_only_the_organizer_draws() {
+    let e = create_env();
+    let test_data = create_raffle(&e);
+
+    test_data.client.buy_tickets(&test_data.alice, &MIN_TICKETS);
+    end_sales(&e);
+
+    // A buyer drawing could revert every draw it does not win
+    e.mock_auths(&[MockAuth {
+        address: &test_data.alice,
+        invoke: &MockAuthInvoke { contract: &test_data.client.address, fn_name: "draw", args: ().into_val(&e), sub_invokes: &[] },
+    }]);
+    assert!(test_data.client.try_draw().is_err());
+    assert_eq!(test_data.client.status(), RaffleStatus::Closed);
+
+    e.mock_auths(&[MockAuth {
+        address: &test_data.organizer,
+        invoke: &MockAuthInvoke { contract: &test_data.client.address, fn_name: "draw", args: ().into_val(&e), sub_invokes: &[] },
+    }]);
+    assert_eq!(test_data.client.draw(), test_data.alice);
+    assert_eq!(test_data.client.status(), RaffleStatus::Drawn);
+}
+
+#[test]
+fn test_undrawn_raffle_is_cancelled_after_the_draw_window() {
+    let e = create_env();
+    let test_data = create_raffle(&e);
+
+    test_data.client.buy_tickets(&test_data.alice, &MIN_TICKETS);
+    e.ledger().set_timestamp(SALES_END + DRAW_WINDOW - 1);
+    assert_eq!(test_data.client.status(), RaffleStatus::Closed);
+
+    e.ledger().set_timestamp(SALES_END + DRAW_WINDOW);
+    assert_eq!(test_data.client.status(), RaffleStatus::Cancelled);
+    assert_eq!(test_data.client.try_draw(), Err(Ok(crate::Error::DrawWindowExpired)));
+    assert_eq!(test_data.client.refund(&test_data.alice), TICKET_PRICE * MIN_TICKETS as i128);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1801)")]
+fn test_buy_after_the_sales_end() {
+    let e = create_env();
+    let test_data = create_raffle(&e);
+
+    end_sales(&e);
+    test_data.client.buy_tickets(&test_data.alice, &1);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1802)")]
+fn test_draw_before_the_sales_end() {
+    let e = create_env();
+    let test_data = create_raffle(&e);
+
+    test_data.client.buy_tickets(&test_data.alice, &MIN_TICKETS);
+    test_data.client.draw();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1803)")]
+fn test_draw_twice() {
+    let e = create_env();
+    let test_data = create_raffle(&e);
+
+    test_data.client.buy_tickets(&test_data.alice, &MIN_TICKETS);
+    end_sales(&e);
+    test_data.client.draw();
+    test_data.client.draw();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1804)")]
+fn test_draw_below_the_minimum() {
+    let e = create_env();
+    let test_data = create_raffle(&e);
+
+    test_data.client.buy_tickets(&test_data.alice, &(MIN_TICKETS - 1));
+    end_sales(&e);
+    test_data.client.draw();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1805)")]
+fn test_refund_after_reaching_the_minimum() {
+    let e = create_env();
+    let test_data = create_raffle(&e);
+
+    test_data.client.buy_tickets(&test_data.alice, &MIN_TICKETS);
+    end_sales(&e);
+    test_data.client.refund(&test_data.alice);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1806)")]
+fn test_refund_twice() {
+    let e = create_env();
+    let test_data = create_raffle(&e);
+
+    test_data.client.buy_tickets(&test_data.alice, &1);
+    end_sales(&e);
+    test_data.client.refund(&test_data.alice);
+    test_data.client.refund(&test_data.alice);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1807)")]
+fn test_buy_no_tickets() {
+    let e = create_env();
+    let test_data = create_raffle(&e);
+
+    test_data.client.buy_tickets(&test_data.alice, &0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Context, InvalidAction)")]
+fn test_fee_above_the_pot() {
+    let e = create_env();
+    let test_data = create_raffle(&e);
+
+    e.register(Raffle, (test_data.organizer.clone(), test_data.token.address.clone(), TICKET_PRICE, MIN_TICKETS, 10_001_u32, SALES_END));
+}
+
+#[test]
+fn error_codes_in_registry_range() {
+    use super::Error;
+
+    assert!(soroban_examples_errors::RAFFLE.contains(Error::SalesClosed as u32));
+    assert!(soroban_examples_errors::RAFFLE.contains(Error::DrawWindowExpired as u32));
+}
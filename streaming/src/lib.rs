@@ -0,0 +1,154 @@
+#![no_std]
+
+//! Per-second payment streaming. A sender deposits an amount that flows to a recipient at a constant
+//! rate between a start and an end time; at any moment the recipient can `withdraw` what has accrued
+//! so far. Where an `Investment` pays its investors in discrete monthly installments, a stream pays
+//! continuously, which suits salaries or vesting-like grants with no schedule to maintain.
+//!
+//! Either side can cancel a stream: the recipient gets what had accrued and not been withdrawn, and
+//! the sender the part that had not streamed yet. A stream is removed once fully paid out or cancelled.
+
+use soroban_examples_events as events;
+use soroban_examples_fixed::{self as fixed, MathError, Rounding};
+use soroban_sdk::{contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Env, Symbol};
+
+mod storage;
+
+// Topics follow the workspace event schema: (CONTRACT_TAG, action)
+pub const CONTRACT_TAG: Symbol = symbol_short!("STREAM");
+pub const TOPIC_CREATED: Symbol = symbol_short!("CREATED");
+pub const TOPIC_WITHDRAWN: Symbol = symbol_short!("WITHDRAWN");
+pub const TOPIC_CANCELLED: Symbol = symbol_short!("CANCELLED");
+
+macro_rules! require {
+    ($cond:expr, $err:expr) => {
+        if !$cond {
+            return Err($err);
+        }
+    };
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    StreamNotFound = 1301,
+    NotStreamParty = 1302,
+    NothingToWithdraw = 1303,
+    InvalidStream = 1304,
+    ArithmeticError = 1305,
+}
+
+impl From<MathError> for Error {
+    fn from(_err: MathError) -> Self {
+        Error::ArithmeticError
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Stream {
+    pub sender: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub deposit: i128,
+    pub start_ts: u64,
+    pub end_ts: u64,
+    /// Paid to the recipient so far.
+    pub withdrawn: i128,
+}
+
+/// Part of the deposit of `stream` that has flowed to the recipient by `ts`, rounded down. Fails with
+/// `ArithmeticError` if the deposit is too large to scale by the elapsed time.
+pub fn streamed_at(stream: &Stream, ts: u64) -> Result<i128, Error> {
+    if ts <= stream.start_ts {
+        return Ok(0);
+    }
+    if ts >= stream.end_ts {
+        return Ok(stream.deposit);
+    }
+
+    let (elapsed, duration) = ((ts - stream.start_ts) as i128, (stream.end_ts - stream.start_ts) as i128);
+    Ok(fixed::mul_div(stream.deposit, elapsed, duration, Rounding::Down)?)
+}
+
+fn get_stream(e: &Env, stream_id: u64) -> Result<Stream, Error> {
+    storage::get_stream(e, stream_id).ok_or(Error::StreamNotFound)
+}
+
+fn withdrawable(e: &Env, stream: &Stream) -> Result<i128, Error> {
+    Ok(streamed_at(stream, e.ledger().timestamp())? - stream.withdrawn)
+}
+
+#[contract]
+pub struct PaymentStreaming;
+
+#[contractimpl]
+impl PaymentStreaming {
+    /// `sender` deposits `deposit` of `token`, streamed to `recipient` from `start_ts` to `end_ts`.
+    /// Returns the id of the stream.
+    pub fn create_stream(e: Env, sender: Address, recipient: Address, token: Address, deposit: i128, start_ts: u64, end_ts: u64) -> Result<u64, Error> {
+        sender.require_auth();
+        require!(deposit > 0 && sender != recipient, Error::InvalidStream);
+        require!(start_ts < end_ts && end_ts > e.ledger().timestamp(), Error::InvalidStream);
+
+        let stream_id = storage::next_stream_id(&e);
+        let stream = Stream { sender: sender.clone(), recipient: recipient.clone(), token: token.clone(), deposit, start_ts, end_ts, withdrawn: 0 };
+        storage::set_stream(&e, stream_id, &stream);
+        token::Client::new(&e, &token).transfer(&sender, &e.current_contract_address(), &deposit);
+        events::publish(&e, CONTRACT_TAG, TOPIC_CREATED, (stream_id, sender, recipient, deposit));
+        Ok(stream_id)
+    }
+
+    /// Pays the recipient everything streamed and not withdrawn yet. Returns the amount paid.
+    pub fn withdraw(e: Env, stream_id: u64) -> Result<i128, Error> {
+        let mut stream = get_stream(&e, stream_id)?;
+        stream.recipient.require_auth();
+        let amount = withdrawable(&e, &stream)?;
+        require!(amount > 0, Error::NothingToWithdraw);
+
+        stream.withdrawn += amount;
+        if stream.withdrawn == stream.deposit {
+            storage::remove_stream(&e, stream_id);
+        } else {
+            storage::set_stream(&e, stream_id, &stream);
+        }
+        token::Client::new(&e, &stream.token).transfer(&e.current_contract_address(), &stream.recipient, &amount);
+        events::publish(&e, CONTRACT_TAG, TOPIC_WITHDRAWN, (stream_id, amount));
+        Ok(amount)
+    }
+
+    /// The sender or the recipient stops the stream. The recipient is paid what it is owed and the
+    /// sender what has not streamed yet; returns both amounts, the recipient's first.
+    pub fn cancel(e: Env, caller: Address, stream_id: u64) -> Result<(i128, i128), Error> {
+        caller.require_auth();
+        let stream = get_stream(&e, stream_id)?;
+        require!(caller == stream.sender || caller == stream.recipient, Error::NotStreamParty);
+
+        let streamed = streamed_at(&stream, e.ledger().timestamp())?;
+        let (recipient_amount, sender_amount) = (streamed - stream.withdrawn, stream.deposit - streamed);
+        storage::remove_stream(&e, stream_id);
+
+        let token = token::Client::new(&e, &stream.token);
+        if recipient_amount > 0 {
+            token.transfer(&e.current_contract_address(), &stream.recipient, &recipient_amount);
+        }
+        if sender_amount > 0 {
+            token.transfer(&e.current_contract_address(), &stream.sender, &sender_amount);
+        }
+        events::publish(&e, CONTRACT_TAG, TOPIC_CANCELLED, (stream_id, recipient_amount, sender_amount));
+        Ok((recipient_amount, sender_amount))
+    }
+
+    pub fn stream(e: Env, stream_id: u64) -> Result<Stream, Error> {
+        get_stream(&e, stream_id)
+    }
+
+    /// Amount the recipient would get from `withdraw` now.
+    pub fn withdrawable(e: Env, stream_id: u64) -> Result<i128, Error> {
+        withdrawable(&e, &get_stream(&e, stream_id)?)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,29 @@
+use crate::Stream;
+use soroban_examples_storage::{StorageCell, StorageMap};
+use soroban_sdk::{contracttype, Env};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    StreamCount,
+    Stream(u64),
+}
+
+const STREAM_COUNT: StorageCell<DataKey, u64> = StorageCell::instance(DataKey::StreamCount);
+const STREAMS: StorageMap<u64, Stream, DataKey> = StorageMap::persistent(|id| DataKey::Stream(*id));
+
+pub fn next_stream_id(e: &Env) -> u64 {
+    STREAM_COUNT.update(e, |count| count.unwrap_or(0) + 1)
+}
+
+pub fn get_stream(e: &Env, id: u64) -> Option<Stream> {
+    STREAMS.get(e, &id)
+}
+
+pub fn set_stream(e: &Env, id: u64, stream: &Stream) {
+    STREAMS.set(e, &id, stream);
+}
+
+pub fn remove_stream(e: &Env, id: u64) {
+    STREAMS.remove(e, &id);
+}
@@ -0,0 +1,174 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::{streamed_at, Error, PaymentStreaming, PaymentStreamingClient, Stream, CONTRACT_TAG, TOPIC_CANCELLED};
+use soroban_examples_events::EVENT_VERSION;
+use soroban_examples_testutils::{advance_days, advance_time, create_env, create_token_contract, mint_to, TokenAdminClient, TokenClient, SECONDS_IN_DAY};
+use soroban_sdk::{testutils::{Address as _, Events, Ledger}, Address, Env, IntoVal};
+
+const START: u64 = 1_000;
+const END: u64 = START + 100 * SECONDS_IN_DAY;
+// One token per second
+const DEPOSIT: i128 = 8_640_000;
+
+struct TestData<'a> {
+    sender: Address,
+    recipient: Address,
+    token: TokenClient<'a>,
+    token_admin: TokenAdminClient<'a>,
+    stream_id: u64,
+    client: PaymentStreamingClient<'a>,
+}
+
+fn create_stream(e: &Env) -> TestData<'_> {
+    let (sender, recipient) = (Address::generate(e), Address::generate(e));
+    let (token, token_admin) = create_token_contract(e, &Address::generate(e));
+    mint_to(&token_admin, &[&sender], DEPOSIT);
+    e.ledger().set_timestamp(START);
+
+    let client = PaymentStreamingClient::new(e, &e.register(PaymentStreaming, ()));
+    let stream_id = client.create_stream(&sender, &recipient, &token.address, &DEPOSIT, &START, &END);
+
+    TestData { sender, recipient, token, token_admin, stream_id, client }
+}
+
+#[test]
+fn test_streamed_amount_grows_every_second() {
+    let e = Env::default();
+    let stream = Stream {
+        sender: Address::generate(&e),
+        recipient: Address::generate(&e),
+        token: Address::generate(&e),
+        deposit: 1_000,
+        start_ts: 100,
+        end_ts: 400,
+        withdrawn: 0,
+    };
+
+    assert_eq!(streamed_at(&stream, 0), Ok(0));
+    assert_eq!(streamed_at(&stream, 100), Ok(0));
+    assert_eq!(streamed_at(&stream, 101), Ok(3));
+    assert_eq!(streamed_at(&stream, 250), Ok(500));
+    assert_eq!(streamed_at(&stream, 400), Ok(1_000));
+    assert_eq!(streamed_at(&stream, u64::MAX), Ok(1_000));
+}
+
+#[test]
+fn test_streamed_amount_overflow() {
+    let e = Env::default();
+    let stream = Stream {
+        sender: Address::generate(&e),
+        recipient: Address::generate(&e),
+        token: Address::generate(&e),
+        deposit: i128::MAX,
+        start_ts: 100,
+        end_ts: 400,
+        withdrawn: 0,
+    };
+
+    // Scaling the deposit by the elapsed time overflows before the division brings it back
+    assert_eq!(streamed_at(&stream, 250), Err(Error::ArithmeticError));
+    assert_eq!(streamed_at(&stream, 400), Ok(i128::MAX));
+}
+
+#[test]
+fn test_recipient_withdraws_as_it_accrues() {
+    let e = create_env();
+    let test_data = create_stream(&e);
+    assert_eq!(test_data.token.balance(&test_data.client.address), DEPOSIT);
+
+    advance_time(&e, 3_600);
+    assert_eq!(test_data.client.withdrawable(&test_data.stream_id), 3_600);
+    assert_eq!(test_data.client.withdraw(&test_data.stream_id), 3_600);
+
+    advance_time(&e, 60);
+    assert_eq!(test_data.client.withdraw(&test_data.stream_id), 60);
+    assert_eq!(test_data.client.stream(&test_data.stream_id).withdrawn, 3_660);
+
+    // The last withdrawal pays the rest and removes the stream
+    advance_days(&e, 200);
+    assert_eq!(test_data.client.withdraw(&test_data.stream_id), DEPOSIT - 3_660);
+    assert_eq!(test_data.token.balance(&test_data.recipient), DEPOSIT);
+    assert!(test_data.client.try_stream(&test_data.stream_id).is_err());
+}
+
+#[test]
+fn test_cancel_splits_the_deposit() {
+    let e = create_env();
+    let test_data = create_stream(&e);
+
+    advance_days(&e, 10);
+    test_data.client.withdraw(&test_data.stream_id);
+    advance_days(&e, 15);
+
+    let owed = 15 * SECONDS_IN_DAY as i128;
+    let unstreamed = 75 * SECONDS_IN_DAY as i128;
+    assert_eq!(test_data.client.cancel(&test_data.sender, &test_data.stream_id), (owed, unstreamed));
+    assert_eq!(
+        e.events().all().last().unwrap(),
+        (test_data.client.address.clone(), (CONTRACT_TAG, TOPIC_CANCELLED).into_val(&e), (EVENT_VERSION, (test_data.stream_id, owed, unstreamed)).into_val(&e))
+    );
+    assert_eq!(test_data.token.balance(&test_data.recipient), 25 * SECONDS_IN_DAY as i128);
+    assert_eq!(test_data.token.balance(&test_data.sender), unstreamed);
+    assert_eq!(test_data.token.balance(&test_data.client.address), 0);
+}
+
+#[test]
+fn test_cancel_before_the_start_refunds_the_sender() {
+    let e = create_env();
+    let test_data = create_stream(&e);
+    mint_to(&test_data.token_admin, &[&test_data.sender], 1_000);
+
+    let start = START + 10 * SECONDS_IN_DAY;
+    let stream_id = test_data.client.create_stream(&test_data.sender, &test_data.recipient, &test_data.token.address, &1_000, &start, &(start + 1_000));
+    assert_ne!(stream_id, test_data.stream_id);
+
+    assert_eq!(test_data.client.cancel(&test_data.recipient, &stream_id), (0, 1_000));
+    assert_eq!(test_data.token.balance(&test_data.sender), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1301)")]
+fn test_withdraw_from_cancelled_stream() {
+    let e = create_env();
+    let test_data = create_stream(&e);
+
+    test_data.client.cancel(&test_data.sender, &test_data.stream_id);
+    advance_days(&e, 1);
+    test_data.client.withdraw(&test_data.stream_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1302)")]
+fn test_outsider_cannot_cancel() {
+    let e = create_env();
+    let test_data = create_stream(&e);
+
+    test_data.client.cancel(&Address::generate(&e), &test_data.stream_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1303)")]
+fn test_withdraw_twice_in_the_same_second() {
+    let e = create_env();
+    let test_data = create_stream(&e);
+
+    advance_time(&e, 10);
+    test_data.client.withdraw(&test_data.stream_id);
+    test_data.client.withdraw(&test_data.stream_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1304)")]
+fn test_stream_ending_before_it_starts() {
+    let e = create_env();
+    let test_data = create_stream(&e);
+
+    test_data.client.create_stream(&test_data.sender, &test_data.recipient, &test_data.token.address, &DEPOSIT, &END, &START);
+}
+
+#[test]
+fn error_codes_in_registry_range() {
+    assert!(soroban_examples_errors::STREAMING.contains(Error::StreamNotFound as u32));
+    assert!(soroban_examples_errors::STREAMING.contains(Error::ArithmeticError as u32));
+}
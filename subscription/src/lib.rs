@@ -0,0 +1,175 @@
+#![no_std]
+
+//! Subscription billing. Merchants register plans (a token, an amount and a billing interval) and
+//! subscribers join them after approving this contract as spender of the plan's token. The subscriber
+//! never signs a payment again: once a period is due, anyone (usually the merchant's billing job) can
+//! call `charge`, which pulls the amount through the allowance and emits an invoice event. The
+//! subscriber stops the billing by cancelling, or simply by letting the allowance run out. A subscriber
+//! can hold several plans at once, each one billed and cancelled on its own.
+
+use soroban_examples_events as events;
+use soroban_examples_transfer::{self as transfer, TransferError};
+use soroban_sdk::{contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, Symbol};
+
+mod storage;
+
+// Topics follow the workspace event schema: (CONTRACT_TAG, action)
+pub const CONTRACT_TAG: Symbol = symbol_short!("SUBSCR");
+pub const TOPIC_PLAN_CREATED: Symbol = symbol_short!("PLAN");
+pub const TOPIC_PLAN_CLOSED: Symbol = symbol_short!("PLANCLOSE");
+pub const TOPIC_SUBSCRIBED: Symbol = symbol_short!("SUBSCRIB");
+pub const TOPIC_INVOICE: Symbol = symbol_short!("INVOICE");
+pub const TOPIC_CANCELLED: Symbol = symbol_short!("CANCELLED");
+
+macro_rules! require {
+    ($cond:expr, $err:expr) => {
+        if !$cond {
+            return Err($err);
+        }
+    };
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    PlanNotFound = 1201,
+    PlanClosed = 1202,
+    AlreadySubscribed = 1203,
+    NotSubscribed = 1204,
+    NotDue = 1205,
+    InsufficientAllowance = 1206,
+    InsufficientBalance = 1207,
+    PaymentFailed = 1208,
+    InvalidPlan = 1209,
+}
+
+impl From<TransferError> for Error {
+    fn from(err: TransferError) -> Self {
+        match err {
+            TransferError::InsufficientAllowance => Error::InsufficientAllowance,
+            TransferError::InsufficientBalance => Error::InsufficientBalance,
+            TransferError::TransferFailed => Error::PaymentFailed,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Plan {
+    pub merchant: Address,
+    pub token: Address,
+    /// Amount billed every `interval` seconds.
+    pub amount: i128,
+    pub interval: u64,
+    /// A closed plan takes no new subscribers and bills nobody.
+    pub active: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Subscription {
+    pub plan_id: u32,
+    pub started_ts: u64,
+    /// Start of the next period to bill.
+    pub next_charge_ts: u64,
+    /// Number of invoices billed so far, which also numbers them.
+    pub invoices: u32,
+}
+
+fn get_plan(e: &Env, plan_id: u32) -> Result<Plan, Error> {
+    storage::get_plan(e, plan_id).ok_or(Error::PlanNotFound)
+}
+
+// Bills the period starting at `next_charge_ts`. Periods that went by without a charge are not billed.
+fn bill(e: &Env, subscriber: &Address, plan: &Plan, subscription: &mut Subscription) -> Result<i128, Error> {
+    let now = e.ledger().timestamp();
+    require!(now >= subscription.next_charge_ts, Error::NotDue);
+
+    let missed = (now - subscription.next_charge_ts) / plan.interval;
+    subscription.next_charge_ts += (missed + 1) * plan.interval;
+    subscription.invoices += 1;
+    storage::set_subscription(e, subscriber, subscription);
+
+    let contract = e.current_contract_address();
+    transfer::transfer_from(e, &plan.token, &contract, subscriber, &plan.merchant, plan.amount)?;
+    events::publish(e, CONTRACT_TAG, TOPIC_INVOICE, (subscriber.clone(), subscription.plan_id, subscription.invoices, plan.amount));
+    Ok(plan.amount)
+}
+
+#[contract]
+pub struct SubscriptionBilling;
+
+#[contractimpl]
+impl SubscriptionBilling {
+    /// `merchant` offers a plan billing `amount` of `token` every `interval` seconds. Returns its id.
+    pub fn create_plan(e: Env, merchant: Address, token: Address, amount: i128, interval: u64) -> Result<u32, Error> {
+        merchant.require_auth();
+        require!(amount > 0 && interval > 0, Error::InvalidPlan);
+
+        let plan_id = storage::next_plan_id(&e);
+        storage::set_plan(&e, plan_id, &Plan { merchant: merchant.clone(), token, amount, interval, active: true });
+        events::publish(&e, CONTRACT_TAG, TOPIC_PLAN_CREATED, (merchant, plan_id, amount, interval));
+        Ok(plan_id)
+    }
+
+    /// The merchant stops offering and billing the plan. Its subscriptions are not charged any more.
+    pub fn close_plan(e: Env, plan_id: u32) -> Result<bool, Error> {
+        let mut plan = get_plan(&e, plan_id)?;
+        plan.merchant.require_auth();
+        require!(plan.active, Error::PlanClosed);
+
+        plan.active = false;
+        storage::set_plan(&e, plan_id, &plan);
+        events::publish(&e, CONTRACT_TAG, TOPIC_PLAN_CLOSED, plan_id);
+        Ok(true)
+    }
+
+    /// `subscriber` joins the plan and pays the first period right away. It can only hold one
+    /// subscription to each plan. It must have approved this
+    /// contract as spender of the plan's token for as many periods as it wants to be billed.
+    pub fn subscribe(e: Env, subscriber: Address, plan_id: u32) -> Result<Subscription, Error> {
+        subscriber.require_auth();
+        let plan = get_plan(&e, plan_id)?;
+        require!(plan.active, Error::PlanClosed);
+        require!(storage::get_subscription(&e, &subscriber, plan_id).is_none(), Error::AlreadySubscribed);
+
+        let now = e.ledger().timestamp();
+        let mut subscription = Subscription { plan_id, started_ts: now, next_charge_ts: now, invoices: 0 };
+        events::publish(&e, CONTRACT_TAG, TOPIC_SUBSCRIBED, (subscriber.clone(), plan_id));
+        bill(&e, &subscriber, &plan, &mut subscription)?;
+        Ok(subscription)
+    }
+
+    /// Bills the current period of the subscription of `subscriber` to `plan_id` once it is due. Anyone
+    /// can call it. Returns the amount billed.
+    pub fn charge(e: Env, subscriber: Address, plan_id: u32) -> Result<i128, Error> {
+        let mut subscription = storage::get_subscription(&e, &subscriber, plan_id).ok_or(Error::NotSubscribed)?;
+        let plan = get_plan(&e, plan_id)?;
+        require!(plan.active, Error::PlanClosed);
+
+        bill(&e, &subscriber, &plan, &mut subscription)
+    }
+
+    /// The subscriber leaves the plan `plan_id`, keeping its other subscriptions. The period already
+    /// paid is not refunded.
+    pub fn cancel(e: Env, subscriber: Address, plan_id: u32) -> Result<bool, Error> {
+        subscriber.require_auth();
+        require!(storage::get_subscription(&e, &subscriber, plan_id).is_some(), Error::NotSubscribed);
+
+        storage::remove_subscription(&e, &subscriber, plan_id);
+        events::publish(&e, CONTRACT_TAG, TOPIC_CANCELLED, (subscriber, plan_id));
+        Ok(true)
+    }
+
+    pub fn plan(e: Env, plan_id: u32) -> Result<Plan, Error> {
+        get_plan(&e, plan_id)
+    }
+
+    pub fn subscription(e: Env, subscriber: Address, plan_id: u32) -> Option<Subscription> {
+        storage::get_subscription(&e, &subscriber, plan_id)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,41 @@
+use crate::{Plan, Subscription};
+use soroban_examples_storage::{StorageCell, StorageMap};
+use soroban_sdk::{contracttype, Address, Env};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    PlanCount,
+    Plan(u32),
+    Subscription(Address, u32),
+}
+
+// Plans and subscriptions grow with the number of merchants and subscribers, so each one gets a persistent entry
+const PLAN_COUNT: StorageCell<DataKey, u32> = StorageCell::instance(DataKey::PlanCount);
+const PLANS: StorageMap<u32, Plan, DataKey> = StorageMap::persistent(|id| DataKey::Plan(*id));
+const SUBSCRIPTIONS: StorageMap<(Address, u32), Subscription, DataKey> =
+    StorageMap::persistent(|(subscriber, plan_id)| DataKey::Subscription(subscriber.clone(), *plan_id));
+
+pub fn next_plan_id(e: &Env) -> u32 {
+    PLAN_COUNT.update(e, |count| count.unwrap_or(0) + 1)
+}
+
+pub fn get_plan(e: &Env, id: u32) -> Option<Plan> {
+    PLANS.get(e, &id)
+}
+
+pub fn set_plan(e: &Env, id: u32, plan: &Plan) {
+    PLANS.set(e, &id, plan);
+}
+
+pub fn get_subscription(e: &Env, subscriber: &Address, plan_id: u32) -> Option<Subscription> {
+    SUBSCRIPTIONS.get(e, &(subscriber.clone(), plan_id))
+}
+
+pub fn set_subscription(e: &Env, subscriber: &Address, subscription: &Subscription) {
+    SUBSCRIPTIONS.set(e, &(subscriber.clone(), subscription.plan_id), subscription);
+}
+
+pub fn remove_subscription(e: &Env, subscriber: &Address, plan_id: u32) {
+    SUBSCRIPTIONS.remove(e, &(subscriber.clone(), plan_id));
+}
@@ -0,0 +1,187 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::{Subscription, SubscriptionBilling, SubscriptionBillingClient, CONTRACT_TAG, TOPIC_INVOICE};
+use soroban_examples_events::EVENT_VERSION;
+use soroban_examples_testutils::{advance_days, create_env, create_token_contract, mint_to, TokenClient, SECONDS_IN_DAY};
+use soroban_sdk::{testutils::{Address as _, Events, Ledger}, Address, Env, IntoVal};
+
+const START: u64 = 1_000;
+const PRICE: i128 = 100;
+const MONTH: u64 = 30 * SECONDS_IN_DAY;
+
+struct TestData<'a> {
+    merchant: Address,
+    subscriber: Address,
+    token: TokenClient<'a>,
+    plan_id: u32,
+    client: SubscriptionBillingClient<'a>,
+}
+
+// The subscriber has approved the billing contract for `allowance`
+fn create_plan(e: &Env, allowance: i128) -> TestData<'_> {
+    let (merchant, subscriber) = (Address::generate(e), Address::generate(e));
+    let (token, token_admin) = create_token_contract(e, &Address::generate(e));
+    mint_to(&token_admin, &[&subscriber], 1_000);
+    e.ledger().set_timestamp(START);
+
+    let client = SubscriptionBillingClient::new(e, &e.register(SubscriptionBilling, ()));
+    let plan_id = client.create_plan(&merchant, &token.address, &PRICE, &MONTH);
+    token.approve(&subscriber, &client.address, &allowance, &(e.ledger().sequence() + 1_000_000));
+
+    TestData { merchant, subscriber, token, plan_id, client }
+}
+
+#[test]
+fn test_monthly_billing() {
+    let e = create_env();
+    let test_data = create_plan(&e, 1_000);
+
+    // The first month is paid on subscription
+    let subscription = test_data.client.subscribe(&test_data.subscriber, &test_data.plan_id);
+    assert_eq!(subscription, Subscription { plan_id: test_data.plan_id, started_ts: START, next_charge_ts: START + MONTH, invoices: 1 });
+    assert_eq!(test_data.token.balance(&test_data.merchant), PRICE);
+
+    advance_days(&e, 30);
+    assert_eq!(test_data.client.charge(&test_data.subscriber, &test_data.plan_id), PRICE);
+    assert_eq!(
+        e.events().all().last().unwrap(),
+        (
+            test_data.client.address.clone(),
+            (CONTRACT_TAG, TOPIC_INVOICE).into_val(&e),
+            (EVENT_VERSION, (test_data.subscriber.clone(), test_data.plan_id, 2_u32, PRICE)).into_val(&e)
+        )
+    );
+    assert_eq!(test_data.token.balance(&test_data.merchant), 2 * PRICE);
+    assert_eq!(test_data.token.allowance(&test_data.subscriber, &test_data.client.address), 1_000 - 2 * PRICE);
+}
+
+#[test]
+fn test_missed_periods_are_not_billed() {
+    let e = create_env();
+    let test_data = create_plan(&e, 1_000);
+    test_data.client.subscribe(&test_data.subscriber, &test_data.plan_id);
+
+    // Nobody charged months 2 and 3: the charge on day 75 bills the third period only
+    advance_days(&e, 75);
+    test_data.client.charge(&test_data.subscriber, &test_data.plan_id);
+    let subscription = test_data.client.subscription(&test_data.subscriber, &test_data.plan_id).unwrap();
+    assert_eq!(subscription.next_charge_ts, START + 3 * MONTH);
+    assert_eq!(subscription.invoices, 2);
+    assert_eq!(test_data.token.balance(&test_data.merchant), 2 * PRICE);
+}
+
+#[test]
+fn test_cancelled_subscription_is_not_billed() {
+    let e = create_env();
+    let test_data = create_plan(&e, 1_000);
+    test_data.client.subscribe(&test_data.subscriber, &test_data.plan_id);
+
+    assert!(test_data.client.cancel(&test_data.subscriber, &test_data.plan_id));
+    assert_eq!(test_data.client.subscription(&test_data.subscriber, &test_data.plan_id), None);
+
+    // It can subscribe again later
+    advance_days(&e, 10);
+    test_data.client.subscribe(&test_data.subscriber, &test_data.plan_id);
+}
+
+#[test]
+fn test_subscriber_holds_several_plans() {
+    let e = create_env();
+    let test_data = create_plan(&e, 1_000);
+    let weekly = test_data.client.create_plan(&test_data.merchant, &test_data.token.address, &(PRICE / 4), &(7 * SECONDS_IN_DAY));
+
+    test_data.client.subscribe(&test_data.subscriber, &test_data.plan_id);
+    test_data.client.subscribe(&test_data.subscriber, &weekly);
+    assert_eq!(test_data.token.balance(&test_data.merchant), PRICE + PRICE / 4);
+
+    // Each plan is billed on its own schedule
+    advance_days(&e, 7);
+    assert_eq!(test_data.client.charge(&test_data.subscriber, &weekly), PRICE / 4);
+    assert!(test_data.client.try_charge(&test_data.subscriber, &test_data.plan_id).is_err());
+
+    // Cancelling one plan leaves the other one running
+    test_data.client.cancel(&test_data.subscriber, &weekly);
+    assert_eq!(test_data.client.subscription(&test_data.subscriber, &weekly), None);
+    advance_days(&e, 23);
+    assert_eq!(test_data.client.charge(&test_data.subscriber, &test_data.plan_id), PRICE);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1201)")]
+fn test_subscribe_to_unknown_plan() {
+    let e = create_env();
+    let test_data = create_plan(&e, 1_000);
+
+    test_data.client.subscribe(&test_data.subscriber, &(test_data.plan_id + 1));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1202)")]
+fn test_closed_plan_bills_nobody() {
+    let e = create_env();
+    let test_data = create_plan(&e, 1_000);
+    test_data.client.subscribe(&test_data.subscriber, &test_data.plan_id);
+
+    test_data.client.close_plan(&test_data.plan_id);
+    advance_days(&e, 30);
+    test_data.client.charge(&test_data.subscriber, &test_data.plan_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1203)")]
+fn test_subscribe_twice() {
+    let e = create_env();
+    let test_data = create_plan(&e, 1_000);
+
+    test_data.client.subscribe(&test_data.subscriber, &test_data.plan_id);
+    test_data.client.subscribe(&test_data.subscriber, &test_data.plan_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1204)")]
+fn test_charge_without_subscription() {
+    let e = create_env();
+    let test_data = create_plan(&e, 1_000);
+
+    test_data.client.charge(&test_data.subscriber, &test_data.plan_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1205)")]
+fn test_charge_before_due() {
+    let e = create_env();
+    let test_data = create_plan(&e, 1_000);
+    test_data.client.subscribe(&test_data.subscriber, &test_data.plan_id);
+
+    e.ledger().set_timestamp(START + MONTH - 1);
+    test_data.client.charge(&test_data.subscriber, &test_data.plan_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1206)")]
+fn test_allowance_used_up() {
+    let e = create_env();
+    let test_data = create_plan(&e, PRICE);
+    test_data.client.subscribe(&test_data.subscriber, &test_data.plan_id);
+
+    advance_days(&e, 30);
+    test_data.client.charge(&test_data.subscriber, &test_data.plan_id);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1209)")]
+fn test_plan_without_interval() {
+    let e = create_env();
+    let test_data = create_plan(&e, 1_000);
+
+    test_data.client.create_plan(&test_data.merchant, &test_data.token.address, &PRICE, &0);
+}
+
+#[test]
+fn error_codes_in_registry_range() {
+    use super::Error;
+
+    assert!(soroban_examples_errors::SUBSCRIPTION.contains(Error::PlanNotFound as u32));
+    assert!(soroban_examples_errors::SUBSCRIPTION.contains(Error::InvalidPlan as u32));
+}
@@ -0,0 +1,128 @@
+#![no_std]
+
+//! Timelock controller: a contract meant to be the admin of other contracts, so that every admin
+//! operation is announced before it takes effect. The proposer (the admin of the controller) queues a
+//! call to a target contract with a delay of at least the minimum one; once the delay has passed,
+//! anyone can execute it, and the controller makes the call as the target's admin. Until then the
+//! proposer can cancel it, and the users of the target get the notice of the `TLSCHED` event.
+//!
+//! It is the timelock `Investment` keeps for its own withdrawals (`soroban-examples-timelock`), applied
+//! to arbitrary calls instead of a single operation.
+
+use soroban_examples_access as access;
+use soroban_examples_events as events;
+use soroban_examples_timelock::{self as timelock, OperationState, TimelockError, TimelockOperation};
+use soroban_sdk::{contract, contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env, Symbol, Val, Vec};
+
+// Topics follow the workspace event schema: (CONTRACT_TAG, action)
+pub const CONTRACT_TAG: Symbol = symbol_short!("TIMELOCK");
+pub const TOPIC_MIN_DELAY: Symbol = symbol_short!("MINDELAY");
+
+// Every queued call is a timelock operation under this symbol, told apart by its payload
+const OP_CALL: Symbol = symbol_short!("call");
+
+macro_rules! require {
+    ($cond:expr, $err:expr) => {
+        if !$cond {
+            return Err($err);
+        }
+    };
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    DelayTooShort = 1501,
+    AlreadyQueued = 1502,
+    NotQueued = 1503,
+    NotReady = 1504,
+    MinDelayCannotDecrease = 1505,
+}
+
+impl From<TimelockError> for Error {
+    fn from(err: TimelockError) -> Self {
+        match err {
+            TimelockError::DelayTooShort => Error::DelayTooShort,
+            TimelockError::AlreadyQueued => Error::AlreadyQueued,
+            TimelockError::NotQueued => Error::NotQueued,
+            TimelockError::NotReady => Error::NotReady,
+        }
+    }
+}
+
+/// Invocation of `function` of the `contract` with `args`. A `salt` tells apart two otherwise identical
+/// calls queued at the same time.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Call {
+    pub contract: Address,
+    pub function: Symbol,
+    pub args: Vec<Val>,
+    pub salt: u32,
+}
+
+fn require_proposer(e: &Env) {
+    access::require_admin(e).unwrap_or_else(|_| panic!("Timelock admin has expired"));
+}
+
+#[contract]
+pub struct TimelockController;
+
+#[contractimpl]
+impl TimelockController {
+    /// `admin` queues and cancels the calls, which wait at least `min_delay` seconds.
+    pub fn __constructor(e: Env, admin: Address, min_delay: u64) {
+        access::set_admin(&e, &admin);
+        timelock::set_min_delay(&e, min_delay);
+    }
+
+    /// Queues `call`, executable `delay` seconds from now. Returns the hash identifying it.
+    pub fn schedule(e: Env, call: Call, delay: u64) -> Result<BytesN<32>, Error> {
+        require_proposer(&e);
+        Ok(timelock::schedule(&e, &OP_CALL, &call, delay)?)
+    }
+
+    /// Makes a queued call whose delay has passed; anyone can trigger it. Returns what the called
+    /// function returned.
+    pub fn execute(e: Env, call: Call) -> Result<Val, Error> {
+        timelock::execute(&e, &OP_CALL, &call)?;
+        Ok(e.invoke_contract::<Val>(&call.contract, &call.function, call.args))
+    }
+
+    /// Drops a queued call (admin only).
+    pub fn cancel(e: Env, hash: BytesN<32>) -> Result<TimelockOperation, Error> {
+        require_proposer(&e);
+        Ok(timelock::cancel(&e, &hash)?)
+    }
+
+    /// Raises the minimum delay (admin only). It can never be lowered, since a shorter notice is
+    /// exactly what the timelock protects the users from; calls already queued keep their delay.
+    pub fn increase_min_delay(e: Env, min_delay: u64) -> Result<bool, Error> {
+        require_proposer(&e);
+        require!(min_delay >= timelock::min_delay(&e), Error::MinDelayCannotDecrease);
+
+        timelock::set_min_delay(&e, min_delay);
+        events::publish(&e, CONTRACT_TAG, TOPIC_MIN_DELAY, min_delay);
+        Ok(true)
+    }
+
+    pub fn min_delay(e: Env) -> u64 {
+        timelock::min_delay(&e)
+    }
+
+    pub fn hash_call(e: Env, call: Call) -> BytesN<32> {
+        timelock::hash_operation(&e, &OP_CALL, &call)
+    }
+
+    pub fn state(e: Env, hash: BytesN<32>) -> OperationState {
+        timelock::state(&e, &hash)
+    }
+
+    pub fn admin(e: Env) -> Address {
+        access::get_admin(&e).unwrap_or_else(|| panic!("Timelock admin has expired"))
+    }
+}
+
+#[cfg(test)]
+mod test;
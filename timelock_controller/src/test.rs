@@ -0,0 +1,127 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::{Call, TimelockController, TimelockControllerClient};
+use soroban_examples_testutils::{advance_days, advance_time, create_env, create_token_contract, TokenClient, SECONDS_IN_DAY};
+use soroban_examples_timelock::OperationState;
+use soroban_sdk::{symbol_short, testutils::{Address as _, Ledger}, vec, Address, Env, IntoVal};
+
+const MIN_DELAY: u64 = 2 * SECONDS_IN_DAY;
+
+struct TestData<'a> {
+    token: TokenClient<'a>,
+    client: TimelockControllerClient<'a>,
+}
+
+// The controller is the admin of a token, so minting has to go through the timelock
+fn create_controller(e: &Env) -> TestData<'_> {
+    let client = TimelockControllerClient::new(e, &e.register(TimelockController, (Address::generate(e), MIN_DELAY)));
+    let (token, _) = create_token_contract(e, &client.address);
+    e.ledger().set_timestamp(1_000);
+
+    TestData { token, client }
+}
+
+fn mint_call(e: &Env, test_data: &TestData, to: &Address, amount: i128, salt: u32) -> Call {
+    Call {
+        contract: test_data.token.address.clone(),
+        function: symbol_short!("mint"),
+        args: vec![e, to.into_val(e), amount.into_val(e)],
+        salt,
+    }
+}
+
+#[test]
+fn test_call_runs_once_the_delay_has_passed() {
+    let e = create_env();
+    let test_data = create_controller(&e);
+    let holder = Address::generate(&e);
+    let call = mint_call(&e, &test_data, &holder, 500, 0);
+
+    let hash = test_data.client.schedule(&call, &MIN_DELAY);
+    assert_eq!(hash, test_data.client.hash_call(&call));
+    assert_eq!(test_data.client.state(&hash), OperationState::Waiting);
+
+    advance_days(&e, 2);
+    assert_eq!(test_data.client.state(&hash), OperationState::Ready);
+    test_data.client.execute(&call);
+    assert_eq!(test_data.token.balance(&holder), 500);
+    assert_eq!(test_data.client.state(&hash), OperationState::Unset);
+}
+
+#[test]
+fn test_salt_tells_identical_calls_apart() {
+    let e = create_env();
+    let test_data = create_controller(&e);
+    let holder = Address::generate(&e);
+
+    test_data.client.schedule(&mint_call(&e, &test_data, &holder, 500, 0), &MIN_DELAY);
+    test_data.client.schedule(&mint_call(&e, &test_data, &holder, 500, 1), &(MIN_DELAY + 60));
+    advance_time(&e, MIN_DELAY + 60);
+    test_data.client.execute(&mint_call(&e, &test_data, &holder, 500, 0));
+    test_data.client.execute(&mint_call(&e, &test_data, &holder, 500, 1));
+    assert_eq!(test_data.token.balance(&holder), 1_000);
+}
+
+#[test]
+fn test_min_delay_only_increases() {
+    let e = create_env();
+    let test_data = create_controller(&e);
+
+    test_data.client.increase_min_delay(&(3 * SECONDS_IN_DAY));
+    assert_eq!(test_data.client.min_delay(), 3 * SECONDS_IN_DAY);
+    assert!(test_data.client.try_increase_min_delay(&MIN_DELAY).is_err());
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1501)")]
+fn test_delay_below_the_minimum() {
+    let e = create_env();
+    let test_data = create_controller(&e);
+
+    test_data.client.schedule(&mint_call(&e, &test_data, &Address::generate(&e), 500, 0), &(MIN_DELAY - 1));
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1502)")]
+fn test_schedule_twice() {
+    let e = create_env();
+    let test_data = create_controller(&e);
+    let call = mint_call(&e, &test_data, &Address::generate(&e), 500, 0);
+
+    test_data.client.schedule(&call, &MIN_DELAY);
+    test_data.client.schedule(&call, &MIN_DELAY);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1503)")]
+fn test_execute_cancelled_call() {
+    let e = create_env();
+    let test_data = create_controller(&e);
+    let call = mint_call(&e, &test_data, &Address::generate(&e), 500, 0);
+
+    let hash = test_data.client.schedule(&call, &MIN_DELAY);
+    test_data.client.cancel(&hash);
+    advance_days(&e, 2);
+    test_data.client.execute(&call);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #1504)")]
+fn test_execute_before_the_delay() {
+    let e = create_env();
+    let test_data = create_controller(&e);
+    let call = mint_call(&e, &test_data, &Address::generate(&e), 500, 0);
+
+    test_data.client.schedule(&call, &MIN_DELAY);
+    advance_time(&e, MIN_DELAY - 1);
+    test_data.client.execute(&call);
+}
+
+#[test]
+fn error_codes_in_registry_range() {
+    use super::Error;
+
+    assert!(soroban_examples_errors::TIMELOCK_CONTROLLER.contains(Error::DelayTooShort as u32));
+    assert!(soroban_examples_errors::TIMELOCK_CONTROLLER.contains(Error::MinDelayCannotDecrease as u32));
+}
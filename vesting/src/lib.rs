@@ -0,0 +1,171 @@
+#![no_std]
+
+//! Token vesting with a cliff and a linear release, e.g. for team or project allocations. The funder
+//! escrows the whole allocation when deploying the contract; nothing vests before the cliff, and from
+//! then on the vested amount grows linearly with the time elapsed since the start, until the whole
+//! allocation has vested at the end of the duration. The beneficiary takes what has vested and not
+//! been claimed yet with `claim_vested`.
+//!
+//! A revocable schedule can be revoked by the funder: the vesting stops at that moment, the unvested
+//! part goes back to the funder and the beneficiary keeps the right to claim what had vested.
+
+use soroban_examples_events as events;
+use soroban_examples_fixed::{self as fixed, MathError, Rounding};
+use soroban_sdk::{contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Env, Symbol};
+
+mod storage;
+
+// Topics follow the workspace event schema: (CONTRACT_TAG, action)
+pub const CONTRACT_TAG: Symbol = symbol_short!("VESTING");
+pub const TOPIC_CLAIMED: Symbol = symbol_short!("CLAIMED");
+pub const TOPIC_REVOKED: Symbol = symbol_short!("REVOKED");
+
+macro_rules! require {
+    ($cond:expr, $err:expr) => {
+        if !$cond {
+            return Err($err);
+        }
+    };
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NothingToClaim = 901,
+    NotRevocable = 902,
+    AlreadyRevoked = 903,
+    InvalidSchedule = 904,
+    ArithmeticError = 905,
+}
+
+impl From<MathError> for Error {
+    fn from(_err: MathError) -> Self {
+        Error::ArithmeticError
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VestingSchedule {
+    pub start_ts: u64,
+    /// Seconds after the start before anything vests. What vested during the cliff becomes
+    /// claimable all at once when it is over.
+    pub cliff: u64,
+    /// Seconds after the start at which the whole allocation has vested.
+    pub duration: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VestingConfig {
+    pub funder: Address,
+    pub beneficiary: Address,
+    pub token: Address,
+    pub total_amount: i128,
+    pub schedule: VestingSchedule,
+    pub revocable: bool,
+}
+
+/// Amount of `total_amount` vested at `ts` according to `schedule`, rounded down. Fails with
+/// `ArithmeticError` if the allocation is too large to scale by the elapsed time.
+pub fn vested_at(schedule: &VestingSchedule, total_amount: i128, ts: u64) -> Result<i128, Error> {
+    if ts < schedule.start_ts + schedule.cliff {
+        return Ok(0);
+    }
+
+    let elapsed = ts - schedule.start_ts;
+    if elapsed >= schedule.duration {
+        return Ok(total_amount);
+    }
+
+    Ok(fixed::mul_div(total_amount, elapsed as i128, schedule.duration as i128, Rounding::Down)?)
+}
+
+// A revoked schedule stops vesting at the revocation
+fn vested_amount(e: &Env, config: &VestingConfig) -> Result<i128, Error> {
+    let now = e.ledger().timestamp();
+    let ts = storage::get_revoked_at(e).map_or(now, |revoked_at| revoked_at.min(now));
+    vested_at(&config.schedule, config.total_amount, ts)
+}
+
+#[contract]
+pub struct TokenVesting;
+
+#[contractimpl]
+impl TokenVesting {
+    /// Vests `total_amount` of `token` to `beneficiary`, escrowed from `funder` right away.
+    pub fn __constructor(
+        e: Env,
+        funder: Address,
+        beneficiary: Address,
+        token: Address,
+        total_amount: i128,
+        schedule: VestingSchedule,
+        revocable: bool,
+    ) -> Result<(), Error> {
+        funder.require_auth();
+        require!(total_amount > 0 && schedule.duration > 0, Error::InvalidSchedule);
+        require!(schedule.cliff <= schedule.duration, Error::InvalidSchedule);
+
+        storage::set_config(&e, &VestingConfig { funder: funder.clone(), beneficiary, token: token.clone(), total_amount, schedule, revocable });
+        token::Client::new(&e, &token).transfer(&funder, &e.current_contract_address(), &total_amount);
+        Ok(())
+    }
+
+    /// Pays the beneficiary what has vested and not been claimed yet. Returns the amount paid.
+    pub fn claim_vested(e: Env) -> Result<i128, Error> {
+        let config = storage::get_config(&e);
+        config.beneficiary.require_auth();
+
+        let claimed = storage::get_claimed(&e);
+        let amount = vested_amount(&e, &config)? - claimed;
+        require!(amount > 0, Error::NothingToClaim);
+
+        storage::set_claimed(&e, claimed + amount);
+        token::Client::new(&e, &config.token).transfer(&e.current_contract_address(), &config.beneficiary, &amount);
+        events::publish(&e, CONTRACT_TAG, TOPIC_CLAIMED, (config.beneficiary, amount));
+        Ok(amount)
+    }
+
+    /// The funder stops a revocable vesting and takes back what has not vested yet, which is returned.
+    /// The beneficiary can still claim what vested before the revocation.
+    pub fn revoke(e: Env) -> Result<i128, Error> {
+        let config = storage::get_config(&e);
+        config.funder.require_auth();
+        require!(config.revocable, Error::NotRevocable);
+        require!(storage::get_revoked_at(&e).is_none(), Error::AlreadyRevoked);
+
+        let unvested = config.total_amount - vested_amount(&e, &config)?;
+        storage::set_revoked_at(&e, e.ledger().timestamp());
+        if unvested > 0 {
+            token::Client::new(&e, &config.token).transfer(&e.current_contract_address(), &config.funder, &unvested);
+        }
+        events::publish(&e, CONTRACT_TAG, TOPIC_REVOKED, unvested);
+        Ok(unvested)
+    }
+
+    pub fn config(e: Env) -> VestingConfig {
+        storage::get_config(&e)
+    }
+
+    pub fn vested(e: Env) -> Result<i128, Error> {
+        vested_amount(&e, &storage::get_config(&e))
+    }
+
+    /// Amount the beneficiary would get from `claim_vested` now.
+    pub fn releasable(e: Env) -> Result<i128, Error> {
+        Ok(vested_amount(&e, &storage::get_config(&e))? - storage::get_claimed(&e))
+    }
+
+    pub fn claimed(e: Env) -> i128 {
+        storage::get_claimed(&e)
+    }
+
+    pub fn revoked_at(e: Env) -> Option<u64> {
+        storage::get_revoked_at(&e)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,39 @@
+use crate::VestingConfig;
+use soroban_examples_storage::StorageCell;
+use soroban_sdk::{contracttype, Env};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Config,
+    Claimed,
+    RevokedAt,
+}
+
+const CONFIG: StorageCell<DataKey, VestingConfig> = StorageCell::instance(DataKey::Config);
+const CLAIMED: StorageCell<DataKey, i128> = StorageCell::instance(DataKey::Claimed);
+const REVOKED_AT: StorageCell<DataKey, u64> = StorageCell::instance(DataKey::RevokedAt);
+
+pub fn get_config(e: &Env) -> VestingConfig {
+    CONFIG.get_or_else(e, || panic!("Vesting config has expired"))
+}
+
+pub fn set_config(e: &Env, config: &VestingConfig) {
+    CONFIG.set(e, config);
+}
+
+pub fn get_claimed(e: &Env) -> i128 {
+    CLAIMED.get(e).unwrap_or(0)
+}
+
+pub fn set_claimed(e: &Env, claimed: i128) {
+    CLAIMED.set(e, &claimed);
+}
+
+pub fn get_revoked_at(e: &Env) -> Option<u64> {
+    REVOKED_AT.get(e)
+}
+
+pub fn set_revoked_at(e: &Env, ts: u64) {
+    REVOKED_AT.set(e, &ts);
+}
@@ -0,0 +1,167 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::{vested_at, Error, TokenVesting, TokenVestingClient, VestingSchedule, CONTRACT_TAG, TOPIC_CLAIMED};
+use soroban_examples_events::EVENT_VERSION;
+use soroban_examples_testutils::{advance_days, create_env, create_token_contract, mint_to, TokenClient, SECONDS_IN_DAY};
+use soroban_sdk::{testutils::{Address as _, Events, Ledger}, Address, Env, IntoVal};
+
+const START: u64 = 1_000;
+// 1000 tokens vest every day
+const TOTAL: i128 = 360_000;
+
+struct TestData<'a> {
+    funder: Address,
+    beneficiary: Address,
+    token: TokenClient<'a>,
+    client: TokenVestingClient<'a>,
+}
+
+// 90-day cliff, everything vested after 360 days
+fn schedule() -> VestingSchedule {
+    VestingSchedule { start_ts: START, cliff: 90 * SECONDS_IN_DAY, duration: 360 * SECONDS_IN_DAY }
+}
+
+fn create_vesting(e: &Env, revocable: bool) -> TestData<'_> {
+    let (funder, beneficiary) = (Address::generate(e), Address::generate(e));
+    let (token, token_admin) = create_token_contract(e, &Address::generate(e));
+    mint_to(&token_admin, &[&funder], TOTAL);
+
+    let client = TokenVestingClient::new(
+        e,
+        &e.register(TokenVesting, (funder.clone(), beneficiary.clone(), token.address.clone(), TOTAL, schedule(), revocable)),
+    );
+    e.ledger().set_timestamp(START);
+
+    TestData { funder, beneficiary, token, client }
+}
+
+#[test]
+fn test_vested_amount_over_time() {
+    assert_eq!(vested_at(&schedule(), TOTAL, 0), Ok(0));
+    assert_eq!(vested_at(&schedule(), TOTAL, START + 90 * SECONDS_IN_DAY - 1), Ok(0));
+    assert_eq!(vested_at(&schedule(), TOTAL, START + 90 * SECONDS_IN_DAY), Ok(90_000));
+    assert_eq!(vested_at(&schedule(), TOTAL, START + 180 * SECONDS_IN_DAY + SECONDS_IN_DAY / 2), Ok(180_500));
+    assert_eq!(vested_at(&schedule(), TOTAL, START + 360 * SECONDS_IN_DAY), Ok(TOTAL));
+    assert_eq!(vested_at(&schedule(), TOTAL, u64::MAX), Ok(TOTAL));
+}
+
+#[test]
+fn test_vested_amount_overflow() {
+    // Scaling the allocation by the elapsed time overflows before the division brings it back
+    assert_eq!(vested_at(&schedule(), i128::MAX, START + 180 * SECONDS_IN_DAY), Err(Error::ArithmeticError));
+    assert_eq!(vested_at(&schedule(), i128::MAX, START + 360 * SECONDS_IN_DAY), Ok(i128::MAX));
+}
+
+#[test]
+fn test_beneficiary_claims_as_it_vests() {
+    let e = create_env();
+    let test_data = create_vesting(&e, false);
+    assert_eq!(test_data.token.balance(&test_data.client.address), TOTAL);
+
+    // What vested during the cliff is released at once
+    advance_days(&e, 90);
+    assert_eq!(test_data.client.releasable(), 90_000);
+    assert_eq!(test_data.client.claim_vested(), 90_000);
+    assert_eq!(
+        e.events().all().last().unwrap(),
+        (test_data.client.address.clone(), (CONTRACT_TAG, TOPIC_CLAIMED).into_val(&e), (EVENT_VERSION, (test_data.beneficiary.clone(), 90_000_i128)).into_val(&e))
+    );
+
+    advance_days(&e, 30);
+    assert_eq!(test_data.client.claim_vested(), 30_000);
+    assert_eq!(test_data.client.claimed(), 120_000);
+
+    // Past the end, the rest of the allocation and nothing more
+    advance_days(&e, 1000);
+    assert_eq!(test_data.client.claim_vested(), 240_000);
+    assert_eq!(test_data.token.balance(&test_data.beneficiary), TOTAL);
+    assert_eq!(test_data.token.balance(&test_data.client.address), 0);
+}
+
+#[test]
+fn test_revoke_returns_the_unvested_part() {
+    let e = create_env();
+    let test_data = create_vesting(&e, true);
+
+    advance_days(&e, 100);
+    test_data.client.claim_vested();
+    advance_days(&e, 20);
+    assert_eq!(test_data.client.revoke(), 240_000);
+    assert_eq!(test_data.token.balance(&test_data.funder), 240_000);
+
+    // The vesting stops at the revocation, but what vested until then stays claimable
+    advance_days(&e, 100);
+    assert_eq!(test_data.client.vested(), 120_000);
+    assert_eq!(test_data.client.claim_vested(), 20_000);
+    assert_eq!(test_data.token.balance(&test_data.client.address), 0);
+}
+
+#[test]
+fn test_revoke_during_the_cliff() {
+    let e = create_env();
+    let test_data = create_vesting(&e, true);
+
+    advance_days(&e, 30);
+    assert_eq!(test_data.client.revoke(), TOTAL);
+    assert_eq!(test_data.client.revoked_at(), Some(START + 30 * SECONDS_IN_DAY));
+
+    advance_days(&e, 400);
+    assert_eq!(test_data.client.releasable(), 0);
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #901)")]
+fn test_claim_before_the_cliff() {
+    let e = create_env();
+    let test_data = create_vesting(&e, false);
+
+    advance_days(&e, 89);
+    test_data.client.claim_vested();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #901)")]
+fn test_claim_twice_at_the_same_time() {
+    let e = create_env();
+    let test_data = create_vesting(&e, false);
+
+    advance_days(&e, 100);
+    test_data.client.claim_vested();
+    test_data.client.claim_vested();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #902)")]
+fn test_revoke_irrevocable_vesting() {
+    let e = create_env();
+    let test_data = create_vesting(&e, false);
+
+    test_data.client.revoke();
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #903)")]
+fn test_revoke_twice() {
+    let e = create_env();
+    let test_data = create_vesting(&e, true);
+
+    test_data.client.revoke();
+    test_data.client.revoke();
+}
+
+#[test]
+#[should_panic(expected = "Error(Context, InvalidAction)")]
+fn test_cliff_longer_than_duration() {
+    let e = create_env();
+    let test_data = create_vesting(&e, false);
+
+    let schedule = VestingSchedule { cliff: 400 * SECONDS_IN_DAY, ..schedule() };
+    e.register(TokenVesting, (test_data.funder.clone(), test_data.beneficiary.clone(), test_data.token.address.clone(), TOTAL, schedule, false));
+}
+
+#[test]
+fn error_codes_in_registry_range() {
+    assert!(soroban_examples_errors::VESTING.contains(Error::NothingToClaim as u32));
+    assert!(soroban_examples_errors::VESTING.contains(Error::ArithmeticError as u32));
+}